@@ -0,0 +1,4 @@
+//! `libreadelf_rs.so`, exporting the `relr_*` functions of `readelf_rs::ffi` and, with the
+//! `python` feature, the `readelf_rs` Python module
+
+pub use readelf::*;
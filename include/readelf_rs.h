@@ -0,0 +1,71 @@
+/* C API of readelf-rs, built with `cargo build --release -p readelf-rs-bindings` which
+ * produces libreadelf_rs.so. Strings returned through the structs below are owned by the
+ * handle and stay valid until relr_close. */
+
+#ifndef READELF_RS_H
+#define READELF_RS_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct RelrFile RelrFile;
+
+typedef struct {
+    uint8_t class;
+    uint8_t data;
+    uint8_t os_abi;
+    uint16_t object_type;
+    uint16_t machine;
+    uint64_t entry;
+    uint32_t flags;
+    uint16_t phnum;
+    uint16_t shnum;
+} RelrHeader;
+
+typedef struct {
+    const char *name;
+    uint32_t section_type;
+    uint64_t flags;
+    uint64_t addr;
+    uint64_t offset;
+    uint64_t size;
+    uint32_t link;
+    uint32_t info;
+    uint64_t addralign;
+    uint64_t entsize;
+} RelrSection;
+
+typedef struct {
+    const char *name;
+    /* ".symtab" or ".dynsym" */
+    const char *table;
+    uint64_t value;
+    uint64_t size;
+    uint8_t info;
+    uint8_t other;
+    uint16_t shndx;
+} RelrSymbol;
+
+/* Return NULL when the file cannot be read or is not ELF */
+RelrFile *relr_open(const char *path);
+RelrFile *relr_open_bytes(const uint8_t *data, size_t len);
+void relr_close(RelrFile *file);
+
+/* The *_at and relr_header functions return 0 on success and -1 on a bad index or NULL */
+int relr_header(const RelrFile *file, RelrHeader *out);
+
+size_t relr_section_count(const RelrFile *file);
+int relr_section_at(const RelrFile *file, size_t index, RelrSection *out);
+
+size_t relr_symbol_count(const RelrFile *file);
+int relr_symbol_at(const RelrFile *file, size_t index, RelrSymbol *out);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
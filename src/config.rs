@@ -0,0 +1,147 @@
+#[cfg(feature = "config")]
+use std::fs;
+use std::{collections::HashMap, env, io, path::PathBuf};
+
+use readelf_rs::display::Theme;
+#[cfg(feature = "config")]
+use serde::Deserialize;
+use termcolor::Color;
+
+/// Defaults for flags, the command line always wins over these
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "config", derive(Deserialize))]
+#[cfg_attr(
+    feature = "config",
+    serde(default, rename_all = "kebab-case", deny_unknown_fields)
+)]
+pub struct Defaults {
+    pub pager: bool,
+    /// Turns on -W
+    pub wide: bool,
+    /// Turns on -C, ignored without the `demangle` feature
+    pub demangle: bool,
+    pub output_format: Option<String>,
+}
+
+/// A named theme, roles left out keep their default color
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "config", derive(Deserialize))]
+#[cfg_attr(feature = "config", serde(default, deny_unknown_fields))]
+pub struct ThemeConfig {
+    heading: Option<String>,
+    accent: Option<String>,
+    label: Option<String>,
+    name: Option<String>,
+    text: Option<String>,
+}
+
+/// The contents of `config.toml`
+///
+/// ```toml
+/// theme = "dark"
+///
+/// [defaults]
+/// pager = true
+/// wide = true
+/// demangle = true
+/// output-format = "gnu"
+///
+/// [themes.dark]
+/// heading = "cyan"
+/// accent = "33"
+/// name = "255,128,0"
+/// ```
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "config", derive(Deserialize))]
+#[cfg_attr(feature = "config", serde(default, deny_unknown_fields))]
+pub struct Config {
+    pub defaults: Defaults,
+    pub theme: Option<String>,
+    pub themes: HashMap<String, ThemeConfig>,
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+impl Config {
+    /// `$XDG_CONFIG_HOME/readelf-rs/config.toml`, falling back to `~/.config`. Always
+    /// `None` without the `config` feature.
+    pub fn path() -> Option<PathBuf> {
+        if !cfg!(feature = "config") {
+            return None;
+        }
+        let base = match env::var_os("XDG_CONFIG_HOME") {
+            Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+            _ => PathBuf::from(env::var_os("HOME")?).join(".config"),
+        };
+        Some(base.join("readelf-rs").join("config.toml"))
+    }
+
+    /// Reads the config at `path`, a missing file is an empty config
+    #[cfg(feature = "config")]
+    pub fn load(path: &PathBuf) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(contents) => toml::from_str(&contents).map_err(|err| invalid(err.to_string())),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    #[cfg(not(feature = "config"))]
+    pub fn load(_path: &PathBuf) -> io::Result<Self> {
+        Ok(Self::default())
+    }
+
+    /// Resolves the theme named by `name`, or by the config's `theme` key when `None`
+    pub fn theme(&self, name: Option<&str>) -> io::Result<Theme> {
+        let name = match name.or(self.theme.as_deref()) {
+            Some(name) => name,
+            None => return Ok(Theme::default()),
+        };
+        let config = match self.themes.get(name) {
+            Some(config) => config,
+            None if name == "default" => return Ok(Theme::default()),
+            None => return Err(invalid(format!("unknown theme '{}'", name))),
+        };
+
+        let color = |color: &Option<String>, fallback: Color| match color {
+            Some(color) => color
+                .parse::<Color>()
+                .map_err(|err| invalid(format!("theme '{}': {}", name, err))),
+            None => Ok(fallback),
+        };
+        let default = Theme::default();
+        Ok(Theme {
+            heading: color(&config.heading, default.heading)?,
+            accent: color(&config.accent, default.accent)?,
+            label: color(&config.label, default.label)?,
+            name: color(&config.name, default.name)?,
+            text: color(&config.text, default.text)?,
+        })
+    }
+}
+
+#[cfg(all(test, feature = "config"))]
+mod test {
+    use termcolor::Color;
+
+    use super::Config;
+
+    #[test]
+    fn resolves_named_theme() {
+        let config: Config = toml::from_str(
+            "theme = \"dark\"\n[defaults]\nwide = true\noutput-format = \"gnu\"\n\
+             [themes.dark]\nheading = \"cyan\"\nname = \"255,128,0\"\n",
+        )
+        .unwrap();
+
+        let theme = config.theme(None).unwrap();
+        assert_eq!(theme.heading, Color::Cyan);
+        assert_eq!(theme.name, Color::Rgb(255, 128, 0));
+        assert_eq!(theme.text, Color::White);
+        assert_eq!(config.defaults.output_format.as_deref(), Some("gnu"));
+        assert!(config.defaults.wide && !config.defaults.demangle);
+        assert!(config.theme(Some("missing")).is_err());
+    }
+}
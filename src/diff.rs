@@ -0,0 +1,432 @@
+use std::{
+    collections::BTreeMap,
+    io::{self, Write},
+};
+
+use clap::Args;
+use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
+
+#[cfg(feature = "demangle")]
+use readelf_rs::elf::demangle::demangle;
+use readelf_rs::elf::{
+    bytes::string_at,
+    core::FileData,
+    export::{Export, ExportList},
+    sym::SymbolType,
+};
+
+/// Arguments of `readelf-rs diff`
+#[derive(Args, Debug)]
+pub struct DiffArgs {
+    /// Show the bytes of the named section that differ, row by row at the same offsets
+    #[clap(long = "hex", value_name = "SECTION")]
+    hex: Option<String>,
+
+    /// Compare the defined symbols, listing those added, removed or resized by how much
+    /// they changed the size, largest first
+    #[clap(long = "symbols")]
+    symbols: bool,
+
+    /// Compare the exported dynamic symbols of two shared objects, their versions, types
+    /// and object sizes, and tell compatible additions from breaking changes. Only
+    /// breaking changes make the exit status 1.
+    #[clap(long = "abi")]
+    abi: bool,
+
+    /// The file before the change
+    old: String,
+
+    /// The file after the change
+    new: String,
+}
+
+/// Runs `readelf-rs diff`, returning the exit status: 0 when the files compare equal, 1
+/// when they differ and 2 when they could not be compared
+pub fn run(args: &DiffArgs) -> i32 {
+    if args.hex.is_none() && !args.symbols && !args.abi {
+        eprintln!(
+            "readelf-rs: Error: diff: nothing to compare, expected --hex SECTION, --symbols or \
+             --abi"
+        );
+        return 2;
+    }
+
+    let mut files = Vec::new();
+    for path in [&args.old, &args.new] {
+        match FileData::new(path) {
+            Ok(elf) => files.push(elf),
+            Err(err) => {
+                eprintln!("readelf-rs: Error: '{}': {}", path, err);
+                return 2;
+            }
+        }
+    }
+
+    let mut stdout = StandardStream::stdout(ColorChoice::Always);
+    let mut differs = false;
+
+    if let Some(section) = &args.hex {
+        let mut contents = Vec::new();
+        for (path, elf) in [&args.old, &args.new].into_iter().zip(&files) {
+            let data = match elf.section_by_name(section) {
+                Some(shdr) => elf.section_data(&shdr).map(|data| (shdr.addr(), data)),
+                None => Err(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("no section named '{}'", section),
+                )),
+            };
+            match data {
+                Ok(data) => contents.push(data),
+                Err(err) => {
+                    eprintln!("readelf-rs: Error: '{}': {}", path, err);
+                    return 2;
+                }
+            }
+        }
+
+        let (addr, old) = &contents[0];
+        let new = &contents[1].1;
+        match hex_diff(&mut stdout, section, *addr, old, new) {
+            Ok(changed) => differs |= changed != 0,
+            Err(err) => {
+                eprintln!("readelf-rs: Error: {}", err);
+                return 2;
+            }
+        }
+    }
+
+    if args.symbols {
+        let mut sizes = Vec::new();
+        for (path, elf) in [&args.old, &args.new].into_iter().zip(&files) {
+            match symbol_sizes(elf) {
+                Ok(symbols) => sizes.push(symbols),
+                Err(err) => {
+                    eprintln!("readelf-rs: Error: '{}': {}", path, err);
+                    return 2;
+                }
+            }
+        }
+
+        match symbol_diff(&mut stdout, &sizes[0], &sizes[1]) {
+            Ok(changed) => differs |= changed != 0,
+            Err(err) => {
+                eprintln!("readelf-rs: Error: {}", err);
+                return 2;
+            }
+        }
+    }
+
+    if args.abi {
+        let mut exports = Vec::new();
+        for (path, elf) in [&args.old, &args.new].into_iter().zip(&files) {
+            match ExportList::read(elf) {
+                Ok(list) => exports.push(list),
+                Err(err) => {
+                    eprintln!("readelf-rs: Error: '{}': {}", path, err);
+                    return 2;
+                }
+            }
+        }
+
+        match abi_diff(&mut stdout, &exports[0], &exports[1]) {
+            Ok(breaking) => differs |= breaking != 0,
+            Err(err) => {
+                eprintln!("readelf-rs: Error: {}", err);
+                return 2;
+            }
+        }
+    }
+
+    match differs {
+        true => 1,
+        false => 0,
+    }
+}
+
+/// Prints the 16 byte rows of `old` and `new` that differ, the old row in red over the new
+/// one in green with the changed bytes highlighted. Returns the number of bytes that differ,
+/// counting bytes past the end of the shorter side.
+fn hex_diff(
+    stream: &mut StandardStream,
+    section: &str,
+    addr: u64,
+    old: &[u8],
+    new: &[u8],
+) -> io::Result<usize> {
+    let len = old.len().max(new.len());
+    let differs = |i: usize| old.get(i) != new.get(i);
+    let changed = (0..len).filter(|&i| differs(i)).count();
+
+    writeln!(
+        stream,
+        "\nHex diff of section '{}': 0x{:x} bytes before, 0x{:x} after, {} {} differ",
+        section,
+        old.len(),
+        new.len(),
+        changed,
+        if changed == 1 { "byte" } else { "bytes" }
+    )?;
+
+    let mut previous = None;
+    for row in (0..len).step_by(16) {
+        if !(row..(row + 16).min(len)).any(differs) {
+            continue;
+        }
+        if matches!(previous, Some(previous) if previous + 16 != row) {
+            writeln!(stream, "  ...")?;
+        }
+        previous = Some(row);
+
+        for (sign, side, other, color) in
+            [('-', old, new, Color::Red), ('+', new, old, Color::Green)]
+        {
+            stream.reset()?;
+            write!(stream, "{} 0x{:08x} ", sign, addr + row as u64)?;
+            for i in row..row + 16 {
+                match side.get(i) {
+                    Some(byte) if other.get(i) != Some(byte) => {
+                        stream.set_color(ColorSpec::new().set_fg(Some(color)).set_bold(true))?;
+                        write!(stream, "{:02x}", byte)?;
+                        stream.reset()?;
+                    }
+                    Some(byte) => write!(stream, "{:02x}", byte)?,
+                    None => write!(stream, "  ")?,
+                }
+                if i % 4 == 3 {
+                    write!(stream, " ")?;
+                }
+            }
+            let ascii = side
+                .get(row..(row + 16).min(side.len()))
+                .unwrap_or_default()
+                .iter()
+                .map(|&c| match c {
+                    0x20..=0x7e => c as char,
+                    _ => '.',
+                })
+                .collect::<String>();
+            writeln!(stream, "{}", ascii)?;
+        }
+    }
+    stream.reset()?;
+
+    Ok(changed)
+}
+
+/// The total size of the defined functions and objects by name, from `.symtab` or from
+/// `.dynsym` when the file is stripped. Local symbols sharing a name, like the statics of
+/// different files, are added together.
+fn symbol_sizes(elf: &FileData) -> io::Result<BTreeMap<String, u64>> {
+    let tables = elf.table_symbols()?;
+    let table = tables
+        .iter()
+        .find(|(name, _, _)| name == ".symtab")
+        .or_else(|| tables.first());
+
+    let mut sizes = BTreeMap::new();
+    if let Some((_, strtab, syms)) = table {
+        for sym in syms.iter() {
+            // Absolute symbols, like the ones naming version definitions, take no space
+            if matches!(sym.shndx(), 0 | 0xfff1)
+                || !matches!(
+                    sym.symbol_type(),
+                    Some(SymbolType::Func | SymbolType::Object | SymbolType::Tls)
+                )
+            {
+                continue;
+            }
+            let name = string_at(strtab, sym.name() as usize);
+            if !name.is_empty() {
+                *sizes.entry(name).or_default() += sym.size();
+            }
+        }
+    }
+
+    Ok(sizes)
+}
+
+#[cfg(feature = "demangle")]
+fn display_name(name: &str) -> String {
+    demangle(name).map_or_else(|| name.to_string(), |(demangled, _)| demangled)
+}
+
+#[cfg(not(feature = "demangle"))]
+fn display_name(name: &str) -> String {
+    name.to_string()
+}
+
+/// Prints the symbols added, removed or resized between `old` and `new`, the largest size
+/// change first, and the total. Returns the number of symbols that differ.
+fn symbol_diff(
+    stream: &mut StandardStream,
+    old: &BTreeMap<String, u64>,
+    new: &BTreeMap<String, u64>,
+) -> io::Result<usize> {
+    let mut changes = old
+        .keys()
+        .chain(new.keys().filter(|name| !old.contains_key(*name)))
+        .map(|name| (name, old.get(name).copied(), new.get(name).copied()))
+        .filter(|(_, old, new)| old != new)
+        .collect::<Vec<_>>();
+    let delta =
+        |old: Option<u64>, new: Option<u64>| new.unwrap_or(0) as i64 - old.unwrap_or(0) as i64;
+    changes.sort_by_key(|&(_, old, new)| std::cmp::Reverse(delta(old, new).abs()));
+
+    let count = |added: bool, removed: bool| {
+        changes
+            .iter()
+            .filter(|(_, old, new)| old.is_none() == added && new.is_none() == removed)
+            .count()
+    };
+    let total = changes
+        .iter()
+        .map(|&(_, old, new)| delta(old, new))
+        .sum::<i64>();
+    writeln!(
+        stream,
+        "\nSymbol diff: {} added, {} removed, {} resized, {:+} bytes in total",
+        count(true, false),
+        count(false, true),
+        count(false, false),
+        total
+    )?;
+    if changes.is_empty() {
+        return Ok(0);
+    }
+
+    writeln!(
+        stream,
+        "  {:>10}  {:>10}  {:>10}  Symbol",
+        "Delta", "Old size", "New size"
+    )?;
+    let size = |size: Option<u64>| size.map_or("-".to_string(), |size| size.to_string());
+    for &(name, old, new) in &changes {
+        let color = match (old, new) {
+            (None, _) => Color::Green,
+            (_, None) => Color::Red,
+            _ => Color::Yellow,
+        };
+        stream.reset()?;
+        write!(
+            stream,
+            "  {:>+10}  {:>10}  {:>10}  ",
+            delta(old, new),
+            size(old),
+            size(new)
+        )?;
+        stream.set_color(ColorSpec::new().set_fg(Some(color)))?;
+        writeln!(stream, "{}", display_name(name))?;
+    }
+    stream.reset()?;
+
+    Ok(changes.len())
+}
+
+/// Compares the exports of two versions of a shared object the way programs linked against
+/// the old one see them, printing breaking changes before compatible ones. Returns the
+/// number of breaking changes.
+fn abi_diff(stream: &mut StandardStream, old: &ExportList, new: &ExportList) -> io::Result<usize> {
+    let is_data = |export: &Export| {
+        matches!(
+            export.symbol_type,
+            Some(SymbolType::Object | SymbolType::Tls | SymbolType::Common)
+        )
+    };
+    let type_name = |export: &Export| {
+        export
+            .symbol_type
+            .map_or("?".to_string(), |symbol_type| symbol_type.to_string())
+    };
+
+    let mut breaking = Vec::new();
+    let mut compatible = Vec::new();
+    for export in old.exports() {
+        // Unversioned references bind to whatever version is the default now
+        let found = new.exports().find(|new| {
+            new.name == export.name
+                && match &export.version {
+                    Some(_) => new.version == export.version,
+                    None => !new.hidden,
+                }
+        });
+        let new = match found {
+            Some(new) => new,
+            None => {
+                breaking.push(("removed", export.versioned_name()));
+                continue;
+            }
+        };
+
+        if type_name(export) != type_name(new) {
+            breaking.push((
+                "type changed",
+                format!(
+                    "{} ({} -> {})",
+                    export.versioned_name(),
+                    type_name(export),
+                    type_name(new)
+                ),
+            ));
+        } else if is_data(export) && export.size != new.size {
+            // Copy relocations reserve the old size in every executable using the object
+            breaking.push((
+                "size changed",
+                format!(
+                    "{} ({} -> {} bytes)",
+                    export.versioned_name(),
+                    export.size,
+                    new.size
+                ),
+            ));
+        }
+        if export.version.is_none() && new.version.is_some() {
+            compatible.push(("versioned", new.versioned_name()));
+        }
+        if !export.weak && new.weak {
+            compatible.push(("now weak", new.versioned_name()));
+        }
+    }
+    for export in new.exports() {
+        if !old
+            .exports()
+            .any(|old| old.name == export.name && old.version == export.version)
+            && !(export.version.is_some()
+                && old
+                    .exports()
+                    .any(|old| old.name == export.name && old.version.is_none()))
+        {
+            compatible.push(("added", export.versioned_name()));
+        }
+    }
+
+    writeln!(
+        stream,
+        "\nABI diff: {} breaking {}, {} compatible",
+        breaking.len(),
+        if breaking.len() == 1 {
+            "change"
+        } else {
+            "changes"
+        },
+        compatible.len()
+    )?;
+    for (heading, changes, color) in [
+        ("Breaking", &breaking, Color::Red),
+        ("Compatible", &compatible, Color::Green),
+    ] {
+        if changes.is_empty() {
+            continue;
+        }
+        stream.reset()?;
+        writeln!(stream, "  {}:", heading)?;
+        for (kind, symbol) in changes {
+            stream.reset()?;
+            write!(stream, "    {:<13} ", kind)?;
+            stream.set_color(ColorSpec::new().set_fg(Some(color)))?;
+            writeln!(stream, "{}", symbol)?;
+        }
+    }
+    stream.reset()?;
+
+    Ok(breaking.len())
+}
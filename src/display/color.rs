@@ -0,0 +1,29 @@
+use termcolor::Color;
+
+/// The colors of the text output, each role can be any color termcolor parses: a name
+/// such as `cyan`, an ANSI 256 index or an `r,g,b` triple
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    /// View titles such as `ELF Header` and `Section Headers`
+    pub heading: Color,
+    /// File paths and the brackets around indices
+    pub accent: Color,
+    /// Attribute names and column headers
+    pub label: Color,
+    /// Highlighted names such as note owners and symbol table names
+    pub name: Color,
+    /// Everything else
+    pub text: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            heading: Color::Yellow,
+            accent: Color::Blue,
+            label: Color::Green,
+            name: Color::Magenta,
+            text: Color::White,
+        }
+    }
+}
@@ -1,5 +1,12 @@
 mod color;
 mod list;
+mod render;
 mod table;
+#[cfg(feature = "json")]
+mod tree;
 
+pub use color::Theme;
+pub use render::{Renderer, SymBase, TextRenderer};
 pub use table::{Entry, Table};
+#[cfg(feature = "json")]
+pub use tree::{TreeFormat, TreeRenderer, Value};
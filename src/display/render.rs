@@ -0,0 +1,522 @@
+use std::io::{self, Write};
+
+use termcolor::{Color, ColorSpec, StandardStream, WriteColor};
+
+use super::{
+    color::Theme,
+    table::{column_width, terminal_width, truncate, TRUNCATION_MARKER},
+};
+
+use crate::elf::{
+    diag::Diagnostic,
+    hdr::{ElfClass, Endian},
+    note::{Note, NoteContainer, NT_GNU_PROPERTY_TYPE_0},
+    notedecode::{decode_note, note_type_name},
+    shdr::{flag_key, split_lto_section_name, ElfShdr, LTO_SECTION_PREFIX},
+    sym::ElfSym,
+    ElfHdr, EI_OSABI, ELFVER,
+};
+
+/// Receives the views of a file as they are walked, one call per header, row or note.
+///
+/// `main` drives a `Box<dyn Renderer>` so the output format is picked at runtime, library
+/// users can implement it to produce their own formats.
+pub trait Renderer {
+    fn begin_file(&mut self, path: &str) -> io::Result<()>;
+
+    /// Shows the addresses of loaded sections and defined symbols rebased by `bias`, for
+    /// a file mapped somewhere other than its link-time addresses. Holds until changed.
+    fn set_load_bias(&mut self, _bias: u64) {}
+
+    /// Describes an `ET_DYN` file header as a position independent executable rather than
+    /// a shared object. Holds until changed.
+    fn set_position_independent(&mut self, _pie: bool) {}
+
+    fn file_header(&mut self, hdr: &ElfHdr) -> io::Result<()>;
+
+    /// Starts the section header table, `hdr` decides how the OS and processor specific
+    /// flags read
+    fn begin_sections(&mut self, hdr: &ElfHdr, count: usize, offset: u64) -> io::Result<()>;
+    fn section_row(&mut self, index: usize, name: &str, shdr: &ElfShdr) -> io::Result<()>;
+    fn end_sections(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn begin_symbols(&mut self, table: &str, count: usize, class: ElfClass) -> io::Result<()>;
+    fn symbol_row(&mut self, index: usize, name: &str, sym: &ElfSym) -> io::Result<()>;
+    fn end_symbols(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Starts the notes of one section or segment, called once per container
+    fn begin_notes(&mut self, container: &NoteContainer, count: usize) -> io::Result<()>;
+    /// One note of the file whose class and `e_machine` are `class` and `machine`
+    fn note(
+        &mut self,
+        note: &Note,
+        endian: Endian,
+        class: ElfClass,
+        machine: u16,
+    ) -> io::Result<()>;
+    fn end_notes(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// The problems found while reading the file, called before [`Renderer::end_file`]
+    fn diagnostics(&mut self, diagnostics: &[Diagnostic]) -> io::Result<()>;
+
+    fn end_file(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+pub fn class_name(class: Option<ElfClass>) -> &'static str {
+    match class {
+        Some(ElfClass::ElfClass32) => "ELF32",
+        Some(ElfClass::ElfClass64) => "ELF64",
+        _ => "Unknown",
+    }
+}
+
+pub fn data_name(endian: Option<Endian>) -> &'static str {
+    match endian {
+        Some(Endian::Big) => "2's complement, big endian",
+        Some(Endian::Little) => "2's complement, little endian",
+        _ => "Unknown",
+    }
+}
+
+pub fn section_index_name(shndx: u16) -> String {
+    match shndx {
+        0 => "UND".to_string(),
+        65521 => "ABS".to_string(),
+        i => i.to_string(),
+    }
+}
+
+/// Cuts a section name down to `width` characters. GCC LTO sections all end in the same
+/// object ID, so that goes first to keep the part telling them apart.
+fn section_name_column(name: &str, width: usize) -> String {
+    if let Some((stream, Some(_))) = split_lto_section_name(name) {
+        let short = format!("{}{}.{}", LTO_SECTION_PREFIX, stream, TRUNCATION_MARKER);
+        if name.chars().count() > width && short.chars().count() <= width {
+            return short;
+        }
+    }
+    truncate(name, width)
+}
+
+/// The numeric base symbol values are printed in, chosen with `--sym-base`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SymBase {
+    #[default]
+    Hex,
+    Decimal,
+    Octal,
+}
+
+impl SymBase {
+    pub fn from_radix(radix: u32) -> Option<Self> {
+        match radix {
+            16 => Some(Self::Hex),
+            10 => Some(Self::Decimal),
+            8 => Some(Self::Octal),
+            _ => None,
+        }
+    }
+
+    /// Formats `value` for a table column of `width` digits, hex zero padded like
+    /// addresses and the other bases right aligned
+    pub fn column(self, value: u64, width: usize) -> String {
+        match self {
+            Self::Hex => format!("{:0width$x}", value, width = width),
+            Self::Decimal => format!("{:>width$}", value, width = width),
+            Self::Octal => format!("{:>width$o}", value, width = width),
+        }
+    }
+
+    /// Formats `value` on its own, with a `0x` or `0` prefix outside of decimal
+    pub fn prefixed(self, value: u64) -> String {
+        match self {
+            Self::Hex => format!("0x{:x}", value),
+            Self::Decimal => value.to_string(),
+            Self::Octal => format!("0{:o}", value),
+        }
+    }
+}
+
+/// The colored human readable output
+pub struct TextRenderer {
+    stream: StandardStream,
+    path: String,
+    should_pad: bool,
+    section_pad: usize,
+    sections: Vec<(usize, String, ElfShdr)>,
+    /// `e_machine` and `EI_OSABI` of the file whose sections are being listed
+    flag_target: (u16, u8),
+    banners: bool,
+    theme: Theme,
+    sym_base: SymBase,
+    value_width: usize,
+    truncate: bool,
+    bias: u64,
+    pie: bool,
+}
+
+impl TextRenderer {
+    pub fn new(stream: StandardStream) -> Self {
+        Self {
+            stream,
+            path: String::new(),
+            should_pad: false,
+            section_pad: 1,
+            sections: Vec::new(),
+            flag_target: (0, 0),
+            banners: false,
+            theme: Theme::default(),
+            sym_base: SymBase::default(),
+            value_width: 16,
+            truncate: true,
+            bias: 0,
+            pie: false,
+        }
+    }
+
+    /// Prints a `File: <path>` banner at the start of every file, for multi-file runs
+    pub fn with_banners(mut self, banners: bool) -> Self {
+        self.banners = banners;
+        self
+    }
+
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    pub fn with_sym_base(mut self, sym_base: SymBase) -> Self {
+        self.sym_base = sym_base;
+        self
+    }
+
+    /// Whether names wider than the terminal are cut, otherwise their column grows to fit
+    /// the longest one and lines wrap
+    pub fn with_truncation(mut self, truncate: bool) -> Self {
+        self.truncate = truncate;
+        self
+    }
+
+    fn color(&mut self, color: Color) -> io::Result<()> {
+        self.stream.set_color(ColorSpec::new().set_fg(Some(color)))
+    }
+
+    fn attr(&mut self, attr: &str, value: &str) -> io::Result<()> {
+        self.color(self.theme.label)?;
+        write!(self.stream, "{}", attr)?;
+        self.color(self.theme.text)?;
+        writeln!(
+            self.stream,
+            ":{value:>pad$}",
+            pad = 36 - attr.len() + value.len(),
+            value = value
+        )
+    }
+}
+
+impl Renderer for TextRenderer {
+    fn begin_file(&mut self, path: &str) -> io::Result<()> {
+        self.path = path.to_string();
+        if self.banners {
+            self.color(self.theme.text)?;
+            write!(self.stream, "\nFile: ")?;
+            self.color(self.theme.accent)?;
+            writeln!(self.stream, "{}", path)?;
+            self.color(self.theme.text)?;
+        }
+        Ok(())
+    }
+
+    /// Printed on stderr like readelf does, so they stay out of the output proper
+    fn diagnostics(&mut self, diagnostics: &[Diagnostic]) -> io::Result<()> {
+        for diagnostic in diagnostics {
+            eprintln!(
+                "readelf-rs: {}: '{}': {}",
+                diagnostic.severity, self.path, diagnostic.message
+            );
+        }
+        Ok(())
+    }
+
+    fn set_load_bias(&mut self, bias: u64) {
+        self.bias = bias;
+    }
+
+    fn set_position_independent(&mut self, pie: bool) {
+        self.pie = pie;
+    }
+
+    fn file_header(&mut self, hdr: &ElfHdr) -> io::Result<()> {
+        self.color(self.theme.heading)?;
+        write!(self.stream, "ELF Header")?;
+        self.color(self.theme.accent)?;
+        writeln!(self.stream, " {}", self.path)?;
+        self.color(self.theme.name)?;
+        write!(self.stream, "Magic")?;
+        self.color(self.theme.text)?;
+        write!(self.stream, ":\t\t")?;
+        for i in hdr.ident() {
+            write!(self.stream, " {:02x}", i)?;
+        }
+        writeln!(self.stream)?;
+
+        self.attr("Class", class_name(hdr.class()))?;
+        self.attr("Data", data_name(hdr.endian()))?;
+        self.attr(
+            "Version",
+            &format!(
+                "{} {}",
+                hdr.version(),
+                match hdr.version() {
+                    ELFVER => "(current version)",
+                    _ => "",
+                }
+            ),
+        )?;
+        self.attr("OS/ABI", &hdr.os_abi().to_string())?;
+        self.attr("ABI Version", &hdr.abi_version().to_string())?;
+        self.attr("Type", &hdr.describe_type(self.pie))?;
+        self.attr("Machine", &hdr.machine().to_string())?;
+        self.attr("Entry point addresss", &format!("0x{:x}", hdr.entry()))?;
+        self.attr(
+            "Start of program headers",
+            &format!("{} (bytes into file)", hdr.phstart()),
+        )?;
+        self.attr(
+            "Start of section headers",
+            &format!("{} (bytes into file)", hdr.shstart()),
+        )?;
+        self.attr("Flags", &hdr.flags().to_string())?;
+        self.attr(
+            "Size of this header",
+            &format!("{} (bytes)", hdr.header_size()),
+        )?;
+        self.attr(
+            "Size of program headers",
+            &format!("{} (bytes)", hdr.program_headers_size()),
+        )?;
+        self.attr("Number of program headers", &hdr.nheaders().to_string())?;
+        self.attr(
+            "Size of program headers",
+            &format!("{} (bytes)", hdr.section_size()),
+        )?;
+        self.attr(
+            "Number of section headers",
+            &hdr.nsection_headers().to_string(),
+        )?;
+        self.attr(
+            "Section header string table index",
+            &hdr.table_index().to_string(),
+        )?;
+
+        self.should_pad = true;
+        Ok(())
+    }
+
+    fn begin_sections(&mut self, hdr: &ElfHdr, count: usize, offset: u64) -> io::Result<()> {
+        if self.should_pad {
+            writeln!(self.stream)?;
+        }
+        self.section_pad = count.log10() as usize + 1;
+        self.sections.clear();
+        self.flag_target = (hdr.machine(), hdr.e_ident[EI_OSABI]);
+
+        self.color(self.theme.text)?;
+        writeln!(
+            self.stream,
+            "There are {} section headers, starting at offset 0x{:x}",
+            count, offset
+        )
+    }
+
+    fn section_row(&mut self, index: usize, name: &str, shdr: &ElfShdr) -> io::Result<()> {
+        self.sections.push((index, name.to_string(), *shdr));
+        Ok(())
+    }
+
+    /// Lays the buffered rows out once every name is known, so the Name column can grow
+    /// into whatever the terminal has left after the fixed columns
+    fn end_sections(&mut self) -> io::Result<()> {
+        let fixed = 3 + self.section_pad + 2 + 2 + 1 + 18 + 16 + 2 + 8;
+        let available = match self.truncate {
+            true => terminal_width().unwrap_or(80).saturating_sub(fixed),
+            false => usize::MAX,
+        };
+        let width = column_width(
+            self.sections.iter().map(|(_, name, _)| name.as_str()),
+            16,
+            available,
+        );
+
+        self.color(self.theme.heading)?;
+        write!(self.stream, "Section Headers\n  ")?;
+
+        self.color(self.theme.accent)?;
+        write!(self.stream, "[")?;
+        self.color(self.theme.text)?;
+        write!(self.stream, "Nr")?;
+        self.color(self.theme.accent)?;
+        write!(self.stream, "]")?;
+
+        self.color(self.theme.label)?;
+        write!(self.stream, " {:w$}", "Name", w = width + 2)?;
+        write!(self.stream, " {:17}", "Type")?;
+        write!(self.stream, " {:17}", "Address")?;
+        write!(self.stream, " {:16}\n      ", "Offset")?;
+        write!(self.stream, " {:18}", "Size")?;
+        write!(self.stream, " {:17}", "EntSize")?;
+        write!(self.stream, " {:18}", "Flags  Link  Info")?;
+        write!(self.stream, " {:18}", "Align")?;
+
+        for (index, name, shdr) in std::mem::take(&mut self.sections) {
+            self.color(self.theme.accent)?;
+            write!(self.stream, "\n  [")?;
+            self.color(self.theme.text)?;
+            write!(self.stream, "{i:pad$}", i = index, pad = self.section_pad)?;
+            self.color(self.theme.accent)?;
+            write!(self.stream, "] ")?;
+            self.color(self.theme.text)?;
+
+            write!(
+                self.stream,
+                "{:w$}",
+                section_name_column(&name, width),
+                w = width + 2
+            )?;
+            write!(self.stream, " {:17} ", shdr.type_name())?;
+            write!(self.stream, "{:016x}", shdr.rebased_addr(self.bias))?;
+            writeln!(self.stream, "  {:08x}", shdr.offset())?;
+            write!(self.stream, "{:7}{:016x}", "", shdr.size())?;
+            write!(self.stream, "   {:017x}", shdr.entsize())?;
+            let (machine, os_abi) = self.flag_target;
+            write!(self.stream, " {:^8}", shdr.flag_letters(machine, os_abi))?;
+            write!(self.stream, "{:>3}", shdr.link())?;
+            write!(self.stream, "{:>6}", shdr.info())?;
+            write!(self.stream, "{:>6}", shdr.addralign())?;
+        }
+
+        let (machine, os_abi) = self.flag_target;
+        writeln!(self.stream, "\n{}", flag_key(machine, os_abi))
+    }
+
+    fn begin_symbols(&mut self, table: &str, count: usize, class: ElfClass) -> io::Result<()> {
+        self.color(self.theme.text)?;
+        write!(self.stream, "Symbol table")?;
+        self.color(self.theme.name)?;
+        write!(self.stream, " {} ", table)?;
+        self.color(self.theme.text)?;
+        write!(self.stream, "contains")?;
+        self.color(self.theme.label)?;
+        write!(self.stream, " {} ", count)?;
+        self.color(self.theme.text)?;
+        writeln!(self.stream, "entries")?;
+
+        self.value_width = match class {
+            ElfClass::ElfClass64 => 16,
+            _ => 8,
+        };
+        if class == ElfClass::ElfClass64 {
+            writeln!(
+                self.stream,
+                "   Num:    Value          Size Type    Bind   Vis      Ndx Name"
+            )
+        } else {
+            writeln!(
+                self.stream,
+                "   Num:    Value  Size Type    Bind   Vis      Ndx Name"
+            )
+        }
+    }
+
+    fn symbol_row(&mut self, index: usize, name: &str, sym: &ElfSym) -> io::Result<()> {
+        writeln!(
+            self.stream,
+            "{:>6}: {}  {:>4} {:7} {:6} {} {:>3} {}",
+            index,
+            self.sym_base
+                .column(sym.rebased_value(self.bias), self.value_width),
+            sym.size(),
+            sym.symbol_type().map_or_else(
+                || format!("<unknown>: {}", sym.info() & 0xf),
+                |t| t.to_string()
+            ),
+            sym.binding().map_or_else(
+                || format!("<unknown>: {}", sym.info() >> 4),
+                |b| b.to_string()
+            ),
+            sym.visibility().unwrap(),
+            section_index_name(sym.shndx()),
+            name
+        )
+    }
+
+    fn end_symbols(&mut self) -> io::Result<()> {
+        writeln!(self.stream, "\n\n")
+    }
+
+    fn begin_notes(&mut self, container: &NoteContainer, _count: usize) -> io::Result<()> {
+        self.color(self.theme.text)?;
+        match container {
+            NoteContainer::Section(name) => {
+                write!(self.stream, "\nDisplaying notes found in: ")?;
+                self.color(self.theme.name)?;
+                writeln!(self.stream, "{}", name)?;
+            }
+            NoteContainer::Segment { offset, size } => writeln!(
+                self.stream,
+                "\nDisplaying notes found at file offset 0x{:08x} with length 0x{:08x}:",
+                offset, size
+            )?,
+        }
+        self.color(self.theme.text)?;
+        writeln!(
+            self.stream,
+            "  {:<20} {:<10}\tDescription",
+            "Owner", "Data size"
+        )
+    }
+
+    fn note(
+        &mut self,
+        note: &Note,
+        endian: Endian,
+        class: ElfClass,
+        machine: u16,
+    ) -> io::Result<()> {
+        self.color(self.theme.name)?;
+        write!(self.stream, "  {:<20}", note.name)?;
+        self.color(self.theme.text)?;
+        writeln!(
+            self.stream,
+            " 0x{:08x}\t{}",
+            note.desc.len(),
+            note_type_name(note)
+        )?;
+
+        // binutils indents the property list two columns deeper than other notes
+        let indent = match note.is_gnu(NT_GNU_PROPERTY_TYPE_0) {
+            true => 6,
+            false => 4,
+        };
+        for field in decode_note(note, class, endian, machine) {
+            writeln!(
+                self.stream,
+                "{:indent$}{}: {}",
+                "",
+                field.label,
+                field.value,
+                indent = indent
+            )?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,7 +1,50 @@
+use std::env;
 use std::io;
 use std::io::Write;
 
 use termcolor::{Color, ColorSpec, StandardStream, WriteColor};
+use terminal_size::{terminal_size, Width};
+
+/// Marker readelf leaves in names that do not fit their column
+pub const TRUNCATION_MARKER: &str = "[...]";
+
+/// Width of the terminal stdout is attached to, or `COLUMNS` when it is not a terminal
+pub fn terminal_width() -> Option<usize> {
+    terminal_size()
+        .map(|(Width(width), _)| width as usize)
+        .or_else(|| env::var("COLUMNS").ok()?.parse().ok())
+}
+
+/// Width of a column holding `values`: the longest value, but no less than `min` and no
+/// more than `available` unless `min` itself does not fit
+pub fn column_width<'a, I: IntoIterator<Item = &'a str>>(
+    values: I,
+    min: usize,
+    available: usize,
+) -> usize {
+    let longest = values
+        .into_iter()
+        .map(|value| value.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    longest.min(available).max(min)
+}
+
+/// Cuts `s` down to `width` characters, ending it with [`TRUNCATION_MARKER`] when cut
+pub fn truncate(s: &str, width: usize) -> String {
+    if s.chars().count() <= width {
+        return s.to_string();
+    }
+    if width <= TRUNCATION_MARKER.len() {
+        return s.chars().take(width).collect();
+    }
+
+    s.chars()
+        .take(width - TRUNCATION_MARKER.len())
+        .chain(TRUNCATION_MARKER.chars())
+        .collect()
+}
 
 // Consider using a colored buffer?
 pub trait Entry {
@@ -10,6 +53,7 @@ pub trait Entry {
 }
 
 pub struct Table<E> {
+    #[allow(dead_code)]
     columns: Vec<String>,
     entries: Vec<E>,
 }
@@ -75,7 +119,7 @@ mod test {
 
     use crate::elf::shdr::ElfShdr;
 
-    use super::Entry;
+    use super::{column_width, truncate, Entry};
 
     impl Entry for ElfShdr {
         fn header(&self) -> String {
@@ -91,4 +135,14 @@ mod test {
 
     #[test]
     fn foo() {}
+
+    #[test]
+    fn fits_names_to_the_column() {
+        let names = [".text", ".note.gnu.build-id"];
+        assert_eq!(column_width(names, 16, 80), 18);
+        assert_eq!(column_width(names, 16, 17), 17);
+        assert_eq!(column_width(names, 16, 4), 16);
+        assert_eq!(truncate(".note.gnu.build-id", 16), ".note.gnu.b[...]");
+        assert_eq!(truncate(".text", 16), ".text");
+    }
 }
@@ -0,0 +1,406 @@
+use std::io::{self, Write};
+
+use crate::elf::{
+    diag::Diagnostic,
+    hdr::{ElfClass, Endian},
+    note::{Note, NoteContainer},
+    notedecode::{decode_note, note_type_name},
+    shdr::ElfShdr,
+    sym::ElfSym,
+    ElfHdr, EI_OSABI,
+};
+
+use super::render::{class_name, data_name, section_index_name, Renderer, SymBase};
+
+/// A JSON-like document tree, built up per file and serialized once the file is done
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Bool(bool),
+    Number(u64),
+    String(String),
+    List(Vec<Value>),
+    Map(Vec<(String, Value)>),
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Value::String(s.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Self {
+        Value::String(s)
+    }
+}
+
+impl From<u64> for Value {
+    fn from(n: u64) -> Self {
+        Value::Number(n)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(b: bool) -> Self {
+        Value::Bool(b)
+    }
+}
+
+impl From<&Diagnostic> for Value {
+    fn from(diagnostic: &Diagnostic) -> Self {
+        let mut fields = vec![
+            ("severity", diagnostic.severity.to_string().into()),
+            ("rule", diagnostic.rule.into()),
+        ];
+        if let Some(offset) = diagnostic.offset {
+            fields.push(("offset", offset.into()));
+        }
+        fields.push(("message", diagnostic.message.as_str().into()));
+        map(fields)
+    }
+}
+
+fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len() + 2);
+    escaped.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+impl Value {
+    /// Compact single line JSON
+    pub fn to_json(&self) -> String {
+        match self {
+            Value::Bool(b) => b.to_string(),
+            Value::Number(n) => n.to_string(),
+            Value::String(s) => escape(s),
+            Value::List(items) => format!(
+                "[{}]",
+                items
+                    .iter()
+                    .map(Value::to_json)
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ),
+            Value::Map(fields) => format!(
+                "{{{}}}",
+                fields
+                    .iter()
+                    .map(|(key, value)| format!("{}:{}", escape(key), value.to_json()))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ),
+        }
+    }
+
+    /// Block style YAML, strings are always double quoted so no value needs further escaping
+    pub fn to_yaml(&self, indent: usize) -> String {
+        let pad = " ".repeat(indent);
+        match self {
+            Value::List(items) if items.is_empty() => " []".to_string(),
+            Value::Map(fields) if fields.is_empty() => " {}".to_string(),
+            Value::List(items) => items
+                .iter()
+                .map(|item| match item {
+                    Value::Map(_) | Value::List(_) => {
+                        let nested = item.to_yaml(indent + 2);
+                        format!("\n{}- {}", pad, nested.trim_start())
+                    }
+                    scalar => format!("\n{}-{}", pad, scalar.to_yaml(indent + 2)),
+                })
+                .collect(),
+            Value::Map(fields) => fields
+                .iter()
+                .map(|(key, value)| format!("\n{}{}:{}", pad, key, value.to_yaml(indent + 2)))
+                .collect(),
+            scalar => format!(" {}", scalar.to_json()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TreeFormat {
+    /// One JSON object per file and line
+    Json,
+    /// One YAML document per file
+    Yaml,
+}
+
+/// Collects each file into a [`Value`] and writes it as JSON or YAML at `end_file`
+pub struct TreeRenderer<W: Write> {
+    writer: W,
+    format: TreeFormat,
+    file: Vec<(String, Value)>,
+    rows: Vec<Value>,
+    tables: Vec<Value>,
+    table: String,
+    flag_target: (u16, u8),
+    note_containers: Vec<Value>,
+    container: Vec<(&'static str, Value)>,
+    sym_base: Option<SymBase>,
+    bias: u64,
+    pie: bool,
+}
+
+impl<W: Write> TreeRenderer<W> {
+    pub fn new(writer: W, format: TreeFormat) -> Self {
+        Self {
+            writer,
+            format,
+            file: Vec::new(),
+            rows: Vec::new(),
+            tables: Vec::new(),
+            table: String::new(),
+            flag_target: (0, 0),
+            note_containers: Vec::new(),
+            container: Vec::new(),
+            sym_base: None,
+            bias: 0,
+            pie: false,
+        }
+    }
+
+    /// Writes symbol values as strings in `sym_base` instead of plain numbers, decimal
+    /// stays a number
+    pub fn with_sym_base(mut self, sym_base: SymBase) -> Self {
+        self.sym_base = Some(sym_base);
+        self
+    }
+
+    fn field<V: Into<Value>>(&mut self, key: &str, value: V) {
+        self.file.push((key.to_string(), value.into()));
+    }
+
+    fn flush_rows(&mut self, key: &str) {
+        let rows = Value::List(std::mem::take(&mut self.rows));
+        self.field(key, rows);
+    }
+}
+
+fn map(fields: Vec<(&str, Value)>) -> Value {
+    Value::Map(
+        fields
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value))
+            .collect(),
+    )
+}
+
+impl<W: Write> Renderer for TreeRenderer<W> {
+    fn begin_file(&mut self, path: &str) -> io::Result<()> {
+        self.file.clear();
+        self.field("file", path);
+        Ok(())
+    }
+
+    fn diagnostics(&mut self, diagnostics: &[Diagnostic]) -> io::Result<()> {
+        if !diagnostics.is_empty() {
+            let list = diagnostics.iter().map(Value::from).collect();
+            self.field("diagnostics", Value::List(list));
+        }
+        Ok(())
+    }
+
+    fn set_load_bias(&mut self, bias: u64) {
+        self.bias = bias;
+        if bias != 0 {
+            self.field("load_bias", bias);
+        }
+    }
+
+    fn set_position_independent(&mut self, pie: bool) {
+        self.pie = pie;
+    }
+
+    fn file_header(&mut self, hdr: &ElfHdr) -> io::Result<()> {
+        let header = map(vec![
+            ("class", class_name(hdr.class()).into()),
+            ("data", data_name(hdr.endian()).into()),
+            ("version", (hdr.version() as u64).into()),
+            ("os_abi", hdr.os_abi().to_string().into()),
+            ("abi_version", (hdr.abi_version() as u64).into()),
+            ("type", hdr.describe_type(self.pie).into()),
+            ("machine", (hdr.machine() as u64).into()),
+            ("entry", hdr.entry().into()),
+            ("phoff", hdr.phstart().into()),
+            ("shoff", hdr.shstart().into()),
+            ("flags", (hdr.flags() as u64).into()),
+            ("ehsize", (hdr.header_size() as u64).into()),
+            ("phentsize", (hdr.program_headers_size() as u64).into()),
+            ("phnum", (hdr.nheaders() as u64).into()),
+            ("shentsize", (hdr.section_size() as u64).into()),
+            ("shnum", (hdr.nsection_headers() as u64).into()),
+            ("shstrndx", (hdr.table_index() as u64).into()),
+        ]);
+        self.field("header", header);
+        Ok(())
+    }
+
+    fn begin_sections(&mut self, hdr: &ElfHdr, _count: usize, _offset: u64) -> io::Result<()> {
+        self.rows.clear();
+        self.flag_target = (hdr.machine(), hdr.e_ident[EI_OSABI]);
+        Ok(())
+    }
+
+    fn section_row(&mut self, index: usize, name: &str, shdr: &ElfShdr) -> io::Result<()> {
+        self.rows.push(map(vec![
+            ("index", (index as u64).into()),
+            ("name", name.into()),
+            ("type", shdr.type_name().into()),
+            ("address", shdr.rebased_addr(self.bias).into()),
+            ("offset", shdr.offset().into()),
+            ("size", shdr.size().into()),
+            ("entsize", shdr.entsize().into()),
+            (
+                "flags",
+                shdr.flag_letters(self.flag_target.0, self.flag_target.1)
+                    .into(),
+            ),
+            ("link", (shdr.link() as u64).into()),
+            ("info", (shdr.info() as u64).into()),
+            ("align", shdr.addralign().into()),
+        ]));
+        Ok(())
+    }
+
+    fn end_sections(&mut self) -> io::Result<()> {
+        self.flush_rows("sections");
+        Ok(())
+    }
+
+    fn begin_symbols(&mut self, table: &str, _count: usize, _class: ElfClass) -> io::Result<()> {
+        self.rows.clear();
+        self.table = table.to_string();
+        Ok(())
+    }
+
+    fn symbol_row(&mut self, index: usize, name: &str, sym: &ElfSym) -> io::Result<()> {
+        let display = |name: Option<String>| name.unwrap_or_else(|| "UNKNOWN".to_string());
+        self.rows.push(map(vec![
+            ("index", (index as u64).into()),
+            (
+                "value",
+                match self.sym_base {
+                    None | Some(SymBase::Decimal) => sym.rebased_value(self.bias).into(),
+                    Some(base) => base.prefixed(sym.rebased_value(self.bias)).into(),
+                },
+            ),
+            ("size", sym.size().into()),
+            (
+                "type",
+                display(sym.symbol_type().map(|t| t.to_string())).into(),
+            ),
+            ("bind", display(sym.binding().map(|b| b.to_string())).into()),
+            (
+                "visibility",
+                display(sym.visibility().map(|v| v.to_string())).into(),
+            ),
+            ("ndx", section_index_name(sym.shndx()).into()),
+            ("name", name.into()),
+        ]));
+        Ok(())
+    }
+
+    fn end_symbols(&mut self) -> io::Result<()> {
+        let entries = Value::List(std::mem::take(&mut self.rows));
+        self.tables.push(map(vec![
+            ("table", std::mem::take(&mut self.table).into()),
+            ("entries", entries),
+        ]));
+        Ok(())
+    }
+
+    fn begin_notes(&mut self, container: &NoteContainer, _count: usize) -> io::Result<()> {
+        self.rows.clear();
+        self.container = match container {
+            NoteContainer::Section(name) => vec![("section", name.as_str().into())],
+            NoteContainer::Segment { offset, size } => {
+                vec![("offset", (*offset).into()), ("size", (*size).into())]
+            }
+        };
+        Ok(())
+    }
+
+    fn note(
+        &mut self,
+        note: &Note,
+        endian: Endian,
+        class: ElfClass,
+        machine: u16,
+    ) -> io::Result<()> {
+        let decoded = decode_note(note, class, endian, machine);
+        let mut fields = vec![
+            ("owner", note.name.as_str().into()),
+            ("type", (note.ntype as u64).into()),
+            ("description", note_type_name(note).into()),
+            ("size", (note.desc.len() as u64).into()),
+        ];
+        for field in &decoded {
+            fields.push((field.key.as_str(), field.value.as_str().into()));
+        }
+        self.rows.push(map(fields));
+        Ok(())
+    }
+
+    fn end_notes(&mut self) -> io::Result<()> {
+        let mut fields = std::mem::take(&mut self.container);
+        fields.push(("entries", Value::List(std::mem::take(&mut self.rows))));
+        self.note_containers.push(map(fields));
+        Ok(())
+    }
+
+    fn end_file(&mut self) -> io::Result<()> {
+        if !self.tables.is_empty() {
+            let tables = Value::List(std::mem::take(&mut self.tables));
+            self.field("symbols", tables);
+        }
+        if !self.note_containers.is_empty() {
+            let notes = Value::List(std::mem::take(&mut self.note_containers));
+            self.field("notes", notes);
+        }
+        let file = Value::Map(std::mem::take(&mut self.file));
+        match self.format {
+            TreeFormat::Json => writeln!(self.writer, "{}", file.to_json()),
+            TreeFormat::Yaml => writeln!(self.writer, "---{}", file.to_yaml(0)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Value;
+
+    #[test]
+    fn serializes_nested_values() {
+        let value = Value::Map(vec![
+            ("name".to_string(), Value::from(".text\t\"x\"")),
+            (
+                "rows".to_string(),
+                Value::List(vec![Value::Map(vec![
+                    ("a".to_string(), Value::from(1u64)),
+                    ("b".to_string(), Value::from(true)),
+                ])]),
+            ),
+        ]);
+
+        assert_eq!(
+            value.to_json(),
+            r#"{"name":".text\t\"x\"","rows":[{"a":1,"b":true}]}"#
+        );
+        assert_eq!(
+            value.to_yaml(0),
+            "\nname: \".text\\t\\\"x\\\"\"\nrows:\n  - a: 1\n    b: true"
+        );
+    }
+}
@@ -0,0 +1,424 @@
+//! Address to source position lookups over the line number programs of `.debug_line`,
+//! and to the chain of functions inlined at an address, as `addr2line -f -i` prints them
+
+use std::{collections::HashMap, io, ops::Range, path::Path};
+
+use super::{
+    bytes::ByteReader,
+    core::FileData,
+    dwarf::{
+        is_unit_ref, parse_abbrevs, read_dies, Attribute, Die, DwarfSections, LineProgram,
+        UnitBases, UnitHeader, DW_AT_ABSTRACT_ORIGIN, DW_AT_CALL_COLUMN, DW_AT_CALL_FILE,
+        DW_AT_CALL_LINE, DW_AT_COMP_DIR, DW_AT_LINKAGE_NAME, DW_AT_MIPS_LINKAGE_NAME, DW_AT_NAME,
+        DW_AT_SPECIFICATION, DW_AT_STMT_LIST, DW_FORM_REF_ADDR, DW_TAG_INLINED_SUBROUTINE,
+        DW_TAG_SUBPROGRAM,
+    },
+    hdr::Endian,
+};
+
+/// The DWARF sections a lookup reads, owned so that they outlive the view reading them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DebugInfo {
+    pub endian: Endian,
+    pub info: Vec<u8>,
+    pub abbrev: Vec<u8>,
+    pub line: Vec<u8>,
+    pub str: Vec<u8>,
+    pub line_str: Vec<u8>,
+    pub str_offsets: Vec<u8>,
+    pub addr: Vec<u8>,
+    pub ranges: Vec<u8>,
+    pub rnglists: Vec<u8>,
+}
+
+impl DebugInfo {
+    /// Reads the sections of `elf`, inflating compressed ones. Missing sections are empty.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub fn read(elf: &FileData) -> io::Result<Self> {
+        let section = |name: &str| -> io::Result<Vec<u8>> {
+            match elf.debug_section_by_name(name) {
+                Some(shdr) => elf.decompressed_section_data(&shdr),
+                None => Ok(Vec::new()),
+            }
+        };
+
+        Ok(Self {
+            endian: elf.header().endian().unwrap_or(Endian::Little),
+            info: section(".debug_info")?,
+            abbrev: section(".debug_abbrev")?,
+            line: section(".debug_line")?,
+            str: section(".debug_str")?,
+            line_str: section(".debug_line_str")?,
+            str_offsets: section(".debug_str_offsets")?,
+            addr: section(".debug_addr")?,
+            ranges: section(".debug_ranges")?,
+            rnglists: section(".debug_rnglists")?,
+        })
+    }
+
+    pub fn sections(&self) -> DwarfSections<'_> {
+        DwarfSections {
+            debug_str: &self.str,
+            line_str: &self.line_str,
+            str_offsets: &self.str_offsets,
+            addr: &self.addr,
+            ranges: &self.ranges,
+            rnglists: &self.rnglists,
+            ..DwarfSections::new(self.endian)
+        }
+    }
+}
+
+/// Calls `f` with each unit of `.debug_info`, its DIEs and the bases of its indexed forms
+fn for_each_unit<'a>(debug: &'a DebugInfo, mut f: impl FnMut(&UnitHeader, &[Die<'a>], &UnitBases)) {
+    let sections = debug.sections();
+    let mut abbrevs = HashMap::new();
+    let mut reader = ByteReader::new(&debug.info, debug.endian);
+    while !reader.is_empty() {
+        let unit = match UnitHeader::parse(&mut reader, false) {
+            Some(unit) => unit,
+            None => break,
+        };
+        let abbrevs = abbrevs
+            .entry(unit.abbrev_offset)
+            .or_insert_with(|| parse_abbrevs(&debug.abbrev, unit.abbrev_offset, debug.endian));
+        let (dies, _) = read_dies(&debug.info, &unit, abbrevs, debug.endian);
+        if let Some(die) = dies.first() {
+            f(&unit, &dies, &sections.unit_bases(die, unit.encoding));
+        }
+        if reader.seek(unit.end).is_none() {
+            break;
+        }
+    }
+}
+
+/// A source position
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    /// The file as the line program names it, joined to the compilation directory when
+    /// relative
+    pub file: String,
+    pub line: u64,
+    pub column: u64,
+}
+
+/// The rows of every line number program, as the address ranges they cover
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LineTable {
+    /// `(addresses, file, line, column)`, sorted by address. The file indexes `files`.
+    rows: Vec<(Range<u64>, usize, u64, u64)>,
+    files: Vec<String>,
+}
+
+impl LineTable {
+    /// Runs the line program of each unit with a `DW_AT_stmt_list`
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub fn new(debug: &DebugInfo) -> Self {
+        let mut table = Self::default();
+        for_each_unit(debug, |unit, dies, bases| {
+            table.add_unit(debug, unit, &dies[0], bases);
+        });
+        table.sort();
+        table
+    }
+
+    /// Adds the rows of the line program of a unit, whose DIE is `die`. The index its file
+    /// table starts at in `files`, `None` when it has no line program.
+    fn add_unit(
+        &mut self,
+        debug: &DebugInfo,
+        unit: &UnitHeader,
+        die: &Die,
+        bases: &UnitBases,
+    ) -> Option<usize> {
+        let offset = die.value(DW_AT_STMT_LIST)?;
+        let comp_dir = die
+            .attr(DW_AT_COMP_DIR)
+            .and_then(|attr| debug.sections().string(attr, bases, unit.encoding));
+        let program = LineProgram::parse(
+            &debug.line,
+            offset,
+            debug.endian,
+            &debug.str,
+            &debug.line_str,
+        )?;
+
+        let first_file = self.files.len();
+        self.files.extend(
+            program
+                .files
+                .names
+                .iter()
+                .map(|name| match comp_dir.as_deref() {
+                    Some(dir) if !name.is_empty() && !name.starts_with('/') => {
+                        Path::new(dir).join(name).to_string_lossy().into_owned()
+                    }
+                    _ => name.clone(),
+                }),
+        );
+        let files = first_file..self.files.len();
+
+        let rows = program.rows(&debug.line, debug.endian);
+        for pair in rows.windows(2) {
+            let (row, next) = (pair[0], pair[1]);
+            let file = first_file.saturating_add(row.file as usize);
+            if row.end_sequence || row.address >= next.address || !files.contains(&file) {
+                continue;
+            }
+            self.rows
+                .push((row.address..next.address, file, row.line, row.column));
+        }
+        Some(first_file)
+    }
+
+    fn sort(&mut self) {
+        self.rows.sort_by_key(|(range, ..)| range.start);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// The name of file `file` of the line program whose files start at `first`
+    fn file(&self, first: usize, file: u64) -> Option<String> {
+        let file = first.checked_add(usize::try_from(file).ok()?)?;
+        self.files
+            .get(file)
+            .filter(|name| !name.is_empty())
+            .cloned()
+    }
+
+    /// The source position of the instruction at `address`
+    pub fn find(&self, address: u64) -> Option<Location> {
+        let end = self
+            .rows
+            .partition_point(|(range, ..)| range.start <= address);
+        let (range, file, line, column) = self.rows[..end].last()?;
+        let file = &self.files[*file];
+        (range.contains(&address) && !file.is_empty()).then(|| Location {
+            file: file.clone(),
+            line: *line,
+            column: *column,
+        })
+    }
+}
+
+/// A function an address is in, and where in it the address is
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    /// The linkage name of the function, or its name when it has none
+    pub function: Option<String>,
+    pub location: Option<Location>,
+}
+
+/// A subprogram or an inlined subroutine
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Function {
+    depth: usize,
+    ranges: Vec<Range<u64>>,
+    /// The subprogram DIE naming it, an inlined subroutine's abstract origin
+    origin: Option<usize>,
+    /// Where an inlined subroutine was called from
+    call: Option<Location>,
+}
+
+/// The units of `.debug_info` and their functions, for finding the inline chain of an
+/// address the way `addr2line -i` does. Split units are not followed, their addresses
+/// resolve to the line tables only.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Addr2Line {
+    lines: LineTable,
+    /// The ranges of each unit DIE and the functions of the unit in DIE order
+    units: Vec<(Vec<Range<u64>>, Vec<Function>)>,
+    /// The name of each subprogram DIE, or the DIE it refers to through
+    /// `DW_AT_abstract_origin` or `DW_AT_specification`
+    names: HashMap<usize, (Option<String>, Option<usize>)>,
+}
+
+impl Addr2Line {
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub fn new(debug: &DebugInfo) -> Self {
+        let sections = debug.sections();
+        let mut this = Self::default();
+        for_each_unit(debug, |unit, dies, bases| {
+            let first_file = this.lines.add_unit(debug, unit, &dies[0], bases);
+            let encoding = unit.encoding;
+            let reference = |attr: &Attribute| match attr.form {
+                form if is_unit_ref(form) => Some(unit.offset + attr.value.as_u64()? as usize),
+                DW_FORM_REF_ADDR => attr.value.as_u64().map(|offset| offset as usize),
+                _ => None,
+            };
+
+            let mut functions = Vec::new();
+            for die in dies {
+                match die.tag {
+                    DW_TAG_SUBPROGRAM => {
+                        let name = [DW_AT_LINKAGE_NAME, DW_AT_MIPS_LINKAGE_NAME, DW_AT_NAME]
+                            .iter()
+                            .find_map(|&name| die.attr(name))
+                            .and_then(|attr| sections.string(attr, bases, encoding));
+                        let referred = die
+                            .attr(DW_AT_ABSTRACT_ORIGIN)
+                            .or_else(|| die.attr(DW_AT_SPECIFICATION))
+                            .and_then(reference);
+                        this.names.insert(die.offset, (name, referred));
+                        functions.push(Function {
+                            depth: die.depth,
+                            ranges: sections.die_ranges(die, bases, encoding),
+                            origin: Some(die.offset),
+                            call: None,
+                        });
+                    }
+                    DW_TAG_INLINED_SUBROUTINE => {
+                        let file = die
+                            .value(DW_AT_CALL_FILE)
+                            .zip(first_file)
+                            .and_then(|(file, first)| this.lines.file(first, file));
+                        functions.push(Function {
+                            depth: die.depth,
+                            ranges: sections.die_ranges(die, bases, encoding),
+                            origin: die.attr(DW_AT_ABSTRACT_ORIGIN).and_then(reference),
+                            call: file.map(|file| Location {
+                                file,
+                                line: die.value(DW_AT_CALL_LINE).unwrap_or(0),
+                                column: die.value(DW_AT_CALL_COLUMN).unwrap_or(0),
+                            }),
+                        });
+                    }
+                    _ => {}
+                }
+            }
+            let ranges = sections.die_ranges(&dies[0], bases, encoding);
+            this.units.push((ranges, functions));
+        });
+        this.lines.sort();
+        this
+    }
+
+    /// The name of a subprogram DIE, following its abstract origin or specification
+    fn name(&self, mut offset: usize) -> Option<&str> {
+        // A bound on the references followed, in case they form a cycle
+        for _ in 0..16 {
+            match self.names.get(&offset)? {
+                (Some(name), _) => return Some(name),
+                (None, referred) => offset = (*referred)?,
+            }
+        }
+        None
+    }
+
+    /// The functions inlined at `address`, innermost first, each with the position in it
+    /// the next one was inlined at. The outermost is the subprogram containing `address`.
+    /// A single frame without a function when no unit describes the address.
+    pub fn frames(&self, address: u64) -> Vec<Frame> {
+        let contains = |ranges: &[Range<u64>]| ranges.iter().any(|range| range.contains(&address));
+        let mut location = self.lines.find(address);
+        for (ranges, functions) in &self.units {
+            if !ranges.is_empty() && !contains(ranges) {
+                continue;
+            }
+            // The functions containing the address, outermost first. The children of a
+            // function follow it, so the chain is complete at the first function past
+            // the innermost one's children.
+            let mut chain: Vec<&Function> = Vec::new();
+            for function in functions {
+                if matches!(chain.last(), Some(last) if last.depth >= function.depth) {
+                    break;
+                }
+                if contains(&function.ranges) {
+                    chain.push(function);
+                }
+            }
+            if chain.is_empty() {
+                continue;
+            }
+
+            return chain
+                .iter()
+                .rev()
+                .map(|function| {
+                    let frame = Frame {
+                        function: function
+                            .origin
+                            .and_then(|origin| self.name(origin))
+                            .map(str::to_string),
+                        location: location.take(),
+                    };
+                    location = function.call.clone();
+                    frame
+                })
+                .collect();
+        }
+
+        vec![Frame {
+            function: None,
+            location,
+        }]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::ops::Range;
+
+    use super::{Addr2Line, Frame, Function, LineTable, Location};
+
+    #[test]
+    fn walks_inline_chain() {
+        let location = |line| {
+            Some(Location {
+                file: "/src/a.c".to_string(),
+                line,
+                column: 0,
+            })
+        };
+        let function = |depth, range: Range<u64>, origin, call| Function {
+            depth,
+            ranges: vec![range],
+            origin: Some(origin),
+            call,
+        };
+        // main at 0x10 calls work inlined on line 7, which calls sq inlined on line 3,
+        // followed by the abstract instance of sq
+        let unit = 0x10..0x40;
+        let resolver = Addr2Line {
+            lines: LineTable {
+                rows: vec![(0x10..0x20, 0, 7, 0), (0x20..0x28, 0, 2, 0)],
+                files: vec!["/src/a.c".to_string()],
+            },
+            units: vec![(
+                vec![unit.clone()],
+                vec![
+                    function(1, unit, 0x100, None),
+                    function(2, 0x18..0x30, 0x200, location(7)),
+                    function(3, 0x20..0x28, 0x300, location(3)),
+                    function(1, 0..0, 0x300, None),
+                ],
+            )],
+            names: [
+                (0x100, (Some("main".to_string()), None)),
+                (0x200, (Some("work".to_string()), None)),
+                (0x300, (Some("sq".to_string()), None)),
+            ]
+            .into_iter()
+            .collect(),
+        };
+
+        let frame = |function: &str, line| Frame {
+            function: Some(function.to_string()),
+            location: location(line),
+        };
+        assert_eq!(
+            resolver.frames(0x24),
+            [frame("sq", 2), frame("work", 3), frame("main", 7)]
+        );
+        assert_eq!(resolver.frames(0x12), [frame("main", 7)]);
+        assert_eq!(
+            resolver.frames(0x50),
+            [Frame {
+                function: None,
+                location: None
+            }]
+        );
+    }
+}
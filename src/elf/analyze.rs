@@ -0,0 +1,126 @@
+use std::{
+    io,
+    sync::{Arc, PoisonError, RwLock},
+};
+
+use super::{core::FileData, hdr::Endian, shdr::ElfShdr};
+
+/// Something that knows how to read a kind of section, such as the metadata a toolchain
+/// or a vendor stores in the file. `--analyze` runs every registered analyzer over the
+/// sections it matches.
+pub trait SectionAnalyzer: Send + Sync {
+    /// Short name the output is labelled with, `comment`
+    fn name(&self) -> &str;
+
+    /// Whether the section, by name or header, is one this analyzer reads
+    fn matches(&self, name: &str, shdr: &ElfShdr) -> bool;
+
+    /// The findings rendered as text, `None` when there is nothing to report
+    fn analyze(&self, data: &[u8], endian: Endian) -> Option<String>;
+}
+
+/// The output of one analyzer for one section
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Analysis {
+    pub section: String,
+    pub analyzer: String,
+    pub output: String,
+}
+
+static ANALYZERS: RwLock<Vec<Arc<dyn SectionAnalyzer>>> = RwLock::new(Vec::new());
+
+/// Adds `analyzer` after the built-in ones and those registered before it
+pub fn register_section_analyzer(analyzer: impl SectionAnalyzer + 'static) {
+    ANALYZERS
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .push(Arc::new(analyzer));
+}
+
+/// The built-in analyzers followed by the registered ones
+pub fn section_analyzers() -> Vec<Arc<dyn SectionAnalyzer>> {
+    let mut analyzers: Vec<Arc<dyn SectionAnalyzer>> = vec![Arc::new(CommentAnalyzer)];
+    analyzers.extend(
+        ANALYZERS
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .cloned(),
+    );
+    analyzers
+}
+
+/// Runs every analyzer over the sections it matches, in section order
+pub fn analyze_sections(elf: &FileData) -> io::Result<Vec<Analysis>> {
+    let endian = elf.header().endian().unwrap_or(Endian::Little);
+    let analyzers = section_analyzers();
+    let mut results = Vec::new();
+
+    for shdr in elf.section_headers() {
+        let name = elf.section_name(shdr).unwrap_or_default();
+        let matching = analyzers
+            .iter()
+            .filter(|analyzer| analyzer.matches(&name, shdr))
+            .collect::<Vec<_>>();
+        if matching.is_empty() {
+            continue;
+        }
+
+        let data = elf.decompressed_section_data(shdr)?;
+        for analyzer in matching {
+            if let Some(output) = analyzer.analyze(&data, endian) {
+                results.push(Analysis {
+                    section: name.clone(),
+                    analyzer: analyzer.name().to_string(),
+                    output,
+                });
+            }
+        }
+    }
+
+    Ok(results)
+}
+
+/// Lists the toolchain identification strings compilers and linkers append to `.comment`
+struct CommentAnalyzer;
+
+impl SectionAnalyzer for CommentAnalyzer {
+    fn name(&self) -> &str {
+        "comment"
+    }
+
+    fn matches(&self, name: &str, _shdr: &ElfShdr) -> bool {
+        name == ".comment"
+    }
+
+    fn analyze(&self, data: &[u8], _endian: Endian) -> Option<String> {
+        let mut strings = Vec::new();
+        for string in data.split(|&c| c == 0).filter(|s| !s.is_empty()) {
+            let string = String::from_utf8_lossy(string).into_owned();
+            if !strings.contains(&string) {
+                strings.push(string);
+            }
+        }
+
+        match strings.is_empty() {
+            true => None,
+            false => Some(strings.join("\n")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{CommentAnalyzer, SectionAnalyzer};
+    use crate::elf::hdr::Endian;
+
+    #[test]
+    fn lists_comment_strings() {
+        let data = b"GCC: (GNU) 12.2.0\0GCC: (GNU) 12.2.0\0Linker: LLD 16.0.6\0";
+        assert_eq!(
+            CommentAnalyzer.analyze(data, Endian::Little).as_deref(),
+            Some("GCC: (GNU) 12.2.0\nLinker: LLD 16.0.6")
+        );
+        assert_eq!(CommentAnalyzer.analyze(b"\0\0", Endian::Little), None);
+    }
+}
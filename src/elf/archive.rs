@@ -0,0 +1,158 @@
+//! `ar` archives, the static libraries whose members readelf dumps one by one
+use core::str;
+
+/// The magic a regular archive starts with. Thin archives (`!<thin>`) only record the
+/// paths of their members and are not read.
+pub const ARMAG: &[u8] = b"!<arch>\n";
+
+const HEADER_SIZE: usize = 60;
+
+/// A member of an archive, with its name resolved through the long name table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Member<'a> {
+    pub name: &'a str,
+    pub data: &'a [u8],
+}
+
+/// An archive borrowed from memory, in the GNU/System V or the BSD format
+#[derive(Debug, Clone, Copy)]
+pub struct Archive<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Archive<'a> {
+    /// Checks the magic, `None` for anything that is not an archive
+    pub fn parse(data: &'a [u8]) -> Option<Self> {
+        data.starts_with(ARMAG).then_some(Self { data })
+    }
+
+    /// The members in archive order, without the symbol index and the long name table.
+    /// Iteration stops at the first malformed header.
+    pub fn members(&self) -> impl Iterator<Item = Member<'a>> + 'a {
+        let data = self.data;
+        let mut offset = ARMAG.len();
+        let mut long_names: &[u8] = &[];
+        core::iter::from_fn(move || loop {
+            let (name, contents, next) = read_member(data, offset)?;
+            // Members start on even offsets
+            offset = next.checked_add(next & 1)?;
+            match name {
+                b"/" | b"/SYM64/" | b"__.SYMDEF" | b"__.SYMDEF SORTED" => continue,
+                b"//" => {
+                    long_names = contents;
+                    continue;
+                }
+                _ => {}
+            }
+            let (name, contents) = match name {
+                // BSD: the name follows the header, counted in the member size
+                [b'#', b'1', b'/', len @ ..] => {
+                    let len = parse_decimal(len)?;
+                    let name = contents.get(..len)?;
+                    let name = &name[..name.iter().position(|&c| c == 0).unwrap_or(len)];
+                    (name, &contents[len..])
+                }
+                // GNU: an offset into the long name table, the name ending in "/\n"
+                [b'/', index @ ..] => {
+                    let names = long_names.get(parse_decimal(index)?..)?;
+                    let end = names.iter().position(|&c| c == b'\n')?;
+                    (
+                        names[..end].strip_suffix(b"/").unwrap_or(&names[..end]),
+                        contents,
+                    )
+                }
+                _ => (name.strip_suffix(b"/").unwrap_or(name), contents),
+            };
+            return Some(Member {
+                name: str::from_utf8(name).ok()?,
+                data: contents,
+            });
+        })
+    }
+}
+
+/// The raw name and contents of the member whose header is at `offset`, and the offset
+/// its contents end at
+fn read_member(data: &[u8], offset: usize) -> Option<(&[u8], &[u8], usize)> {
+    let header = data.get(offset..offset.checked_add(HEADER_SIZE)?)?;
+    if &header[58..] != b"`\n" {
+        return None;
+    }
+    let len = header[..16]
+        .iter()
+        .rposition(|&c| c != b' ')
+        .map_or(0, |end| end + 1);
+    let name = &header[..len];
+    let start = offset + HEADER_SIZE;
+    let end = start.checked_add(parse_decimal(&header[48..58])?)?;
+
+    Some((name, data.get(start..end)?, end))
+}
+
+/// A space padded decimal field
+fn parse_decimal(field: &[u8]) -> Option<usize> {
+    str::from_utf8(field).ok()?.trim_end().parse().ok()
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::{format, vec::Vec};
+
+    use super::{Archive, Member, ARMAG};
+
+    fn member(archive: &mut Vec<u8>, name: &str, data: &[u8]) {
+        let header = format!(
+            "{:<16}{:<12}{:<6}{:<6}{:<8}{:<10}`\n",
+            name,
+            0,
+            0,
+            0,
+            644,
+            data.len()
+        );
+        archive.extend_from_slice(header.as_bytes());
+        archive.extend_from_slice(data);
+        if data.len() % 2 == 1 {
+            archive.push(b'\n');
+        }
+    }
+
+    #[test]
+    fn resolves_member_names() {
+        let mut gnu = ARMAG.to_vec();
+        member(&mut gnu, "/", &[0; 4]);
+        member(&mut gnu, "//", b"a_rather_long_name.o/\n");
+        member(&mut gnu, "short.o/", b"abc");
+        member(&mut gnu, "/0", b"de");
+        let members: Vec<_> = Archive::parse(&gnu).unwrap().members().collect();
+        assert_eq!(
+            members,
+            [
+                Member {
+                    name: "short.o",
+                    data: b"abc"
+                },
+                Member {
+                    name: "a_rather_long_name.o",
+                    data: b"de"
+                },
+            ]
+        );
+
+        let mut bsd = ARMAG.to_vec();
+        member(&mut bsd, "#1/12", b"bsd_name.o\0\0xyz");
+        let members: Vec<_> = Archive::parse(&bsd).unwrap().members().collect();
+        assert_eq!(
+            members,
+            [Member {
+                name: "bsd_name.o",
+                data: b"xyz"
+            }]
+        );
+
+        // A header cut short ends the iteration
+        gnu.truncate(gnu.len() - 1);
+        assert_eq!(Archive::parse(&gnu).unwrap().members().count(), 1);
+        assert!(Archive::parse(b"\x7fELF").is_none());
+    }
+}
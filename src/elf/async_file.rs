@@ -0,0 +1,233 @@
+use std::{
+    io::{self, SeekFrom},
+    path::{Path, PathBuf},
+};
+
+use tokio::{
+    fs,
+    io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt},
+};
+
+use super::{
+    bytes::string_at,
+    hdr::{Elf64Hdr, ElfClass, Endian},
+    image::{program_header_at, section_header_at, ElfImage},
+    note::Note,
+    phdr::ProgramType,
+    shdr::{ElfShdr, SectionType},
+    ElfHdr, ElfPhdr,
+};
+
+/// Anything [`AsyncFileData`] can read an image from, a [`tokio::fs::File`] by default
+pub trait AsyncSource: AsyncRead + AsyncSeek + Unpin + Send {}
+
+impl<T: AsyncRead + AsyncSeek + Unpin + Send> AsyncSource for T {}
+
+/// The async counterpart of [`FileData`](super::core::FileData), every read goes through
+/// tokio so inspecting many files concurrently does not block the runtime.
+///
+/// Only the headers and the section name table are read up front, section contents are read
+/// on demand the same way as the blocking backend.
+pub struct AsyncFileData {
+    file_path: PathBuf,
+    file: Box<dyn AsyncSource>,
+    header: ElfHdr,
+    program_headers: Vec<ElfPhdr>,
+    section_headers: Vec<ElfShdr>,
+    string_table: Vec<u8>,
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+impl AsyncFileData {
+    pub async fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = fs::File::open(&path).await?;
+        Self::from_source(Box::new(file), PathBuf::from(path.as_ref())).await
+    }
+
+    /// Parses an image from any async seekable reader
+    pub async fn from_reader<R: AsyncSource + 'static>(reader: R) -> io::Result<Self> {
+        Self::from_source(Box::new(reader), PathBuf::new()).await
+    }
+
+    async fn from_source(mut file: Box<dyn AsyncSource>, file_path: PathBuf) -> io::Result<Self> {
+        let mut ident = vec![0; std::mem::size_of::<Elf64Hdr>()];
+        let read = read_up_to(&mut file, &mut ident).await?;
+        let header = *ElfImage::parse(&ident[..read])
+            .ok_or_else(|| {
+                invalid_data("Not an ELF file - it has the wrong magic bytes at the start")
+            })?
+            .header();
+        let elf64 = header.class() == Some(ElfClass::ElfClass64);
+
+        let mut this = Self {
+            file_path,
+            file,
+            header,
+            program_headers: Vec::new(),
+            section_headers: Vec::new(),
+            string_table: Vec::new(),
+        };
+
+        let (phoff, phentsize) = (header.e_phoff, header.e_phentsize as usize);
+        let table = this
+            .read_at(phoff, header.e_phnum as usize * phentsize)
+            .await?;
+        this.program_headers = (0..header.e_phnum as usize)
+            .map_while(|i| program_header_at(&table, i * phentsize, elf64))
+            .collect();
+
+        let (shoff, shentsize) = (header.e_shoff, header.e_shentsize as usize);
+        let table = this
+            .read_at(shoff, header.e_shnum as usize * shentsize)
+            .await?;
+        this.section_headers = (0..header.e_shnum as usize)
+            .map_while(|i| section_header_at(&table, i * shentsize, elf64))
+            .collect();
+        if this.section_headers.len() != header.e_shnum as usize {
+            return Err(invalid_data("section header table is truncated"));
+        }
+
+        if let Some(strtab) = this
+            .section_headers
+            .get(header.e_shstrndx as usize)
+            .copied()
+        {
+            this.string_table = this.section_data(&strtab).await?;
+        }
+
+        Ok(this)
+    }
+
+    pub fn file_path(&self) -> &Path {
+        &self.file_path
+    }
+
+    pub fn header(&self) -> &ElfHdr {
+        &self.header
+    }
+
+    pub fn section_headers(&self) -> &[ElfShdr] {
+        &self.section_headers
+    }
+
+    pub fn program_headers(&self) -> &[ElfPhdr] {
+        &self.program_headers
+    }
+
+    pub fn section_name(&self, shdr: &ElfShdr) -> Option<String> {
+        let index = shdr.name() as usize;
+        (index <= self.string_table.len()).then(|| string_at(&self.string_table, index))
+    }
+
+    pub fn section_by_name(&self, name: &str) -> Option<ElfShdr> {
+        self.section_headers
+            .iter()
+            .find(|shdr| self.section_name(shdr).as_deref() == Some(name))
+            .copied()
+    }
+
+    /// Reads `size` bytes at `offset`, failing with `UnexpectedEof` before allocating
+    /// anything when the range ends past the file
+    pub async fn read_at(&mut self, offset: u64, size: usize) -> io::Result<Vec<u8>> {
+        let len = self.file.seek(SeekFrom::End(0)).await?;
+        match offset.checked_add(size as u64) {
+            Some(end) if end <= len => {}
+            _ => return Err(io::ErrorKind::UnexpectedEof.into()),
+        }
+        let mut buf = vec![0; size];
+        self.file.seek(SeekFrom::Start(offset)).await?;
+        self.file.read_exact(&mut buf).await?;
+
+        Ok(buf)
+    }
+
+    /// Reads the raw contents of a section, `SHT_NOBITS` sections are empty
+    pub async fn section_data(&mut self, shdr: &ElfShdr) -> io::Result<Vec<u8>> {
+        if shdr.section_type() == Some(SectionType::NoBits) {
+            return Ok(Vec::new());
+        }
+
+        self.read_at(shdr.offset(), shdr.size() as usize).await
+    }
+
+    /// Collects the notes of every `SHT_NOTE` section, falling back to the `PT_NOTE`
+    /// segments when the section headers are missing
+    pub async fn notes(&mut self) -> io::Result<Vec<Note>> {
+        let endian = self.header.endian().unwrap_or(Endian::Little);
+        let mut regions = self
+            .section_headers
+            .iter()
+            .filter(|shdr| shdr.section_type() == Some(SectionType::Note))
+            .map(|shdr| (shdr.offset(), shdr.size(), shdr.addralign()))
+            .collect::<Vec<_>>();
+        if regions.is_empty() {
+            regions = self
+                .program_headers
+                .iter()
+                .filter(|phdr| phdr.program_type() == Some(ProgramType::Note))
+                .map(|phdr| (phdr.offset(), phdr.filesz(), phdr.align()))
+                .collect();
+        }
+
+        let mut notes = Vec::new();
+        for (offset, size, align) in regions {
+            notes.extend(Note::parse_aligned(
+                &self.read_at(offset, size as usize).await?,
+                endian,
+                align,
+            ));
+        }
+
+        Ok(notes)
+    }
+}
+
+/// Fills as much of `buf` as the source has, a short read only means a small file
+async fn read_up_to(file: &mut Box<dyn AsyncSource>, buf: &mut [u8]) -> io::Result<usize> {
+    let mut read = 0;
+    while read < buf.len() {
+        match file.read(&mut buf[read..]).await? {
+            0 => break,
+            n => read += n,
+        }
+    }
+    Ok(read)
+}
+
+#[cfg(test)]
+mod test {
+    use super::AsyncFileData;
+    use crate::elf::core::FileData;
+
+    #[tokio::test]
+    async fn matches_blocking_backend() {
+        let path = std::env::current_exe().unwrap();
+        let mut elf = AsyncFileData::open(&path).await.unwrap();
+        let blocking = FileData::new(&path).unwrap();
+
+        let names = elf
+            .section_headers()
+            .iter()
+            .map(|shdr| elf.section_name(shdr))
+            .collect::<Vec<_>>();
+        let expected = blocking
+            .section_headers()
+            .iter()
+            .map(|shdr| blocking.section_name(shdr))
+            .collect::<Vec<_>>();
+        assert_eq!(names, expected);
+        assert_eq!(
+            elf.program_headers().len(),
+            blocking.program_headers().len()
+        );
+
+        let text = elf.section_by_name(".text").unwrap();
+        assert_eq!(
+            elf.section_data(&text).await.unwrap().len() as u64,
+            text.size()
+        );
+    }
+}
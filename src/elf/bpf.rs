@@ -0,0 +1,476 @@
+use std::io;
+
+use super::{
+    btf::{Btf, BtfKind},
+    bytes::{string_at, ByteReader},
+    core::FileData,
+    hdr::Endian,
+    shdr::{SectionFlag, SectionType},
+    sym::SymbolType,
+};
+
+pub const BPF_INSN_SIZE: u64 = 8;
+
+/// Section name prefixes understood by libbpf, mapped to the program type and the
+/// attach kind they imply. Longer prefixes must come before shorter ones.
+static SECTION_DEFS: &[(&str, &str, &str)] = &[
+    ("socket", "SOCKET_FILTER", "socket"),
+    (
+        "sk_reuseport/migrate",
+        "SK_REUSEPORT",
+        "sk_reuseport_migrate",
+    ),
+    ("sk_reuseport", "SK_REUSEPORT", "sk_reuseport"),
+    ("kprobe.multi/", "KPROBE", "kprobe_multi"),
+    ("kretprobe.multi/", "KPROBE", "kretprobe_multi"),
+    ("kprobe/", "KPROBE", "kprobe"),
+    ("kretprobe/", "KPROBE", "kretprobe"),
+    ("uprobe/", "KPROBE", "uprobe"),
+    ("uretprobe/", "KPROBE", "uretprobe"),
+    ("ksyscall/", "KPROBE", "ksyscall"),
+    ("kretsyscall/", "KPROBE", "kretsyscall"),
+    ("usdt/", "KPROBE", "usdt"),
+    ("tc/ingress", "SCHED_CLS", "tc_ingress"),
+    ("tc/egress", "SCHED_CLS", "tc_egress"),
+    ("tcx/ingress", "SCHED_CLS", "tcx_ingress"),
+    ("tcx/egress", "SCHED_CLS", "tcx_egress"),
+    ("tc", "SCHED_CLS", "tc"),
+    ("classifier", "SCHED_CLS", "tc"),
+    ("action", "SCHED_ACT", "tc_action"),
+    ("tracepoint/", "TRACEPOINT", "tracepoint"),
+    ("tp/", "TRACEPOINT", "tracepoint"),
+    (
+        "raw_tracepoint.w/",
+        "RAW_TRACEPOINT_WRITABLE",
+        "raw_tracepoint",
+    ),
+    ("raw_tp.w/", "RAW_TRACEPOINT_WRITABLE", "raw_tracepoint"),
+    ("raw_tracepoint/", "RAW_TRACEPOINT", "raw_tracepoint"),
+    ("raw_tp/", "RAW_TRACEPOINT", "raw_tracepoint"),
+    ("tp_btf/", "TRACING", "tp_btf"),
+    ("fentry/", "TRACING", "fentry"),
+    ("fexit/", "TRACING", "fexit"),
+    ("fmod_ret/", "TRACING", "fmod_ret"),
+    ("iter/", "TRACING", "iter"),
+    ("freplace/", "EXT", "freplace"),
+    ("lsm/", "LSM", "lsm_mac"),
+    ("lsm_cgroup/", "LSM", "lsm_cgroup"),
+    ("xdp.frags/devmap", "XDP", "xdp_devmap"),
+    ("xdp.frags/cpumap", "XDP", "xdp_cpumap"),
+    ("xdp/devmap", "XDP", "xdp_devmap"),
+    ("xdp/cpumap", "XDP", "xdp_cpumap"),
+    ("xdp.frags", "XDP", "xdp"),
+    ("xdp", "XDP", "xdp"),
+    ("perf_event", "PERF_EVENT", "perf_event"),
+    ("lwt_in", "LWT_IN", "lwt_in"),
+    ("lwt_out", "LWT_OUT", "lwt_out"),
+    ("lwt_xmit", "LWT_XMIT", "lwt_xmit"),
+    ("lwt_seg6local", "LWT_SEG6LOCAL", "lwt_seg6local"),
+    ("sockops", "SOCK_OPS", "cgroup_sock_ops"),
+    ("sk_skb/stream_parser", "SK_SKB", "sk_skb_stream_parser"),
+    ("sk_skb/stream_verdict", "SK_SKB", "sk_skb_stream_verdict"),
+    ("sk_skb", "SK_SKB", "sk_skb"),
+    ("sk_msg", "SK_MSG", "sk_msg_verdict"),
+    ("lirc_mode2", "LIRC_MODE2", "lirc_mode2"),
+    ("flow_dissector", "FLOW_DISSECTOR", "flow_dissector"),
+    ("cgroup_skb/ingress", "CGROUP_SKB", "cgroup_inet_ingress"),
+    ("cgroup_skb/egress", "CGROUP_SKB", "cgroup_inet_egress"),
+    ("cgroup/skb", "CGROUP_SKB", "cgroup_skb"),
+    (
+        "cgroup/sock_create",
+        "CGROUP_SOCK",
+        "cgroup_inet_sock_create",
+    ),
+    (
+        "cgroup/sock_release",
+        "CGROUP_SOCK",
+        "cgroup_inet_sock_release",
+    ),
+    ("cgroup/sock", "CGROUP_SOCK", "cgroup_inet_sock_create"),
+    ("cgroup/post_bind4", "CGROUP_SOCK", "cgroup_inet4_post_bind"),
+    ("cgroup/post_bind6", "CGROUP_SOCK", "cgroup_inet6_post_bind"),
+    ("cgroup/bind4", "CGROUP_SOCK_ADDR", "cgroup_inet4_bind"),
+    ("cgroup/bind6", "CGROUP_SOCK_ADDR", "cgroup_inet6_bind"),
+    (
+        "cgroup/connect4",
+        "CGROUP_SOCK_ADDR",
+        "cgroup_inet4_connect",
+    ),
+    (
+        "cgroup/connect6",
+        "CGROUP_SOCK_ADDR",
+        "cgroup_inet6_connect",
+    ),
+    ("cgroup/sendmsg4", "CGROUP_SOCK_ADDR", "cgroup_udp4_sendmsg"),
+    ("cgroup/sendmsg6", "CGROUP_SOCK_ADDR", "cgroup_udp6_sendmsg"),
+    ("cgroup/recvmsg4", "CGROUP_SOCK_ADDR", "cgroup_udp4_recvmsg"),
+    ("cgroup/recvmsg6", "CGROUP_SOCK_ADDR", "cgroup_udp6_recvmsg"),
+    (
+        "cgroup/getpeername4",
+        "CGROUP_SOCK_ADDR",
+        "cgroup_inet4_getpeername",
+    ),
+    (
+        "cgroup/getpeername6",
+        "CGROUP_SOCK_ADDR",
+        "cgroup_inet6_getpeername",
+    ),
+    (
+        "cgroup/getsockname4",
+        "CGROUP_SOCK_ADDR",
+        "cgroup_inet4_getsockname",
+    ),
+    (
+        "cgroup/getsockname6",
+        "CGROUP_SOCK_ADDR",
+        "cgroup_inet6_getsockname",
+    ),
+    ("cgroup/sysctl", "CGROUP_SYSCTL", "cgroup_sysctl"),
+    ("cgroup/getsockopt", "CGROUP_SOCKOPT", "cgroup_getsockopt"),
+    ("cgroup/setsockopt", "CGROUP_SOCKOPT", "cgroup_setsockopt"),
+    ("cgroup/dev", "CGROUP_DEVICE", "cgroup_device"),
+    ("struct_ops/", "STRUCT_OPS", "struct_ops"),
+    ("struct_ops", "STRUCT_OPS", "struct_ops"),
+    ("sk_lookup", "SK_LOOKUP", "sk_lookup"),
+    ("syscall", "SYSCALL", "syscall"),
+    ("netfilter", "NETFILTER", "netfilter"),
+];
+
+#[derive(Debug, Clone)]
+pub struct BpfProgram {
+    /// Section header index of the program
+    pub index: usize,
+    pub section: String,
+    /// `BPF_PROG_TYPE_*` without the prefix, `None` for sections libbpf would not load
+    pub prog_type: Option<&'static str>,
+    pub attach: Option<&'static str>,
+    /// Whatever follows the attach kind in the section name, e.g. the kprobe'd function
+    pub target: Option<String>,
+    pub insns: u64,
+    pub functions: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct BpfMap {
+    pub name: String,
+    pub section: String,
+    pub map_type: u32,
+    pub key_size: Option<u64>,
+    pub value_size: Option<u64>,
+    pub max_entries: Option<u32>,
+    pub flags: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BpfRelocTarget {
+    /// A map defined in `maps`/`.maps`
+    Map(String),
+    /// A global variable living in the implicit `.data`/`.rodata`/`.bss` array maps
+    GlobalData(String),
+    /// A BPF-to-BPF call or a reference to another program's code
+    Subprog(String),
+    /// An `extern` resolved by the loader (kconfig or ksym)
+    Extern(String),
+    Unknown(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct BpfReloc {
+    pub section: String,
+    pub offset: u64,
+    pub rtype: u32,
+    pub symbol: String,
+    pub target: BpfRelocTarget,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct BpfObject {
+    pub license: Option<String>,
+    pub kern_version: Option<u32>,
+    pub programs: Vec<BpfProgram>,
+    pub maps: Vec<BpfMap>,
+    pub relocations: Vec<BpfReloc>,
+}
+
+/// Derives the program type, attach kind and attach target from a section name
+pub fn section_program_type(name: &str) -> Option<(&'static str, &'static str, Option<String>)> {
+    SECTION_DEFS
+        .iter()
+        .find(|(prefix, _, _)| {
+            name == *prefix
+                || prefix.ends_with('/') && name.starts_with(prefix)
+                || name.starts_with(prefix) && name[prefix.len()..].starts_with('/')
+        })
+        .map(|(prefix, prog_type, attach)| {
+            let rest = name[prefix.len()..].trim_start_matches('/');
+            (
+                *prog_type,
+                *attach,
+                (!rest.is_empty()).then(|| rest.to_string()),
+            )
+        })
+}
+
+pub fn map_type_name(map_type: u32) -> &'static str {
+    match map_type {
+        0 => "UNSPEC",
+        1 => "HASH",
+        2 => "ARRAY",
+        3 => "PROG_ARRAY",
+        4 => "PERF_EVENT_ARRAY",
+        5 => "PERCPU_HASH",
+        6 => "PERCPU_ARRAY",
+        7 => "STACK_TRACE",
+        8 => "CGROUP_ARRAY",
+        9 => "LRU_HASH",
+        10 => "LRU_PERCPU_HASH",
+        11 => "LPM_TRIE",
+        12 => "ARRAY_OF_MAPS",
+        13 => "HASH_OF_MAPS",
+        14 => "DEVMAP",
+        15 => "SOCKMAP",
+        16 => "CPUMAP",
+        17 => "XSKMAP",
+        18 => "SOCKHASH",
+        19 => "CGROUP_STORAGE",
+        20 => "REUSEPORT_SOCKARRAY",
+        21 => "PERCPU_CGROUP_STORAGE",
+        22 => "QUEUE",
+        23 => "STACK",
+        24 => "SK_STORAGE",
+        25 => "DEVMAP_HASH",
+        26 => "STRUCT_OPS",
+        27 => "RINGBUF",
+        28 => "INODE_STORAGE",
+        29 => "TASK_STORAGE",
+        30 => "BLOOM_FILTER",
+        31 => "USER_RINGBUF",
+        32 => "CGRP_STORAGE",
+        _ => "UNKNOWN",
+    }
+}
+
+fn is_data_section(name: &str) -> bool {
+    [".data", ".rodata", ".bss", ".kconfig"]
+        .iter()
+        .any(|prefix| name == *prefix || name.starts_with(&format!("{}.", prefix)))
+}
+
+/// Decodes a BTF-defined map, where every attribute is a member whose type encodes
+/// the value: `__uint(name, val)` is a pointer to an array of `val` elements and
+/// `__type(name, T)` is a pointer to `T`
+fn btf_map(btf: &Btf, name: String, type_id: u32) -> Option<BpfMap> {
+    let def = btf.resolve_type(type_id)?;
+    if def.kind() != BtfKind::Struct {
+        return None;
+    }
+
+    let mut map = BpfMap {
+        name,
+        section: ".maps".to_string(),
+        map_type: 0,
+        key_size: None,
+        value_size: None,
+        max_entries: None,
+        flags: None,
+    };
+
+    for member in &def.members {
+        let pointee = match btf.resolve_type(member.type_id) {
+            Some(ty) if ty.kind() == BtfKind::Ptr => ty.size_or_type,
+            _ => continue,
+        };
+        let uint = || {
+            btf.resolve_type(pointee)
+                .and_then(|ty| ty.array)
+                .map(|(_, _, nelems)| nelems)
+        };
+
+        match btf.string(member.name_off).as_str() {
+            "type" => map.map_type = uint().unwrap_or(0),
+            "max_entries" => map.max_entries = uint(),
+            "map_flags" => map.flags = uint(),
+            "key_size" => map.key_size = uint().map(u64::from),
+            "value_size" => map.value_size = uint().map(u64::from),
+            "key" => map.key_size = btf.type_size(pointee),
+            "value" => map.value_size = btf.type_size(pointee),
+            _ => {}
+        }
+    }
+
+    Some(map)
+}
+
+impl BpfObject {
+    pub fn read(elf: &FileData) -> io::Result<Self> {
+        let endian = elf.header().endian().unwrap_or(Endian::Little);
+        let shdrs = elf.section_headers().to_vec();
+        let names = shdrs
+            .iter()
+            .map(|shdr| elf.section_name(shdr).unwrap_or_default())
+            .collect::<Vec<_>>();
+
+        let mut obj = BpfObject::default();
+
+        if let Some(shdr) = elf.section_by_name("license") {
+            let data = elf.section_data(&shdr)?;
+            obj.license = Some(string_at(&data, 0));
+        }
+
+        if let Some(shdr) = elf.section_by_name("version") {
+            let data = elf.section_data(&shdr)?;
+            obj.kern_version = ByteReader::new(&data, endian).u32();
+        }
+
+        let (strtab, syms) = match shdrs
+            .iter()
+            .find(|shdr| shdr.section_type() == Some(SectionType::SymTab))
+        {
+            Some(symtab) => elf.symbol_table(symtab)?,
+            None => Default::default(),
+        };
+
+        for (index, shdr) in shdrs.iter().enumerate() {
+            if shdr.flags() & SectionFlag::ExecInstr as u64 == 0 || shdr.size() == 0 {
+                continue;
+            }
+
+            let section = names[index].clone();
+            let (prog_type, attach, target) = match section_program_type(&section) {
+                Some((prog_type, attach, target)) => (Some(prog_type), Some(attach), target),
+                None => (None, None, None),
+            };
+
+            let functions = syms
+                .iter()
+                .filter(|sym| {
+                    sym.shndx() as usize == index
+                        && matches!(sym.symbol_type(), Some(SymbolType::Func))
+                })
+                .map(|sym| string_at(strtab, sym.name() as usize))
+                .collect();
+
+            obj.programs.push(BpfProgram {
+                index,
+                section,
+                prog_type,
+                attach,
+                target,
+                insns: shdr.size() / BPF_INSN_SIZE,
+                functions,
+            });
+        }
+
+        // Legacy `struct bpf_map_def` arrays, one symbol per definition
+        if let Some(index) = names.iter().position(|name| name == "maps") {
+            let data = elf.section_data(&shdrs[index])?;
+            let mut defs = syms
+                .iter()
+                .filter(|sym| {
+                    sym.shndx() as usize == index
+                        && !matches!(sym.symbol_type(), Some(SymbolType::Section))
+                })
+                .collect::<Vec<_>>();
+            defs.sort_by_key(|sym| sym.value());
+
+            for sym in defs {
+                let mut reader = ByteReader::new(&data, endian);
+                if reader.seek(sym.value() as usize).is_none() {
+                    continue;
+                }
+
+                obj.maps.push(BpfMap {
+                    name: string_at(strtab, sym.name() as usize),
+                    section: "maps".to_string(),
+                    map_type: reader.u32().unwrap_or(0),
+                    key_size: reader.u32().map(u64::from),
+                    value_size: reader.u32().map(u64::from),
+                    max_entries: reader.u32(),
+                    flags: reader.u32(),
+                });
+            }
+        }
+
+        let btf = match elf.section_by_name(".BTF") {
+            Some(shdr) => Btf::parse(&elf.section_data(&shdr)?),
+            None => None,
+        };
+
+        if let Some(btf) = &btf {
+            let maps_sec = btf
+                .types
+                .iter()
+                .find(|ty| ty.kind() == BtfKind::DataSec && btf.string(ty.name_off) == ".maps");
+
+            for var in maps_sec
+                .map(|sec| sec.members.as_slice())
+                .unwrap_or_default()
+            {
+                if let Some(var) = btf.type_by_id(var.type_id) {
+                    if let Some(map) = btf_map(btf, btf.type_name(var), var.size_or_type) {
+                        obj.maps.push(map);
+                    }
+                }
+            }
+        }
+
+        for (index, name) in names.iter().enumerate() {
+            let shdr = &shdrs[index];
+            if is_data_section(name) && shdr.flags() & SectionFlag::Alloc as u64 != 0 {
+                obj.maps.push(BpfMap {
+                    name: name.clone(),
+                    section: name.clone(),
+                    map_type: 2,
+                    key_size: Some(4),
+                    value_size: Some(shdr.size()),
+                    max_entries: Some(1),
+                    flags: None,
+                });
+            }
+        }
+
+        let programs = obj.programs.iter().map(|p| p.index).collect::<Vec<_>>();
+        for (shdr, rels) in elf.relocations()? {
+            let section = shdr.info() as usize;
+            if !programs.contains(&section) {
+                continue;
+            }
+
+            for rel in rels {
+                let sym = match syms.get(rel.sym() as usize) {
+                    Some(sym) => sym,
+                    None => continue,
+                };
+
+                let shndx = sym.shndx() as usize;
+                let sym_section = names.get(shndx).cloned().unwrap_or_default();
+                let mut symbol = string_at(strtab, sym.name() as usize);
+                if symbol.is_empty() {
+                    symbol = sym_section.clone();
+                }
+
+                let target = if shndx == 0 {
+                    BpfRelocTarget::Extern(symbol.clone())
+                } else if sym_section == "maps" || sym_section == ".maps" {
+                    BpfRelocTarget::Map(symbol.clone())
+                } else if is_data_section(&sym_section) {
+                    BpfRelocTarget::GlobalData(sym_section)
+                } else if programs.contains(&shndx) {
+                    BpfRelocTarget::Subprog(symbol.clone())
+                } else {
+                    BpfRelocTarget::Unknown(sym_section)
+                };
+
+                obj.relocations.push(BpfReloc {
+                    section: names[section].clone(),
+                    offset: rel.offset(),
+                    rtype: rel.rtype(),
+                    symbol,
+                    target,
+                });
+            }
+        }
+
+        Ok(obj)
+    }
+}
@@ -0,0 +1,179 @@
+use std::{collections::HashSet, io};
+
+use super::{
+    bytes::string_at,
+    core::FileData,
+    dynamic::{DT_AARCH64_BTI_PLT, DT_AARCH64_PAC_PLT},
+    hdr::{ElfClass, Endian},
+    property::{
+        GnuProperty, GNU_PROPERTY_AARCH64_FEATURE_1_AND, GNU_PROPERTY_AARCH64_FEATURE_1_BTI,
+        GNU_PROPERTY_AARCH64_FEATURE_1_PAC,
+    },
+    shdr::{SectionFlag, SectionType},
+    sym::{SymbolBinding, SymbolType},
+};
+
+/// `bti`, `bti c`, `bti j` and `bti jc`, the hint instructions a BTI enabled branch may
+/// land on
+const BTI: [u32; 4] = [0xd503241f, 0xd503245f, 0xd503249f, 0xd50324df];
+/// `paciasp` and `pacibsp`, which sign the return address and also act as `bti c`
+const PACIXSP: [u32; 2] = [0xd503233f, 0xd503237f];
+/// `autia1716`, which the PAC PLT entries run on x17 before branching to it
+const AUTIA1716: u32 = 0xd503219f;
+
+/// Control-flow protection of an AArch64 file: what the GNU property note claims, what the
+/// dynamic linker is told about the PLT, and what the code actually does
+#[derive(Debug, Clone, Default)]
+pub struct BranchProtection {
+    /// The `GNU_PROPERTY_AARCH64_FEATURE_1_AND` bitmask, `None` without the property,
+    /// which is what objects built without `-mbranch-protection` get
+    pub feature_1_and: Option<u32>,
+    pub bti_plt_tag: bool,
+    pub pac_plt_tag: bool,
+    /// `.plt` entries starting with a `bti c` landing pad
+    pub bti_plt: bool,
+    /// `.plt` entries authenticating their target with `autia1716`
+    pub pac_plt: bool,
+    /// Functions with code, from `.symtab` or else `.dynsym`
+    pub functions: usize,
+    /// Functions starting on a landing pad, `bti` or `paciasp`
+    pub landing_pads: usize,
+    /// Functions signing their return address
+    pub signed_returns: usize,
+    /// Global functions without a landing pad, which fault when called through a pointer
+    /// or the PLT with BTI enforced. Compilers leave the pad out of local functions whose
+    /// address is never taken, so those are not listed.
+    pub unprotected: Vec<String>,
+}
+
+impl BranchProtection {
+    pub fn read(elf: &FileData) -> io::Result<Self> {
+        let class = elf.header().class().unwrap_or(ElfClass::ElfClass64);
+        let endian = elf.header().endian().unwrap_or(Endian::Little);
+        let mut report = Self::default();
+
+        for note in elf.notes()? {
+            for property in GnuProperty::parse(&note, class, endian) {
+                if property.ptype == GNU_PROPERTY_AARCH64_FEATURE_1_AND {
+                    report.feature_1_and = property.bitmask(endian);
+                }
+            }
+        }
+        for entry in elf.dynamic_section() {
+            match entry.tag {
+                DT_AARCH64_BTI_PLT => report.bti_plt_tag = true,
+                DT_AARCH64_PAC_PLT => report.pac_plt_tag = true,
+                _ => {}
+            }
+        }
+
+        // Instructions are little-endian even in big-endian files
+        let code = elf
+            .section_headers()
+            .iter()
+            .filter(|shdr| shdr.flags() & SectionFlag::ExecInstr as u64 != 0)
+            .filter(|shdr| shdr.section_type() == Some(SectionType::ProgBits))
+            .map(|shdr| Ok((*shdr, elf.section_data(shdr)?)))
+            .collect::<io::Result<Vec<_>>>()?;
+        let instructions = |data: &[u8]| {
+            data.chunks_exact(4)
+                .map(|word| u32::from_le_bytes([word[0], word[1], word[2], word[3]]))
+                .collect::<Vec<_>>()
+        };
+
+        if let Some((_, plt)) = code
+            .iter()
+            .find(|(shdr, _)| elf.section_name(shdr).as_deref() == Some(".plt"))
+        {
+            let plt = instructions(plt);
+            report.bti_plt = plt.first().map(|insn| BTI.contains(insn)) == Some(true);
+            report.pac_plt = plt.contains(&AUTIA1716);
+        }
+
+        let tables = elf.table_symbols()?;
+        let symbols = tables
+            .iter()
+            .find(|(name, _, _)| name == ".symtab")
+            .or_else(|| tables.first());
+        if let Some((_, strtab, syms)) = symbols {
+            let mut seen = HashSet::new();
+            for sym in syms.iter() {
+                if sym.symbol_type() != Some(SymbolType::Func)
+                    || sym.shndx() == 0
+                    || sym.size() == 0
+                    || !seen.insert(sym.value())
+                {
+                    continue;
+                }
+                let body = code.iter().find_map(|(shdr, data)| {
+                    let start = sym.value().checked_sub(shdr.addr())? as usize;
+                    data.get(start..start.checked_add(sym.size() as usize)?)
+                });
+                let body = match body {
+                    Some(body) => instructions(body),
+                    None => continue,
+                };
+
+                report.functions += 1;
+                let entry = body.first().copied().unwrap_or(0);
+                if BTI.contains(&entry) || PACIXSP.contains(&entry) {
+                    report.landing_pads += 1;
+                } else if sym.binding() != Some(SymbolBinding::Local) {
+                    report
+                        .unprotected
+                        .push(string_at(strtab, sym.name() as usize));
+                }
+                if body.iter().any(|insn| PACIXSP.contains(insn)) {
+                    report.signed_returns += 1;
+                }
+            }
+        }
+        report.unprotected.sort();
+
+        Ok(report)
+    }
+
+    pub fn bti(&self) -> bool {
+        self.feature_1_and
+            .map(|bitmask| bitmask & GNU_PROPERTY_AARCH64_FEATURE_1_BTI != 0)
+            == Some(true)
+    }
+
+    pub fn pac(&self) -> bool {
+        self.feature_1_and
+            .map(|bitmask| bitmask & GNU_PROPERTY_AARCH64_FEATURE_1_PAC != 0)
+            == Some(true)
+    }
+
+    /// Inconsistencies between the property note, the dynamic tags and the code, each of
+    /// which either weakens the protection or breaks the program once it is enforced
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.bti() && !self.unprotected.is_empty() {
+            problems.push(format!(
+                "marked BTI but {} global functions have no landing pad",
+                self.unprotected.len()
+            ));
+        }
+        if self.bti_plt_tag != self.bti_plt {
+            problems.push(match self.bti_plt_tag {
+                true => "DT_AARCH64_BTI_PLT is set but the PLT has no bti c".to_string(),
+                false => "the PLT has bti c but DT_AARCH64_BTI_PLT is not set".to_string(),
+            });
+        }
+        if self.pac_plt_tag != self.pac_plt {
+            problems.push(match self.pac_plt_tag {
+                true => "DT_AARCH64_PAC_PLT is set but the PLT does not authenticate".to_string(),
+                false => "the PLT authenticates but DT_AARCH64_PAC_PLT is not set".to_string(),
+            });
+        }
+        if !self.bti() && self.functions != 0 && self.landing_pads == self.functions {
+            problems.push(
+                "every function has a landing pad but the file is not marked BTI, some input \
+                 object lacks the property"
+                    .to_string(),
+            );
+        }
+        problems
+    }
+}
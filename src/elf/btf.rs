@@ -0,0 +1,600 @@
+// BPF Type Format, see https://www.kernel.org/doc/html/latest/bpf/btf.html
+
+use num_derive::FromPrimitive;
+use num_traits::FromPrimitive;
+
+use super::{
+    bytes::{string_at, ByteReader},
+    hdr::Endian,
+};
+
+pub const BTF_MAGIC: u16 = 0xeb9f;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromPrimitive)]
+pub enum BtfKind {
+    Unknown,
+    Int,
+    Ptr,
+    Array,
+    Struct,
+    Union,
+    Enum,
+    Fwd,
+    Typedef,
+    Volatile,
+    Const,
+    Restrict,
+    Func,
+    FuncProto,
+    Var,
+    DataSec,
+    Float,
+    DeclTag,
+    TypeTag,
+    Enum64,
+}
+
+#[derive(Debug, Clone)]
+pub struct BtfHeader {
+    pub version: u8,
+    pub flags: u8,
+    pub hdr_len: u32,
+    pub type_off: u32,
+    pub type_len: u32,
+    pub str_off: u32,
+    pub str_len: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct BtfMember {
+    pub name_off: u32,
+    pub type_id: u32,
+    /// Bit offset for struct/union members, the value for enumerators and the
+    /// offset in bytes for data section variables
+    pub offset: u64,
+    /// Bitfield size for struct members when `kind_flag` is set, or the size of a
+    /// data section variable
+    pub size: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct BtfType {
+    pub name_off: u32,
+    pub info: u32,
+    /// Either the size of the type or a referenced type id depending on the kind
+    pub size_or_type: u32,
+    /// Kind specific trailing data (int encoding, var linkage, decl tag component)
+    pub extra: u32,
+    pub array: Option<(u32, u32, u32)>,
+    pub members: Vec<BtfMember>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Btf {
+    pub header: BtfHeader,
+    pub endian: Endian,
+    pub types: Vec<BtfType>,
+    pub strings: Vec<u8>,
+}
+
+#[derive(Debug, Clone)]
+pub struct BtfFuncInfo {
+    pub insn_off: u32,
+    pub type_id: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct BtfLineInfo {
+    pub insn_off: u32,
+    pub file_name_off: u32,
+    pub line_off: u32,
+    pub line: u32,
+    pub column: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct BtfExtSection<T> {
+    pub sec_name_off: u32,
+    pub records: Vec<T>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct BtfExt {
+    pub func_info: Vec<BtfExtSection<BtfFuncInfo>>,
+    pub line_info: Vec<BtfExtSection<BtfLineInfo>>,
+}
+
+fn detect_endian(data: &[u8]) -> Option<Endian> {
+    match data.get(..2)? {
+        b if u16::from_le_bytes([b[0], b[1]]) == BTF_MAGIC => Some(Endian::Little),
+        b if u16::from_be_bytes([b[0], b[1]]) == BTF_MAGIC => Some(Endian::Big),
+        _ => None,
+    }
+}
+
+impl BtfType {
+    pub fn kind(&self) -> BtfKind {
+        BtfKind::from_u32((self.info >> 24) & 0x1f).unwrap_or(BtfKind::Unknown)
+    }
+
+    pub fn vlen(&self) -> u32 {
+        self.info & 0xffff
+    }
+
+    pub fn kind_flag(&self) -> bool {
+        self.info >> 31 == 1
+    }
+
+    fn read(reader: &mut ByteReader) -> Option<Self> {
+        let mut ty = Self {
+            name_off: reader.u32()?,
+            info: reader.u32()?,
+            size_or_type: reader.u32()?,
+            extra: 0,
+            array: None,
+            members: Vec::new(),
+        };
+
+        let vlen = ty.vlen();
+        match ty.kind() {
+            BtfKind::Int | BtfKind::Var | BtfKind::DeclTag => ty.extra = reader.u32()?,
+            BtfKind::Array => ty.array = Some((reader.u32()?, reader.u32()?, reader.u32()?)),
+            BtfKind::Struct | BtfKind::Union => {
+                for _ in 0..vlen {
+                    let name_off = reader.u32()?;
+                    let type_id = reader.u32()?;
+                    let offset = reader.u32()?;
+                    let (offset, size) = if ty.kind_flag() {
+                        (offset & 0xffffff, offset >> 24)
+                    } else {
+                        (offset, 0)
+                    };
+                    ty.members.push(BtfMember {
+                        name_off,
+                        type_id,
+                        offset: offset as u64,
+                        size,
+                    });
+                }
+            }
+            BtfKind::Enum => {
+                for _ in 0..vlen {
+                    ty.members.push(BtfMember {
+                        name_off: reader.u32()?,
+                        type_id: 0,
+                        offset: reader.i32()? as i64 as u64,
+                        size: 0,
+                    });
+                }
+            }
+            BtfKind::Enum64 => {
+                for _ in 0..vlen {
+                    let name_off = reader.u32()?;
+                    let lo = reader.u32()? as u64;
+                    let hi = reader.u32()? as u64;
+                    ty.members.push(BtfMember {
+                        name_off,
+                        type_id: 0,
+                        offset: hi << 32 | lo,
+                        size: 0,
+                    });
+                }
+            }
+            BtfKind::FuncProto => {
+                for _ in 0..vlen {
+                    ty.members.push(BtfMember {
+                        name_off: reader.u32()?,
+                        type_id: reader.u32()?,
+                        offset: 0,
+                        size: 0,
+                    });
+                }
+            }
+            BtfKind::DataSec => {
+                for _ in 0..vlen {
+                    ty.members.push(BtfMember {
+                        name_off: 0,
+                        type_id: reader.u32()?,
+                        offset: reader.u32()? as u64,
+                        size: reader.u32()?,
+                    });
+                }
+            }
+            _ => {}
+        }
+
+        Some(ty)
+    }
+}
+
+impl Btf {
+    /// Parses the contents of a `.BTF` section (or a raw BTF blob such as
+    /// `/sys/kernel/btf/vmlinux`)
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        let endian = detect_endian(data)?;
+        let mut reader = ByteReader::new(data, endian);
+        reader.skip(2)?;
+
+        let header = BtfHeader {
+            version: reader.u8()?,
+            flags: reader.u8()?,
+            hdr_len: reader.u32()?,
+            type_off: reader.u32()?,
+            type_len: reader.u32()?,
+            str_off: reader.u32()?,
+            str_len: reader.u32()?,
+        };
+
+        let base = header.hdr_len as usize;
+        let str_start = base + header.str_off as usize;
+        let strings = data
+            .get(str_start..str_start + header.str_len as usize)?
+            .to_vec();
+
+        let type_start = base + header.type_off as usize;
+        let type_data = data.get(type_start..type_start + header.type_len as usize)?;
+        let mut reader = ByteReader::new(type_data, endian);
+
+        let mut types = Vec::new();
+        while !reader.is_empty() {
+            types.push(BtfType::read(&mut reader)?);
+        }
+
+        Some(Self {
+            header,
+            endian,
+            types,
+            strings,
+        })
+    }
+
+    pub fn string(&self, off: u32) -> String {
+        string_at(&self.strings, off as usize)
+    }
+
+    /// Type ids are 1-based, id 0 is reserved for `void`
+    pub fn type_by_id(&self, id: u32) -> Option<&BtfType> {
+        self.types.get((id as usize).checked_sub(1)?)
+    }
+
+    /// Follows typedefs and cv-qualifiers down to the underlying type
+    pub fn resolve_type(&self, mut id: u32) -> Option<&BtfType> {
+        for _ in 0..self.types.len() {
+            let ty = self.type_by_id(id)?;
+            match ty.kind() {
+                BtfKind::Typedef
+                | BtfKind::Volatile
+                | BtfKind::Const
+                | BtfKind::Restrict
+                | BtfKind::TypeTag => id = ty.size_or_type,
+                _ => return Some(ty),
+            }
+        }
+
+        None
+    }
+
+    /// Size in bytes of a type, `None` for types without a size such as `void`
+    /// or function prototypes
+    pub fn type_size(&self, id: u32) -> Option<u64> {
+        let ty = self.resolve_type(id)?;
+        match ty.kind() {
+            BtfKind::Int
+            | BtfKind::Struct
+            | BtfKind::Union
+            | BtfKind::Enum
+            | BtfKind::Enum64
+            | BtfKind::DataSec
+            | BtfKind::Float => Some(ty.size_or_type as u64),
+            BtfKind::Ptr => Some(8),
+            BtfKind::Array => {
+                let (elem, _, nelems) = ty.array?;
+                Some(self.type_size(elem)? * nelems as u64)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn type_name(&self, ty: &BtfType) -> String {
+        match self.string(ty.name_off) {
+            s if s.is_empty() => "(anon)".to_string(),
+            s => s,
+        }
+    }
+
+    /// Formats a type the way `bpftool btf dump` does in raw mode, members are
+    /// printed on their own tab-indented lines
+    pub fn display_type(&self, id: usize, ty: &BtfType) -> String {
+        let kind = ty.kind();
+        let mut s = format!("[{}] {} '{}'", id, kind.display(), self.type_name(ty));
+
+        match kind {
+            BtfKind::Int => s.push_str(&format!(
+                " size={} bits_offset={} nr_bits={} encoding={}",
+                ty.size_or_type,
+                (ty.extra >> 16) & 0xff,
+                ty.extra & 0xff,
+                match (ty.extra >> 24) & 0xf {
+                    1 => "SIGNED",
+                    2 => "CHAR",
+                    4 => "BOOL",
+                    _ => "(none)",
+                }
+            )),
+            BtfKind::Ptr
+            | BtfKind::Typedef
+            | BtfKind::Volatile
+            | BtfKind::Const
+            | BtfKind::Restrict
+            | BtfKind::TypeTag => s.push_str(&format!(" type_id={}", ty.size_or_type)),
+            BtfKind::Array => {
+                let (elem, index, nelems) = ty.array.unwrap_or_default();
+                s.push_str(&format!(
+                    " type_id={} index_type_id={} nr_elems={}",
+                    elem, index, nelems
+                ))
+            }
+            BtfKind::Struct | BtfKind::Union => {
+                s.push_str(&format!(" size={} vlen={}", ty.size_or_type, ty.vlen()));
+                for m in &ty.members {
+                    s.push_str(&format!(
+                        "\n\t'{}' type_id={} bits_offset={}",
+                        self.string(m.name_off),
+                        m.type_id,
+                        m.offset
+                    ));
+                    if ty.kind_flag() && m.size != 0 {
+                        s.push_str(&format!(" bitfield_size={}", m.size));
+                    }
+                }
+            }
+            BtfKind::Enum | BtfKind::Enum64 => {
+                s.push_str(&format!(
+                    " encoding={} size={} vlen={}",
+                    if ty.kind_flag() { "SIGNED" } else { "UNSIGNED" },
+                    ty.size_or_type,
+                    ty.vlen()
+                ));
+                for m in &ty.members {
+                    if ty.kind_flag() {
+                        s.push_str(&format!(
+                            "\n\t'{}' val={}",
+                            self.string(m.name_off),
+                            m.offset as i64
+                        ));
+                    } else {
+                        s.push_str(&format!(
+                            "\n\t'{}' val={}",
+                            self.string(m.name_off),
+                            m.offset
+                        ));
+                    }
+                }
+            }
+            BtfKind::Fwd => s.push_str(if ty.kind_flag() {
+                " fwd_kind=union"
+            } else {
+                " fwd_kind=struct"
+            }),
+            BtfKind::Func => s.push_str(&format!(
+                " type_id={} linkage={}",
+                ty.size_or_type,
+                match ty.vlen() {
+                    0 => "static",
+                    1 => "global",
+                    2 => "extern",
+                    _ => "(unknown)",
+                }
+            )),
+            BtfKind::FuncProto => {
+                s.push_str(&format!(
+                    " ret_type_id={} vlen={}",
+                    ty.size_or_type,
+                    ty.vlen()
+                ));
+                for m in &ty.members {
+                    s.push_str(&format!(
+                        "\n\t'{}' type_id={}",
+                        self.string(m.name_off),
+                        m.type_id
+                    ));
+                }
+            }
+            BtfKind::Var => s.push_str(&format!(
+                " type_id={}, linkage={}",
+                ty.size_or_type,
+                match ty.extra {
+                    0 => "static",
+                    1 => "global-alloc",
+                    2 => "extern",
+                    _ => "(unknown)",
+                }
+            )),
+            BtfKind::DataSec => {
+                s.push_str(&format!(" size={} vlen={}", ty.size_or_type, ty.vlen()));
+                for m in &ty.members {
+                    s.push_str(&format!(
+                        "\n\ttype_id={} offset={} size={}",
+                        m.type_id, m.offset, m.size
+                    ));
+                }
+            }
+            BtfKind::Float => s.push_str(&format!(" size={}", ty.size_or_type)),
+            BtfKind::DeclTag => s.push_str(&format!(
+                " type_id={} component_idx={}",
+                ty.size_or_type, ty.extra as i32
+            )),
+            BtfKind::Unknown => {}
+        }
+
+        s
+    }
+}
+
+impl BtfExt {
+    /// Parses the contents of a `.BTF.ext` section. String offsets in the records
+    /// refer to the string section of the accompanying `.BTF`
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        let endian = detect_endian(data)?;
+        let mut reader = ByteReader::new(data, endian);
+        reader.skip(4)?;
+
+        let hdr_len = reader.u32()? as usize;
+        let func_info_off = reader.u32()? as usize;
+        let func_info_len = reader.u32()? as usize;
+        let line_info_off = reader.u32()? as usize;
+        let line_info_len = reader.u32()? as usize;
+
+        let func_data =
+            data.get(hdr_len + func_info_off..hdr_len + func_info_off + func_info_len)?;
+        let line_data =
+            data.get(hdr_len + line_info_off..hdr_len + line_info_off + line_info_len)?;
+
+        Some(Self {
+            func_info: Self::read_info(func_data, endian, |r| {
+                Some(BtfFuncInfo {
+                    insn_off: r.u32()?,
+                    type_id: r.u32()?,
+                })
+            })?,
+            line_info: Self::read_info(line_data, endian, |r| {
+                let insn_off = r.u32()?;
+                let file_name_off = r.u32()?;
+                let line_off = r.u32()?;
+                let line_col = r.u32()?;
+                Some(BtfLineInfo {
+                    insn_off,
+                    file_name_off,
+                    line_off,
+                    line: line_col >> 10,
+                    column: line_col & 0x3ff,
+                })
+            })?,
+        })
+    }
+
+    fn read_info<T, F>(data: &[u8], endian: Endian, read: F) -> Option<Vec<BtfExtSection<T>>>
+    where
+        F: Fn(&mut ByteReader) -> Option<T>,
+    {
+        let mut sections = Vec::new();
+        if data.is_empty() {
+            return Some(sections);
+        }
+
+        let mut reader = ByteReader::new(data, endian);
+        let rec_size = reader.u32()? as usize;
+
+        while !reader.is_empty() {
+            let sec_name_off = reader.u32()?;
+            let num_info = reader.u32()? as usize;
+            // The count comes from the file, it cannot be trusted before the records fit
+            if num_info > reader.remaining() / rec_size.max(1) {
+                return None;
+            }
+            let mut records = Vec::with_capacity(num_info);
+            for _ in 0..num_info {
+                let mut record = ByteReader::new(reader.bytes(rec_size)?, endian);
+                records.push(read(&mut record)?);
+            }
+            sections.push(BtfExtSection {
+                sec_name_off,
+                records,
+            });
+        }
+
+        Some(sections)
+    }
+}
+
+impl BtfKind {
+    pub fn display(&self) -> &'static str {
+        match self {
+            Self::Unknown => "UNKN",
+            Self::Int => "INT",
+            Self::Ptr => "PTR",
+            Self::Array => "ARRAY",
+            Self::Struct => "STRUCT",
+            Self::Union => "UNION",
+            Self::Enum => "ENUM",
+            Self::Fwd => "FWD",
+            Self::Typedef => "TYPEDEF",
+            Self::Volatile => "VOLATILE",
+            Self::Const => "CONST",
+            Self::Restrict => "RESTRICT",
+            Self::Func => "FUNC",
+            Self::FuncProto => "FUNC_PROTO",
+            Self::Var => "VAR",
+            Self::DataSec => "DATASEC",
+            Self::Float => "FLOAT",
+            Self::DeclTag => "DECL_TAG",
+            Self::TypeTag => "TYPE_TAG",
+            Self::Enum64 => "ENUM64",
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Btf, BtfExt, BtfKind};
+
+    fn blob(types: &[u32], strings: &[u8]) -> Vec<u8> {
+        let mut data = vec![0x9f, 0xeb, 1, 0];
+        let type_len = (types.len() * 4) as u32;
+        for v in [24, 0, type_len, type_len, strings.len() as u32] {
+            data.extend_from_slice(&v.to_le_bytes());
+        }
+        for v in types {
+            data.extend_from_slice(&v.to_le_bytes());
+        }
+        data.extend_from_slice(strings);
+        data
+    }
+
+    #[test]
+    fn parses_int_and_struct() {
+        let types = [
+            // [1] INT 'int' size=4 nr_bits=32 SIGNED
+            1,
+            1 << 24,
+            4,
+            1 << 24 | 32,
+            // [2] STRUCT 'foo' size=8 vlen=2
+            5,
+            4 << 24 | 2,
+            8,
+            9,
+            1,
+            0,
+            11,
+            1,
+            32,
+        ];
+        let btf = Btf::parse(&blob(&types, b"\0int\0foo\0a\0b\0")).unwrap();
+
+        assert_eq!(btf.types.len(), 2);
+        assert_eq!(btf.types[0].kind(), BtfKind::Int);
+        assert_eq!(btf.type_name(&btf.types[0]), "int");
+
+        let foo = btf.type_by_id(2).unwrap();
+        assert_eq!(foo.kind(), BtfKind::Struct);
+        assert_eq!(btf.type_name(foo), "foo");
+        assert_eq!(btf.string(foo.members[1].name_off), "b");
+        assert_eq!(foo.members[1].offset, 32);
+    }
+
+    #[test]
+    fn rejects_bad_magic() {
+        assert!(Btf::parse(&[0, 0, 1, 0]).is_none());
+    }
+
+    #[test]
+    fn rejects_ext_counts_past_the_data() {
+        let mut data = vec![0x9f, 0xeb, 1, 0];
+        // func_info holds one section claiming 0xffffffff records of 8 bytes
+        for v in [24, 0, 12, 12, 0, 8, 0, u32::MAX] {
+            data.extend_from_slice(&v.to_le_bytes());
+        }
+        assert!(BtfExt::parse(&data).is_none());
+    }
+}
@@ -0,0 +1,155 @@
+use alloc::string::String;
+
+use super::hdr::Endian;
+
+/// A bounds-checked cursor over raw section contents.
+///
+/// Every read returns `None` instead of panicking when the data is truncated, so
+/// decoders for loosely specified formats can bail out early on corrupt input.
+#[derive(Clone, Copy, Debug)]
+pub struct ByteReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    endian: Endian,
+}
+
+impl<'a> ByteReader<'a> {
+    pub fn new(data: &'a [u8], endian: Endian) -> Self {
+        Self {
+            data,
+            pos: 0,
+            endian,
+        }
+    }
+
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    pub fn endian(&self) -> Endian {
+        self.endian
+    }
+
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+
+    pub fn seek(&mut self, pos: usize) -> Option<()> {
+        if pos > self.data.len() {
+            return None;
+        }
+        self.pos = pos;
+        Some(())
+    }
+
+    pub fn skip(&mut self, n: usize) -> Option<()> {
+        self.seek(self.pos.checked_add(n)?)
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.data.len() - self.pos
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    pub fn bytes(&mut self, n: usize) -> Option<&'a [u8]> {
+        let end = self.pos.checked_add(n)?;
+        let slice = self.data.get(self.pos..end)?;
+        self.pos = end;
+        Some(slice)
+    }
+
+    pub fn u8(&mut self) -> Option<u8> {
+        self.bytes(1).map(|b| b[0])
+    }
+
+    pub fn u16(&mut self) -> Option<u16> {
+        let b = self.bytes(2)?.try_into().ok()?;
+        Some(match self.endian {
+            Endian::Little => u16::from_le_bytes(b),
+            Endian::Big => u16::from_be_bytes(b),
+        })
+    }
+
+    pub fn u32(&mut self) -> Option<u32> {
+        let b = self.bytes(4)?.try_into().ok()?;
+        Some(match self.endian {
+            Endian::Little => u32::from_le_bytes(b),
+            Endian::Big => u32::from_be_bytes(b),
+        })
+    }
+
+    pub fn u64(&mut self) -> Option<u64> {
+        let b = self.bytes(8)?.try_into().ok()?;
+        Some(match self.endian {
+            Endian::Little => u64::from_le_bytes(b),
+            Endian::Big => u64::from_be_bytes(b),
+        })
+    }
+
+    pub fn i32(&mut self) -> Option<i32> {
+        self.u32().map(|v| v as i32)
+    }
+
+    /// Reads a 4 or 8 byte word, for address sized fields
+    pub fn word(&mut self, size: usize) -> Option<u64> {
+        match size {
+            4 => self.u32().map(u64::from),
+            8 => self.u64(),
+            _ => None,
+        }
+    }
+
+    pub fn uleb128(&mut self) -> Option<u64> {
+        let mut result = 0u64;
+        let mut shift = 0;
+        loop {
+            let byte = self.u8()?;
+            if shift < 64 {
+                result |= ((byte & 0x7f) as u64) << shift;
+            }
+            shift += 7;
+            if byte & 0x80 == 0 {
+                return Some(result);
+            }
+        }
+    }
+
+    pub fn sleb128(&mut self) -> Option<i64> {
+        let mut result = 0i64;
+        let mut shift = 0;
+        loop {
+            let byte = self.u8()?;
+            if shift < 64 {
+                result |= ((byte & 0x7f) as i64) << shift;
+            }
+            shift += 7;
+            if byte & 0x80 == 0 {
+                if shift < 64 && byte & 0x40 != 0 {
+                    result |= -1 << shift;
+                }
+                return Some(result);
+            }
+        }
+    }
+
+    /// Reads a NUL-terminated string, consuming the terminator
+    pub fn cstr(&mut self) -> Option<&'a [u8]> {
+        let rest = self.data.get(self.pos..)?;
+        let len = rest.iter().position(|&c| c == 0)?;
+        self.pos += len + 1;
+        Some(&rest[..len])
+    }
+}
+
+/// Looks up a NUL-terminated string at `index` inside a string table
+pub fn string_at(table: &[u8], index: usize) -> String {
+    table
+        .iter()
+        .skip(index)
+        .take_while(|&&c| c != 0)
+        .map(|&c| c as char)
+        .collect()
+}
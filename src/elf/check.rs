@@ -0,0 +1,135 @@
+use super::{
+    core::FileData,
+    diag::Diagnostic,
+    dynamic::{dynamic_tag_name, DynamicTag},
+    hdr::{ElfClass, ObjectType},
+    phdr::{ElfPhdr, ProgramType},
+};
+
+/// Dynamic tags whose value is an address the dynamic linker reads or writes
+const POINTER_TAGS: [DynamicTag; 18] = [
+    DynamicTag::PltGot,
+    DynamicTag::Hash,
+    DynamicTag::StrTab,
+    DynamicTag::SymTab,
+    DynamicTag::Rela,
+    DynamicTag::Init,
+    DynamicTag::Fini,
+    DynamicTag::Rel,
+    DynamicTag::JmpRel,
+    DynamicTag::InitArray,
+    DynamicTag::FiniArray,
+    DynamicTag::PreInitArray,
+    DynamicTag::RelR,
+    DynamicTag::GnuHash,
+    DynamicTag::Versym,
+    DynamicTag::Verdef,
+    DynamicTag::Verneed,
+    DynamicTag::SymtabShndx,
+];
+
+fn contains(phdr: &ElfPhdr, addr: u64) -> bool {
+    addr >= phdr.vaddr() && addr - phdr.vaddr() < phdr.memsz()
+}
+
+impl FileData {
+    /// Addresses in the headers that do not agree with the segments: an entry point
+    /// outside the executable `PT_LOAD` segments, a `PT_PHDR` that does not describe the
+    /// program header table or is not loaded, and dynamic entries pointing at memory no
+    /// segment maps
+    pub fn pointer_diagnostics(&self) -> Vec<Diagnostic> {
+        let mut warnings = Vec::new();
+        let loads = self
+            .program_headers()
+            .iter()
+            .enumerate()
+            .filter(|(_, phdr)| phdr.program_type() == Some(ProgramType::Load))
+            .collect::<Vec<_>>();
+        let loaded = |addr: u64| loads.iter().find(|(_, phdr)| contains(phdr, addr));
+
+        let header = self.header();
+        let entry = header.e_entry;
+        // e_entry sits at the same offset in both classes
+        let entry_field = 0x18;
+        if matches!(header.ftype(), Some(ObjectType::Exec | ObjectType::Dyn)) && entry != 0 {
+            match loaded(entry) {
+                Some((_, phdr)) if phdr.flags().execute() => {}
+                Some((i, _)) => warnings.push(
+                    Diagnostic::warning(
+                        "entry-not-executable",
+                        format!(
+                            "entry point 0x{:x} is in segment {}, which is not executable",
+                            entry, i
+                        ),
+                    )
+                    .at(entry_field),
+                ),
+                None => warnings.push(
+                    Diagnostic::warning(
+                        "entry-outside-load",
+                        format!(
+                            "entry point 0x{:x} is not inside any loadable segment",
+                            entry
+                        ),
+                    )
+                    .at(entry_field),
+                ),
+            }
+        }
+
+        let phdr_segment = self
+            .program_headers()
+            .iter()
+            .position(|phdr| phdr.program_type() == Some(ProgramType::Phdr));
+        if let Some(i) = phdr_segment {
+            let phdr = &self.program_headers()[i];
+            let phdr_entry = header.e_phoff + i as u64 * header.e_phentsize as u64;
+            let size = header.e_phnum as u64 * header.e_phentsize as u64;
+            if phdr.offset() != header.e_phoff || phdr.filesz() != size {
+                let message = format!(
+                    "PT_PHDR covers 0x{:x} bytes at offset 0x{:x}, the program headers take \
+                     0x{:x} bytes at offset 0x{:x}",
+                    phdr.filesz(),
+                    phdr.offset(),
+                    size,
+                    header.e_phoff
+                );
+                warnings.push(Diagnostic::warning("phdr-mismatch", message).at(phdr_entry));
+            }
+            if !loads.is_empty() && loaded(phdr.vaddr()).is_none() {
+                let message = format!(
+                    "PT_PHDR at 0x{:x} is not inside any loadable segment",
+                    phdr.vaddr()
+                );
+                warnings.push(Diagnostic::warning("phdr-not-loaded", message).at(phdr_entry));
+            }
+            if matches!(loads.first(), Some(&(first, _)) if first < i) {
+                let message = "PT_PHDR does not precede the loadable segments";
+                warnings.push(Diagnostic::warning("phdr-order", message).at(phdr_entry));
+            }
+        }
+
+        if self.has_dynamic_section() && !loads.is_empty() {
+            let entsize = match header.class() {
+                Some(ElfClass::ElfClass32) => 8,
+                _ => 16,
+            };
+            for (i, entry) in self.dynamic_section().iter().enumerate() {
+                let value = unsafe { entry.value.val };
+                let pointer = POINTER_TAGS.iter().any(|&tag| entry.tag == tag as u64);
+                if pointer && value != 0 && loaded(value).is_none() {
+                    let message = format!(
+                        "{} points at 0x{:x}, outside every loadable segment",
+                        dynamic_tag_name(entry.tag),
+                        value
+                    );
+                    let offset = self.dynamic_offset() + i as u64 * entsize;
+                    warnings
+                        .push(Diagnostic::warning("dynamic-pointer-unmapped", message).at(offset));
+                }
+            }
+        }
+
+        warnings
+    }
+}
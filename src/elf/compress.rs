@@ -0,0 +1,164 @@
+use std::io;
+#[cfg(feature = "zlib")]
+use std::io::Read;
+
+#[cfg(feature = "zlib")]
+use flate2::read::ZlibDecoder;
+
+use super::{
+    bytes::ByteReader,
+    hdr::{ElfClass, Endian},
+    shdr::{ElfShdr, SectionFlag},
+};
+
+pub const ELFCOMPRESS_ZLIB: u32 = 1;
+pub const ELFCOMPRESS_ZSTD: u32 = 2;
+
+/// How the contents of a compressed section are stored
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    /// `SHF_COMPRESSED` section starting with an `Elf_Chdr`
+    Elf {
+        ch_type: u32,
+        size: u64,
+        header: usize,
+    },
+    /// Legacy `.zdebug_*` section, `ZLIB` followed by the big-endian uncompressed size
+    Gnu { size: u64 },
+}
+
+impl Compression {
+    /// Detects the compression of a section from its flags, or its name and magic for
+    /// the GNU style that predates `SHF_COMPRESSED`
+    pub fn detect(
+        shdr: &ElfShdr,
+        name: &str,
+        data: &[u8],
+        class: ElfClass,
+        endian: Endian,
+    ) -> Option<Self> {
+        if shdr.flags() & SectionFlag::Compressed as u64 != 0 {
+            let mut reader = ByteReader::new(data, endian);
+            return match class {
+                ElfClass::ElfClass64 => {
+                    let ch_type = reader.u32()?;
+                    reader.skip(4)?;
+                    Some(Compression::Elf {
+                        ch_type,
+                        size: reader.u64()?,
+                        header: 24,
+                    })
+                }
+                _ => Some(Compression::Elf {
+                    ch_type: reader.u32()?,
+                    size: reader.u32()? as u64,
+                    header: 12,
+                }),
+            };
+        }
+
+        if name.starts_with(".zdebug") && data.starts_with(b"ZLIB") {
+            let size = ByteReader::new(data.get(4..12)?, Endian::Big).u64()?;
+            return Some(Compression::Gnu { size });
+        }
+
+        None
+    }
+
+    pub fn size(&self) -> u64 {
+        match *self {
+            Self::Elf { size, .. } | Self::Gnu { size } => size,
+        }
+    }
+
+    /// Inflates the section contents, only zlib streams are supported
+    pub fn decompress(&self, data: &[u8]) -> io::Result<Vec<u8>> {
+        let stream = match *self {
+            Self::Elf {
+                ch_type: ELFCOMPRESS_ZLIB,
+                header,
+                ..
+            } => data.get(header..),
+            Self::Gnu { .. } => data.get(12..),
+            Self::Elf { ch_type, .. } => {
+                return Err(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    format!("unsupported compression type {}", ch_type),
+                ))
+            }
+        }
+        .ok_or_else(|| io::Error::from(io::ErrorKind::UnexpectedEof))?;
+
+        let buf = inflate(stream, self.size())?;
+        if buf.len() as u64 != self.size() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "decompressed {} bytes, header claims {}",
+                    buf.len(),
+                    self.size()
+                ),
+            ));
+        }
+
+        Ok(buf)
+    }
+}
+
+/// Deflate expands its input at most about 1032 times, a larger size in the header is
+/// corrupt and is not worth reserving memory for
+#[cfg(feature = "zlib")]
+const MAX_INFLATE_RATIO: u64 = 1032;
+
+/// Inflates at most one byte more than the header claims, enough for the caller to tell
+/// the stream does not match it
+#[cfg(feature = "zlib")]
+fn inflate(stream: &[u8], size: u64) -> io::Result<Vec<u8>> {
+    let reserve = size.min((stream.len() as u64).saturating_mul(MAX_INFLATE_RATIO));
+    let mut buf = Vec::with_capacity(reserve as usize);
+    ZlibDecoder::new(stream)
+        .take(size.saturating_add(1))
+        .read_to_end(&mut buf)?;
+    Ok(buf)
+}
+
+#[cfg(not(feature = "zlib"))]
+fn inflate(_stream: &[u8], _size: u64) -> io::Result<Vec<u8>> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "zlib compressed sections need the zlib feature",
+    ))
+}
+
+/// Name of the uncompressed section, `.zdebug_info` is read as `.debug_info`
+pub fn debug_name(name: &str) -> String {
+    match name.strip_prefix(".zdebug") {
+        Some(rest) => format!(".debug{}", rest),
+        None => name.to_string(),
+    }
+}
+
+#[cfg(all(test, feature = "zlib"))]
+mod test {
+    use std::io::Write;
+
+    use flate2::{write::ZlibEncoder, Compression as Level};
+
+    use super::{debug_name, Compression};
+
+    #[test]
+    fn inflates_zdebug_payload() {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Level::default());
+        encoder.write_all(b"debug contents").unwrap();
+        let mut data = b"ZLIB".to_vec();
+        data.extend(14u64.to_be_bytes());
+        data.extend(encoder.finish().unwrap());
+
+        let compression = Compression::Gnu { size: 14 };
+        assert_eq!(compression.decompress(&data).unwrap(), b"debug contents");
+        assert_eq!(debug_name(".zdebug_line"), ".debug_line");
+
+        let lying = Compression::Gnu { size: u64::MAX };
+        assert!(lying.decompress(&data).is_err());
+    }
+}
@@ -0,0 +1,51 @@
+use std::io;
+
+use super::{bytes::string_at, core::FileData, rel::copy_type};
+
+#[derive(Debug, Clone)]
+pub struct CopyReloc {
+    pub offset: u64,
+    pub symbol: String,
+    /// Size of the object as seen at link time, the amount of data the dynamic linker copies
+    pub size: u64,
+    pub version: Option<String>,
+    /// Shared object expected to provide the definition, from `.gnu.version_r`
+    pub library: Option<String>,
+}
+
+impl CopyReloc {
+    pub fn read(elf: &FileData) -> io::Result<Vec<Self>> {
+        let copy = match copy_type(elf.header().machine()) {
+            Some(copy) => copy,
+            None => return Ok(Vec::new()),
+        };
+        let versions = elf.symbol_versions()?;
+        let mut copies = Vec::new();
+
+        for (shdr, rels) in elf.relocations()? {
+            if !rels.iter().any(|rel| rel.rtype() == copy) {
+                continue;
+            }
+
+            let symtab = match elf.section_headers().get(shdr.link() as usize) {
+                Some(symtab) => *symtab,
+                None => continue,
+            };
+            let (strtab, syms) = elf.symbol_table(&symtab)?;
+
+            for rel in rels.iter().filter(|rel| rel.rtype() == copy) {
+                let sym = syms.get(rel.sym() as usize);
+                let needed = versions.needed(rel.sym() as usize);
+                copies.push(CopyReloc {
+                    offset: rel.offset(),
+                    symbol: sym.map_or(String::new(), |sym| string_at(strtab, sym.name() as usize)),
+                    size: sym.map_or(0, |sym| sym.size()),
+                    version: needed.map(|(_, version)| version.to_string()),
+                    library: needed.map(|(file, _)| file.to_string()),
+                });
+            }
+        }
+
+        Ok(copies)
+    }
+}
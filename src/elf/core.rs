@@ -1,29 +1,91 @@
 use std::{
-    alloc::{alloc, dealloc, Layout},
     fs,
-    io::{self, Read, Seek, SeekFrom},
+    io::{self, Read, Seek},
     path::{Path, PathBuf},
-    slice,
+    sync::OnceLock,
 };
 
-use crate::elf::ver::ElfVerdef;
+#[cfg(unix)]
+use crate::elf::mmap::Mmap;
+use crate::elf::source::{Locked, SourceReader};
+use crate::elf::ver::{ElfVerneed, SymbolVersions, VersionDef};
 
 use super::{
-    dynamic::{Dyn, DynamicTag},
-    hdr::ElfClass,
-    internal::get_data,
+    bytes::string_at,
+    compress::Compression,
+    diag::{Diagnostic, Diagnostics},
+    dynamic::{Dyn, DynamicTag, RelaState, DF_1_PIE, DYNAMIC_RELOCATIONS},
+    hash::{GnuHashTable, SymbolHash, SysvHashTable},
+    hdr::{ElfClass, Endian, ObjectType},
+    internal::Entries,
+    note::{Note, NoteContainer},
     phdr::ProgramType,
-    shdr::{ElfShdr, SectionType},
-    sym::{Elf32Sym, Elf64Sym, ElfSym},
+    rel::{Elf32Rel, Elf32Rela, Elf64Rel, Elf64Rela, ElfRel},
+    section::SymtabSection,
+    shdr::{ElfShdr, SectionFlag, SectionType},
+    sym::{Elf32Sym, Elf64Sym, ElfSym, SymbolMap, SymbolType},
     ElfHdr, ElfPhdr,
 };
 
 use num_traits::FromPrimitive;
 
 type Table = Vec<u8>;
+/// A symbol table section as its name, linked string table and symbols
+type SymbolSection<'a> = (String, &'a [u8], &'a [ElfSym]);
+
+pub use super::source::Source;
+
+/// How much of the file [`FileData::open_with`] parses up front
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum ParseLevel {
+    /// Only the file and program headers, the section headers are left empty
+    Headers,
+    /// The section headers and section name table as well, what [`FileData::new`] does
+    #[default]
+    Sections,
+    /// Every symbol table as well, so that errors in them surface when opening
+    Symbols,
+}
+
+/// How [`FileData::open_with`] reads the file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Backend {
+    /// Buffered reads through the file descriptor
+    #[default]
+    Read,
+    /// A read-only mapping of the whole file, plain reads on targets without mmap
+    Mmap,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ParseOptions {
+    pub level: ParseLevel,
+    /// Fail on the structural problems [`FileData::anomalies`] reports instead of
+    /// parsing what is there
+    pub strict: bool,
+    pub backend: Backend,
+}
+
+impl ParseOptions {
+    pub fn with_level(mut self, level: ParseLevel) -> Self {
+        self.level = level;
+        self
+    }
+
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    pub fn with_backend(mut self, backend: Backend) -> Self {
+        self.backend = backend;
+        self
+    }
+}
+
 pub struct FileData {
     file_path: PathBuf,
-    file: fs::File,
+    file: Box<dyn Source>,
     header: ElfHdr,
     program_headers: Vec<ElfPhdr>,
     section_headers: Vec<ElfShdr>,
@@ -31,30 +93,110 @@ pub struct FileData {
     dynamic_size: usize,
     dynamic_info: [u64; DynamicTag::Encoding as usize],
     string_table: Vec<u8>,
+    load_bias: u64,
+    // Decoded on first use and kept, the file is never expected to change underneath
+    /// The symbols and linked string table of each section, by section index
+    symbol_tables: Vec<OnceLock<(Table, Vec<ElfSym>)>>,
+    symbol_map: OnceLock<SymbolMap>,
+    symbol_hash: OnceLock<Option<SymbolHash>>,
+    dynamic: OnceLock<Vec<Dyn>>,
+    diagnostics: Diagnostics,
 }
 
 impl FileData {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(skip_all, fields(path = %path.as_ref().display()))
+    )]
     pub fn new<P>(path: P) -> Result<Self, std::io::Error>
     where
         P: AsRef<Path>,
     {
-        let mut file = fs::File::open(&path)?;
-        let header = ElfHdr::read_file(&mut file)?;
+        Self::open_with(path, ParseOptions::default())
+    }
+
+    /// Opens a file choosing how much is parsed up front, how strictly and how it is read
+    pub fn open_with<P: AsRef<Path>>(path: P, options: ParseOptions) -> io::Result<Self> {
+        let file: Box<dyn Source> = match options.backend {
+            #[cfg(unix)]
+            Backend::Mmap => Box::new(Mmap::open(&path)?),
+            #[cfg(any(unix, windows))]
+            _ => Box::new(fs::File::open(&path)?),
+            #[cfg(not(any(unix, windows)))]
+            _ => Box::new(Locked::new(fs::File::open(&path)?)),
+        };
+        Self::from_source(file, PathBuf::from(path.as_ref()), options)
+    }
+
+    /// Parses an image already in memory, without touching the filesystem
+    pub fn from_bytes<B: Into<Vec<u8>>>(bytes: B) -> io::Result<Self> {
+        Self::from_bytes_with(bytes, ParseOptions::default())
+    }
+
+    /// [`FileData::from_bytes`] with the options of [`FileData::open_with`]
+    pub fn from_bytes_with<B: Into<Vec<u8>>>(bytes: B, options: ParseOptions) -> io::Result<Self> {
+        Self::from_source(Box::new(bytes.into()), PathBuf::new(), options)
+    }
+
+    /// Parses an image from any seekable reader, reads are serialized through a lock
+    pub fn from_reader<R: Read + Seek + Send + 'static>(reader: R) -> io::Result<Self> {
+        Self::from_source(
+            Box::new(Locked::new(reader)),
+            PathBuf::new(),
+            ParseOptions::default(),
+        )
+    }
 
-        let program_headers = ElfPhdr::read(&header, &mut file).unwrap();
-        let section_headers = ElfShdr::iter(&path)?.collect::<Vec<ElfShdr>>();
-        let string_table = ElfShdr::get_string_table(&mut file, &header)?;
+    pub(crate) fn from_source(
+        file: Box<dyn Source>,
+        file_path: PathBuf,
+        options: ParseOptions,
+    ) -> io::Result<Self> {
+        let mut reader = SourceReader::new(&*file);
+        let header = {
+            #[cfg(feature = "tracing")]
+            let _span = tracing::debug_span!("header").entered();
+            ElfHdr::read_file(&mut reader)?
+        };
+
+        let diagnostics = Diagnostics::default();
+        let program_headers = {
+            #[cfg(feature = "tracing")]
+            let _span =
+                tracing::debug_span!("program_headers", count = header.nheaders()).entered();
+            ElfPhdr::read(&header, &mut reader).unwrap_or_else(|err| {
+                diagnostics.warn(
+                    "unreadable-program-headers",
+                    header.e_phoff,
+                    format!("unable to read the program headers: {}", err),
+                );
+                Vec::new()
+            })
+        };
+        let (section_headers, string_table) = match options.level {
+            ParseLevel::Headers => (Vec::new(), Vec::new()),
+            _ => {
+                #[cfg(feature = "tracing")]
+                let _span =
+                    tracing::debug_span!("sections", count = header.nsection_headers()).entered();
+                (
+                    ElfShdr::read_all(&header, &mut reader)?,
+                    ElfShdr::get_string_table(&mut reader, &header)?,
+                )
+            }
+        };
 
         let (dynamic_addr, dynamic_size) = match program_headers
             .iter()
-            .find(|phdr| phdr.program_type().unwrap() == ProgramType::Dynamic)
+            .find(|phdr| phdr.program_type() == Some(ProgramType::Dynamic))
         {
             Some(phdr) => (phdr.offset(), phdr.filesz() as usize),
             None => (0, 0usize),
         };
 
-        Ok(Self {
-            file_path: PathBuf::from(path.as_ref()),
+        let sections = section_headers.len();
+        let elf = Self {
+            file_path,
             file,
             header,
             program_headers,
@@ -63,7 +205,66 @@ impl FileData {
             dynamic_size,
             dynamic_info: [0; 38],
             string_table,
-        })
+            load_bias: 0,
+            symbol_tables: (0..sections).map(|_| OnceLock::new()).collect(),
+            symbol_map: OnceLock::new(),
+            symbol_hash: OnceLock::new(),
+            dynamic: OnceLock::new(),
+            diagnostics,
+        };
+
+        let anomalies = elf.anomalies()?;
+        if options.strict {
+            if let Some(anomaly) = anomalies.into_iter().next() {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, anomaly.message));
+            }
+        } else {
+            for anomaly in anomalies {
+                elf.diagnostics.push(anomaly);
+            }
+        }
+        if options.level == ParseLevel::Symbols {
+            elf.table_symbols()?;
+        }
+
+        Ok(elf)
+    }
+
+    /// The warnings collected so far. Tables are read on first use, so more can show up
+    /// as the file is queried.
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        self.diagnostics.to_vec()
+    }
+
+    /// How far the image is mapped from its link-time addresses, only ever non-zero for an
+    /// image read out of a running process
+    pub fn load_bias(&self) -> u64 {
+        self.load_bias
+    }
+
+    /// The load bias of the file when it is mapped at `base`, the start of its first
+    /// mapping in `/proc/<pid>/maps` or the base address a crash log gives for it. `None`
+    /// without `PT_LOAD` segments.
+    pub fn bias_for_base(&self, base: u64) -> Option<u64> {
+        self.program_headers()
+            .iter()
+            .filter(|phdr| phdr.program_type() == Some(ProgramType::Load))
+            .min_by_key(|phdr| phdr.offset())
+            .map(|first| base.wrapping_sub(first.vaddr().wrapping_sub(first.offset())))
+    }
+
+    pub(crate) fn set_load_bias(&mut self, bias: u64) {
+        self.load_bias = bias;
+    }
+
+    /// Gives up the parsed state and returns the source it was parsed from
+    pub(crate) fn into_source(self) -> Box<dyn Source> {
+        self.file
+    }
+
+    /// A fresh cursor over the source, for the decoders that read sequentially
+    fn reader(&self) -> SourceReader<&dyn Source> {
+        SourceReader::new(&*self.file)
     }
 
     pub fn header(&self) -> &ElfHdr {
@@ -78,83 +279,308 @@ impl FileData {
         &self.program_headers
     }
 
-    pub fn dynamic_symbols(&mut self) -> Option<io::Result<Vec<ElfSym>>> {
-        if let Some(dyn_section) = self.section_headers.iter().find(|shdr| {
-            shdr.section_type()
-                .map_or(false, |stype| stype == SectionType::DynSym)
-        }) {
-            let syms = ElfSym::read_symbols(&mut self.file, &self.header, dyn_section)?;
+    pub fn section_name(&self, shdr: &ElfShdr) -> Option<String> {
+        self.string_lookup(shdr.name() as usize)
+    }
+
+    pub fn section_index(&self, name: &str) -> Option<usize> {
+        self.section_headers
+            .iter()
+            .position(|shdr| self.section_name(shdr).as_deref() == Some(name))
+    }
 
-            return Some(syms);
+    pub fn section_by_name(&self, name: &str) -> Option<ElfShdr> {
+        self.section_index(name).map(|i| self.section_headers[i])
+    }
+
+    /// Reads the raw contents of a section, `SHT_NOBITS` sections are empty
+    pub fn section_data(&self, shdr: &ElfShdr) -> io::Result<Vec<u8>> {
+        if shdr.section_type() == Some(SectionType::NoBits) {
+            return Ok(Vec::new());
         }
 
-        None
+        self.read_at(shdr.offset(), shdr.size() as usize)
     }
 
-    // Please for the love of god someone rewrite this
-    // This is a powder keg waiting to explode
-    pub fn table_symbols(&mut self) -> io::Result<Vec<(String, Table, Vec<ElfSym>)>> {
-        let sym_sections = self.section_headers.iter().filter(|shdr| {
-            shdr.section_type()
-                .map(|st| st == SectionType::SymTab || st == SectionType::DynSym)
-                .unwrap_or(false)
-        });
+    /// Reads the contents of a section, inflating `SHF_COMPRESSED` and `.zdebug_*` sections
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(offset = shdr.offset())))]
+    pub fn decompressed_section_data(&self, shdr: &ElfShdr) -> io::Result<Vec<u8>> {
+        let data = self.section_data(shdr)?;
+        let name = self.section_name(shdr).unwrap_or_default();
+        let class = self.header().class().unwrap_or(ElfClass::ElfClass64);
+        let endian = self.header().endian().unwrap_or(Endian::Little);
 
-        let mut v = Vec::new();
+        match Compression::detect(shdr, &name, &data, class, endian) {
+            Some(compression) => compression.decompress(&data),
+            None => Ok(data),
+        }
+    }
 
-        for shdr in sym_sections {
-            let table = if shdr.link() == self.header.table_index().into() {
-                ElfShdr::get_string_table(&mut self.file, &self.header)
-            } else {
-                ElfShdr::get_data(
-                    &mut self.file,
-                    &self.header,
-                    shdr.link() as u64,
-                    self.header.e_shoff,
-                )
-            }
-            .unwrap();
-
-            let name = self.string_lookup(shdr.name() as usize).unwrap();
-
-            self.file.seek(SeekFrom::Start(shdr.offset()))?;
-
-            let buf = unsafe {
-                let layout =
-                    Layout::array::<Elf64Sym>((shdr.size() / shdr.entsize()) as usize).unwrap();
-
-                let ptr = alloc(layout);
-                let slice = slice::from_raw_parts_mut(ptr, shdr.size() as usize);
-
-                self.file.read(slice)?;
-
-                let buf = match self.header.class().unwrap() {
-                    ElfClass::ElfClass32 => (*std::ptr::slice_from_raw_parts(
-                        ptr as *const Elf32Sym,
-                        (shdr.size() / shdr.entsize()) as usize as usize,
-                    ))
-                    .iter()
-                    .map(|sym| sym.try_into().unwrap())
-                    .collect(),
-                    ElfClass::ElfClass64 => (*std::ptr::slice_from_raw_parts(
-                        ptr as *const Elf64Sym,
-                        (shdr.size() / shdr.entsize()) as usize as usize,
-                    ))
-                    .iter()
-                    .map(|sym| sym.into())
-                    .collect::<Vec<ElfSym>>(),
-                    _ => panic!("Unsupported elf type"),
-                };
+    /// Finds a debug section by its uncompressed name, falling back to the `.zdebug_*` spelling
+    pub fn debug_section_by_name(&self, name: &str) -> Option<ElfShdr> {
+        self.section_by_name(name).or_else(|| {
+            name.strip_prefix(".debug")
+                .and_then(|rest| self.section_by_name(&format!(".zdebug{}", rest)))
+        })
+    }
 
-                dealloc(ptr, layout);
+    /// The allocated section whose address range contains `vaddr`. A range that wraps
+    /// around the address space contains nothing.
+    pub fn section_at_vaddr(&self, vaddr: u64) -> Option<ElfShdr> {
+        self.section_headers
+            .iter()
+            .find(|shdr| {
+                shdr.flags() & SectionFlag::Alloc as u64 != 0
+                    && matches!(shdr.addr().checked_add(shdr.size()),
+                        Some(end) if vaddr >= shdr.addr() && vaddr < end)
+            })
+            .copied()
+    }
 
-                buf
+    /// What `vaddr` belongs to: the allocated section containing it and, when `symbols` has
+    /// a function or object there, that symbol, e.g. `.data.rel.ro+0x18 (vtable+0x8)`
+    pub fn describe_vaddr(&self, symbols: &SymbolMap, vaddr: u64) -> Option<String> {
+        let shdr = self.section_at_vaddr(vaddr)?;
+        let name = self.section_name(&shdr).unwrap_or_default();
+        let section = match vaddr - shdr.addr() {
+            0 => name,
+            offset => format!("{}+0x{:x}", name, offset),
+        };
+        Some(match symbols.describe_from(vaddr, shdr.addr()) {
+            Some(symbol) => format!("{} ({})", section, symbol),
+            None => section,
+        })
+    }
+
+    /// Size of the whole image in bytes
+    pub fn file_size(&self) -> io::Result<u64> {
+        self.file.size()
+    }
+
+    /// Reads `size` bytes at `offset`, failing with `UnexpectedEof` before allocating
+    /// anything when the range ends past the image, as it does for corrupt size fields
+    pub fn read_at(&self, offset: u64, size: usize) -> io::Result<Vec<u8>> {
+        match offset.checked_add(size as u64) {
+            Some(end) if end <= self.file.size()? => {}
+            _ => return Err(io::ErrorKind::UnexpectedEof.into()),
+        }
+        let mut buf = vec![0; size];
+        self.file.read_exact_at(&mut buf, offset)?;
+
+        Ok(buf)
+    }
+
+    /// Structural problems that do not stop parsing but usually mean a truncated or
+    /// corrupted file: sections and segments reaching past the end of the file, or a
+    /// section name table index that does not exist
+    pub fn anomalies(&self) -> io::Result<Vec<Diagnostic>> {
+        let len = self.file.size()?;
+        let header = &self.header;
+        let mut anomalies = Vec::new();
+
+        for (i, shdr) in self.section_headers.iter().enumerate() {
+            let end = shdr.offset().saturating_add(shdr.size());
+            if shdr.section_type() != Some(SectionType::NoBits) && end > len {
+                let message = format!(
+                    "section {} ends at 0x{:x}, past the end of the file (0x{:x})",
+                    i, end, len
+                );
+                let entry = header.e_shoff + i as u64 * header.e_shentsize as u64;
+                anomalies.push(Diagnostic::warning("section-past-eof", message).at(entry));
+            }
+        }
+        for (i, phdr) in self.program_headers.iter().enumerate() {
+            let end = phdr.offset().saturating_add(phdr.filesz());
+            if end > len {
+                let message = format!(
+                    "segment {} ends at 0x{:x}, past the end of the file (0x{:x})",
+                    i, end, len
+                );
+                let entry = header.e_phoff + i as u64 * header.e_phentsize as u64;
+                anomalies.push(Diagnostic::warning("segment-past-eof", message).at(entry));
+            }
+        }
+        let shstrndx = header.table_index() as usize;
+        if !self.section_headers.is_empty() && shstrndx >= self.section_headers.len() {
+            let message = format!(
+                "section name table index {} is out of range ({} sections)",
+                shstrndx,
+                self.section_headers.len()
+            );
+            let field = match header.class() {
+                Some(ElfClass::ElfClass32) => 0x32,
+                _ => 0x3e,
             };
+            anomalies.push(Diagnostic::warning("shstrndx-out-of-range", message).at(field));
+        }
+
+        Ok(anomalies)
+    }
+
+    /// Translates a virtual address into a file offset through the `PT_LOAD` segments
+    pub fn vaddr_to_offset(&self, vaddr: u64) -> Option<u64> {
+        let phdr = self.load_segment_at(vaddr)?;
+        (vaddr - phdr.vaddr()).checked_add(phdr.offset())
+    }
+
+    /// The `PT_LOAD` segment whose file backed range contains `vaddr`, skipping ranges
+    /// that wrap around the address space
+    fn load_segment_at(&self, vaddr: u64) -> Option<&ElfPhdr> {
+        self.program_headers.iter().find(|phdr| {
+            phdr.program_type() == Some(ProgramType::Load)
+                && matches!(phdr.vaddr().checked_add(phdr.filesz()),
+                    Some(end) if vaddr >= phdr.vaddr() && vaddr < end)
+        })
+    }
+
+    /// Collects the notes of every `SHT_NOTE` section, falling back to the `PT_NOTE`
+    /// segments when the section headers are missing
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub fn notes(&self) -> io::Result<Vec<Note>> {
+        Ok(self
+            .note_containers()?
+            .into_iter()
+            .flat_map(|(_, notes)| notes)
+            .collect())
+    }
 
-            v.push((name, table, buf));
+    /// The notes of [`FileData::notes`] grouped by the section or segment holding them
+    pub fn note_containers(&self) -> io::Result<Vec<(NoteContainer, Vec<Note>)>> {
+        let endian = self.header().endian().unwrap_or(Endian::Little);
+        let mut containers = Vec::new();
+
+        for shdr in self.section_headers() {
+            if shdr.section_type() != Some(SectionType::Note) {
+                continue;
+            }
+            let notes = Note::parse_aligned(&self.section_data(shdr)?, endian, shdr.addralign());
+            let name = self.section_name(shdr).unwrap_or_default();
+            containers.push((NoteContainer::Section(name), notes));
         }
 
-        Ok(v)
+        if containers.is_empty() {
+            let segments = self
+                .program_headers()
+                .iter()
+                .filter(|phdr| phdr.program_type() == Some(ProgramType::Note))
+                .map(|phdr| (phdr.offset(), phdr.filesz(), phdr.align()))
+                .collect::<Vec<_>>();
+            for (offset, size, align) in segments {
+                let data = self.read_at(offset, size as usize)?;
+                containers.push((
+                    NoteContainer::Segment { offset, size },
+                    Note::parse_aligned(&data, endian, align),
+                ));
+            }
+        }
+
+        Ok(containers)
+    }
+
+    pub fn read_vaddr(&self, vaddr: u64, size: usize) -> io::Result<Vec<u8>> {
+        match self.vaddr_to_offset(vaddr) {
+            Some(offset) => self.read_at(offset, size),
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("address 0x{:x} is not mapped by any PT_LOAD segment", vaddr),
+            )),
+        }
+    }
+
+    /// The `SHT_DYNSYM` section and its string table, `None` when there is none
+    fn dynamic_symbol_table(&self) -> Option<io::Result<(&[u8], &[ElfSym])>> {
+        let dyn_section = self
+            .section_headers
+            .iter()
+            .find(|shdr| shdr.section_type() == Some(SectionType::DynSym))?;
+        Some(self.symbol_table(dyn_section))
+    }
+
+    pub fn dynamic_symbols(&self) -> Option<io::Result<&[ElfSym]>> {
+        Some(self.dynamic_symbol_table()?.map(|(_, syms)| syms))
+    }
+
+    /// The `DT_GNU_HASH` table, or the `DT_HASH` one when there is none
+    fn symbol_hash(&self) -> Option<&SymbolHash> {
+        self.symbol_hash
+            .get_or_init(|| {
+                let dynamic = self.dynamic_section();
+                let addr = |tag: DynamicTag| {
+                    dynamic
+                        .iter()
+                        .find(|d| d.tag == tag as u64)
+                        .map(|d| unsafe { d.value.ptr })
+                };
+                let word = match self.header.class() {
+                    Some(ElfClass::ElfClass32) => 4,
+                    _ => 8,
+                };
+                let endian = self.header.endian().unwrap_or(Endian::Little);
+
+                let gnu = addr(DynamicTag::GnuHash)
+                    .and_then(|addr| self.read_vaddr_extent(addr).ok())
+                    .and_then(|data| GnuHashTable::parse(&data, word, endian))
+                    .map(SymbolHash::Gnu);
+                gnu.or_else(|| {
+                    addr(DynamicTag::Hash)
+                        .and_then(|addr| self.read_vaddr_extent(addr).ok())
+                        .and_then(|data| SysvHashTable::parse(&data, endian))
+                        .map(SymbolHash::Sysv)
+                })
+            })
+            .as_ref()
+    }
+
+    /// Reads from `vaddr` to the end of the section containing it, or of its segment when
+    /// there are no section headers
+    fn read_vaddr_extent(&self, vaddr: u64) -> io::Result<Vec<u8>> {
+        let end = match self.section_at_vaddr(vaddr) {
+            Some(shdr) => shdr.addr().checked_add(shdr.size()),
+            None => self
+                .load_segment_at(vaddr)
+                .and_then(|phdr| phdr.vaddr().checked_add(phdr.filesz())),
+        }
+        .unwrap_or(vaddr);
+        self.read_vaddr(vaddr, (end - vaddr) as usize)
+    }
+
+    /// Finds a dynamic symbol and its index by name through `DT_GNU_HASH` or `DT_HASH`, the
+    /// way the dynamic linker would, only scanning the whole table when there is neither
+    pub fn lookup_dynamic_symbol(&self, name: &str) -> io::Result<Option<(usize, ElfSym)>> {
+        let (strtab, syms) = match self.dynamic_symbol_table() {
+            Some(table) => table?,
+            None => return Ok(None),
+        };
+        let matches = |index: &usize| {
+            syms.get(*index)
+                .and_then(|sym| strtab.get(sym.name() as usize..))
+                .and_then(|s| s.split(|&c| c == 0).next())
+                == Some(name.as_bytes())
+        };
+
+        let index = match self.symbol_hash() {
+            Some(hash) => hash.candidates(name.as_bytes()).find(matches),
+            None => (0..syms.len()).find(matches),
+        };
+        Ok(index.map(|index| (index, syms[index].clone())))
+    }
+
+    /// Every `SHT_SYMTAB`/`SHT_DYNSYM` section as its name, linked string table and symbols
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub fn table_symbols(&self) -> io::Result<Vec<SymbolSection<'_>>> {
+        self.section_headers
+            .iter()
+            .filter(|shdr| {
+                matches!(
+                    shdr.section_type(),
+                    Some(SectionType::SymTab | SectionType::DynSym)
+                )
+            })
+            .map(|shdr| {
+                let (table, syms) = self.symbol_table(shdr)?;
+                Ok((self.section_name(shdr).unwrap_or_default(), table, syms))
+            })
+            .collect()
     }
 
     pub fn string_lookup_iter(&self, index: usize) -> Option<impl Iterator<Item = char> + '_> {
@@ -175,114 +601,330 @@ impl FileData {
         self.string_lookup_iter(index).map(|it| it.collect())
     }
 
-    pub fn relocations(&mut self) -> io::Result<Vec<(String, Table, Vec<ElfSym>)>> {
-        let sym_sections = self
+    /// Reads a `SHT_SYMTAB`/`SHT_DYNSYM` section along with the string table it links to.
+    /// The tables of the file's own sections are decoded once and kept.
+    pub fn symbol_table(&self, shdr: &ElfShdr) -> io::Result<(&[u8], &[ElfSym])> {
+        let cache = self
             .section_headers
             .iter()
-            .filter(|shdr| {
-                shdr.section_type()
-                    .map(|st| st == SectionType::Rela)
-                    .unwrap_or(false)
-            })
-            .map(|shdr| self.string_lookup(shdr.name() as usize).unwrap())
-            .collect::<String>();
+            .position(|section| section == shdr)
+            .and_then(|index| self.symbol_tables.get(index))
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "not a section header of this file",
+                )
+            })?;
+        if let Some((table, syms)) = cache.get() {
+            return Ok((table, syms));
+        }
 
-        println!("{}", sym_sections);
+        let table = match self.section_headers.get(shdr.link() as usize).copied() {
+            Some(strtab) => self.section_data(&strtab)?,
+            None => Vec::new(),
+        };
+        let data = self.section_data(shdr)?;
+        let syms = SymtabSection::new(&self.header, *shdr, &data)
+            .iter()
+            .collect();
 
-        todo!()
+        let (table, syms) = cache.get_or_init(|| (table, syms));
+        Ok((table, syms))
     }
 
-    pub fn process_relocs(&mut self) {
-        self.process_dynamic_section();
+    /// Decodes the entries of a `SHT_SYMTAB`/`SHT_DYNSYM` section one at a time instead of
+    /// reading the whole table like [`symbol_table`](Self::symbol_table)
+    pub fn symbols_iter(
+        &self,
+        shdr: &ElfShdr,
+    ) -> io::Result<Entries<SourceReader<&dyn Source>, ElfSym>> {
+        let nmemb = match shdr.entsize() {
+            0 => 0,
+            entsize => (shdr.size() / entsize) as usize,
+        };
+
+        unsafe {
+            Entries::new::<Elf32Sym, Elf64Sym>(self.reader(), &self.header, nmemb, shdr.offset())
+        }
+    }
 
-        for shdr in self.section_headers.iter().filter(|shdr| {
-            matches!(
-                shdr.section_type().unwrap(),
-                SectionType::Rela | SectionType::Rel
-            )
-        }) {
-            // let vers = ElfSym::read_symbols(&mut self.file, &self.header, shdr).unwrap();
-            // println!("{:#?}", vers);
-            print!("\nRelocation section ");
-            print!("{}", self.string_lookup(shdr.name() as usize).unwrap());
-
-            let rel_offset = shdr.offset();
-            let rel_size = shdr.size();
-            let num_rela = rel_size / shdr.entsize();
-
-            println!(
-                " at offset 0x{:x} contains {} entries:",
-                rel_offset, num_rela
-            );
+    /// Decodes the entries of a `SHT_REL`/`SHT_RELA` section one at a time
+    pub fn relocations_iter(
+        &self,
+        shdr: &ElfShdr,
+    ) -> io::Result<Entries<SourceReader<&dyn Source>, ElfRel>> {
+        let nmemb = match shdr.entsize() {
+            0 => 0,
+            entsize => (shdr.size() / entsize) as usize,
+        };
+        let (file, header) = (self.reader(), &self.header);
 
-            if shdr.link() != 0 && shdr.link() < self.header().e_shnum.into() {
-                let symsec = self.section_headers()[shdr.link() as usize];
-                if !matches!(
-                    symsec.section_type().unwrap(),
-                    SectionType::SymTab | SectionType::DynSym
-                ) {
-                    continue;
+        unsafe {
+            match shdr.section_type() {
+                Some(SectionType::Rela) => {
+                    Entries::new::<Elf32Rela, Elf64Rela>(file, header, nmemb, shdr.offset())
                 }
+                Some(SectionType::Rel) => {
+                    Entries::new::<Elf32Rel, Elf64Rel>(file, header, nmemb, shdr.offset())
+                }
+                _ => Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "not a relocation section",
+                )),
+            }
+        }
+    }
 
-                //println!("{}", self.string_lookup(symsec.name() as usize).unwrap());
+    /// Builds an address lookup from the defined function and object symbols of
+    /// `.symtab` and `.dynsym`
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub fn symbol_map(&self) -> io::Result<&SymbolMap> {
+        if let Some(map) = self.symbol_map.get() {
+            return Ok(map);
+        }
 
-                let table = ElfShdr::get_data(
-                    &mut self.file,
-                    &self.header,
-                    symsec.link().into(),
-                    self.header.e_shoff,
+        let tables = self
+            .section_headers
+            .iter()
+            .filter(|shdr| {
+                matches!(
+                    shdr.section_type(),
+                    Some(SectionType::SymTab | SectionType::DynSym)
                 )
-                .unwrap();
-
-                let syms = unsafe {
-                    get_data::<_, Elf32Sym, Elf64Sym, ElfSym>(
-                        &mut self.file,
-                        &self.header,
-                        (shdr.size() / shdr.entsize()) as usize,
-                        SeekFrom::Start(symsec.offset()),
-                    )
-                    .unwrap()
-                };
+            })
+            .copied()
+            .collect::<Vec<_>>();
+
+        let mut symbols = Vec::new();
+        for shdr in tables {
+            let (strtab, syms) = self.symbol_table(&shdr)?;
+            symbols.extend(
+                syms.iter()
+                    .filter(|sym| sym.shndx() != 0)
+                    .filter(|sym| {
+                        matches!(
+                            sym.symbol_type(),
+                            Some(SymbolType::Func | SymbolType::Object | SymbolType::GnuIFunc)
+                        )
+                    })
+                    .map(|sym| {
+                        (
+                            sym.value(),
+                            sym.size(),
+                            string_at(strtab, sym.name() as usize),
+                        )
+                    }),
+            );
+        }
+
+        Ok(self.symbol_map.get_or_init(|| SymbolMap::new(symbols)))
+    }
+
+    /// Reads `.gnu.version`, `.gnu.version_r` and `.gnu.version_d`, any may be missing
+    pub fn symbol_versions(&self) -> io::Result<SymbolVersions> {
+        let endian = self.header.endian().unwrap_or(Endian::Little);
+        let mut versions = SymbolVersions::default();
+
+        let versym = self
+            .section_headers
+            .iter()
+            .find(|shdr| shdr.section_type() == Some(SectionType::VerSym))
+            .copied();
+        if let Some(shdr) = versym {
+            versions.versym = SymbolVersions::parse_versym(&self.section_data(&shdr)?, endian);
+        }
+
+        for section_type in [SectionType::VerNeed, SectionType::VerDef] {
+            let shdr = match self
+                .section_headers
+                .iter()
+                .find(|shdr| shdr.section_type() == Some(section_type))
+                .copied()
+            {
+                Some(shdr) => shdr,
+                None => continue,
+            };
 
-                println!("{:#?}", syms);
-                for sym in syms {
-                    println!(
-                        "{} {}",
-                        table
-                            .iter()
-                            .skip(sym.name() as usize)
-                            .take_while(|&&p| p != 0)
-                            .map(|i| *i as char)
-                            .collect::<String>(),
-                        sym.info(),
-                    );
+            let data = self.section_data(&shdr)?;
+            let strtab = match self.section_headers.get(shdr.link() as usize).copied() {
+                Some(strtab) => self.section_data(&strtab)?,
+                None => Vec::new(),
+            };
+            let count = shdr.info() as usize;
+            match section_type {
+                SectionType::VerNeed => {
+                    versions.verneed = ElfVerneed::parse(&data, &strtab, count, endian)
                 }
+                _ => versions.verdef = VersionDef::parse(&data, &strtab, count, endian),
             }
+        }
 
-            if shdr.link() != 0 && shdr.link() < self.header.e_shnum.into() {
-                ElfSym::read_symbols(&mut self.file, &self.header, shdr);
-            }
+        Ok(versions)
+    }
+
+    /// The relocation tables `DT_REL`, `DT_RELA` and `DT_JMPREL` point at, found through
+    /// the `PT_LOAD` segments so they can be read when the section headers are stripped.
+    ///
+    /// Each table comes with the name `readelf -D` gives it (`REL`, `RELA` or `PLT`) and a
+    /// header standing in for its section.
+    pub fn dynamic_relocations(&self) -> io::Result<Vec<(&'static str, ElfShdr, Vec<ElfRel>)>> {
+        let word = match self.header.class() {
+            Some(ElfClass::ElfClass32) => 4,
+            _ => 8,
+        };
+
+        let mut tables = Vec::new();
+        for reloc in &DYNAMIC_RELOCATIONS {
+            let (addr, size) = match (
+                self.dynamic_value(reloc.reloc),
+                self.dynamic_value(reloc.size),
+            ) {
+                (Some(addr), Some(size)) if size != 0 => (addr, size),
+                _ => continue,
+            };
+            let rela = match reloc.rela {
+                RelaState::True => true,
+                RelaState::False => false,
+                RelaState::Unknown => {
+                    self.dynamic_value(DynamicTag::PltRel) == Some(DynamicTag::Rela as u64)
+                }
+            };
+            let (section_type, entsize) = match rela {
+                true => (
+                    SectionType::Rela,
+                    self.dynamic_value(DynamicTag::RelaEnt).unwrap_or(3 * word),
+                ),
+                false => (
+                    SectionType::Rel,
+                    self.dynamic_value(DynamicTag::RelEnt).unwrap_or(2 * word),
+                ),
+            };
+            let offset = self.vaddr_to_offset(addr).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "{} relocations at 0x{:x} are not mapped by any PT_LOAD segment",
+                        reloc.name, addr
+                    ),
+                )
+            })?;
+
+            let shdr = ElfShdr::synthetic(section_type, addr, offset, size, entsize);
+            let rels = ElfRel::read_relocations(&mut self.reader(), &self.header, &shdr)
+                .unwrap_or(Ok(vec![]))?;
+            tables.push((reloc.name, shdr, rels));
         }
 
-        // for reloc in &DYNAMIC_RELOCATIONS {
-        //     let is_rela = reloc.rela == RelaState::True;
-        //     let name = reloc.name;
+        Ok(tables)
+    }
 
-        //     let rel_size = self.dynamic_info[reloc.size as usize];
-        //     let rel_offset = self.dynamic_info[reloc.reloc as usize];
+    /// The dynamic symbol table and its string table read through `DT_SYMTAB` and
+    /// `DT_STRTAB` instead of the section headers. The table has no size of its own, the
+    /// number of symbols comes from the hash table.
+    pub fn dynamic_segment_symbols(&self) -> io::Result<(Table, Vec<ElfSym>)> {
+        let (symtab, strtab, strsz) = match (
+            self.dynamic_value(DynamicTag::SymTab),
+            self.dynamic_value(DynamicTag::StrTab),
+            self.dynamic_value(DynamicTag::StrSz),
+        ) {
+            (Some(symtab), Some(strtab), Some(strsz)) => (symtab, strtab, strsz),
+            _ => return Ok((Vec::new(), Vec::new())),
+        };
+        let entsize = self
+            .dynamic_value(DynamicTag::SymEnt)
+            .unwrap_or(match self.header.class() {
+                Some(ElfClass::ElfClass32) => 16,
+                _ => 24,
+            });
+        let count = self.symbol_hash().map_or(0, SymbolHash::symbol_count) as u64;
+        let offset = self.vaddr_to_offset(symtab).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "dynamic symbol table at 0x{:x} is not mapped by any PT_LOAD segment",
+                    symtab
+                ),
+            )
+        })?;
+
+        let shdr = ElfShdr::synthetic(
+            SectionType::DynSym,
+            symtab,
+            offset,
+            count * entsize,
+            entsize,
+        );
+        let syms =
+            ElfSym::read_symbols(&mut self.reader(), &self.header, &shdr).unwrap_or(Ok(vec![]))?;
+        Ok((self.read_vaddr(strtab, strsz as usize)?, syms))
+    }
 
-        //     println!("\nRelocation section");
+    /// Reads the version tables through `DT_VERSYM`, `DT_VERNEED` and `DT_VERDEF` instead
+    /// of the sections like [`symbol_versions`](Self::symbol_versions), for files whose
+    /// section headers are stripped
+    pub fn dynamic_symbol_versions(&self) -> io::Result<SymbolVersions> {
+        let endian = self.header.endian().unwrap_or(Endian::Little);
+        let mut versions = SymbolVersions::default();
+        let strtab = match (
+            self.dynamic_value(DynamicTag::StrTab),
+            self.dynamic_value(DynamicTag::StrSz),
+        ) {
+            (Some(strtab), Some(strsz)) => self.read_vaddr(strtab, strsz as usize)?,
+            _ => return Ok(versions),
+        };
 
-        //     self.string_lookup()
+        if let Some(versym) = self.dynamic_value(DynamicTag::Versym) {
+            let count = self.symbol_hash().map_or(0, SymbolHash::symbol_count);
+            versions.versym =
+                SymbolVersions::parse_versym(&self.read_vaddr(versym, count * 2)?, endian);
+        }
+        // Neither table records its size, only its number of entries
+        if let (Some(verneed), Some(count)) = (
+            self.dynamic_value(DynamicTag::Verneed),
+            self.dynamic_value(DynamicTag::Verneednum),
+        ) {
+            let data = self.read_vaddr_extent(verneed)?;
+            versions.verneed = ElfVerneed::parse(&data, &strtab, count as usize, endian);
+        }
+        if let (Some(verdef), Some(count)) = (
+            self.dynamic_value(DynamicTag::Verdef),
+            self.dynamic_value(DynamicTag::Verdefnum),
+        ) {
+            let data = self.read_vaddr_extent(verdef)?;
+            versions.verdef = VersionDef::parse(&data, &strtab, count as usize, endian);
+        }
 
-        // println!(
-        //     "{} {} {} {}",
-        //     reloc.size as usize, reloc.reloc as usize, rel_size, rel_offset
-        // );
+        Ok(versions)
+    }
+
+    /// Every `SHT_REL`/`SHT_RELA` section with its decoded entries
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub fn relocations(&self) -> io::Result<Vec<(ElfShdr, Vec<ElfRel>)>> {
+        let rel_sections = self
+            .section_headers
+            .iter()
+            .filter(|shdr| {
+                matches!(
+                    shdr.section_type(),
+                    Some(SectionType::Rela | SectionType::Rel)
+                )
+            })
+            .copied()
+            .collect::<Vec<_>>();
+
+        let mut v = Vec::new();
+        for shdr in rel_sections {
+            let rels = ElfRel::read_relocations(&mut self.reader(), &self.header, &shdr)
+                .unwrap_or(Ok(vec![]))?;
+            v.push((shdr, rels));
+        }
+
+        Ok(v)
     }
 
     pub fn process_dynamic_section(&mut self) {
-        let dynamic_section = self.dynamic_section();
+        // dynamic_info is written while walking the entries, which borrow self
+        let dynamic_section = self.dynamic_section().to_vec();
 
         for entry in &dynamic_section {
             if entry.tag == DynamicTag::SymTab as u64 {
@@ -321,21 +963,99 @@ impl FileData {
         }
     }
 
-    pub fn dynamic_section(&mut self) -> Vec<Dyn> {
+    /// Whether the file has a `PT_DYNAMIC` segment to read with [`FileData::dynamic_section`]
+    pub fn has_dynamic_section(&self) -> bool {
+        self.dynamic_size != 0
+    }
+
+    /// File offset of the `PT_DYNAMIC` segment
+    pub fn dynamic_offset(&self) -> u64 {
+        self.dynamic_addr
+    }
+
+    /// Number of dynamic entries up to and including the `DT_NULL` terminator, which
+    /// [`FileData::dynamic_section`] leaves off
+    pub fn dynamic_entry_count(&self) -> usize {
+        let entsize = match self.header.class() {
+            Some(ElfClass::ElfClass32) => 8,
+            _ => 16,
+        };
+        (self.dynamic_section().len() + 1).min(self.dynamic_size / entsize)
+    }
+
+    /// Whether this is a position independent executable rather than a shared object.
+    /// Linkers mark PIEs with `DF_1_PIE`; older ones did not, their PIEs are told apart by
+    /// an interpreter together with a `DT_DEBUG` entry, which shared objects do not have.
+    pub fn is_pie(&self) -> bool {
+        if self.header.ftype() != Some(ObjectType::Dyn) || !self.has_dynamic_section() {
+            return false;
+        }
+        let flags_1 = self.dynamic_value(DynamicTag::Flags1).unwrap_or(0);
+        let interp = self
+            .program_headers
+            .iter()
+            .any(|phdr| phdr.program_type() == Some(ProgramType::Interp));
+        flags_1 & DF_1_PIE != 0 || (interp && self.dynamic_value(DynamicTag::Debug).is_some())
+    }
+
+    /// The value of the first `tag` entry of the dynamic section
+    pub fn dynamic_value(&self, tag: DynamicTag) -> Option<u64> {
+        self.dynamic_section()
+            .iter()
+            .find(|d| d.tag == tag as u64)
+            .map(|d| unsafe { d.value.val })
+    }
+
+    pub fn dynamic_section(&self) -> &[Dyn] {
+        self.dynamic.get_or_init(|| self.read_dynamic_section())
+    }
+
+    fn read_dynamic_section(&self) -> Vec<Dyn> {
         let mut dyns = Dyn::read(
-            &mut self.file,
+            &mut self.reader(),
             &self.header,
             self.dynamic_addr,
             self.dynamic_size,
         )
-        .unwrap();
+        .unwrap_or_else(|err| {
+            self.diagnostics.warn(
+                "unreadable-dynamic",
+                self.dynamic_addr,
+                format!(
+                    "unable to read the dynamic segment at offset 0x{:x}: {}",
+                    self.dynamic_addr, err
+                ),
+            );
+            Vec::new()
+        });
+
+        // The dynamic linker relocates some d_ptr entries in place, take the bias back off
+        // whatever points inside the mapped image so it reads like the file on disk
+        if self.load_bias != 0 {
+            let loads = self
+                .program_headers
+                .iter()
+                .filter(|phdr| phdr.program_type() == Some(ProgramType::Load));
+            let start = loads.clone().map(|phdr| phdr.vaddr()).min().unwrap_or(0);
+            let end = loads
+                .map(|phdr| phdr.vaddr() + phdr.memsz())
+                .max()
+                .unwrap_or(0);
+            let bias = self.load_bias;
+            for d in dyns.iter_mut().filter(|d| DynamicTag::is_address(d.tag)) {
+                let ptr = unsafe { d.value.ptr };
+                if ptr.wrapping_sub(bias) >= start && ptr.wrapping_sub(bias) < end {
+                    d.value.ptr = ptr.wrapping_sub(bias);
+                }
+            }
+        }
 
         dyns.drain(..)
             .take_while(|d| d.tag != DynamicTag::Null as u64)
             .collect()
     }
 
-    // pub fn versyms(&mut self) -> Vec<Elf64Half> {
+    // pub fn versyms(&self) -> Vec<Elf64Half> {
 
     // }
 }
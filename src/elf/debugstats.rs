@@ -0,0 +1,159 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    io,
+};
+
+use super::{
+    bytes::ByteReader,
+    core::FileData,
+    dwarf::{
+        is_strx, parse_abbrevs, read_form, Abbreviation, AttrValue, UnitHeader, DW_FORM_LINE_STRP,
+        DW_FORM_STRP,
+    },
+    hdr::Endian,
+};
+
+/// Size of a debug section, as stored and once inflated
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DebugSectionSize {
+    pub name: String,
+    pub size: u64,
+    /// Size after inflating, for compressed sections
+    pub uncompressed: Option<u64>,
+}
+
+/// A summary of the DWARF in a file, to track debug info size across builds without
+/// dumping it
+#[derive(Debug, Clone, Default)]
+pub struct DebugStats {
+    pub sections: Vec<DebugSectionSize>,
+    /// Units by `DW_UT_*` type
+    pub units: BTreeMap<u8, usize>,
+    /// Units by DWARF version
+    pub versions: BTreeMap<u16, usize>,
+    /// DIEs by `DW_TAG_*`, null entries closing sibling chains left out
+    pub dies: BTreeMap<u64, usize>,
+    /// Distinct abbreviation tables the units use
+    pub abbrev_tables: usize,
+    /// Abbreviation declarations in those tables
+    pub abbrevs: usize,
+    /// `.debug_str` size and number of strings
+    pub str_size: u64,
+    pub str_count: usize,
+    pub line_str_size: u64,
+    /// Attributes referring to a string in `.debug_str` or `.debug_line_str`
+    pub strp_refs: usize,
+    /// `DW_FORM_string` attributes, whose text is stored in the DIE
+    pub inline_strings: usize,
+    pub inline_string_bytes: u64,
+    /// Units that stopped early on a form this reader does not know
+    pub truncated_units: usize,
+    /// Sections left out of the counts as they could not be read, such as those compressed
+    /// with zstd, along with the reason
+    pub skipped: Vec<(String, String)>,
+}
+
+impl DebugStats {
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub fn read(elf: &FileData) -> io::Result<Self> {
+        let endian = elf.header().endian().unwrap_or(Endian::Little);
+        let mut stats = Self::default();
+
+        for shdr in elf.section_headers() {
+            let name = elf.section_name(shdr).unwrap_or_default();
+            if !name.starts_with(".debug_") && !name.starts_with(".zdebug_") {
+                continue;
+            }
+            let data = match elf.decompressed_section_data(shdr) {
+                Ok(data) => data,
+                Err(err) => {
+                    stats.skipped.push((name.clone(), err.to_string()));
+                    stats.sections.push(DebugSectionSize {
+                        size: shdr.size(),
+                        uncompressed: None,
+                        name,
+                    });
+                    continue;
+                }
+            };
+            stats.sections.push(DebugSectionSize {
+                size: shdr.size(),
+                uncompressed: Some(data.len() as u64).filter(|&len| len != shdr.size()),
+                name,
+            });
+        }
+
+        // Sections that failed to read are already in `skipped` and count as empty
+        let section = |name: &str| {
+            elf.debug_section_by_name(name)
+                .and_then(|shdr| elf.decompressed_section_data(&shdr).ok())
+                .unwrap_or_default()
+        };
+        let debug_str = section(".debug_str");
+        stats.str_size = debug_str.len() as u64;
+        stats.str_count = debug_str.iter().filter(|&&c| c == 0).count();
+        stats.line_str_size = section(".debug_line_str").len() as u64;
+
+        let debug_abbrev = section(".debug_abbrev");
+        let mut tables = HashMap::new();
+        for (name, types) in [(".debug_info", false), (".debug_types", true)] {
+            let data = section(name);
+            let mut reader = ByteReader::new(&data, endian);
+            while !reader.is_empty() {
+                let unit = match UnitHeader::parse(&mut reader, types) {
+                    Some(unit) if unit.end <= data.len() => unit,
+                    _ => break,
+                };
+                *stats.units.entry(unit.unit_type).or_default() += 1;
+                *stats.versions.entry(unit.encoding.version).or_default() += 1;
+                let abbrevs = tables
+                    .entry(unit.abbrev_offset)
+                    .or_insert_with(|| parse_abbrevs(&debug_abbrev, unit.abbrev_offset, endian));
+
+                let mut dies = ByteReader::new(&data[..unit.end], endian);
+                dies.seek(unit.entries);
+                if stats.count_dies(&mut dies, abbrevs, &unit).is_none() {
+                    stats.truncated_units += 1;
+                }
+                reader.seek(unit.end);
+            }
+        }
+        stats.abbrev_tables = tables.len();
+        stats.abbrevs = tables.values().map(HashMap::len).sum();
+
+        Ok(stats)
+    }
+
+    /// Counts the DIEs of a unit, `None` when they could not all be read
+    fn count_dies(
+        &mut self,
+        reader: &mut ByteReader,
+        abbrevs: &HashMap<u64, Abbreviation>,
+        unit: &UnitHeader,
+    ) -> Option<()> {
+        while !reader.is_empty() {
+            let code = reader.uleb128()?;
+            if code == 0 {
+                continue;
+            }
+            let abbrev = abbrevs.get(&code)?;
+            *self.dies.entry(abbrev.tag).or_default() += 1;
+            for &(_, form, implicit_const) in &abbrev.attributes {
+                let value = read_form(reader, form, unit.encoding, implicit_const)?;
+                if let AttrValue::String(s) = value {
+                    self.inline_strings += 1;
+                    self.inline_string_bytes += s.len() as u64 + 1;
+                }
+                // Along with the DW_FORM_strx* indices into .debug_str_offsets
+                if matches!(form, DW_FORM_STRP | DW_FORM_LINE_STRP) || is_strx(form) {
+                    self.strp_refs += 1;
+                }
+            }
+        }
+        Some(())
+    }
+
+    pub fn die_count(&self) -> usize {
+        self.dies.values().sum()
+    }
+}
@@ -0,0 +1,189 @@
+use std::fmt::{self, Display};
+
+use cpp_demangle::{DemangleOptions, Symbol};
+
+/// The mangling scheme a symbol name was recognized as
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    /// The v0 scheme (`_R...`) or the legacy one, Itanium with a trailing `17h<hash>E`
+    Rust,
+    /// The Itanium C++ ABI (`_Z...`)
+    Cpp,
+}
+
+impl Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Self::Rust => "Rust",
+            Self::Cpp => "C++",
+        })
+    }
+}
+
+/// Whether `name` is a legacy Rust symbol, which is valid Itanium mangling too and only
+/// told apart from C++ by the hash rustc appends as the last path component
+fn is_legacy_rust(name: &str) -> bool {
+    // LLVM may append suffixes like `.llvm.1234` after the closing `E`
+    let name = name.split('.').next().unwrap_or_default();
+    name.starts_with("_ZN")
+        && name
+            .len()
+            .checked_sub(20)
+            .and_then(|start| name.get(start..))
+            .filter(|hash| hash.starts_with("17h") && hash.ends_with('E'))
+            .map(|hash| hash[3..19].bytes().all(|c| c.is_ascii_hexdigit()))
+            .unwrap_or(false)
+}
+
+/// Splits `{vtable(name)}`, how `cpp_demangle` writes some special names, into its parts
+fn special_name(demangled: &str) -> Option<(&str, &str)> {
+    let inner = demangled.strip_prefix('{')?.strip_suffix(")}")?;
+    inner.split_once('(')
+}
+
+/// Demangles a Rust or C++ symbol name, `None` when it is not mangled or does not parse.
+/// Rust names lose their hash, like `rustc-demangle`'s alternate format.
+pub fn demangle(name: &str) -> Option<(String, Language)> {
+    if name.starts_with("_R") || is_legacy_rust(name) {
+        if let Ok(demangled) = rustc_demangle::try_demangle(name) {
+            return Some((format!("{:#}", demangled), Language::Rust));
+        }
+    }
+    if name.starts_with("_Z") {
+        let demangled = Symbol::new(name).ok()?;
+        let demangled = demangled.demangle(&DemangleOptions::default()).ok()?;
+        // Spelled the way c++filt and binutils do
+        let demangled = match special_name(&demangled) {
+            Some(("vtable", name)) => format!("vtable for {}", name),
+            Some(("vtt", name)) => format!("VTT for {}", name),
+            Some(("virtual override thunk", thunk)) => match thunk.split_once("}, ") {
+                Some((offset, name)) if offset.starts_with("{virtual offset") => {
+                    format!("virtual thunk to {}", name)
+                }
+                Some((_, name)) => format!("non-virtual thunk to {}", name),
+                None => demangled,
+            },
+            _ => demangled,
+        };
+        return Some((demangled, Language::Cpp));
+    }
+    None
+}
+
+/// A symbol name from [`DemangleExt::demangled`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DemangledName<T> {
+    /// The name as it was passed in
+    pub original: T,
+    pub demangled: Option<String>,
+    pub language: Option<Language>,
+}
+
+impl<T: AsRef<str>> DemangledName<T> {
+    pub fn new(original: T) -> Self {
+        let (demangled, language) = match demangle(original.as_ref()) {
+            Some((demangled, language)) => (Some(demangled), Some(language)),
+            None => (None, None),
+        };
+        Self {
+            original,
+            demangled,
+            language,
+        }
+    }
+
+    /// The demangled name, or the original one when it was not mangled
+    pub fn name(&self) -> &str {
+        self.demangled
+            .as_deref()
+            .unwrap_or_else(|| self.original.as_ref())
+    }
+}
+
+/// Iterator returned by [`DemangleExt::demangled`]
+#[derive(Debug, Clone)]
+pub struct Demangled<I> {
+    names: I,
+}
+
+impl<I> Iterator for Demangled<I>
+where
+    I: Iterator,
+    I::Item: AsRef<str>,
+{
+    type Item = DemangledName<I::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.names.next().map(DemangledName::new)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.names.size_hint()
+    }
+}
+
+/// Adds [`demangled`](DemangleExt::demangled) to iterators of symbol names
+pub trait DemangleExt: Iterator + Sized {
+    /// Pairs every name with its demangled form and the language it was mangled for
+    fn demangled(self) -> Demangled<Self> {
+        Demangled { names: self }
+    }
+}
+
+impl<I> DemangleExt for I
+where
+    I: Iterator,
+    I::Item: AsRef<str>,
+{
+}
+
+#[cfg(test)]
+mod test {
+    use super::{demangle, DemangleExt, Language};
+
+    #[test]
+    fn demangles_rust_and_cpp() {
+        assert_eq!(
+            demangle("_ZN3std2io5stdio6_print17h0123456789abcdefE"),
+            Some(("std::io::stdio::_print".to_string(), Language::Rust))
+        );
+        assert_eq!(
+            demangle("_RNvCs1234_7mycrate3foo"),
+            Some(("mycrate::foo".to_string(), Language::Rust))
+        );
+        assert_eq!(
+            demangle("_ZNSt6vectorIiSaIiEE9push_backERKi"),
+            Some((
+                "std::vector<int, std::allocator<int> >::push_back(int const&)".to_string(),
+                Language::Cpp
+            ))
+        );
+        assert_eq!(
+            demangle("_ZTVSt9bad_alloc"),
+            Some(("vtable for std::bad_alloc".to_string(), Language::Cpp))
+        );
+        assert_eq!(
+            demangle("_ZThn16_NSdD1Ev"),
+            Some((
+                "non-virtual thunk to std::basic_iostream<char, std::char_traits<char> \
+                 >::~basic_iostream()"
+                    .to_string(),
+                Language::Cpp
+            ))
+        );
+        assert_eq!(demangle("main"), None);
+
+        let names = ["printf", "_ZN3foo3barEv"]
+            .iter()
+            .demangled()
+            .map(|name| (name.name().to_string(), name.language))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec![
+                ("printf".to_string(), None),
+                ("foo::bar()".to_string(), Some(Language::Cpp))
+            ]
+        );
+    }
+}
@@ -0,0 +1,301 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+use super::{
+    bytes::{string_at, ByteReader},
+    core::FileData,
+    dynamic::DynamicTag,
+    hdr::{ElfClass, Endian},
+    EI_CLASS, EI_DATA, ELFMAG,
+};
+
+use num_traits::FromPrimitive;
+
+/// Directories searched after `RUNPATH` and the `ld.so.conf` entries, in that order
+pub const DEFAULT_LIBRARY_DIRS: [&str; 4] = ["/lib", "/usr/lib", "/lib64", "/usr/lib64"];
+pub const LD_SO_CONF: &str = "/etc/ld.so.conf";
+
+/// The dependency related entries of the dynamic section
+#[derive(Debug, Clone, Default)]
+pub struct DynamicDeps {
+    pub soname: Option<String>,
+    pub needed: Vec<String>,
+    pub rpath: Vec<String>,
+    pub runpath: Vec<String>,
+}
+
+impl DynamicDeps {
+    pub fn read(elf: &FileData) -> io::Result<Self> {
+        let mut deps = DynamicDeps::default();
+        if !elf.has_dynamic_section() {
+            return Ok(deps);
+        }
+
+        let entries = elf
+            .dynamic_section()
+            .iter()
+            .map(|entry| (entry.tag, unsafe { entry.value.val }))
+            .collect::<Vec<_>>();
+        let find = |tag: DynamicTag| {
+            entries
+                .iter()
+                .find(|(t, _)| *t == tag as u64)
+                .map(|(_, value)| *value)
+        };
+
+        let strtab = match (find(DynamicTag::StrTab), find(DynamicTag::StrSz)) {
+            (Some(addr), Some(size)) => elf.read_vaddr(addr, size as usize)?,
+            _ => return Ok(deps),
+        };
+        let split = |value: u64| {
+            string_at(&strtab, value as usize)
+                .split(':')
+                .filter(|dir| !dir.is_empty())
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        };
+
+        for &(tag, value) in &entries {
+            match tag {
+                t if t == DynamicTag::Needed as u64 => {
+                    deps.needed.push(string_at(&strtab, value as usize))
+                }
+                t if t == DynamicTag::SoName as u64 => {
+                    deps.soname = Some(string_at(&strtab, value as usize))
+                }
+                t if t == DynamicTag::RPath as u64 => deps.rpath.extend(split(value)),
+                t if t == DynamicTag::RunPath as u64 => deps.runpath.extend(split(value)),
+                _ => {}
+            }
+        }
+
+        Ok(deps)
+    }
+}
+
+/// Reads the directories listed in an `ld.so.conf` style file, following `include` lines
+pub fn ld_so_conf_dirs(path: &Path) -> Vec<PathBuf> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut dirs = Vec::new();
+    for line in content.lines() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        match line.strip_prefix("include") {
+            Some(pattern) if pattern.starts_with(char::is_whitespace) => {
+                for include in expand_include(pattern.trim(), path) {
+                    dirs.extend(ld_so_conf_dirs(&include));
+                }
+            }
+            _ => dirs.push(PathBuf::from(line)),
+        }
+    }
+
+    dirs
+}
+
+/// Expands the single `*` wildcard that `include` lines use in practice
+fn expand_include(pattern: &str, conf: &Path) -> Vec<PathBuf> {
+    let pattern = conf.parent().unwrap_or(Path::new("/")).join(pattern);
+    let (dir, file) = match (pattern.parent(), pattern.file_name()) {
+        (Some(dir), Some(file)) => (dir, file.to_string_lossy().into_owned()),
+        _ => return Vec::new(),
+    };
+    let (prefix, suffix) = file.split_once('*').unwrap_or((&file, ""));
+
+    let mut paths = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| {
+                let name = path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                if file.contains('*') {
+                    name.starts_with(prefix) && name.ends_with(suffix)
+                } else {
+                    name == file
+                }
+            })
+            .collect::<Vec<_>>(),
+        Err(_) => return Vec::new(),
+    };
+    paths.sort();
+    paths
+}
+
+#[derive(Debug, Clone)]
+pub struct DepNode {
+    /// `DT_NEEDED` spelling for libraries, the command line path for the root
+    pub name: String,
+    /// `None` when the library could not be found
+    pub path: Option<PathBuf>,
+    pub needed: Vec<String>,
+}
+
+/// Breadth first closure of `DT_NEEDED`, following the dynamic linker's search order
+#[derive(Debug, Clone)]
+pub struct DepGraph {
+    /// Nodes in load order, the root first
+    pub nodes: Vec<DepNode>,
+}
+
+/// Whether `path` is an ELF file the root can load, same class and machine. Only the
+/// identification bytes are read, so non-ELF candidates such as linker scripts are skipped.
+fn compatible(path: &Path, class: Option<ElfClass>, machine: u16) -> bool {
+    let mut ident = [0; 20];
+    let read = fs::File::open(path).and_then(|mut file| file.read_exact(&mut ident));
+    if read.is_err() || &ident[..4] != ELFMAG.as_bytes() {
+        return false;
+    }
+
+    let endian = match ident[EI_DATA] {
+        2 => Endian::Big,
+        _ => Endian::Little,
+    };
+    let mut reader = ByteReader::new(&ident, endian);
+    reader.seek(18);
+
+    ElfClass::from_u8(ident[EI_CLASS]) == class && reader.u16() == Some(machine)
+}
+
+fn expand_origin(dir: &str, origin: &Path) -> PathBuf {
+    let origin = origin.to_string_lossy();
+    PathBuf::from(
+        dir.replace("${ORIGIN}", &origin)
+            .replace("$ORIGIN", &origin),
+    )
+}
+
+impl DepGraph {
+    pub fn resolve<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref();
+        let root = FileData::new(path)?;
+        let class = root.header().class();
+        let machine = root.header().machine();
+        let root_deps = DynamicDeps::read(&root)?;
+
+        let system_dirs = ld_so_conf_dirs(Path::new(LD_SO_CONF))
+            .into_iter()
+            .chain(DEFAULT_LIBRARY_DIRS.iter().map(PathBuf::from))
+            .collect::<Vec<_>>();
+        let env_dirs = std::env::var("LD_LIBRARY_PATH")
+            .map(|dirs| {
+                dirs.split(':')
+                    .filter(|dir| !dir.is_empty())
+                    .map(PathBuf::from)
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        let mut graph = DepGraph {
+            nodes: vec![DepNode {
+                name: path.to_string_lossy().into_owned(),
+                path: Some(path.to_path_buf()),
+                needed: root_deps.needed.clone(),
+            }],
+        };
+        let mut seen = HashMap::new();
+        let mut queue = VecDeque::from([(0, root_deps)]);
+
+        while let Some((index, deps)) = queue.pop_front() {
+            let origin = graph.nodes[index]
+                .path
+                .as_ref()
+                .and_then(|path| fs::canonicalize(path).ok())
+                .and_then(|path| path.parent().map(Path::to_path_buf))
+                .unwrap_or_default();
+
+            // DT_RPATH is ignored when DT_RUNPATH is present
+            let mut search = Vec::new();
+            if deps.runpath.is_empty() {
+                search.extend(deps.rpath.iter().map(|dir| expand_origin(dir, &origin)));
+            }
+            search.extend(env_dirs.iter().cloned());
+            search.extend(deps.runpath.iter().map(|dir| expand_origin(dir, &origin)));
+            search.extend(system_dirs.iter().cloned());
+
+            for name in &deps.needed {
+                if seen.contains_key(name) {
+                    continue;
+                }
+
+                let found = if name.contains('/') {
+                    Some(PathBuf::from(name)).filter(|path| compatible(path, class, machine))
+                } else {
+                    search
+                        .iter()
+                        .map(|dir| dir.join(name))
+                        .find(|path| compatible(path, class, machine))
+                };
+
+                let lib_deps = match &found {
+                    Some(path) => DynamicDeps::read(&FileData::new(path)?)?,
+                    None => DynamicDeps::default(),
+                };
+                seen.insert(name.clone(), graph.nodes.len());
+                graph.nodes.push(DepNode {
+                    name: name.clone(),
+                    path: found,
+                    needed: lib_deps.needed.clone(),
+                });
+                queue.push_back((graph.nodes.len() - 1, lib_deps));
+            }
+        }
+
+        Ok(graph)
+    }
+
+    /// Renders the graph in Graphviz format, unresolved libraries are drawn dashed
+    pub fn to_dot(&self) -> String {
+        let mut dot =
+            String::from("digraph dependencies {\n    rankdir=LR;\n    node [shape=box];\n");
+        for node in &self.nodes {
+            let label = match &node.path {
+                Some(path) if path.to_string_lossy() != node.name => format!(
+                    "{}\\n{}",
+                    escape(&node.name),
+                    escape(&path.to_string_lossy())
+                ),
+                _ => escape(&node.name),
+            };
+            dot.push_str(&format!(
+                "    \"{}\" [label=\"{}\"{}];\n",
+                escape(&node.name),
+                label,
+                if node.path.is_none() {
+                    ", style=dashed, color=red"
+                } else {
+                    ""
+                }
+            ));
+        }
+        for node in &self.nodes {
+            for needed in &node.needed {
+                dot.push_str(&format!(
+                    "    \"{}\" -> \"{}\";\n",
+                    escape(&node.name),
+                    escape(needed)
+                ));
+            }
+        }
+        dot.push_str("}\n");
+
+        dot
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
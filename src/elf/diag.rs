@@ -0,0 +1,113 @@
+use std::{
+    fmt::{self, Display},
+    sync::{Mutex, PoisonError},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Warning => "Warning",
+            Self::Error => "Error",
+        })
+    }
+}
+
+/// A problem that did not stop parsing, like the warnings GNU readelf prints for slightly
+/// corrupt files
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// Stable identifier of the kind of problem, e.g. `segment-past-eof`, for tools to
+    /// filter on
+    pub rule: &'static str,
+    /// File offset of the header field or table entry at fault, when there is one
+    pub offset: Option<u64>,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn warning<S: Into<String>>(rule: &'static str, message: S) -> Self {
+        Self {
+            severity: Severity::Warning,
+            rule,
+            offset: None,
+            message: message.into(),
+        }
+    }
+
+    pub fn error<S: Into<String>>(rule: &'static str, message: S) -> Self {
+        Self {
+            severity: Severity::Error,
+            ..Self::warning(rule, message)
+        }
+    }
+
+    pub fn at(mut self, offset: u64) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.severity, self.message)
+    }
+}
+
+/// Diagnostics collected while a file is parsed. Tables are decoded lazily behind a shared
+/// reference, so this takes a lock rather than `&mut self`.
+#[derive(Debug, Default)]
+pub struct Diagnostics(Mutex<Vec<Diagnostic>>);
+
+impl Diagnostics {
+    /// Records a diagnostic, the same one reported twice is kept once
+    pub fn push(&self, diagnostic: Diagnostic) {
+        let mut diagnostics = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        if !diagnostics.contains(&diagnostic) {
+            diagnostics.push(diagnostic);
+        }
+    }
+
+    pub fn warn<S: Into<String>>(&self, rule: &'static str, offset: u64, message: S) {
+        self.push(Diagnostic::warning(rule, message).at(offset));
+    }
+
+    pub fn to_vec(&self) -> Vec<Diagnostic> {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Diagnostic, Severity};
+    use crate::elf::core::FileData;
+
+    #[test]
+    fn collects_anomalies_as_warnings() {
+        let mut data = std::fs::read(std::env::current_exe().unwrap()).unwrap();
+        let phoff = u64::from_le_bytes(data[0x20..0x28].try_into().unwrap()) as usize;
+        // Stretch the first segment's file size past the end of the file
+        data[phoff + 0x20..phoff + 0x28].copy_from_slice(&u64::MAX.to_le_bytes());
+
+        let elf = FileData::from_bytes(data).unwrap();
+        let diagnostics = elf.diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert_eq!(diagnostics[0].rule, "segment-past-eof");
+        assert_eq!(diagnostics[0].offset, Some(phoff as u64));
+        assert!(diagnostics[0].message.starts_with("segment 0 ends at"));
+        assert_eq!(
+            Diagnostic::warning("test", "bad").to_string(),
+            "Warning: bad".to_string()
+        );
+    }
+}
@@ -0,0 +1,125 @@
+//! x86 and x86-64 disassembly, decoded and printed in AT&T syntax by iced-x86
+
+use iced_x86::{Decoder, DecoderOptions, Formatter, GasFormatter, Instruction as Decoded, OpKind};
+
+use super::{EM_386, EM_X86_64};
+
+/// A decoded instruction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instruction<'a> {
+    pub address: u64,
+    pub bytes: &'a [u8],
+    /// The instruction in AT&T syntax, `(bad)` for bytes that do not decode
+    pub text: String,
+    /// The target of a direct call or jump, which ends `text`
+    pub target: Option<u64>,
+    /// The address a RIP relative memory operand refers to
+    pub memory: Option<u64>,
+}
+
+/// The decoder bitness for an `e_machine`, `None` for the machines it cannot disassemble
+pub fn bitness(machine: u16) -> Option<u32> {
+    match machine {
+        EM_386 => Some(32),
+        EM_X86_64 => Some(64),
+        _ => None,
+    }
+}
+
+/// Disassembles `code`, loaded at `address`. Bytes that do not decode take one
+/// `(bad)` instruction each, as in objdump, and decoding resumes after them.
+pub fn disassemble(code: &[u8], address: u64, bitness: u32) -> Vec<Instruction<'_>> {
+    let mut decoder = Decoder::with_ip(bitness, code, address, DecoderOptions::NONE);
+    let mut formatter = GasFormatter::new();
+    // Mnemonics padded to 6 columns, numbers in lowercase hex, zero displacements shown
+    // and RIP relative operands kept relative, like objdump
+    let options = formatter.options_mut();
+    options.set_first_operand_char_index(7);
+    options.set_uppercase_hex(false);
+    options.set_small_hex_numbers_in_decimal(false);
+    options.set_branch_leading_zeros(false);
+    options.set_show_branch_size(false);
+    options.set_show_zero_displacements(true);
+    options.set_rip_relative_addresses(true);
+
+    let mut instructions = Vec::new();
+    let mut decoded = Decoded::default();
+    while decoder.can_decode() {
+        let start = decoder.position();
+        decoder.decode_out(&mut decoded);
+        let address = address.wrapping_add(start as u64);
+        if decoded.is_invalid() {
+            instructions.push(Instruction {
+                address,
+                bytes: &code[start..start + 1],
+                text: "(bad)".to_string(),
+                target: None,
+                memory: None,
+            });
+            // Decoding an invalid instruction may have consumed more than its first byte
+            let _ = decoder.set_position(start + 1);
+            decoder.set_ip(address.wrapping_add(1));
+            continue;
+        }
+        let mut text = String::new();
+        formatter.format(&decoded, &mut text);
+        let target = match decoded.op0_kind() {
+            OpKind::NearBranch16 | OpKind::NearBranch32 | OpKind::NearBranch64 => {
+                Some(decoded.near_branch_target())
+            }
+            _ => None,
+        };
+        // objdump prints branch targets without the 0x prefix of other numbers
+        if let Some(target) = target {
+            let prefixed = format!("0x{:x}", target);
+            if text.ends_with(&prefixed) {
+                text.truncate(text.len() - prefixed.len());
+                text.push_str(&format!("{:x}", target));
+            }
+        }
+        let memory = (decoded.op_count() > 0 && decoded.is_ip_rel_memory_operand())
+            .then(|| decoded.ip_rel_memory_address());
+        instructions.push(Instruction {
+            address,
+            bytes: &code[start..start + decoded.len()],
+            text,
+            target,
+            memory,
+        });
+    }
+
+    instructions
+}
+
+#[cfg(test)]
+mod test {
+    use super::disassemble;
+
+    #[test]
+    fn decodes_att_syntax() {
+        // sub $0x8,%rsp; mov 0x10(%rip),%rax; jmp to the start; ret; a lone 0x0f escape
+        let code = [
+            0x48, 0x83, 0xec, 0x08, 0x48, 0x8b, 0x05, 0x10, 0x00, 0x00, 0x00, 0xeb, 0xf3, 0xc3,
+            0x0f,
+        ];
+        let text: Vec<_> = disassemble(&code, 0x1000, 64)
+            .into_iter()
+            .map(|insn| (insn.address, insn.text, insn.target, insn.memory))
+            .collect();
+        assert_eq!(
+            text,
+            [
+                (0x1000, "sub    $0x8,%rsp".to_string(), None, None),
+                (
+                    0x1004,
+                    "mov    0x10(%rip),%rax".to_string(),
+                    None,
+                    Some(0x101b)
+                ),
+                (0x100b, "jmp    1000".to_string(), Some(0x1000), None),
+                (0x100d, "ret".to_string(), None, None),
+                (0x100e, "(bad)".to_string(), None, None),
+            ]
+        );
+    }
+}
@@ -0,0 +1,1687 @@
+use std::{collections::HashMap, ops::Range};
+
+use super::{
+    bytes::{string_at, ByteReader},
+    hdr::Endian,
+};
+
+/// The sizes a DWARF unit is encoded with, needed to read its attribute values
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Encoding {
+    pub version: u16,
+    /// 4 in the 32-bit DWARF format, 8 in the 64-bit one
+    pub offset_size: usize,
+    pub address_size: usize,
+}
+
+/// Reads the initial length field of a unit, returning the length after it and the offset
+/// size of the format it selects. `0xffffffff` escapes to the 64-bit format.
+pub fn unit_length(reader: &mut ByteReader) -> Option<(u64, usize)> {
+    match reader.u32()? {
+        0xffff_ffff => Some((reader.u64()?, 8)),
+        length => Some((length as u64, 4)),
+    }
+}
+
+/// An attribute value as read from its form
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttrValue<'a> {
+    /// Addresses, constants, references, section offsets and indices
+    Unsigned(u64),
+    Signed(i64),
+    Flag(bool),
+    /// `DW_FORM_block*`, `DW_FORM_exprloc` and `DW_FORM_data16`
+    Block(&'a [u8]),
+    /// An inline `DW_FORM_string`
+    String(&'a [u8]),
+}
+
+impl AttrValue<'_> {
+    pub fn as_u64(&self) -> Option<u64> {
+        match *self {
+            Self::Unsigned(value) => Some(value),
+            Self::Signed(value) => Some(value as u64),
+            Self::Flag(value) => Some(value as u64),
+            _ => None,
+        }
+    }
+}
+
+pub const DW_FORM_ADDR: u64 = 0x01;
+pub const DW_FORM_STRP: u64 = 0x0e;
+pub const DW_FORM_REF_ADDR: u64 = 0x10;
+pub const DW_FORM_INDIRECT: u64 = 0x16;
+pub const DW_FORM_SEC_OFFSET: u64 = 0x17;
+pub const DW_FORM_FLAG_PRESENT: u64 = 0x19;
+pub const DW_FORM_STRX: u64 = 0x1a;
+pub const DW_FORM_ADDRX: u64 = 0x1b;
+pub const DW_FORM_IMPLICIT_CONST: u64 = 0x21;
+pub const DW_FORM_LOCLISTX: u64 = 0x22;
+pub const DW_FORM_RNGLISTX: u64 = 0x23;
+pub const DW_FORM_LINE_STRP: u64 = 0x1f;
+pub const DW_FORM_GNU_ADDR_INDEX: u64 = 0x1f01;
+pub const DW_FORM_GNU_STR_INDEX: u64 = 0x1f02;
+
+/// Whether a form holds an index into the unit's part of `.debug_str_offsets`
+pub fn is_strx(form: u64) -> bool {
+    matches!(form, DW_FORM_STRX | 0x25..=0x28 | DW_FORM_GNU_STR_INDEX)
+}
+
+/// Whether a form holds an index into the unit's part of `.debug_addr`
+pub fn is_addrx(form: u64) -> bool {
+    matches!(form, DW_FORM_ADDRX | 0x29..=0x2c | DW_FORM_GNU_ADDR_INDEX)
+}
+
+/// Whether a form refers to another DIE of the same unit, by its offset in the unit
+pub fn is_unit_ref(form: u64) -> bool {
+    matches!(form, 0x11..=0x15)
+}
+
+/// Reads a value of the given `DW_FORM_*`, `implicit_const` is the value an abbreviation
+/// stores for `DW_FORM_implicit_const`. `None` for unknown forms, whose size cannot be
+/// known, and truncated data.
+pub fn read_form<'a>(
+    reader: &mut ByteReader<'a>,
+    form: u64,
+    encoding: Encoding,
+    implicit_const: i64,
+) -> Option<AttrValue<'a>> {
+    use AttrValue::*;
+
+    let offset = encoding.offset_size;
+    Some(match form {
+        DW_FORM_ADDR => Unsigned(reader.word(encoding.address_size)?),
+        0x03 => {
+            let len = reader.u16()? as usize;
+            Block(reader.bytes(len)?)
+        }
+        0x04 => {
+            let len = reader.u32()? as usize;
+            Block(reader.bytes(len)?)
+        }
+        0x05 | 0x12 => Unsigned(reader.u16()? as u64),
+        0x06 | 0x13 | 0x1c => Unsigned(reader.u32()? as u64),
+        0x07 | 0x14 | 0x20 | 0x24 => Unsigned(reader.u64()?),
+        0x08 => String(reader.cstr()?),
+        0x09 | 0x18 => {
+            let len = reader.uleb128()? as usize;
+            Block(reader.bytes(len)?)
+        }
+        0x0a => {
+            let len = reader.u8()? as usize;
+            Block(reader.bytes(len)?)
+        }
+        0x0b | 0x11 | 0x25 | 0x29 => Unsigned(reader.u8()? as u64),
+        0x0c => Flag(reader.u8()? != 0),
+        0x0d => Signed(reader.sleb128()?),
+        // `DW_FORM_ref_addr` was address sized before DWARF 3
+        0x10 if encoding.version < 3 => Unsigned(reader.word(encoding.address_size)?),
+        DW_FORM_STRP | 0x10 | 0x17 | 0x1d | DW_FORM_LINE_STRP | 0x1f20 | 0x1f21 => {
+            Unsigned(reader.word(offset)?)
+        }
+        0x0f | 0x15 | 0x1a | 0x1b | 0x22 | 0x23 | 0x1f01 | 0x1f02 => Unsigned(reader.uleb128()?),
+        DW_FORM_INDIRECT => {
+            let form = reader.uleb128()?;
+            return read_form(reader, form, encoding, implicit_const);
+        }
+        DW_FORM_FLAG_PRESENT => Flag(true),
+        0x1e => Block(reader.bytes(16)?),
+        DW_FORM_IMPLICIT_CONST => Signed(implicit_const),
+        0x26 | 0x2a => Unsigned(reader.u16()? as u64),
+        0x27 | 0x2b => {
+            let bytes = reader.bytes(3)?;
+            let (low, high) = match reader.endian() {
+                Endian::Big => (bytes[2], bytes[0]),
+                _ => (bytes[0], bytes[2]),
+            };
+            Unsigned(low as u64 | (bytes[1] as u64) << 8 | (high as u64) << 16)
+        }
+        0x28 | 0x2c => Unsigned(reader.u32()? as u64),
+        _ => return None,
+    })
+}
+
+pub const DW_TAG_INLINED_SUBROUTINE: u64 = 0x1d;
+pub const DW_TAG_SUBPROGRAM: u64 = 0x2e;
+
+/// The name of a `DW_TAG_*` constant without its prefix
+pub fn tag_name(tag: u64) -> Option<&'static str> {
+    Some(match tag {
+        0x01 => "array_type",
+        0x02 => "class_type",
+        0x03 => "entry_point",
+        0x04 => "enumeration_type",
+        0x05 => "formal_parameter",
+        0x08 => "imported_declaration",
+        0x0a => "label",
+        0x0b => "lexical_block",
+        0x0d => "member",
+        0x0f => "pointer_type",
+        0x10 => "reference_type",
+        0x11 => "compile_unit",
+        0x12 => "string_type",
+        0x13 => "structure_type",
+        0x15 => "subroutine_type",
+        0x16 => "typedef",
+        0x17 => "union_type",
+        0x18 => "unspecified_parameters",
+        0x19 => "variant",
+        0x1a => "common_block",
+        0x1b => "common_inclusion",
+        0x1c => "inheritance",
+        0x1d => "inlined_subroutine",
+        0x1e => "module",
+        0x1f => "ptr_to_member_type",
+        0x20 => "set_type",
+        0x21 => "subrange_type",
+        0x22 => "with_stmt",
+        0x23 => "access_declaration",
+        0x24 => "base_type",
+        0x25 => "catch_block",
+        0x26 => "const_type",
+        0x27 => "constant",
+        0x28 => "enumerator",
+        0x29 => "file_type",
+        0x2a => "friend",
+        0x2b => "namelist",
+        0x2c => "namelist_item",
+        0x2d => "packed_type",
+        0x2e => "subprogram",
+        0x2f => "template_type_param",
+        0x30 => "template_value_param",
+        0x31 => "thrown_type",
+        0x32 => "try_block",
+        0x33 => "variant_part",
+        0x34 => "variable",
+        0x35 => "volatile_type",
+        0x36 => "dwarf_procedure",
+        0x37 => "restrict_type",
+        0x38 => "interface_type",
+        0x39 => "namespace",
+        0x3a => "imported_module",
+        0x3b => "unspecified_type",
+        0x3c => "partial_unit",
+        0x3d => "imported_unit",
+        0x3f => "condition",
+        0x40 => "shared_type",
+        0x41 => "type_unit",
+        0x42 => "rvalue_reference_type",
+        0x43 => "template_alias",
+        0x44 => "coarray_type",
+        0x45 => "generic_subrange",
+        0x46 => "dynamic_type",
+        0x47 => "atomic_type",
+        0x48 => "call_site",
+        0x49 => "call_site_parameter",
+        0x4a => "skeleton_unit",
+        0x4b => "immutable_type",
+        0x4106 => "GNU_template_template_param",
+        0x4107 => "GNU_template_parameter_pack",
+        0x4108 => "GNU_formal_parameter_pack",
+        0x4109 => "GNU_call_site",
+        0x410a => "GNU_call_site_parameter",
+        _ => return None,
+    })
+}
+
+/// `DW_TAG_name` the way binutils prints it, or the raw value for unknown tags
+pub fn tag_string(tag: u64) -> String {
+    match tag_name(tag) {
+        Some(name) => format!("DW_TAG_{}", name),
+        None => format!("DW_TAG_<0x{:x}>", tag),
+    }
+}
+
+pub const DW_AT_LOCATION: u64 = 0x02;
+pub const DW_AT_NAME: u64 = 0x03;
+pub const DW_AT_STMT_LIST: u64 = 0x10;
+pub const DW_AT_LOW_PC: u64 = 0x11;
+pub const DW_AT_HIGH_PC: u64 = 0x12;
+pub const DW_AT_COMP_DIR: u64 = 0x1b;
+pub const DW_AT_ABSTRACT_ORIGIN: u64 = 0x31;
+pub const DW_AT_SPECIFICATION: u64 = 0x47;
+pub const DW_AT_RANGES: u64 = 0x55;
+pub const DW_AT_CALL_COLUMN: u64 = 0x57;
+pub const DW_AT_CALL_FILE: u64 = 0x58;
+pub const DW_AT_CALL_LINE: u64 = 0x59;
+pub const DW_AT_LINKAGE_NAME: u64 = 0x6e;
+pub const DW_AT_STR_OFFSETS_BASE: u64 = 0x72;
+pub const DW_AT_ADDR_BASE: u64 = 0x73;
+pub const DW_AT_RNGLISTS_BASE: u64 = 0x74;
+pub const DW_AT_DWO_NAME: u64 = 0x76;
+pub const DW_AT_LOCLISTS_BASE: u64 = 0x8c;
+pub const DW_AT_MIPS_LINKAGE_NAME: u64 = 0x2007;
+pub const DW_AT_GNU_DWO_NAME: u64 = 0x2130;
+pub const DW_AT_GNU_DWO_ID: u64 = 0x2131;
+pub const DW_AT_GNU_RANGES_BASE: u64 = 0x2132;
+pub const DW_AT_GNU_ADDR_BASE: u64 = 0x2133;
+pub const DW_AT_GNU_LOCVIEWS: u64 = 0x2137;
+
+/// The name of a `DW_AT_*` constant without its prefix
+pub fn at_name(at: u64) -> Option<&'static str> {
+    Some(match at {
+        0x01 => "sibling",
+        0x02 => "location",
+        0x03 => "name",
+        0x09 => "ordering",
+        0x0b => "byte_size",
+        0x0c => "bit_offset",
+        0x0d => "bit_size",
+        0x10 => "stmt_list",
+        0x11 => "low_pc",
+        0x12 => "high_pc",
+        0x13 => "language",
+        0x15 => "discr",
+        0x16 => "discr_value",
+        0x17 => "visibility",
+        0x18 => "import",
+        0x19 => "string_length",
+        0x1a => "common_reference",
+        0x1b => "comp_dir",
+        0x1c => "const_value",
+        0x1d => "containing_type",
+        0x1e => "default_value",
+        0x20 => "inline",
+        0x21 => "is_optional",
+        0x22 => "lower_bound",
+        0x25 => "producer",
+        0x27 => "prototyped",
+        0x2a => "return_addr",
+        0x2c => "start_scope",
+        0x2e => "bit_stride",
+        0x2f => "upper_bound",
+        0x31 => "abstract_origin",
+        0x32 => "accessibility",
+        0x33 => "address_class",
+        0x34 => "artificial",
+        0x35 => "base_types",
+        0x36 => "calling_convention",
+        0x37 => "count",
+        0x38 => "data_member_location",
+        0x39 => "decl_column",
+        0x3a => "decl_file",
+        0x3b => "decl_line",
+        0x3c => "declaration",
+        0x3d => "discr_list",
+        0x3e => "encoding",
+        0x3f => "external",
+        0x40 => "frame_base",
+        0x41 => "friend",
+        0x42 => "identifier_case",
+        0x43 => "macro_info",
+        0x44 => "namelist_item",
+        0x45 => "priority",
+        0x46 => "segment",
+        0x47 => "specification",
+        0x48 => "static_link",
+        0x49 => "type",
+        0x4a => "use_location",
+        0x4b => "variable_parameter",
+        0x4c => "virtuality",
+        0x4d => "vtable_elem_location",
+        0x4e => "allocated",
+        0x4f => "associated",
+        0x50 => "data_location",
+        0x51 => "byte_stride",
+        0x52 => "entry_pc",
+        0x53 => "use_UTF8",
+        0x54 => "extension",
+        0x55 => "ranges",
+        0x56 => "trampoline",
+        0x57 => "call_column",
+        0x58 => "call_file",
+        0x59 => "call_line",
+        0x5a => "description",
+        0x5b => "binary_scale",
+        0x5c => "decimal_scale",
+        0x5d => "small",
+        0x5e => "decimal_sign",
+        0x5f => "digit_count",
+        0x60 => "picture_string",
+        0x61 => "mutable",
+        0x62 => "threads_scaled",
+        0x63 => "explicit",
+        0x64 => "object_pointer",
+        0x65 => "endianity",
+        0x66 => "elemental",
+        0x67 => "pure",
+        0x68 => "recursive",
+        0x69 => "signature",
+        0x6a => "main_subprogram",
+        0x6b => "data_bit_offset",
+        0x6c => "const_expr",
+        0x6d => "enum_class",
+        0x6e => "linkage_name",
+        0x6f => "string_length_bit_size",
+        0x70 => "string_length_byte_size",
+        0x71 => "rank",
+        0x72 => "str_offsets_base",
+        0x73 => "addr_base",
+        0x74 => "rnglists_base",
+        0x76 => "dwo_name",
+        0x77 => "reference",
+        0x78 => "rvalue_reference",
+        0x79 => "macros",
+        0x7a => "call_all_calls",
+        0x7b => "call_all_source_calls",
+        0x7c => "call_all_tail_calls",
+        0x7d => "call_return_pc",
+        0x7e => "call_value",
+        0x7f => "call_origin",
+        0x80 => "call_parameter",
+        0x81 => "call_pc",
+        0x82 => "call_tail_call",
+        0x83 => "call_target",
+        0x84 => "call_target_clobbered",
+        0x85 => "call_data_location",
+        0x86 => "call_data_value",
+        0x87 => "noreturn",
+        0x88 => "alignment",
+        0x89 => "export_symbols",
+        0x8a => "deleted",
+        0x8b => "defaulted",
+        0x8c => "loclists_base",
+        0x2007 => "MIPS_linkage_name",
+        0x2107 => "GNU_vector",
+        0x210f => "GNU_odr_signature",
+        0x2110 => "GNU_template_name",
+        0x2111 => "GNU_call_site_value",
+        0x2112 => "GNU_call_site_data_value",
+        0x2113 => "GNU_call_site_target",
+        0x2114 => "GNU_call_site_target_clobbered",
+        0x2115 => "GNU_tail_call",
+        0x2116 => "GNU_all_tail_call_sites",
+        0x2117 => "GNU_all_call_sites",
+        0x2118 => "GNU_all_source_call_sites",
+        0x2119 => "GNU_macros",
+        0x211a => "GNU_deleted",
+        0x2130 => "GNU_dwo_name",
+        0x2131 => "GNU_dwo_id",
+        0x2132 => "GNU_ranges_base",
+        0x2133 => "GNU_addr_base",
+        0x2134 => "GNU_pubnames",
+        0x2135 => "GNU_pubtypes",
+        0x2136 => "GNU_discriminator",
+        0x2137 => "GNU_locviews",
+        0x2138 => "GNU_entry_view",
+        _ => return None,
+    })
+}
+
+/// `DW_AT_name` the way binutils prints it, or the raw value for unknown attributes
+pub fn at_string(at: u64) -> String {
+    match at_name(at) {
+        Some(name) => format!("DW_AT_{}", name),
+        None => format!("DW_AT_<0x{:x}>", at),
+    }
+}
+
+/// Whether an attribute holding a section offset points into the location lists, rather
+/// than being a plain constant
+pub fn is_location_attr(at: u64) -> bool {
+    matches!(
+        at,
+        DW_AT_LOCATION | 0x19 | 0x2a | 0x38 | 0x40 | 0x46 | 0x48 | 0x4a | 0x4d
+    )
+}
+
+/// The name of a `DW_OP_*` operation without its prefix
+fn op_name(op: u8) -> Option<&'static str> {
+    Some(match op {
+        0x03 => "addr",
+        0x06 => "deref",
+        0x08 => "const1u",
+        0x09 => "const1s",
+        0x0a => "const2u",
+        0x0b => "const2s",
+        0x0c => "const4u",
+        0x0d => "const4s",
+        0x0e => "const8u",
+        0x0f => "const8s",
+        0x10 => "constu",
+        0x11 => "consts",
+        0x12 => "dup",
+        0x13 => "drop",
+        0x14 => "over",
+        0x15 => "pick",
+        0x16 => "swap",
+        0x17 => "rot",
+        0x18 => "xderef",
+        0x19 => "abs",
+        0x1a => "and",
+        0x1b => "div",
+        0x1c => "minus",
+        0x1d => "mod",
+        0x1e => "mul",
+        0x1f => "neg",
+        0x20 => "not",
+        0x21 => "or",
+        0x22 => "plus",
+        0x23 => "plus_uconst",
+        0x24 => "shl",
+        0x25 => "shr",
+        0x26 => "shra",
+        0x27 => "xor",
+        0x28 => "bra",
+        0x29 => "eq",
+        0x2a => "ge",
+        0x2b => "gt",
+        0x2c => "le",
+        0x2d => "lt",
+        0x2e => "ne",
+        0x2f => "skip",
+        0x90 => "regx",
+        0x91 => "fbreg",
+        0x92 => "bregx",
+        0x93 => "piece",
+        0x94 => "deref_size",
+        0x95 => "xderef_size",
+        0x96 => "nop",
+        0x97 => "push_object_address",
+        0x98 => "call2",
+        0x99 => "call4",
+        0x9a => "call_ref",
+        0x9b => "form_tls_address",
+        0x9c => "call_frame_cfa",
+        0x9d => "bit_piece",
+        0x9e => "implicit_value",
+        0x9f => "stack_value",
+        0xa0 => "implicit_pointer",
+        0xa1 => "addrx",
+        0xa2 => "constx",
+        0xa3 => "entry_value",
+        0xa4 => "const_type",
+        0xa5 => "regval_type",
+        0xa6 => "deref_type",
+        0xa7 => "xderef_type",
+        0xa8 => "convert",
+        0xa9 => "reinterpret",
+        0xe0 => "GNU_push_tls_address",
+        0xf0 => "GNU_uninit",
+        0xf1 => "GNU_encoded_addr",
+        0xf2 => "GNU_implicit_pointer",
+        0xf3 => "GNU_entry_value",
+        0xf4 => "GNU_const_type",
+        0xf5 => "GNU_regval_type",
+        0xf6 => "GNU_deref_type",
+        0xf7 => "GNU_convert",
+        0xf9 => "GNU_reinterpret",
+        0xfa => "GNU_parameter_ref",
+        0xfb => "GNU_addr_index",
+        0xfc => "GNU_const_index",
+        0xfd => "GNU_variable_value",
+        _ => return None,
+    })
+}
+
+/// A location expression the way binutils prints it, `DW_OP_reg5; DW_OP_stack_value`.
+/// Operations past one that cannot be decoded are left out.
+pub fn expression_string(expr: &[u8], encoding: Encoding, endian: Endian) -> String {
+    let mut reader = ByteReader::new(expr, endian);
+    let mut ops = Vec::new();
+    let next = |reader: &mut ByteReader| -> Option<String> {
+        let op = reader.u8()?;
+        let name = match op {
+            0x30..=0x4f => return Some(format!("DW_OP_lit{}", op - 0x30)),
+            0x50..=0x6f => return Some(format!("DW_OP_reg{}", op - 0x50)),
+            0x70..=0x8f => return Some(format!("DW_OP_breg{}: {}", op - 0x70, reader.sleb128()?)),
+            _ => format!("DW_OP_{}", op_name(op)?),
+        };
+        let operands = match op {
+            0x03 => format!("{:x}", reader.word(encoding.address_size)?),
+            0x08 | 0x15 | 0x94 | 0x95 => reader.u8()?.to_string(),
+            0x09 => (reader.u8()? as i8).to_string(),
+            0x0a | 0x98 => reader.u16()?.to_string(),
+            0x0b | 0x28 | 0x2f => (reader.u16()? as i16).to_string(),
+            0x0c | 0x99 => reader.u32()?.to_string(),
+            0x0d => reader.i32()?.to_string(),
+            0x0e => reader.u64()?.to_string(),
+            0x0f => (reader.u64()? as i64).to_string(),
+            0x10 | 0x23 | 0x90 | 0x93 | 0xa8 | 0xa9 | 0xf7 | 0xf9 => reader.uleb128()?.to_string(),
+            0xa1 | 0xa2 | 0xfb | 0xfc => format!("<0x{:x}>", reader.uleb128()?),
+            0x11 | 0x91 => reader.sleb128()?.to_string(),
+            0x92 => format!("{} ({})", reader.uleb128()?, reader.sleb128()?),
+            0x9d => format!("size: {} offset: {}", reader.uleb128()?, reader.uleb128()?),
+            0x9a | 0xfd => format!("<0x{:x}>", reader.word(encoding.offset_size)?),
+            0xa0 | 0xf2 => format!(
+                "<0x{:x}> {}",
+                reader.word(encoding.offset_size)?,
+                reader.sleb128()?
+            ),
+            0xfa => format!("<0x{:x}>", reader.u32()?),
+            0x9e => {
+                let len = reader.uleb128()? as usize;
+                let bytes = reader.bytes(len)?;
+                let bytes: Vec<_> = bytes.iter().map(|b| format!("{:x}", b)).collect();
+                format!("{} byte block: {}", len, bytes.join(" "))
+            }
+            0xa3 | 0xf3 => {
+                let len = reader.uleb128()? as usize;
+                format!(
+                    "({})",
+                    expression_string(reader.bytes(len)?, encoding, endian)
+                )
+            }
+            0xa4 | 0xf4 => {
+                let die = reader.uleb128()?;
+                let len = reader.u8()? as usize;
+                let bytes: Vec<_> = reader
+                    .bytes(len)?
+                    .iter()
+                    .map(|b| format!("{:x}", b))
+                    .collect();
+                format!("<0x{:x}> {} byte block: {}", die, len, bytes.join(" "))
+            }
+            0xa5 | 0xf5 => format!("{} <0x{:x}>", reader.uleb128()?, reader.uleb128()?),
+            0xa6 | 0xa7 | 0xf6 => format!("{}: <0x{:x}>", reader.u8()?, reader.uleb128()?),
+            _ => return Some(name),
+        };
+        Some(format!("{}: {}", name, operands))
+    };
+    while !reader.is_empty() {
+        match next(&mut reader) {
+            Some(op) => ops.push(op),
+            None => break,
+        }
+    }
+
+    ops.join("; ")
+}
+
+/// The file names of a line number program header, numbered the way `DW_AT_decl_file`
+/// and `DW_MACRO_start_file` refer to them
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileTable {
+    pub version: u16,
+    /// Paths joined with their include directory
+    pub names: Vec<String>,
+}
+
+impl FileTable {
+    /// Reads the header of the line number program at `offset` in `.debug_line`. DWARF 5
+    /// headers may keep their strings in `.debug_str` or `.debug_line_str`.
+    pub fn parse(
+        debug_line: &[u8],
+        offset: u64,
+        endian: Endian,
+        debug_str: &[u8],
+        line_str: &[u8],
+    ) -> Option<Self> {
+        LineProgram::parse(debug_line, offset, endian, debug_str, line_str)
+            .map(|program| program.files)
+    }
+
+    pub fn name(&self, file: u64) -> Option<&str> {
+        self.names
+            .get(file as usize)
+            .map(String::as_str)
+            .filter(|name| !name.is_empty())
+    }
+}
+
+const DW_LNCT_PATH: u64 = 1;
+const DW_LNCT_DIRECTORY_INDEX: u64 = 2;
+
+/// A line number program of `.debug_line`: its header and where its opcodes are
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineProgram {
+    pub files: FileTable,
+    min_inst_length: u8,
+    default_is_stmt: bool,
+    line_base: i8,
+    line_range: u8,
+    opcode_base: u8,
+    /// Operand counts of the standard opcodes, so unknown ones can be skipped
+    standard_lengths: Vec<u8>,
+    /// The opcodes, as offsets in the section
+    program: Range<usize>,
+}
+
+/// A row of the line number matrix, the source position of the instructions from
+/// `address` up to the next row
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineRow {
+    pub address: u64,
+    /// Index into the program's [`FileTable`]
+    pub file: u64,
+    pub line: u64,
+    pub column: u64,
+    pub is_stmt: bool,
+    /// The first address past a sequence of instructions, the row holds no position
+    pub end_sequence: bool,
+}
+
+impl LineProgram {
+    /// Reads the header of the line number program at `offset` in `.debug_line`, the
+    /// DWARF 2 to 4 layout or the DWARF 5 one with its entry formats
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "line_program",
+            level = "debug",
+            skip_all,
+            fields(offset = offset)
+        )
+    )]
+    pub fn parse(
+        debug_line: &[u8],
+        offset: u64,
+        endian: Endian,
+        debug_str: &[u8],
+        line_str: &[u8],
+    ) -> Option<Self> {
+        let mut reader = ByteReader::new(debug_line, endian);
+        reader.seek(offset as usize)?;
+        let (length, offset_size) = unit_length(&mut reader)?;
+        let end = reader
+            .pos()
+            .checked_add(length as usize)?
+            .min(debug_line.len());
+        let version = reader.u16()?;
+        let mut address_size = offset_size;
+        if version >= 5 {
+            address_size = reader.u8()? as usize;
+            reader.skip(1)?;
+        }
+        let header_length = reader.word(offset_size)?;
+        let start = reader.pos().checked_add(header_length as usize)?;
+        let min_inst_length = reader.u8()?;
+        if version >= 4 {
+            // maximum_operations_per_instruction, only VLIW targets use more than one
+            reader.skip(1)?;
+        }
+        let default_is_stmt = reader.u8()? != 0;
+        let line_base = reader.u8()? as i8;
+        let line_range = reader.u8()?;
+        let opcode_base = reader.u8()?;
+        let standard_lengths = reader
+            .bytes((opcode_base as usize).saturating_sub(1))?
+            .to_vec();
+        let header = |files| Self {
+            files,
+            min_inst_length,
+            default_is_stmt,
+            line_base,
+            line_range,
+            opcode_base,
+            standard_lengths,
+            program: start..end,
+        };
+
+        if version < 5 {
+            let mut directories = Vec::new();
+            loop {
+                let directory = reader.cstr()?;
+                if directory.is_empty() {
+                    break;
+                }
+                directories.push(String::from_utf8_lossy(directory).into_owned());
+            }
+            // File 0 is the compilation unit's own file, which is not listed before DWARF 5
+            let mut names = vec![String::new()];
+            loop {
+                let name = reader.cstr()?;
+                if name.is_empty() {
+                    break;
+                }
+                let directory = reader.uleb128()?;
+                reader.uleb128()?;
+                reader.uleb128()?;
+                let name = String::from_utf8_lossy(name).into_owned();
+                // Directory 0 is the compilation directory, names in it stay relative
+                let directory = (directory as usize)
+                    .checked_sub(1)
+                    .and_then(|directory| directories.get(directory));
+                names.push(match directory {
+                    Some(directory) => format!("{}/{}", directory, name),
+                    None => name,
+                });
+            }
+            return Some(header(FileTable { version, names }));
+        }
+
+        let encoding = Encoding {
+            version,
+            offset_size,
+            address_size,
+        };
+        let entries = |reader: &mut ByteReader| -> Option<Vec<(String, u64)>> {
+            let format_count = reader.u8()?;
+            let formats = (0..format_count)
+                .map(|_| Some((reader.uleb128()?, reader.uleb128()?)))
+                .collect::<Option<Vec<_>>>()?;
+            let count = reader.uleb128()?;
+            (0..count)
+                .map(|_| {
+                    let (mut path, mut directory) = (String::new(), 0);
+                    for &(content, form) in &formats {
+                        let value = read_form(reader, form, encoding, 0)?;
+                        match (content, value) {
+                            (DW_LNCT_PATH, AttrValue::String(s)) => {
+                                path = String::from_utf8_lossy(s).into_owned()
+                            }
+                            (DW_LNCT_PATH, AttrValue::Unsigned(offset)) => {
+                                path = match form {
+                                    DW_FORM_LINE_STRP => string_at(line_str, offset as usize),
+                                    _ => string_at(debug_str, offset as usize),
+                                }
+                            }
+                            (DW_LNCT_DIRECTORY_INDEX, value) => {
+                                directory = value.as_u64().unwrap_or(0)
+                            }
+                            _ => {}
+                        }
+                    }
+                    Some((path, directory))
+                })
+                .collect()
+        };
+        let directories = entries(&mut reader)?;
+        let names = entries(&mut reader)?
+            .into_iter()
+            .map(|(name, index)| match directories.get(index as usize) {
+                Some((directory, _)) if index != 0 && !name.starts_with('/') => {
+                    format!("{}/{}", directory, name)
+                }
+                _ => name,
+            })
+            .collect();
+
+        Some(header(FileTable { version, names }))
+    }
+
+    /// Runs the program, the rows of every sequence in the order they are emitted
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub fn rows(&self, debug_line: &[u8], endian: Endian) -> Vec<LineRow> {
+        let mut rows = Vec::new();
+        let data = match debug_line.get(..self.program.end) {
+            Some(data) => data,
+            None => return rows,
+        };
+        let mut reader = ByteReader::new(data, endian);
+        if reader.seek(self.program.start).is_none() || self.line_range == 0 {
+            return rows;
+        }
+
+        let initial = LineRow {
+            address: 0,
+            file: 1,
+            line: 1,
+            column: 0,
+            is_stmt: self.default_is_stmt,
+            end_sequence: false,
+        };
+        let min_inst_length = self.min_inst_length as u64;
+        let mut row = initial;
+        let mut step = |reader: &mut ByteReader| -> Option<()> {
+            let opcode = reader.u8()?;
+            if opcode >= self.opcode_base {
+                let adjusted = opcode - self.opcode_base;
+                row.address = row
+                    .address
+                    .wrapping_add((adjusted / self.line_range) as u64 * min_inst_length);
+                let advance = self.line_base as i64 + (adjusted % self.line_range) as i64;
+                row.line = row.line.wrapping_add(advance as u64);
+                rows.push(row);
+                return Some(());
+            }
+            match opcode {
+                0 => {
+                    let len = reader.uleb128()? as usize;
+                    let next = reader.pos().checked_add(len)?;
+                    match reader.u8()? {
+                        // DW_LNE_end_sequence
+                        1 => {
+                            row.end_sequence = true;
+                            rows.push(row);
+                            row = initial;
+                        }
+                        // DW_LNE_set_address, sized by the operand before DWARF 5 gave
+                        // the header an address size
+                        2 => row.address = reader.word(len.saturating_sub(1))?,
+                        _ => {}
+                    }
+                    reader.seek(next)?;
+                }
+                // DW_LNS_copy
+                1 => rows.push(row),
+                2 => {
+                    let advance = reader.uleb128()?;
+                    row.address = row.address.wrapping_add(advance * min_inst_length);
+                }
+                3 => row.line = row.line.wrapping_add(reader.sleb128()? as u64),
+                4 => row.file = reader.uleb128()?,
+                5 => row.column = reader.uleb128()?,
+                6 => row.is_stmt = !row.is_stmt,
+                // DW_LNS_const_add_pc, the address advance of special opcode 255
+                8 => {
+                    let adjusted = 255 - self.opcode_base;
+                    row.address = row
+                        .address
+                        .wrapping_add((adjusted / self.line_range) as u64 * min_inst_length);
+                }
+                9 => row.address = row.address.wrapping_add(reader.u16()? as u64),
+                _ => {
+                    let operands = self.standard_lengths.get(opcode as usize - 1)?;
+                    for _ in 0..*operands {
+                        reader.uleb128()?;
+                    }
+                }
+            }
+            Some(())
+        };
+        while !reader.is_empty() {
+            if step(&mut reader).is_none() {
+                break;
+            }
+        }
+
+        rows
+    }
+}
+
+/// An entry of `.debug_abbrev`, the shape shared by every DIE using its code
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Abbreviation {
+    pub code: u64,
+    pub tag: u64,
+    pub has_children: bool,
+    /// `(DW_AT_*, DW_FORM_*, implicit constant)` triples in DIE order
+    pub attributes: Vec<(u64, u64, i64)>,
+}
+
+/// Parses the abbreviation table at `offset` in `.debug_abbrev`, keyed by code
+pub fn parse_abbrevs(data: &[u8], offset: u64, endian: Endian) -> HashMap<u64, Abbreviation> {
+    let mut abbrevs = HashMap::new();
+    let mut reader = ByteReader::new(data, endian);
+    if reader.seek(offset as usize).is_none() {
+        return abbrevs;
+    }
+
+    let mut next = || {
+        let code = reader.uleb128()?;
+        if code == 0 {
+            return None;
+        }
+        let tag = reader.uleb128()?;
+        let has_children = reader.u8()? != 0;
+        let mut attributes = Vec::new();
+        loop {
+            let name = reader.uleb128()?;
+            let form = reader.uleb128()?;
+            if name == 0 && form == 0 {
+                break;
+            }
+            let implicit_const = match form {
+                DW_FORM_IMPLICIT_CONST => reader.sleb128()?,
+                _ => 0,
+            };
+            attributes.push((name, form, implicit_const));
+        }
+        Some(Abbreviation {
+            code,
+            tag,
+            has_children,
+            attributes,
+        })
+    };
+    while let Some(abbrev) = next() {
+        abbrevs.insert(abbrev.code, abbrev);
+    }
+
+    abbrevs
+}
+
+/// The header of a unit of `.debug_info` or `.debug_types`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnitHeader {
+    pub offset: usize,
+    pub length: u64,
+    pub encoding: Encoding,
+    /// `DW_UT_*`, `DW_UT_compile` for DWARF 4 `.debug_info` units and `DW_UT_type` for
+    /// `.debug_types` ones
+    pub unit_type: u8,
+    pub abbrev_offset: u64,
+    /// The id tying a skeleton unit to its split unit
+    pub dwo_id: Option<u64>,
+    /// Signature and unit relative offset of the type DIE, for type units
+    pub type_signature: Option<(u64, u64)>,
+    /// Offset of the first DIE in the section
+    pub entries: usize,
+    /// Offset just past the unit
+    pub end: usize,
+}
+
+pub const DW_UT_COMPILE: u8 = 1;
+pub const DW_UT_TYPE: u8 = 2;
+
+impl UnitHeader {
+    /// Reads the header of the unit at the reader's position, `types` for the DWARF 4
+    /// `.debug_types` section whose units carry a type signature
+    pub fn parse(reader: &mut ByteReader, types: bool) -> Option<Self> {
+        let offset = reader.pos();
+        let (length, offset_size) = unit_length(reader)?;
+        let end = reader.pos().checked_add(length as usize)?;
+        let version = reader.u16()?;
+        let (unit_type, address_size, abbrev_offset) = if version >= 5 {
+            let unit_type = reader.u8()?;
+            let address_size = reader.u8()?;
+            (unit_type, address_size, reader.word(offset_size)?)
+        } else {
+            let abbrev_offset = reader.word(offset_size)?;
+            let unit_type = if types { DW_UT_TYPE } else { DW_UT_COMPILE };
+            (unit_type, reader.u8()?, abbrev_offset)
+        };
+        let (mut dwo_id, mut type_signature) = (None, None);
+        match unit_type {
+            // Skeleton and split compilation units carry the DWO id
+            4 | 5 => dwo_id = Some(reader.u64()?),
+            // Type units carry the signature and the offset of the type DIE
+            DW_UT_TYPE | 6 => type_signature = Some((reader.u64()?, reader.word(offset_size)?)),
+            _ => {}
+        }
+
+        Some(Self {
+            offset,
+            length,
+            encoding: Encoding {
+                version,
+                offset_size,
+                address_size: address_size as usize,
+            },
+            unit_type,
+            abbrev_offset,
+            dwo_id,
+            type_signature,
+            entries: reader.pos(),
+            end,
+        })
+    }
+}
+
+/// The name of a `DW_UT_*` unit type without its prefix
+pub fn unit_type_name(unit_type: u8) -> Option<&'static str> {
+    Some(match unit_type {
+        DW_UT_COMPILE => "compile",
+        DW_UT_TYPE => "type",
+        3 => "partial",
+        4 => "skeleton",
+        5 => "split_compile",
+        6 => "split_type",
+        _ => return None,
+    })
+}
+
+/// An attribute of a DIE and the offset in the section its value was read from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Attribute<'a> {
+    pub name: u64,
+    pub form: u64,
+    pub value: AttrValue<'a>,
+    pub offset: usize,
+}
+
+/// A debugging information entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Die<'a> {
+    /// Offset in the section
+    pub offset: usize,
+    /// Nesting level, 0 for the unit DIE
+    pub depth: usize,
+    /// Abbreviation code, 0 for the null entries closing a list of children
+    pub code: u64,
+    pub tag: u64,
+    pub attributes: Vec<Attribute<'a>>,
+}
+
+impl<'a> Die<'a> {
+    pub fn attr(&self, name: u64) -> Option<&Attribute<'a>> {
+        self.attributes.iter().find(|attr| attr.name == name)
+    }
+
+    pub fn value(&self, name: u64) -> Option<u64> {
+        self.attr(name).and_then(|attr| attr.value.as_u64())
+    }
+}
+
+/// Reads the DIEs of `unit`, whose section is `data`, null entries included. The flag is
+/// false when they stopped early on an unknown abbreviation or form.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(offset = unit.offset))
+)]
+pub fn read_dies<'a>(
+    data: &'a [u8],
+    unit: &UnitHeader,
+    abbrevs: &HashMap<u64, Abbreviation>,
+    endian: Endian,
+) -> (Vec<Die<'a>>, bool) {
+    let mut dies = Vec::new();
+    let mut reader = ByteReader::new(&data[..unit.end.min(data.len())], endian);
+    if reader.seek(unit.entries).is_none() {
+        return (dies, false);
+    }
+
+    let mut depth = 0;
+    let mut next = |reader: &mut ByteReader<'a>| -> Option<Die<'a>> {
+        let offset = reader.pos();
+        let code = reader.uleb128()?;
+        if code == 0 {
+            let die = Die {
+                offset,
+                depth,
+                code,
+                tag: 0,
+                attributes: Vec::new(),
+            };
+            depth = depth.saturating_sub(1);
+            return Some(die);
+        }
+        let abbrev = abbrevs.get(&code)?;
+        let attributes = abbrev
+            .attributes
+            .iter()
+            .map(|&(name, form, implicit_const)| {
+                let offset = reader.pos();
+                let value = read_form(reader, form, unit.encoding, implicit_const)?;
+                Some(Attribute {
+                    name,
+                    form,
+                    value,
+                    offset,
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let die = Die {
+            offset,
+            depth,
+            code,
+            tag: abbrev.tag,
+            attributes,
+        };
+        if abbrev.has_children {
+            depth += 1;
+        }
+        Some(die)
+    };
+    while !reader.is_empty() {
+        match next(&mut reader) {
+            Some(die) => dies.push(die),
+            None => return (dies, false),
+        }
+    }
+
+    (dies, true)
+}
+
+/// Where a unit's entries start in the tables DWARF 5 forms index, from the
+/// `DW_AT_*_base` attributes of its unit DIE
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UnitBases {
+    pub str_offsets: Option<u64>,
+    pub addr: Option<u64>,
+    pub rnglists: Option<u64>,
+    pub loclists: Option<u64>,
+    /// `DW_AT_low_pc` of the unit DIE, the base address of its range and location lists
+    pub low_pc: u64,
+}
+
+/// The sections attribute values point or index into, empty when a file lacks them
+#[derive(Debug, Clone, Copy)]
+pub struct DwarfSections<'a> {
+    pub endian: Endian,
+    pub debug_str: &'a [u8],
+    pub line_str: &'a [u8],
+    pub str_offsets: &'a [u8],
+    pub addr: &'a [u8],
+    /// `.debug_ranges` and `.debug_loc`, the lists before DWARF 5
+    pub ranges: &'a [u8],
+    pub loc: &'a [u8],
+    pub rnglists: &'a [u8],
+    pub loclists: &'a [u8],
+}
+
+/// An entry of a range or location list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListEntry<'a> {
+    /// The address later offsets are relative to
+    Base(u64),
+    /// Addresses covered, with the location expression that holds there for location lists
+    Range {
+        begin: u64,
+        end: u64,
+        expr: Option<&'a [u8]>,
+    },
+    /// `DW_LLE_default_location`, the expression for addresses no other entry covers
+    Default(&'a [u8]),
+    /// `DW_LLE_GNU_view_pair`, the location views of the next entry
+    ViewPair(u64, u64),
+    End,
+}
+
+/// The header of a DWARF 5 `.debug_rnglists` or `.debug_loclists` table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ListTable {
+    pub offset: usize,
+    pub length: u64,
+    pub encoding: Encoding,
+    pub segment_size: u8,
+    pub offset_entries: u32,
+    /// Offset just past the table
+    pub end: usize,
+}
+
+impl ListTable {
+    /// Reads every table header of a `.debug_rnglists` or `.debug_loclists` section
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "list_tables", level = "debug", skip_all)
+    )]
+    pub fn parse_all(data: &[u8], endian: Endian) -> Vec<Self> {
+        let mut tables = Vec::new();
+        let mut reader = ByteReader::new(data, endian);
+        let next = |reader: &mut ByteReader| -> Option<Self> {
+            let offset = reader.pos();
+            let (length, offset_size) = unit_length(reader)?;
+            let end = reader.pos().checked_add(length as usize)?;
+            let version = reader.u16()?;
+            let address_size = reader.u8()? as usize;
+            let segment_size = reader.u8()?;
+            let offset_entries = reader.u32()?;
+            reader.seek(end)?;
+            Some(Self {
+                offset,
+                length,
+                encoding: Encoding {
+                    version,
+                    offset_size,
+                    address_size,
+                },
+                segment_size,
+                offset_entries,
+                end,
+            })
+        };
+        while !reader.is_empty() {
+            match next(&mut reader) {
+                Some(table) => tables.push(table),
+                None => break,
+            }
+        }
+        tables
+    }
+
+    /// Offset of the offsets array, what `DW_AT_rnglists_base` and `DW_AT_loclists_base`
+    /// point at
+    pub fn base(&self) -> usize {
+        let initial_length = if self.encoding.offset_size == 8 {
+            12
+        } else {
+            4
+        };
+        self.offset + initial_length + 8
+    }
+}
+
+/// The size of a `.debug_rnglists` or `.debug_loclists` table header, where the lists of a
+/// unit without a base attribute start
+fn list_header_size(encoding: Encoding) -> u64 {
+    match encoding.offset_size {
+        8 => 20,
+        _ => 12,
+    }
+}
+
+impl<'a> DwarfSections<'a> {
+    pub fn new(endian: Endian) -> Self {
+        Self {
+            endian,
+            debug_str: &[],
+            line_str: &[],
+            str_offsets: &[],
+            addr: &[],
+            ranges: &[],
+            loc: &[],
+            rnglists: &[],
+            loclists: &[],
+        }
+    }
+
+    fn word_at(&self, data: &[u8], offset: u64, size: usize) -> Option<u64> {
+        let mut reader = ByteReader::new(data, self.endian);
+        reader.seek(usize::try_from(offset).ok()?)?;
+        reader.word(size)
+    }
+
+    /// The string a `DW_FORM_strx*` index names. Without `DW_AT_str_offsets_base`, as in
+    /// split units, the unit's entries follow the first table header, which the GNU
+    /// extension for DWARF 4 did not have.
+    pub fn indexed_string(
+        &self,
+        index: u64,
+        bases: &UnitBases,
+        encoding: Encoding,
+    ) -> Option<String> {
+        let size = encoding.offset_size as u64;
+        let header = if encoding.version >= 5 { 2 * size } else { 0 };
+        let base = bases.str_offsets.unwrap_or(header);
+        let offset = self.word_at(
+            self.str_offsets,
+            base.checked_add(index.checked_mul(size)?)?,
+            encoding.offset_size,
+        )?;
+        Some(string_at(self.debug_str, offset as usize))
+    }
+
+    /// The address a `DW_FORM_addrx*` index names
+    pub fn indexed_address(
+        &self,
+        index: u64,
+        bases: &UnitBases,
+        encoding: Encoding,
+    ) -> Option<u64> {
+        let size = encoding.address_size as u64;
+        let base = bases
+            .addr
+            .unwrap_or(if encoding.version >= 5 { 8 } else { 0 });
+        self.word_at(
+            self.addr,
+            base.checked_add(index.checked_mul(size)?)?,
+            encoding.address_size,
+        )
+    }
+
+    /// The text of a string attribute, whichever section or index its form goes through
+    pub fn string(
+        &self,
+        attr: &Attribute,
+        bases: &UnitBases,
+        encoding: Encoding,
+    ) -> Option<String> {
+        match (attr.form, attr.value) {
+            (_, AttrValue::String(s)) => Some(String::from_utf8_lossy(s).into_owned()),
+            (DW_FORM_STRP, AttrValue::Unsigned(offset)) => {
+                Some(string_at(self.debug_str, offset as usize))
+            }
+            (DW_FORM_LINE_STRP, AttrValue::Unsigned(offset)) => {
+                Some(string_at(self.line_str, offset as usize))
+            }
+            (form, AttrValue::Unsigned(index)) if is_strx(form) => {
+                self.indexed_string(index, bases, encoding)
+            }
+            _ => None,
+        }
+    }
+
+    /// The value of an address attribute, looked up in `.debug_addr` for indexed forms
+    pub fn address(&self, attr: &Attribute, bases: &UnitBases, encoding: Encoding) -> Option<u64> {
+        match (attr.form, attr.value) {
+            (form, AttrValue::Unsigned(index)) if is_addrx(form) => {
+                self.indexed_address(index, bases, encoding)
+            }
+            (DW_FORM_ADDR, AttrValue::Unsigned(address)) => Some(address),
+            _ => None,
+        }
+    }
+
+    /// The bases the unit DIE `die` sets for the rest of its unit
+    pub fn unit_bases(&self, die: &Die, encoding: Encoding) -> UnitBases {
+        let mut bases = UnitBases {
+            str_offsets: die.value(DW_AT_STR_OFFSETS_BASE),
+            addr: die
+                .value(DW_AT_ADDR_BASE)
+                .or_else(|| die.value(DW_AT_GNU_ADDR_BASE)),
+            rnglists: die
+                .value(DW_AT_RNGLISTS_BASE)
+                .or_else(|| die.value(DW_AT_GNU_RANGES_BASE)),
+            loclists: die.value(DW_AT_LOCLISTS_BASE),
+            low_pc: 0,
+        };
+        bases.low_pc = die
+            .attr(DW_AT_LOW_PC)
+            .and_then(|attr| self.address(attr, &bases, encoding))
+            .unwrap_or(0);
+        bases
+    }
+
+    /// The offset in `.debug_rnglists` or `.debug_loclists` a list attribute points at.
+    /// `DW_FORM_rnglistx` and `DW_FORM_loclistx` index the offsets array at the unit's
+    /// base, whose entries are relative to that base.
+    pub fn list_offset(
+        &self,
+        attr: &Attribute,
+        bases: &UnitBases,
+        encoding: Encoding,
+    ) -> Option<u64> {
+        let value = attr.value.as_u64()?;
+        let (data, base) = match attr.form {
+            DW_FORM_RNGLISTX => (self.rnglists, bases.rnglists),
+            DW_FORM_LOCLISTX => (self.loclists, bases.loclists),
+            _ => return Some(value),
+        };
+        let size = encoding.offset_size as u64;
+        let base = base.unwrap_or_else(|| list_header_size(encoding));
+        let relative = self.word_at(
+            data,
+            base.checked_add(value.checked_mul(size)?)?,
+            encoding.offset_size,
+        )?;
+        base.checked_add(relative)
+    }
+
+    /// The offset `DW_AT_GNU_locviews` points at. GCC makes it relative to the unit's
+    /// base in `.debug_loclists` when the location is a `DW_FORM_loclistx` index.
+    pub fn views_offset(
+        &self,
+        views: u64,
+        location: &Attribute,
+        bases: &UnitBases,
+        encoding: Encoding,
+    ) -> u64 {
+        match location.form {
+            DW_FORM_LOCLISTX => {
+                let base = bases.loclists.unwrap_or_else(|| list_header_size(encoding));
+                views.wrapping_add(base)
+            }
+            _ => views,
+        }
+    }
+
+    /// Decodes the range list at `offset`, in `.debug_rnglists` for DWARF 5 units and
+    /// `.debug_ranges` before, each entry with the offset it was read from
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", skip_all, fields(offset = offset))
+    )]
+    pub fn range_list(
+        &self,
+        offset: u64,
+        bases: &UnitBases,
+        encoding: Encoding,
+    ) -> Option<Vec<(usize, ListEntry<'a>)>> {
+        match encoding.version >= 5 {
+            true => self.read_list(self.rnglists, offset, bases, encoding, false),
+            false => self.read_legacy_list(self.ranges, offset, bases, encoding, false),
+        }
+    }
+
+    /// Decodes the location list at `offset`, in `.debug_loclists` for DWARF 5 units and
+    /// `.debug_loc` before
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", skip_all, fields(offset = offset))
+    )]
+    pub fn location_list(
+        &self,
+        offset: u64,
+        bases: &UnitBases,
+        encoding: Encoding,
+    ) -> Option<Vec<(usize, ListEntry<'a>)>> {
+        match encoding.version >= 5 {
+            true => self.read_list(self.loclists, offset, bases, encoding, true),
+            false => self.read_legacy_list(self.loc, offset, bases, encoding, true),
+        }
+    }
+
+    /// The location view pairs GCC keeps in front of a location list, from the
+    /// `DW_AT_GNU_locviews` offset up to the list at `end`
+    pub fn location_views(
+        &self,
+        offset: u64,
+        end: u64,
+        encoding: Encoding,
+    ) -> Option<Vec<(usize, u64, u64)>> {
+        let data = match encoding.version >= 5 {
+            true => self.loclists,
+            false => self.loc,
+        };
+        let end = usize::try_from(end).ok()?;
+        let mut reader = ByteReader::new(data.get(..end)?, self.endian);
+        reader.seek(usize::try_from(offset).ok()?)?;
+        let mut views = Vec::new();
+        while !reader.is_empty() {
+            views.push((reader.pos(), reader.uleb128()?, reader.uleb128()?));
+        }
+        Some(views)
+    }
+
+    /// The address ranges a DIE covers, from `DW_AT_low_pc` and `DW_AT_high_pc` or its
+    /// range list
+    pub fn die_ranges(&self, die: &Die, bases: &UnitBases, encoding: Encoding) -> Vec<Range<u64>> {
+        if let Some(attr) = die.attr(DW_AT_RANGES) {
+            let entries = self
+                .list_offset(attr, bases, encoding)
+                .and_then(|offset| self.range_list(offset, bases, encoding));
+            return entries
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|(_, entry)| match entry {
+                    ListEntry::Range { begin, end, .. } if begin < end => Some(begin..end),
+                    _ => None,
+                })
+                .collect();
+        }
+        let low = match die
+            .attr(DW_AT_LOW_PC)
+            .and_then(|attr| self.address(attr, bases, encoding))
+        {
+            Some(low) => low,
+            None => return Vec::new(),
+        };
+        // DWARF 4 made a constant high_pc the size of the range rather than its end
+        let high = die.attr(DW_AT_HIGH_PC).and_then(|attr| match attr.form {
+            DW_FORM_ADDR => attr.value.as_u64(),
+            form if is_addrx(form) => self.address(attr, bases, encoding),
+            _ => attr.value.as_u64().map(|size| low.wrapping_add(size)),
+        });
+        high.filter(|&high| low < high)
+            .map(|high| low..high)
+            .into_iter()
+            .collect()
+    }
+
+    fn read_list(
+        &self,
+        data: &'a [u8],
+        offset: u64,
+        bases: &UnitBases,
+        encoding: Encoding,
+        locations: bool,
+    ) -> Option<Vec<(usize, ListEntry<'a>)>> {
+        let mut reader = ByteReader::new(data, self.endian);
+        reader.seek(usize::try_from(offset).ok()?)?;
+        let address = |reader: &mut ByteReader| reader.word(encoding.address_size);
+        let indexed = |index: u64| self.indexed_address(index, bases, encoding);
+        let mut base = bases.low_pc;
+        let mut entries = Vec::new();
+
+        loop {
+            let at = reader.pos();
+            let kind = reader.u8()?;
+            // The opcodes are the same in both formats up to the location list's
+            // default_location, which shifts the ones after it by one
+            let kind = match (locations, kind) {
+                (true, 5) => {
+                    let len = reader.uleb128()? as usize;
+                    entries.push((at, ListEntry::Default(reader.bytes(len)?)));
+                    continue;
+                }
+                (true, 0x09) => {
+                    let pair = ListEntry::ViewPair(reader.uleb128()?, reader.uleb128()?);
+                    entries.push((at, pair));
+                    continue;
+                }
+                (true, 6..=8) => kind - 1,
+                _ => kind,
+            };
+            let (begin, end) = match kind {
+                0 => {
+                    entries.push((at, ListEntry::End));
+                    return Some(entries);
+                }
+                1 => {
+                    base = indexed(reader.uleb128()?)?;
+                    entries.push((at, ListEntry::Base(base)));
+                    continue;
+                }
+                2 => (indexed(reader.uleb128()?)?, indexed(reader.uleb128()?)?),
+                3 => {
+                    let begin = indexed(reader.uleb128()?)?;
+                    (begin, begin.wrapping_add(reader.uleb128()?))
+                }
+                4 => (
+                    base.wrapping_add(reader.uleb128()?),
+                    base.wrapping_add(reader.uleb128()?),
+                ),
+                5 => {
+                    base = address(&mut reader)?;
+                    entries.push((at, ListEntry::Base(base)));
+                    continue;
+                }
+                6 => (address(&mut reader)?, address(&mut reader)?),
+                7 => {
+                    let begin = address(&mut reader)?;
+                    (begin, begin.wrapping_add(reader.uleb128()?))
+                }
+                _ => return None,
+            };
+            let expr = match locations {
+                true => {
+                    let len = reader.uleb128()? as usize;
+                    Some(reader.bytes(len)?)
+                }
+                false => None,
+            };
+            entries.push((at, ListEntry::Range { begin, end, expr }));
+        }
+    }
+
+    /// Reads a `.debug_ranges` or `.debug_loc` list, pairs of addresses relative to the
+    /// base where a begin of all ones selects a new base
+    fn read_legacy_list(
+        &self,
+        data: &'a [u8],
+        offset: u64,
+        bases: &UnitBases,
+        encoding: Encoding,
+        locations: bool,
+    ) -> Option<Vec<(usize, ListEntry<'a>)>> {
+        let mut reader = ByteReader::new(data, self.endian);
+        reader.seek(usize::try_from(offset).ok()?)?;
+        let size = encoding.address_size;
+        let max = match size {
+            4 => u32::MAX as u64,
+            _ => u64::MAX,
+        };
+        let mut base = bases.low_pc;
+        let mut entries = Vec::new();
+
+        loop {
+            let at = reader.pos();
+            let (begin, end) = (reader.word(size)?, reader.word(size)?);
+            if begin == 0 && end == 0 {
+                entries.push((at, ListEntry::End));
+                return Some(entries);
+            }
+            if begin == max {
+                base = end;
+                entries.push((at, ListEntry::Base(base)));
+                continue;
+            }
+            let expr = match locations {
+                true => {
+                    let len = reader.u16()? as usize;
+                    Some(reader.bytes(len)?)
+                }
+                false => None,
+            };
+            let (begin, end) = (base.wrapping_add(begin), base.wrapping_add(end));
+            entries.push((at, ListEntry::Range { begin, end, expr }));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{DwarfSections, Encoding, LineProgram, ListEntry, UnitBases};
+    use crate::elf::hdr::Endian;
+
+    #[test]
+    fn runs_line_program() {
+        // A DWARF 4 header with one file, then set_address 0x1000, a special opcode
+        // advancing 4 bytes and 1 line, and end_sequence after 2 more bytes. The special
+        // opcode is (1 - line_base) + 4 * line_range + opcode_base.
+        let header = [
+            &[1, 1, 1, 0xfb, 14, 13][..],
+            &[0, 1, 1, 1, 1, 0, 0, 0, 1, 0, 0, 1],
+            b"\0a.c\0\0\0\0\0",
+        ]
+        .concat();
+        let program = [
+            &[0, 9, 2][..],
+            &0x1000u64.to_le_bytes(),
+            &[75, 2, 2, 0, 1, 1],
+        ]
+        .concat();
+        let mut data = Vec::new();
+        let length = 2 + 4 + header.len() + program.len();
+        data.extend_from_slice(&(length as u32).to_le_bytes());
+        data.extend_from_slice(&4u16.to_le_bytes());
+        data.extend_from_slice(&(header.len() as u32).to_le_bytes());
+        data.extend_from_slice(&header);
+        data.extend_from_slice(&program);
+
+        let line = LineProgram::parse(&data, 0, Endian::Little, &[], &[]).unwrap();
+        assert_eq!(line.files.name(1), Some("a.c"));
+        let rows: Vec<_> = line
+            .rows(&data, Endian::Little)
+            .iter()
+            .map(|row| (row.address, row.line, row.end_sequence))
+            .collect();
+        assert_eq!(rows, [(0x1004, 2, false), (0x1006, 2, true)]);
+    }
+
+    #[test]
+    fn reads_range_lists() {
+        let encoding = Encoding {
+            version: 5,
+            offset_size: 4,
+            address_size: 8,
+        };
+        let bases = UnitBases {
+            addr: Some(8),
+            low_pc: 0x1000,
+            ..UnitBases::default()
+        };
+        // .debug_addr holds 0x2000 at index 0 after its header
+        let addr = [[0; 8], 0x2000u64.to_le_bytes()].concat();
+        // offset_pair 0x10..0x20, startx_length 0 + 8, base_address 0x3000,
+        // offset_pair 0..4, end_of_list
+        let rnglists = [
+            &[4, 0x10, 0x20, 3, 0, 8, 5][..],
+            &0x3000u64.to_le_bytes(),
+            &[4, 0, 4, 0],
+        ]
+        .concat();
+        let mut dwarf = DwarfSections::new(Endian::Little);
+        dwarf.addr = &addr;
+        dwarf.rnglists = &rnglists;
+
+        let entries = dwarf.range_list(0, &bases, encoding).unwrap();
+        let range = |begin, end| ListEntry::Range {
+            begin,
+            end,
+            expr: None,
+        };
+        assert_eq!(
+            entries,
+            [
+                (0, range(0x1010, 0x1020)),
+                (3, range(0x2000, 0x2008)),
+                (6, ListEntry::Base(0x3000)),
+                (15, range(0x3000, 0x3004)),
+                (18, ListEntry::End),
+            ]
+        );
+    }
+}
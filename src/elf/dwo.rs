@@ -0,0 +1,224 @@
+//! Split DWARF, where `-gsplit-dwarf` leaves a skeleton unit in the executable and moves
+//! the rest of the unit to a `.dwo` file, or to a `.dwp` package built from them
+
+use std::{
+    collections::HashMap,
+    io,
+    path::{Path, PathBuf},
+};
+
+use super::{bytes::ByteReader, core::FileData, hdr::Endian};
+
+/// The `.dwo` sections of a split unit
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DwoSections {
+    /// The `.dwo` or `.dwp` file they were read from
+    pub path: PathBuf,
+    pub info: Vec<u8>,
+    pub abbrev: Vec<u8>,
+    pub str: Vec<u8>,
+    pub str_offsets: Vec<u8>,
+    pub line: Vec<u8>,
+    pub rnglists: Vec<u8>,
+    pub loclists: Vec<u8>,
+}
+
+/// A unit's part of a `.dwp` section, as listed in `.debug_cu_index`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Contribution {
+    /// `DW_SECT_*`
+    pub section: u32,
+    pub offset: u32,
+    pub size: u32,
+}
+
+/// Parses a `.debug_cu_index` or `.debug_tu_index` section, the contributions of each
+/// unit keyed by its DWO id or type signature. Both the GNU version 2 and the DWARF 5
+/// layout are read, their `DW_SECT_*` numbering differs past `DW_SECT_line`.
+pub fn parse_index(data: &[u8], endian: Endian) -> Option<(u16, HashMap<u64, Vec<Contribution>>)> {
+    let mut reader = ByteReader::new(data, endian);
+    // DWARF 5 made the version a u16 followed by padding
+    let version = (reader.u32()? & 0xffff) as u16;
+    let section_count = reader.u32()? as usize;
+    let unit_count = reader.u32()? as usize;
+    let slot_count = reader.u32()? as usize;
+
+    let signatures = (0..slot_count)
+        .map(|_| reader.u64())
+        .collect::<Option<Vec<_>>>()?;
+    let rows = (0..slot_count)
+        .map(|_| reader.u32())
+        .collect::<Option<Vec<_>>>()?;
+    let sections = (0..section_count)
+        .map(|_| reader.u32())
+        .collect::<Option<Vec<_>>>()?;
+    let mut table = |count| (0..count).map(|_| reader.u32()).collect::<Option<Vec<_>>>();
+    let offsets = table(unit_count * section_count)?;
+    let sizes = table(unit_count * section_count)?;
+
+    let mut units = HashMap::new();
+    // Empty slots have a row of 0, rows count from 1
+    for (&signature, &row) in signatures.iter().zip(&rows) {
+        let row = match (row as usize).checked_sub(1) {
+            Some(row) if row < unit_count => row,
+            _ => continue,
+        };
+        let contributions = sections
+            .iter()
+            .enumerate()
+            .map(|(column, &section)| Contribution {
+                section,
+                offset: offsets[row * section_count + column],
+                size: sizes[row * section_count + column],
+            })
+            .collect();
+        units.insert(signature, contributions);
+    }
+
+    Some((version, units))
+}
+
+impl DwoSections {
+    /// Reads the sections of a `.dwo` file
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(path = %path.display()))
+    )]
+    pub fn read(path: &Path) -> io::Result<Self> {
+        Self::from_file(&FileData::new(path)?, path)
+    }
+
+    fn from_file(elf: &FileData, path: &Path) -> io::Result<Self> {
+        let section = |name: &str| -> io::Result<Vec<u8>> {
+            match elf.debug_section_by_name(name) {
+                Some(shdr) => elf.decompressed_section_data(&shdr),
+                None => Ok(Vec::new()),
+            }
+        };
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            info: section(".debug_info.dwo")?,
+            abbrev: section(".debug_abbrev.dwo")?,
+            str: section(".debug_str.dwo")?,
+            str_offsets: section(".debug_str_offsets.dwo")?,
+            line: section(".debug_line.dwo")?,
+            rnglists: section(".debug_rnglists.dwo")?,
+            loclists: section(".debug_loclists.dwo")?,
+        })
+    }
+
+    /// Reads the sections of the unit `dwo_id` from a `.dwp` package, each cut down to
+    /// the unit's contribution. `None` when the package has no such unit.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            skip_all,
+            fields(path = %path.display(), dwo_id = dwo_id)
+        )
+    )]
+    pub fn read_package(path: &Path, dwo_id: u64) -> io::Result<Option<Self>> {
+        let elf = FileData::new(path)?;
+        let mut sections = Self::from_file(&elf, path)?;
+        let endian = elf.header().endian().unwrap_or(Endian::Little);
+        let index = match elf.section_by_name(".debug_cu_index") {
+            Some(shdr) => elf.decompressed_section_data(&shdr)?,
+            None => return Ok(None),
+        };
+        let (version, units) = parse_index(&index, endian).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidData, ".debug_cu_index is truncated")
+        })?;
+        let contributions = match units.get(&dwo_id) {
+            Some(contributions) => contributions,
+            None => return Ok(None),
+        };
+
+        let cut = |data: &mut Vec<u8>, contribution: &Contribution| {
+            let start = (contribution.offset as usize).min(data.len());
+            let end = start
+                .saturating_add(contribution.size as usize)
+                .min(data.len());
+            *data = data[start..end].to_vec();
+        };
+        for contribution in contributions {
+            let data = match (version, contribution.section) {
+                (_, 1) => &mut sections.info,
+                (_, 3) => &mut sections.abbrev,
+                (_, 4) => &mut sections.line,
+                (5, 5) => &mut sections.loclists,
+                (_, 6) => &mut sections.str_offsets,
+                (5, 8) => &mut sections.rnglists,
+                _ => continue,
+            };
+            cut(data, contribution);
+        }
+
+        Ok(Some(sections))
+    }
+}
+
+/// Where the split unit of a skeleton is, trying `dwo_name` against the compilation
+/// directory and then the executable's directory, as binutils does
+pub fn dwo_path(executable: &Path, comp_dir: Option<&str>, dwo_name: &str) -> Option<PathBuf> {
+    let name = Path::new(dwo_name);
+    let mut candidates = Vec::new();
+    if let Some(comp_dir) = comp_dir {
+        candidates.push(Path::new(comp_dir).join(name));
+    }
+    let directory = executable.parent().unwrap_or_else(|| Path::new(""));
+    candidates.push(directory.join(name));
+    if let Some(file_name) = name.file_name() {
+        candidates.push(directory.join(file_name));
+    }
+
+    candidates.into_iter().find(|path| path.is_file())
+}
+
+/// The package next to an executable, `<executable>.dwp`
+pub fn dwp_path(executable: &Path) -> Option<PathBuf> {
+    let mut path = executable.as_os_str().to_owned();
+    path.push(".dwp");
+    Some(PathBuf::from(path)).filter(|path| path.is_file())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_index, Contribution};
+    use crate::elf::hdr::Endian;
+
+    #[test]
+    fn reads_cu_index() {
+        // Version 5, 2 sections, 1 unit, 2 slots with the unit in the second
+        let mut data = Vec::new();
+        for word in [5u32, 2, 1, 2] {
+            data.extend_from_slice(&word.to_le_bytes());
+        }
+        for signature in [0u64, 0x1234] {
+            data.extend_from_slice(&signature.to_le_bytes());
+        }
+        // Rows, section ids, offsets and sizes
+        for word in [0u32, 1, 1, 3, 0x10, 0x20, 0x30, 0x40] {
+            data.extend_from_slice(&word.to_le_bytes());
+        }
+
+        let (version, units) = parse_index(&data, Endian::Little).unwrap();
+        assert_eq!(version, 5);
+        assert_eq!(units.len(), 1);
+        assert_eq!(
+            units[&0x1234],
+            [
+                Contribution {
+                    section: 1,
+                    offset: 0x10,
+                    size: 0x30
+                },
+                Contribution {
+                    section: 3,
+                    offset: 0x20,
+                    size: 0x40
+                },
+            ]
+        );
+    }
+}
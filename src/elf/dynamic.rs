@@ -1,14 +1,172 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{
+    fmt::{self, Display},
+    mem::transmute,
+    ptr,
+};
+#[cfg(feature = "std")]
 use std::{
     alloc::{alloc, dealloc, Layout},
     io::{self, Read, Seek, SeekFrom},
-    mem::{align_of, size_of, transmute},
-    ptr,
+    mem::{align_of, size_of},
 };
 
 use num_derive::FromPrimitive;
+use num_traits::FromPrimitive;
 
+#[cfg(feature = "std")]
 use super::{hdr::ElfClass, ElfHdr};
 
+/// `DT_FLAGS_1` bit marking a position independent executable
+pub const DF_1_PIE: u64 = 0x08000000;
+
+/// AArch64 tags the linker sets when the PLT entries start with a `bti c` landing pad
+/// or authenticate their target with `autia1716`
+pub const DT_AARCH64_BTI_PLT: u64 = 0x70000001;
+pub const DT_AARCH64_PAC_PLT: u64 = 0x70000003;
+
+/// `DT_AUXILIARY`, `DT_USED` and `DT_FILTER` sit in the processor range but are shared by
+/// every target
+pub const DT_AUXILIARY: u64 = 0x7ffffffd;
+pub const DT_USED: u64 = 0x7ffffffe;
+pub const DT_FILTER: u64 = 0x7fffffff;
+
+const DT_LOOS: u64 = 0x6000000d;
+const DT_HIOS: u64 = 0x6ffff000;
+
+/// `DT_FLAGS` bits by name, in bit order
+const DF_NAMES: [&str; 5] = ["ORIGIN", "SYMBOLIC", "TEXTREL", "BIND_NOW", "STATIC_TLS"];
+
+/// `DT_FLAGS_1` bits by name, in bit order
+const DF_1_NAMES: [&str; 31] = [
+    "NOW",
+    "GLOBAL",
+    "GROUP",
+    "NODELETE",
+    "LOADFLTR",
+    "INITFIRST",
+    "NOOPEN",
+    "ORIGIN",
+    "DIRECT",
+    "TRANS",
+    "INTERPOSE",
+    "NODEFLIB",
+    "NODUMP",
+    "CONFALT",
+    "ENDFILTEE",
+    "DISPRELDNE",
+    "DISPRELPND",
+    "NODIRECT",
+    "IGNMULDEF",
+    "NOKSYMS",
+    "NOHDR",
+    "EDITED",
+    "NORELOC",
+    "SYMINTPOSE",
+    "GLOBAUDIT",
+    "SINGLETON",
+    "STUB",
+    "PIE",
+    "KMOD",
+    "WEAKFILTER",
+    "NOCOMMON",
+];
+
+/// The name readelf gives a dynamic tag, with the OS and processor ranges spelled out for
+/// tags it does not know
+pub fn dynamic_tag_name(tag: u64) -> String {
+    match (tag, DynamicTag::from_u64(tag)) {
+        (DT_AUXILIARY, _) => "AUXILIARY".into(),
+        (DT_USED, _) => "USED".into(),
+        (DT_FILTER, _) => "FILTER".into(),
+        (_, Some(known)) if !matches!(known, DynamicTag::Loproc | DynamicTag::Hiproc) => {
+            known.to_string()
+        }
+        (0x70000000..=0x7fffffff, _) => format!("Processor Specific: {:x}", tag),
+        (DT_LOOS..=DT_HIOS, _) => format!("Operating System specific: {:x}", tag),
+        _ => format!("<unknown>: {:x}", tag),
+    }
+}
+
+/// The `Name/Value` column of readelf's dynamic section listing. `string` looks offsets up
+/// in the dynamic string table.
+pub fn dynamic_value_string(tag: u64, value: u64, string: impl Fn(u64) -> String) -> String {
+    let flags = |names: &[&str]| {
+        let mut set = names
+            .iter()
+            .enumerate()
+            .filter(|(bit, _)| value & 1 << bit != 0)
+            .map(|(_, name)| name.to_string())
+            .collect::<Vec<_>>();
+        let unknown = value & !((1 << names.len()) - 1);
+        if unknown != 0 {
+            set.push(format!("{:x}", unknown));
+        }
+        set.join(" ")
+    };
+
+    match (tag, DynamicTag::from_u64(tag)) {
+        (DT_AUXILIARY, _) => format!("Auxiliary library: [{}]", string(value)),
+        (DT_FILTER, _) => format!("Filter library: [{}]", string(value)),
+        (_, Some(DynamicTag::Needed)) => format!("Shared library: [{}]", string(value)),
+        (_, Some(DynamicTag::SoName)) => format!("Library soname: [{}]", string(value)),
+        (_, Some(DynamicTag::RPath)) => format!("Library rpath: [{}]", string(value)),
+        (_, Some(DynamicTag::RunPath)) => format!("Library runpath: [{}]", string(value)),
+        (_, Some(DynamicTag::Config)) => format!("Configuration file: {}", string(value)),
+        (_, Some(DynamicTag::Depaudit)) => format!("Dependency audit library: {}", string(value)),
+        (_, Some(DynamicTag::Audit)) => format!("Audit library: {}", string(value)),
+        (_, Some(DynamicTag::Flags)) => flags(&DF_NAMES),
+        (_, Some(DynamicTag::Flags1)) => format!("Flags: {}", flags(&DF_1_NAMES)),
+        (_, Some(DynamicTag::Posflag1)) => match value {
+            1 => "Flags: LAZY".into(),
+            2 => "Flags: GROUPPERM".into(),
+            _ => format!("Flags: {:x}", value),
+        },
+        (_, Some(DynamicTag::PltRel)) => match DynamicTag::from_u64(value) {
+            Some(rel @ (DynamicTag::Rel | DynamicTag::Rela)) => rel.to_string(),
+            _ => format!("0x{:x}", value),
+        },
+        (
+            _,
+            Some(
+                DynamicTag::PltRelSz
+                | DynamicTag::RelaSz
+                | DynamicTag::StrSz
+                | DynamicTag::RelSz
+                | DynamicTag::RelaEnt
+                | DynamicTag::SymEnt
+                | DynamicTag::RelEnt
+                | DynamicTag::PltPadSz
+                | DynamicTag::MoveEnt
+                | DynamicTag::MoveSz
+                | DynamicTag::RelrSz
+                | DynamicTag::RelrEnt
+                | DynamicTag::PreInitArraySz
+                | DynamicTag::InitArraySz
+                | DynamicTag::FiniArraySz
+                | DynamicTag::GnuConflictsz
+                | DynamicTag::GnuLiblistsz
+                | DynamicTag::Syminsz
+                | DynamicTag::SymIEntOrValRNGHI,
+            ),
+        ) => format!("{} (bytes)", value),
+        (
+            _,
+            Some(
+                DynamicTag::Verdefnum
+                | DynamicTag::Verneednum
+                | DynamicTag::Relacount
+                | DynamicTag::Relcount,
+            ),
+        ) => value.to_string(),
+        _ => format!("0x{:x}", value),
+    }
+}
+
 pub struct DynamicRelocs {
     pub name: &'static str,
     pub reloc: DynamicTag,
@@ -95,6 +253,117 @@ pub enum DynamicTag {
     Hiproc = 0x7fffffff,
 }
 
+impl DynamicTag {
+    /// Whether entries with this tag hold an address (`d_ptr`) rather than a value
+    pub fn is_address(tag: u64) -> bool {
+        const ADDRRNGHI: u64 = DynamicTag::SymInfoOrAddrrnGHI as u64;
+
+        matches!(tag, 0x6ffffe00..=ADDRRNGHI)
+            || [
+                Self::PltGot,
+                Self::Hash,
+                Self::StrTab,
+                Self::SymTab,
+                Self::Rela,
+                Self::Init,
+                Self::Fini,
+                Self::Rel,
+                Self::Debug,
+                Self::JmpRel,
+                Self::InitArray,
+                Self::FiniArray,
+                Self::PreInitArray,
+                Self::SymtabShndx,
+                Self::RelR,
+                Self::Versym,
+                Self::Verdef,
+                Self::Verneed,
+            ]
+            .iter()
+            .any(|&t| t as u64 == tag)
+    }
+}
+
+impl Display for DynamicTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Self::Null => "NULL",
+            Self::Needed => "NEEDED",
+            Self::PltRelSz => "PLTRELSZ",
+            Self::PltGot => "PLTGOT",
+            Self::Hash => "HASH",
+            Self::StrTab => "STRTAB",
+            Self::SymTab => "SYMTAB",
+            Self::Rela => "RELA",
+            Self::RelaSz => "RELASZ",
+            Self::RelaEnt => "RELAENT",
+            Self::StrSz => "STRSZ",
+            Self::SymEnt => "SYMENT",
+            Self::Init => "INIT",
+            Self::Fini => "FINI",
+            Self::SoName => "SONAME",
+            Self::RPath => "RPATH",
+            Self::Symbolic => "SYMBOLIC",
+            Self::Rel => "REL",
+            Self::RelSz => "RELSZ",
+            Self::RelEnt => "RELENT",
+            Self::PltRel => "PLTREL",
+            Self::Debug => "DEBUG",
+            Self::TextRel => "TEXTREL",
+            Self::JmpRel => "JMPREL",
+            Self::BindNow => "BIND_NOW",
+            Self::InitArray => "INIT_ARRAY",
+            Self::FiniArray => "FINI_ARRAY",
+            Self::InitArraySz => "INIT_ARRAYSZ",
+            Self::FiniArraySz => "FINI_ARRAYSZ",
+            Self::RunPath => "RUNPATH",
+            Self::Flags => "FLAGS",
+            Self::PreInitArray => "PREINIT_ARRAY",
+            Self::PreInitArraySz => "PREINIT_ARRAYSZ",
+            Self::SymtabShndx => "SYMTAB_SHNDX",
+            Self::RelrSz => "RELRSZ",
+            Self::RelR => "RELR",
+            Self::RelrEnt => "RELRENT",
+            Self::Encoding => "ENCODING",
+            Self::Valrnglo => "VALRNGLO",
+            Self::GnuFlags1 => "GNU_FLAGS_1",
+            Self::GnuPrelinked => "GNU_PRELINKED",
+            Self::GnuConflictsz => "GNU_CONFLICTSZ",
+            Self::GnuLiblistsz => "GNU_LIBLISTSZ",
+            Self::Checksum => "CHECKSUM",
+            Self::PltPadSz => "PLTPADSZ",
+            Self::MoveEnt => "MOVEENT",
+            Self::MoveSz => "MOVESZ",
+            Self::Feature => "FEATURE",
+            Self::Posflag1 => "POSFLAG_1",
+            Self::Syminsz => "SYMINSZ",
+            Self::SymIEntOrValRNGHI => "SYMINENT",
+            Self::Addrrnglo => "ADDRRNGLO",
+            Self::GnuHash => "GNU_HASH",
+            Self::TlsdescPlt => "TLSDESC_PLT",
+            Self::TlsdescGot => "TLSDESC_GOT",
+            Self::GnuConflict => "GNU_CONFLICT",
+            Self::GnuLiblist => "GNU_LIBLIST",
+            Self::Config => "CONFIG",
+            Self::Depaudit => "DEPAUDIT",
+            Self::Audit => "AUDIT",
+            Self::PltPad => "PLTPAD",
+            Self::MoveTab => "MOVETAB",
+            Self::SymInfoOrAddrrnGHI => "SYMINFO",
+            Self::Relacount => "RELACOUNT",
+            Self::Relcount => "RELCOUNT",
+            Self::Flags1 => "FLAGS_1",
+            Self::Verdef => "VERDEF",
+            Self::Verdefnum => "VERDEFNUM",
+            Self::Verneed => "VERNEED",
+            Self::Verneednum => "VERNEEDNUM",
+            Self::Versym => "VERSYM",
+            Self::Loproc => "LOPROC",
+            Self::Hiproc => "HIPROC",
+        })
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
 pub enum RelaState {
     False,
@@ -102,6 +371,7 @@ pub enum RelaState {
     Unknown,
 }
 
+#[derive(Clone, Copy)]
 pub union DynValue {
     pub val: u64,
     pub ptr: u64,
@@ -119,6 +389,7 @@ pub union Dyn64Value {
     ptr: [u8; 8],
 }
 
+#[derive(Clone, Copy)]
 pub struct Dyn {
     pub tag: u64,
     pub value: DynValue,
@@ -158,6 +429,7 @@ pub static DYNAMIC_RELOCATIONS: [DynamicRelocs; 3] = [
 ];
 
 impl Dyn {
+    #[cfg(feature = "std")]
     pub fn read<R: Read + Seek>(
         file: &mut R,
         hdr: &ElfHdr,
@@ -0,0 +1,257 @@
+use std::{collections::HashMap, io};
+
+use super::{
+    bytes::{string_at, ByteReader},
+    core::FileData,
+    hdr::{ElfClass, Endian},
+    unwind::{parse_eh_frame, read_encoded, EncodingBases, Fde, DW_EH_PE_INDIRECT, DW_EH_PE_OMIT},
+};
+
+#[derive(Debug, Clone)]
+pub struct CallSite {
+    pub start: u64,
+    pub length: u64,
+    /// Absolute landing pad address, `None` when exceptions propagate through
+    pub landing_pad: Option<u64>,
+    /// Chain of type filters starting at the call site's action record; empty for cleanups
+    pub actions: Vec<i64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TypeEntry {
+    /// Typeinfo address, or the address of the slot holding it for indirect encodings
+    pub address: u64,
+    pub indirect: bool,
+}
+
+/// A decoded language specific data area in the Itanium C++ ABI layout
+#[derive(Debug, Clone)]
+pub struct Lsda {
+    pub address: u64,
+    pub lpstart: u64,
+    pub ttype_encoding: u8,
+    pub call_site_encoding: u8,
+    pub call_sites: Vec<CallSite>,
+    /// Type table entries, index `n` is type filter `n + 1`
+    pub types: Vec<TypeEntry>,
+}
+
+/// Reads the `(filter, next)` chain of an action record
+fn action_chain(actions: &[u8], start: usize, endian: Endian) -> Vec<i64> {
+    let mut reader = ByteReader::new(actions, endian);
+    let mut filters = Vec::new();
+    let mut position = start;
+
+    while filters.len() < 64 && reader.seek(position).is_some() {
+        let filter = match reader.sleb128() {
+            Some(filter) => filter,
+            None => break,
+        };
+        filters.push(filter);
+        let next_at = reader.pos();
+        match reader.sleb128() {
+            Some(next) if next != 0 => position = (next_at as i64 + next) as usize,
+            _ => break,
+        }
+    }
+
+    filters
+}
+
+impl Lsda {
+    /// Parses the LSDA at `address`, `data` being the contents of the section mapped at
+    /// `base` and `func` the start of the function owning the FDE
+    pub fn parse(
+        data: &[u8],
+        base: u64,
+        address: u64,
+        func: u64,
+        class: ElfClass,
+        endian: Endian,
+    ) -> Option<Self> {
+        let ptr_size = match class {
+            ElfClass::ElfClass32 => 4,
+            _ => 8,
+        };
+        let bases = EncodingBases {
+            section: base,
+            func,
+            ..Default::default()
+        };
+        let mut reader = ByteReader::new(data, endian);
+        reader.seek(address.checked_sub(base)? as usize)?;
+
+        let lpstart_encoding = reader.u8()?;
+        let lpstart = match lpstart_encoding {
+            DW_EH_PE_OMIT => func,
+            encoding => read_encoded(&mut reader, encoding, ptr_size, &bases)?,
+        };
+
+        let ttype_encoding = reader.u8()?;
+        let ttype_base = match ttype_encoding {
+            DW_EH_PE_OMIT => None,
+            _ => {
+                let offset = reader.uleb128()? as usize;
+                Some(reader.pos() + offset)
+            }
+        };
+
+        let call_site_encoding = reader.u8()?;
+        let table_length = reader.uleb128()? as usize;
+        let table_end = reader.pos() + table_length;
+
+        let mut raw_sites = Vec::new();
+        while reader.pos() < table_end {
+            let start = read_encoded(&mut reader, call_site_encoding, ptr_size, &bases)?;
+            let length = read_encoded(&mut reader, call_site_encoding, ptr_size, &bases)?;
+            let landing_pad = read_encoded(&mut reader, call_site_encoding, ptr_size, &bases)?;
+            let action = reader.uleb128()? as usize;
+            raw_sites.push((start, length, landing_pad, action));
+        }
+
+        let actions = data.get(table_end..)?;
+        let call_sites = raw_sites
+            .into_iter()
+            .map(|(start, length, landing_pad, action)| CallSite {
+                start: lpstart.wrapping_add(start),
+                length,
+                landing_pad: (landing_pad != 0).then(|| lpstart.wrapping_add(landing_pad)),
+                actions: match action {
+                    0 => Vec::new(),
+                    action => action_chain(actions, action - 1, endian),
+                },
+            })
+            .collect::<Vec<_>>();
+
+        let mut types = Vec::new();
+        if let Some(ttype_base) = ttype_base {
+            let count = call_sites
+                .iter()
+                .flat_map(|site| site.actions.iter())
+                .copied()
+                .filter(|&filter| filter > 0)
+                .max()
+                .unwrap_or(0) as usize;
+            let size = match ttype_encoding & 0x0f {
+                0x02 | 0x0a => 2,
+                0x03 | 0x0b => 4,
+                0x04 | 0x0c => 8,
+                _ => ptr_size,
+            };
+
+            for index in 1..=count {
+                let position = ttype_base.checked_sub(index * size)?;
+                let mut entry = ByteReader::new(data, endian);
+                entry.seek(position)?;
+                // A null entry is a catch-all and takes no application or indirection
+                if read_encoded(&mut entry, ttype_encoding & 0x0f, ptr_size, &bases)? == 0 {
+                    types.push(TypeEntry {
+                        address: 0,
+                        indirect: false,
+                    });
+                    continue;
+                }
+                entry.seek(position)?;
+                types.push(TypeEntry {
+                    address: read_encoded(&mut entry, ttype_encoding & 0x7f, ptr_size, &bases)?,
+                    indirect: ttype_encoding & DW_EH_PE_INDIRECT != 0,
+                });
+            }
+        }
+
+        Some(Lsda {
+            address,
+            lpstart,
+            ttype_encoding,
+            call_site_encoding,
+            call_sites,
+            types,
+        })
+    }
+}
+
+/// Every FDE carrying an LSDA pointer, paired with the decoded LSDA when it lies in
+/// `.gcc_except_table`
+pub fn read_lsdas(elf: &FileData) -> io::Result<Vec<(Fde, Option<Lsda>)>> {
+    let class = elf.header().class().unwrap_or(ElfClass::ElfClass64);
+    let endian = elf.header().endian().unwrap_or(Endian::Little);
+
+    let eh_frame = match elf.section_by_name(".eh_frame") {
+        Some(shdr) => shdr,
+        None => return Ok(Vec::new()),
+    };
+    let fdes = parse_eh_frame(
+        &elf.section_data(&eh_frame)?,
+        eh_frame.addr(),
+        class,
+        endian,
+    );
+
+    let table = match elf.section_by_name(".gcc_except_table") {
+        Some(shdr) => Some((shdr.addr(), elf.section_data(&shdr)?)),
+        None => None,
+    };
+
+    Ok(fdes
+        .into_iter()
+        .filter_map(|fde| {
+            let lsda = fde.lsda?;
+            let decoded = table.as_ref().and_then(|(base, data)| {
+                Lsda::parse(data, *base, lsda, fde.pc_begin, class, endian)
+            });
+            Some((fde, decoded))
+        })
+        .collect())
+}
+
+/// Names the typeinfo objects referenced by the type tables. Indirect entries point at a
+/// slot filled by a relocation, so the relocation's symbol or addend is used instead.
+pub fn typeinfo_names(
+    elf: &FileData,
+    lsdas: &[(Fde, Option<Lsda>)],
+) -> io::Result<HashMap<u64, String>> {
+    let symbols = elf.symbol_map()?;
+    let entries = lsdas
+        .iter()
+        .filter_map(|(_, lsda)| lsda.as_ref())
+        .flat_map(|lsda| lsda.types.iter())
+        .collect::<Vec<_>>();
+
+    let mut slots = HashMap::new();
+    if entries.iter().any(|entry| entry.indirect) {
+        for (shdr, rels) in elf.relocations()? {
+            let symtab = elf.section_headers().get(shdr.link() as usize).copied();
+            let (strtab, syms) = match symtab {
+                Some(symtab) if shdr.link() != 0 => elf.symbol_table(&symtab)?,
+                _ => Default::default(),
+            };
+            for rel in rels {
+                let name = match syms.get(rel.sym() as usize) {
+                    Some(sym) if rel.sym() != 0 => Some(string_at(strtab, sym.name() as usize)),
+                    _ => rel
+                        .addend()
+                        .and_then(|addend| symbols.describe(addend as u64)),
+                };
+                if let Some(name) = name {
+                    slots.insert(rel.offset(), name);
+                }
+            }
+        }
+    }
+
+    let mut names = HashMap::new();
+    for entry in entries {
+        let name = if entry.indirect {
+            slots.get(&entry.address).cloned()
+        } else if entry.address == 0 {
+            Some("catch (...)".to_string())
+        } else {
+            symbols.describe(entry.address)
+        };
+        if let Some(name) = name {
+            names.insert(entry.address, name);
+        }
+    }
+
+    Ok(names)
+}
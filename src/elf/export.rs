@@ -0,0 +1,203 @@
+use std::io;
+
+use super::{
+    bytes::string_at,
+    core::FileData,
+    sym::{SymbolBinding, SymbolType, SymbolVis},
+};
+
+/// A symbol the file exports through its dynamic symbol table
+#[derive(Debug, Clone)]
+pub struct Export {
+    pub name: String,
+    pub version: Option<String>,
+    /// Defined as a non-default version, `name@VERSION`, only bound by old references
+    pub hidden: bool,
+    pub weak: bool,
+    pub symbol_type: Option<SymbolType>,
+    /// Part of the ABI for data, executables hold a copy of that size
+    pub size: u64,
+}
+
+impl Export {
+    /// The name the way binutils writes it, `name@@VERSION` or `name@VERSION`
+    pub fn versioned_name(&self) -> String {
+        match (&self.version, self.hidden) {
+            (Some(version), true) => format!("{}@{}", self.name, version),
+            (Some(version), false) => format!("{}@@{}", self.name, version),
+            (None, _) => self.name.clone(),
+        }
+    }
+}
+
+/// A version definition with the exports carrying it, a node of the version script
+#[derive(Debug, Clone)]
+pub struct VersionNode {
+    pub name: String,
+    /// The nodes this one inherits from, from the `Elf_Verdaux` entries after the first
+    pub parents: Vec<String>,
+    pub exports: Vec<Export>,
+}
+
+/// The exported interface of a shared object, read through the dynamic segment so stripped
+/// files work too
+#[derive(Debug, Clone, Default)]
+pub struct ExportList {
+    /// Exports without a version, all of them when the file defines no versions
+    pub unversioned: Vec<Export>,
+    /// One node per version definition other than the base one, in definition order
+    pub nodes: Vec<VersionNode>,
+}
+
+impl ExportList {
+    pub fn read(elf: &FileData) -> io::Result<Self> {
+        let (strtab, syms) = elf.dynamic_segment_symbols()?;
+        let versions = elf.dynamic_symbol_versions().unwrap_or_default();
+        let mut list = Self {
+            unversioned: Vec::new(),
+            nodes: versions
+                .verdef
+                .iter()
+                .filter(|def| !def.is_base())
+                .filter_map(|def| {
+                    Some(VersionNode {
+                        name: def.name()?.to_string(),
+                        parents: def.names[1..].to_vec(),
+                        exports: Vec::new(),
+                    })
+                })
+                .collect(),
+        };
+
+        for (index, sym) in syms.iter().enumerate().skip(1) {
+            let weak = match sym.binding() {
+                Some(SymbolBinding::Global) => false,
+                Some(SymbolBinding::Weak) => true,
+                _ => continue,
+            };
+            if sym.shndx() == 0
+                || !matches!(
+                    sym.visibility(),
+                    Some(SymbolVis::Default | SymbolVis::Protected)
+                )
+            {
+                continue;
+            }
+            let name = string_at(&strtab, sym.name() as usize);
+            let defined = versions.defined(index);
+            // The absolute symbols ld adds to name each version are not part of the interface
+            if name.is_empty() || matches!(defined, Some((version, _)) if version == name) {
+                continue;
+            }
+
+            let export = Export {
+                name,
+                version: defined.map(|(version, _)| version.to_string()),
+                hidden: matches!(defined, Some((_, true))),
+                weak,
+                symbol_type: sym.symbol_type(),
+                size: sym.size(),
+            };
+            match list
+                .nodes
+                .iter_mut()
+                .find(|node| Some(node.name.as_str()) == export.version.as_deref())
+            {
+                Some(node) => node.exports.push(export),
+                None => list.unversioned.push(export),
+            }
+        }
+
+        list.unversioned.sort_by(|a, b| a.name.cmp(&b.name));
+        for node in &mut list.nodes {
+            node.exports.sort_by(|a, b| a.name.cmp(&b.name));
+        }
+
+        Ok(list)
+    }
+
+    /// Every export, unversioned ones first and then node by node
+    pub fn exports(&self) -> impl Iterator<Item = &Export> {
+        self.unversioned
+            .iter()
+            .chain(self.nodes.iter().flat_map(|node| node.exports.iter()))
+    }
+
+    /// A GNU ld version script reproducing the exports, with `local: *` hiding everything
+    /// else unless some exports have no version. Non-default versions need a `.symver`
+    /// directive in the source on top of the script, they are only listed as comments so
+    /// the script still links.
+    pub fn version_script(&self) -> String {
+        let mut script = String::new();
+
+        if self.nodes.is_empty() {
+            script.push_str("{\n  global:\n");
+            push_symbols(&mut script, &self.unversioned);
+            script.push_str("  local:\n    *;\n};\n");
+            return script;
+        }
+
+        // Symbols no node names stay global in the base version unless `local: *` hides them
+        if !self.unversioned.is_empty() {
+            script
+                .push_str("/* Exported in the base version, kept global by leaving out local: *\n");
+            for export in &self.unversioned {
+                script.push_str(&format!("   {}\n", export.name));
+            }
+            script.push_str("*/\n");
+        }
+        for (i, node) in self.nodes.iter().enumerate() {
+            script.push_str(&format!("{} {{\n", node.name));
+            if node.exports.iter().any(|export| !export.hidden) {
+                script.push_str("  global:\n");
+                push_symbols(&mut script, &node.exports);
+            }
+            for export in node.exports.iter().filter(|export| export.hidden) {
+                script.push_str(&format!(
+                    "    /* {}, non-default, needs .symver */\n",
+                    export.versioned_name()
+                ));
+            }
+            if i == 0 && self.unversioned.is_empty() {
+                script.push_str("  local:\n    *;\n");
+            }
+            match node.parents.is_empty() {
+                true => script.push_str("};\n"),
+                false => script.push_str(&format!("}} {};\n", node.parents.join(" "))),
+            }
+        }
+
+        script
+    }
+
+    /// The exports as a dynamic list for ld's `--export-dynamic-symbol-list` (or
+    /// `--dynamic-list`), one name per line. A list has no notion of versions, so these
+    /// follow as comments and a name defined under several versions is listed once.
+    pub fn dynamic_list(&self) -> String {
+        let mut exports = self.exports().collect::<Vec<_>>();
+        exports.sort_by(|a, b| a.name.cmp(&b.name).then(a.hidden.cmp(&b.hidden)));
+        exports.dedup_by(|b, a| a.name == b.name);
+
+        let mut list = String::from("{\n");
+        for export in exports {
+            match &export.version {
+                Some(version) => list.push_str(&format!(
+                    "  {}; /* {}{} */\n",
+                    export.name,
+                    if export.hidden { "@" } else { "@@" },
+                    version
+                )),
+                None => list.push_str(&format!("  {};\n", export.name)),
+            }
+        }
+        list.push_str("};\n");
+
+        list
+    }
+}
+
+fn push_symbols(script: &mut String, exports: &[Export]) {
+    for export in exports.iter().filter(|export| !export.hidden) {
+        script.push_str(&format!("    {};\n", export.name));
+    }
+}
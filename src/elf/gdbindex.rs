@@ -0,0 +1,196 @@
+use super::{
+    bytes::{string_at, ByteReader},
+    hdr::Endian,
+};
+
+/// A type unit of the index, from the DWARF 4 `.debug_types` section
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndexTypeUnit {
+    /// Offset of the unit in `.debug_types`
+    pub offset: u64,
+    /// Offset of the type DIE within the unit
+    pub type_offset: u64,
+    pub signature: u64,
+}
+
+/// An entry of the address table, the code range a unit covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndexAddress {
+    pub low: u64,
+    /// One past the last address of the range
+    pub high: u64,
+    pub cu: u32,
+}
+
+/// A unit a symbol is defined in, an entry of its CU vector
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndexSymbolUnit(pub u32);
+
+impl IndexSymbolUnit {
+    /// Index into the CU list, followed by the TU list
+    pub fn unit(&self) -> u32 {
+        self.0 & 0x00ff_ffff
+    }
+
+    /// Static symbols are only visible from their own unit, since version 7
+    pub fn is_static(&self) -> bool {
+        self.0 & 0x8000_0000 != 0
+    }
+
+    /// The symbol kind the way binutils prints it, since version 7
+    pub fn kind(&self) -> &'static str {
+        match (self.0 >> 28) & 0x7 {
+            0 => "no info",
+            1 => "type",
+            2 => "variable",
+            3 => "function",
+            4 => "other",
+            _ => "unknown",
+        }
+    }
+}
+
+/// A used slot of the symbol hash table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexSymbol {
+    pub slot: usize,
+    pub name: String,
+    pub units: Vec<IndexSymbolUnit>,
+}
+
+/// The `.gdb_index` section gold, lld and gdb-add-index write so gdb can start without
+/// reading all of the DWARF. Its fields are little-endian whatever the target.
+#[derive(Debug, Clone)]
+pub struct GdbIndex {
+    pub version: u32,
+    /// `(offset, length)` of each compilation unit in `.debug_info`
+    pub comp_units: Vec<(u64, u64)>,
+    pub type_units: Vec<IndexTypeUnit>,
+    pub addresses: Vec<IndexAddress>,
+    /// Number of slots of the symbol hash table, used or not
+    pub slots: usize,
+    pub symbols: Vec<IndexSymbol>,
+}
+
+impl GdbIndex {
+    /// Parses the section, `None` for versions before 3 which laid it out differently
+    /// and for truncated data
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        let mut reader = ByteReader::new(data, Endian::Little);
+        let version = reader.u32()?;
+        if version < 3 {
+            return None;
+        }
+        let cu_list = reader.u32()? as usize;
+        let types_list = reader.u32()? as usize;
+        let address_area = reader.u32()? as usize;
+        let symbol_table = reader.u32()? as usize;
+        // Version 9 adds the offset of a shortcut table ahead of the constant pool
+        if version >= 9 {
+            reader.u32()?;
+        }
+        let constant_pool = reader.u32()? as usize;
+        if cu_list > types_list
+            || types_list > address_area
+            || address_area > symbol_table
+            || constant_pool > data.len()
+        {
+            return None;
+        }
+
+        reader.seek(cu_list)?;
+        let comp_units = (0..(types_list - cu_list) / 16)
+            .map(|_| Some((reader.u64()?, reader.u64()?)))
+            .collect::<Option<Vec<_>>>()?;
+        reader.seek(types_list)?;
+        let type_units = (0..(address_area - types_list) / 24)
+            .map(|_| {
+                Some(IndexTypeUnit {
+                    offset: reader.u64()?,
+                    type_offset: reader.u64()?,
+                    signature: reader.u64()?,
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+        reader.seek(address_area)?;
+        let addresses = (0..(symbol_table - address_area) / 20)
+            .map(|_| {
+                Some(IndexAddress {
+                    low: reader.u64()?,
+                    high: reader.u64()?,
+                    cu: reader.u32()?,
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        reader.seek(symbol_table)?;
+        let slots = constant_pool.saturating_sub(symbol_table) / 8;
+        let pool = &data[constant_pool..];
+        let mut symbols = Vec::new();
+        for slot in 0..slots {
+            let name = reader.u32()? as usize;
+            let vector = reader.u32()? as usize;
+            if name == 0 && vector == 0 {
+                continue;
+            }
+            let mut units = ByteReader::new(pool, Endian::Little);
+            units.seek(vector)?;
+            let count = units.u32()?;
+            symbols.push(IndexSymbol {
+                slot,
+                name: string_at(pool, name),
+                units: (0..count)
+                    .map(|_| units.u32().map(IndexSymbolUnit))
+                    .collect::<Option<Vec<_>>>()?,
+            });
+        }
+
+        Some(Self {
+            version,
+            comp_units,
+            type_units,
+            addresses,
+            slots,
+            symbols,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::GdbIndex;
+
+    #[test]
+    fn parses_gdb_index() {
+        let mut data = Vec::new();
+        // Version 7, the CU list at 24, no TUs, one address range and two slots
+        for word in [7u32, 24, 40, 40, 60, 76] {
+            data.extend_from_slice(&word.to_le_bytes());
+        }
+        for word in [0u64, 0xf9, 0x1000, 0x1040] {
+            data.extend_from_slice(&word.to_le_bytes());
+        }
+        data.extend_from_slice(&0u32.to_le_bytes());
+        for word in [0u32, 0, 8, 0] {
+            data.extend_from_slice(&word.to_le_bytes());
+        }
+        // The CU vector of `main`, a global function of unit 0, then its name
+        for word in [1u32, 0x3000_0000] {
+            data.extend_from_slice(&word.to_le_bytes());
+        }
+        data.extend_from_slice(b"main\0");
+
+        let index = GdbIndex::parse(&data).unwrap();
+        assert_eq!(index.comp_units, vec![(0, 0xf9)]);
+        assert_eq!(index.addresses[0].high, 0x1040);
+        assert_eq!(index.slots, 2);
+        assert_eq!(index.symbols.len(), 1);
+        assert_eq!(index.symbols[0].slot, 1);
+        assert_eq!(index.symbols[0].name, "main");
+        let unit = index.symbols[0].units[0];
+        assert_eq!(
+            (unit.unit(), unit.is_static(), unit.kind()),
+            (0, false, "function")
+        );
+    }
+}
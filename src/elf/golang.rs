@@ -0,0 +1,359 @@
+use std::io;
+
+use super::{
+    bytes::ByteReader, core::FileData, hdr::Endian, note::NT_GO_BUILD_ID, phdr::ProgramType,
+};
+
+pub const BUILDINFO_MAGIC: &[u8; 14] = b"\xff Go buildinf:";
+/// Fixed size of the `.go.buildinfo` header preceding the inline strings
+const BUILDINFO_HEADER_SIZE: usize = 32;
+/// The module info string is wrapped in 16 byte sentinels by `cmd/go`
+const MODINFO_SENTINEL_SIZE: usize = 16;
+
+const FLAG_BIG_ENDIAN: u8 = 0x1;
+const FLAG_INLINE_STRINGS: u8 = 0x2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PclntabVersion {
+    Go12,
+    Go116,
+    Go118,
+    Go120,
+}
+
+impl PclntabVersion {
+    pub fn from_magic(magic: u32) -> Option<Self> {
+        Some(match magic {
+            0xfffffffb => Self::Go12,
+            0xfffffffa => Self::Go116,
+            0xfffffff0 => Self::Go118,
+            0xfffffff1 => Self::Go120,
+            _ => return None,
+        })
+    }
+
+    pub fn display(&self) -> &'static str {
+        match self {
+            Self::Go12 => "go1.2",
+            Self::Go116 => "go1.16",
+            Self::Go118 => "go1.18",
+            Self::Go120 => "go1.20",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct GoBuildInfo {
+    pub version: String,
+    /// Main package path, the `path` line of the module info
+    pub path: Option<String>,
+    /// `(path, version)` of the main module
+    pub main: Option<(String, String)>,
+    pub deps: Vec<(String, String)>,
+    /// `build key=value` settings such as `GOARCH` or `vcs.revision`
+    pub settings: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct GoFunc {
+    pub entry: u64,
+    pub name: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct GoPclntab {
+    pub version: PclntabVersion,
+    pub ptr_size: u8,
+    pub nfunc: usize,
+    pub nfiles: Option<usize>,
+    pub first: Option<GoFunc>,
+    pub last: Option<GoFunc>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct GoBinary {
+    pub build_id: Option<String>,
+    pub buildinfo: Option<GoBuildInfo>,
+    pub pclntab: Option<GoPclntab>,
+}
+
+/// Parses the textual module info embedded by `cmd/go`
+pub fn parse_modinfo(modinfo: &str, info: &mut GoBuildInfo) {
+    for line in modinfo.lines() {
+        let mut fields = line.split('\t');
+        match fields.next() {
+            Some("path") => info.path = fields.next().map(str::to_string),
+            Some("mod") => {
+                if let (Some(path), Some(version)) = (fields.next(), fields.next()) {
+                    info.main = Some((path.to_string(), version.to_string()));
+                }
+            }
+            Some("dep") => {
+                if let (Some(path), Some(version)) = (fields.next(), fields.next()) {
+                    info.deps.push((path.to_string(), version.to_string()));
+                }
+            }
+            Some("build") => {
+                let setting = fields.collect::<Vec<_>>().join("\t");
+                if let Some((key, value)) = setting.split_once('=') {
+                    info.settings.push((key.to_string(), value.to_string()));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn strip_sentinels(modinfo: &[u8]) -> &[u8] {
+    if modinfo.len() >= 2 * MODINFO_SENTINEL_SIZE && modinfo[modinfo.len() - 17] == b'\n' {
+        &modinfo[MODINFO_SENTINEL_SIZE..modinfo.len() - MODINFO_SENTINEL_SIZE]
+    } else {
+        modinfo
+    }
+}
+
+/// Reads a Go string header (`data`, `len`) at `vaddr` and returns its contents
+fn read_go_string(elf: &FileData, vaddr: u64, ptr_size: usize, endian: Endian) -> Option<Vec<u8>> {
+    let header = elf.read_vaddr(vaddr, ptr_size * 2).ok()?;
+    let mut reader = ByteReader::new(&header, endian);
+    let data = reader.word(ptr_size)?;
+    let len = reader.word(ptr_size)?;
+    elf.read_vaddr(data, len as usize).ok()
+}
+
+impl GoBuildInfo {
+    /// Decodes the blob starting at the `\xff Go buildinf:` magic
+    pub fn read(elf: &FileData, data: &[u8]) -> Option<Self> {
+        if data.get(..BUILDINFO_MAGIC.len())? != BUILDINFO_MAGIC {
+            return None;
+        }
+
+        let ptr_size = *data.get(14)? as usize;
+        let flags = *data.get(15)?;
+        let endian = if flags & FLAG_BIG_ENDIAN != 0 {
+            Endian::Big
+        } else {
+            Endian::Little
+        };
+
+        let (version, modinfo) = if flags & FLAG_INLINE_STRINGS != 0 {
+            let mut reader = ByteReader::new(data, endian);
+            reader.seek(BUILDINFO_HEADER_SIZE)?;
+            let len = reader.uleb128()? as usize;
+            let version = reader.bytes(len)?.to_vec();
+            let len = reader.uleb128()? as usize;
+            let modinfo = reader.bytes(len)?.to_vec();
+            (version, modinfo)
+        } else {
+            // Before go1.18 the header holds pointers to the runtime.buildVersion and
+            // runtime.modinfo string headers
+            let mut reader = ByteReader::new(data, endian);
+            reader.seek(16)?;
+            let version = reader.word(ptr_size)?;
+            let modinfo = reader.word(ptr_size)?;
+            (
+                read_go_string(elf, version, ptr_size, endian)?,
+                read_go_string(elf, modinfo, ptr_size, endian).unwrap_or_default(),
+            )
+        };
+
+        let mut info = GoBuildInfo {
+            version: String::from_utf8_lossy(&version).into_owned(),
+            ..Default::default()
+        };
+        parse_modinfo(
+            &String::from_utf8_lossy(strip_sentinels(&modinfo)),
+            &mut info,
+        );
+
+        Some(info)
+    }
+}
+
+/// Reads a NUL-terminated function name at `offset` inside the pclntab
+fn func_name(data: &[u8], offset: usize) -> Option<String> {
+    let name = data.get(offset..)?;
+    let len = name.iter().position(|&c| c == 0)?;
+    Some(String::from_utf8_lossy(&name[..len]).into_owned())
+}
+
+impl GoPclntab {
+    pub fn parse(data: &[u8], endian: Endian) -> Option<Self> {
+        let mut reader = ByteReader::new(data, endian);
+        let version = PclntabVersion::from_magic(reader.u32()?)?;
+        reader.skip(2)?;
+        // Instruction size quantum, followed by the pointer size
+        reader.u8()?;
+        let ptr_size = reader.u8()?;
+        let word = ptr_size as usize;
+
+        let mut pclntab = GoPclntab {
+            version,
+            ptr_size,
+            nfunc: reader.word(word)? as usize,
+            nfiles: None,
+            first: None,
+            last: None,
+        };
+
+        let func_at = |index: usize| -> Option<GoFunc> {
+            let mut reader = ByteReader::new(data, endian);
+            match version {
+                PclntabVersion::Go12 => {
+                    // functab of (pc, funcoff) words right after the header
+                    reader.seek(index.checked_mul(2 * word)?.checked_add(8 + word)?)?;
+                    reader.word(word)?;
+                    let funcoff = reader.word(word)? as usize;
+                    reader.seek(funcoff)?;
+                    let entry = reader.word(word)?;
+                    let nameoff = usize::try_from(reader.i32()?).ok()?;
+                    Some(GoFunc {
+                        entry,
+                        name: func_name(data, nameoff)?,
+                    })
+                }
+                PclntabVersion::Go116 => {
+                    reader.seek(8 + 2 * word)?;
+                    let funcname = reader.word(word)? as usize;
+                    reader.seek(8 + 6 * word)?;
+                    let functab = reader.word(word)? as usize;
+                    reader.seek(functab.checked_add(index.checked_mul(2 * word)?)?)?;
+                    reader.word(word)?;
+                    let funcoff = reader.word(word)? as usize;
+                    reader.seek(functab.checked_add(funcoff)?)?;
+                    let entry = reader.word(word)?;
+                    let nameoff = usize::try_from(reader.i32()?).ok()?;
+                    Some(GoFunc {
+                        entry,
+                        name: func_name(data, funcname.checked_add(nameoff)?)?,
+                    })
+                }
+                PclntabVersion::Go118 | PclntabVersion::Go120 => {
+                    reader.seek(8 + 2 * word)?;
+                    let text_start = reader.word(word)?;
+                    let funcname = reader.word(word)? as usize;
+                    reader.seek(8 + 7 * word)?;
+                    let functab = reader.word(word)? as usize;
+                    reader.seek(functab.checked_add(index.checked_mul(8)?)?)?;
+                    reader.u32()?;
+                    let funcoff = reader.u32()? as usize;
+                    reader.seek(functab.checked_add(funcoff)?)?;
+                    let entry = text_start.checked_add(reader.u32()? as u64)?;
+                    let nameoff = usize::try_from(reader.i32()?).ok()?;
+                    Some(GoFunc {
+                        entry,
+                        name: func_name(data, funcname.checked_add(nameoff)?)?,
+                    })
+                }
+            }
+        };
+
+        if version != PclntabVersion::Go12 {
+            pclntab.nfiles = Some(reader.word(word)? as usize);
+        }
+        if pclntab.nfunc > 0 {
+            pclntab.first = func_at(0);
+            pclntab.last = func_at(pclntab.nfunc - 1);
+        }
+
+        Some(pclntab)
+    }
+}
+
+impl GoBinary {
+    /// Returns `None` when the file carries none of the Go specific sections
+    pub fn read(elf: &FileData) -> io::Result<Option<Self>> {
+        let endian = elf.header().endian().unwrap_or(Endian::Little);
+        let mut go = GoBinary {
+            build_id: elf
+                .notes()?
+                .into_iter()
+                .find(|note| note.name == "Go" && note.ntype == NT_GO_BUILD_ID)
+                .map(|note| String::from_utf8_lossy(&note.desc).into_owned()),
+            ..Default::default()
+        };
+
+        let buildinfo = match elf.section_by_name(".go.buildinfo") {
+            Some(shdr) => Some(elf.section_data(&shdr)?),
+            None => find_buildinfo(elf)?,
+        };
+        if let Some(data) = buildinfo {
+            go.buildinfo = GoBuildInfo::read(elf, &data);
+        }
+
+        if let Some(shdr) = elf.section_by_name(".gopclntab") {
+            go.pclntab = GoPclntab::parse(&elf.section_data(&shdr)?, endian);
+        }
+
+        if go.build_id.is_none() && go.buildinfo.is_none() && go.pclntab.is_none() {
+            return Ok(None);
+        }
+
+        Ok(Some(go))
+    }
+}
+
+/// Stripped binaries may lack section headers, in which case the buildinfo blob is
+/// found by scanning the writable `PT_LOAD` segments for its 16 byte aligned magic
+fn find_buildinfo(elf: &FileData) -> io::Result<Option<Vec<u8>>> {
+    let segments = elf
+        .program_headers()
+        .iter()
+        .filter(|phdr| phdr.program_type() == Some(ProgramType::Load) && phdr.flags().write())
+        .map(|phdr| (phdr.offset(), phdr.filesz()))
+        .collect::<Vec<_>>();
+
+    for (offset, size) in segments {
+        let data = elf.read_at(offset, size as usize)?;
+        if let Some(start) = data
+            .chunks(16)
+            .position(|chunk| chunk.starts_with(BUILDINFO_MAGIC))
+        {
+            return Ok(Some(data[start * 16..].to_vec()));
+        }
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_modinfo, GoBuildInfo, GoPclntab};
+    use crate::elf::hdr::Endian;
+
+    #[test]
+    fn parses_modinfo_lines() {
+        let mut info = GoBuildInfo::default();
+        parse_modinfo(
+            "path\texample.com/cmd/foo\nmod\texample.com\tv1.2.3\th1:abc=\n\
+             dep\tgolang.org/x/sys\tv0.1.0\th1:def=\nbuild\tGOARCH=amd64\nbuild\t-ldflags=-s -w\n",
+            &mut info,
+        );
+
+        assert_eq!(info.path.as_deref(), Some("example.com/cmd/foo"));
+        assert_eq!(
+            info.main,
+            Some(("example.com".to_string(), "v1.2.3".to_string()))
+        );
+        assert_eq!(info.deps.len(), 1);
+        assert_eq!(
+            info.settings,
+            vec![
+                ("GOARCH".to_string(), "amd64".to_string()),
+                ("-ldflags".to_string(), "-s -w".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_overflowing_offsets() {
+        // A go1.16 header whose nfunc, funcnametab and functab words are all ones
+        let mut data = vec![0xfa, 0xff, 0xff, 0xff, 0, 0, 1, 8];
+        data.extend_from_slice(&u64::MAX.to_le_bytes());
+        data.resize(64, 0xff);
+
+        let pclntab = GoPclntab::parse(&data, Endian::Little).unwrap();
+        assert_eq!(pclntab.nfunc, usize::MAX);
+        assert!(pclntab.first.is_none() && pclntab.last.is_none());
+    }
+}
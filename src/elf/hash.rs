@@ -0,0 +1,226 @@
+use alloc::{boxed::Box, vec::Vec};
+
+use super::{bytes::ByteReader, hdr::Endian};
+
+/// The `DT_GNU_HASH` hash function
+pub fn gnu_hash(name: &[u8]) -> u32 {
+    name.iter()
+        .fold(5381u32, |h, &c| h.wrapping_mul(33).wrapping_add(c as u32))
+}
+
+/// The `DT_HASH` hash function from the System V ABI
+pub fn sysv_hash(name: &[u8]) -> u32 {
+    name.iter().fold(0u32, |h, &c| {
+        let h = (h << 4).wrapping_add(c as u32);
+        (h ^ ((h & 0xf000_0000) >> 24)) & 0x0fff_ffff
+    })
+}
+
+/// A decoded `DT_GNU_HASH` table
+#[derive(Debug, Clone)]
+pub struct GnuHashTable {
+    symoffset: u32,
+    bloom_shift: u32,
+    /// Bits per bloom filter word, 32 or 64 following the file class
+    bloom_bits: u32,
+    bloom: Vec<u64>,
+    buckets: Vec<u32>,
+    chains: Vec<u32>,
+}
+
+impl GnuHashTable {
+    /// Decodes the table, `word` is the address size of the file in bytes
+    pub fn parse(data: &[u8], word: usize, endian: Endian) -> Option<Self> {
+        let mut reader = ByteReader::new(data, endian);
+        let nbuckets = reader.u32()?;
+        let symoffset = reader.u32()?;
+        let bloom_size = reader.u32()?;
+        let bloom_shift = reader.u32()?;
+        let bloom = (0..bloom_size)
+            .map(|_| reader.word(word))
+            .collect::<Option<Vec<_>>>()?;
+        let buckets = (0..nbuckets)
+            .map(|_| reader.u32())
+            .collect::<Option<Vec<_>>>()?;
+        let mut chains = core::iter::from_fn(|| reader.u32()).collect::<Vec<_>>();
+
+        // The chains have no count of their own and the data may run past them, they end
+        // with the chain of the highest bucket
+        match buckets.iter().max() {
+            Some(&last) if last >= symoffset => {
+                let mut index = (last - symoffset) as usize;
+                while matches!(chains.get(index), Some(chain) if chain & 1 == 0) {
+                    index += 1;
+                }
+                chains.truncate(index + 1);
+            }
+            _ => chains.clear(),
+        }
+
+        Some(Self {
+            symoffset,
+            bloom_shift,
+            bloom_bits: word as u32 * 8,
+            bloom,
+            buckets,
+            chains,
+        })
+    }
+
+    /// Indices of the symbols whose hash matches `name`, the caller still has to compare
+    /// their names. Empty when the bloom filter rules the name out.
+    pub fn candidates(&self, name: &[u8]) -> impl Iterator<Item = usize> + '_ {
+        let hash = gnu_hash(name);
+        let bits = self.bloom_bits;
+        let word = match self.bloom.len() {
+            0 => 0,
+            len => self.bloom[(hash / bits) as usize % len],
+        };
+        // A shift past the width of the hash is malformed, it leaves no bits to test
+        let second = hash.checked_shr(self.bloom_shift).unwrap_or(0);
+        let mask = 1 << (hash % bits) | 1 << (second % bits);
+        let start = match self.buckets.len() {
+            0 => 0,
+            len if word & mask == mask => self.buckets[hash as usize % len],
+            _ => 0,
+        };
+
+        let mut index = start;
+        let mut done = start < self.symoffset;
+        core::iter::from_fn(move || loop {
+            if done {
+                return None;
+            }
+            let chain = *self.chains.get((index - self.symoffset) as usize)?;
+            let current = index;
+            done = chain & 1 != 0;
+            index += 1;
+            if chain | 1 == hash | 1 {
+                return Some(current as usize);
+            }
+        })
+    }
+
+    /// Number of symbols the table covers, for reading the dynamic symbol table without
+    /// section headers
+    pub fn symbol_count(&self) -> usize {
+        self.symoffset as usize + self.chains.len()
+    }
+}
+
+/// A decoded `DT_HASH` table
+#[derive(Debug, Clone)]
+pub struct SysvHashTable {
+    buckets: Vec<u32>,
+    chains: Vec<u32>,
+}
+
+impl SysvHashTable {
+    pub fn parse(data: &[u8], endian: Endian) -> Option<Self> {
+        let mut reader = ByteReader::new(data, endian);
+        let nbucket = reader.u32()?;
+        let nchain = reader.u32()?;
+        let buckets = (0..nbucket)
+            .map(|_| reader.u32())
+            .collect::<Option<Vec<_>>>()?;
+        let chains = (0..nchain)
+            .map(|_| reader.u32())
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(Self { buckets, chains })
+    }
+
+    /// Indices of the symbols in the bucket of `name`, the caller still has to compare
+    /// their names
+    pub fn candidates(&self, name: &[u8]) -> impl Iterator<Item = usize> + '_ {
+        let mut index = match self.buckets.len() {
+            0 => 0,
+            len => self.buckets[sysv_hash(name) as usize % len],
+        };
+        // A corrupt chain could loop forever, no chain is longer than the table
+        core::iter::from_fn(move || {
+            let current = index;
+            index = *self.chains.get(index as usize)?;
+            (current != 0).then_some(current as usize)
+        })
+        .take(self.chains.len())
+    }
+
+    /// Number of symbols the table covers, `nchain` by definition
+    pub fn symbol_count(&self) -> usize {
+        self.chains.len()
+    }
+}
+
+/// The hash table a dynamic linker would use to look a symbol up
+#[derive(Debug, Clone)]
+pub enum SymbolHash {
+    Gnu(GnuHashTable),
+    Sysv(SysvHashTable),
+}
+
+impl SymbolHash {
+    pub fn candidates<'a>(&'a self, name: &'a [u8]) -> Box<dyn Iterator<Item = usize> + 'a> {
+        match self {
+            Self::Gnu(table) => Box::new(table.candidates(name)),
+            Self::Sysv(table) => Box::new(table.candidates(name)),
+        }
+    }
+
+    pub fn symbol_count(&self) -> usize {
+        match self {
+            Self::Gnu(table) => table.symbol_count(),
+            Self::Sysv(table) => table.symbol_count(),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test {
+    use super::{gnu_hash, sysv_hash, GnuHashTable};
+    use crate::elf::{bytes::string_at, core::FileData, hdr::Endian};
+
+    #[test]
+    fn known_hashes() {
+        assert_eq!(gnu_hash(b""), 0x0000_1505);
+        assert_eq!(gnu_hash(b"printf"), 0x156b_2bb8);
+        assert_eq!(sysv_hash(b""), 0);
+        assert_eq!(sysv_hash(b"printf"), 0x0779_05a6);
+    }
+
+    #[test]
+    fn oversized_bloom_shift() {
+        // One bucket, symoffset 1, one bloom word with every bit set, a shift of 40
+        let mut data = vec![1, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0, 40, 0, 0, 0];
+        data.extend([0xff; 8]);
+        data.extend([1, 0, 0, 0]);
+        data.extend((gnu_hash(b"printf") | 1).to_le_bytes());
+        let table = GnuHashTable::parse(&data, 8, Endian::Little).unwrap();
+        assert_eq!(table.candidates(b"printf").collect::<Vec<_>>(), [1]);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn finds_dynamic_symbols() {
+        // The test binary exports next to nothing, the libc it is linked against has
+        // thousands of symbols behind a GNU hash table
+        let libc = crate::elf::process::read_maps(std::process::id())
+            .unwrap()
+            .into_iter()
+            .filter_map(|map| map.path)
+            .find(|path| path.to_string_lossy().contains("libc.so"))
+            .unwrap();
+        let elf = FileData::new(libc).unwrap();
+        let dynsym = elf.section_by_name(".dynsym").unwrap();
+        let (strtab, syms) = elf.symbol_table(&dynsym).unwrap();
+
+        // Versioned symbols share a name, any of them is a match
+        for sym in syms.iter().filter(|sym| sym.shndx() != 0) {
+            let name = string_at(strtab, sym.name() as usize);
+            let (index, found) = elf.lookup_dynamic_symbol(&name).unwrap().unwrap();
+            assert_eq!(found, syms[index]);
+            assert_eq!(string_at(strtab, found.name() as usize), name);
+        }
+        assert_eq!(elf.lookup_dynamic_symbol("no such symbol").unwrap(), None);
+    }
+}
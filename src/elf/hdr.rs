@@ -1,21 +1,32 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+};
+use core::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+#[cfg(feature = "std")]
 use std::{
-    fmt::Display,
     fs::OpenOptions,
-    io::Read,
+    io::{self, Read},
     mem::MaybeUninit,
     mem::{size_of, transmute},
     path::Path,
     slice,
 };
 
-use num::ToPrimitive;
+#[cfg(feature = "std")]
+use super::ELFMAG;
+
 use num_derive::FromPrimitive;
-use num_traits::cast::FromPrimitive;
+use num_traits::{cast::FromPrimitive, ToPrimitive};
 
 use super::{
     Elf32Addr, Elf32Half, Elf32Off, Elf32Word, Elf64Addr, Elf64Half, Elf64Off, Elf64Word,
     EI_ABIVERSION, EI_CLASS, EI_DATA, EI_MAG0, EI_MAG1, EI_MAG2, EI_MAG3, EI_NINDENT, EI_OSABI,
-    EI_VERSION,
+    EI_VERSION, EM_386, EM_AARCH64, EM_ARM, EM_BPF, EM_IA_64, EM_LOONGARCH, EM_MIPS, EM_NONE,
+    EM_PPC, EM_PPC64, EM_RISCV, EM_S390, EM_SPARC, EM_SPARCV9, EM_X86_64,
 };
 
 #[derive(Debug, Clone, Copy)]
@@ -88,6 +99,7 @@ pub struct Elf64Hdr {
     pub e_shstrndx: Elf64Half,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum OsABI {
     None,
     HpUX,
@@ -101,29 +113,37 @@ pub enum OsABI {
     OpenBSD,
     OpenVMS,
     Tru64,
+    Nsk,
+    Aros,
+    FenixOS,
+    CloudABI,
+    OpenVOS,
+    ArmAeabi,
+    Arm,
+    Standalone,
     Unknown(u8),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ObjectType {
     None,
     Rel,
     Exec,
     Dyn,
     Core,
-    Loos,
-    HiOS,
-    LoProc,
-    HiProc,
+    /// A value in `ET_LOOS..=ET_HIOS`
+    Os(u16),
+    /// A value in `ET_LOPROC..=ET_HIPROC`
+    Proc(u16),
 }
 
-#[derive(FromPrimitive, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[derive(FromPrimitive, PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy)]
 pub enum ElfClass {
     None,
     ElfClass32,
     ElfClass64,
 }
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Endian {
     Little,
     Big,
@@ -139,6 +159,7 @@ pub enum ElfType {
 }
 
 impl ElfHdr {
+    #[cfg(feature = "std")]
     pub fn read<P: AsRef<Path>>(path: P) -> Result<Self, std::io::Error> {
         unsafe {
             let mut buf = MaybeUninit::<Elf32Hdr>::uninit();
@@ -167,20 +188,32 @@ impl ElfHdr {
         }
     }
 
+    #[cfg(feature = "std")]
     pub fn read_file<R: Read>(file: &mut R) -> Result<Self, std::io::Error> {
         unsafe {
             let mut buf = MaybeUninit::<Elf64Hdr>::uninit();
-            file.read(slice::from_raw_parts_mut(
+            let read = file.read(slice::from_raw_parts_mut(
                 transmute(buf.as_mut_ptr()),
                 size_of::<Self>(),
             ))?;
 
             let hdr = buf.as_ptr() as *const Elf32Hdr;
+            if read < EI_NINDENT || !(*hdr).e_ident.starts_with(ELFMAG.as_bytes()) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "Not an ELF file - it has the wrong magic bytes at the start",
+                ));
+            }
 
             Ok(match (*hdr).e_ident[EI_CLASS] {
                 1 => Self::upcast_elf32(&*hdr),
                 2 => Self::upcast_elf64(&*transmute::<_, *const Elf64Hdr>(hdr)),
-                _ => panic!("Unrecognized elf class"),
+                class => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("Unrecognized ELF class {}", class),
+                    ))
+                }
             })
         }
     }
@@ -277,6 +310,28 @@ impl ElfHdr {
         self.e_machine
     }
 
+    /// Human readable architecture name, spelled the way file(1) prints it
+    pub fn machine_name(&self) -> Option<&'static str> {
+        Some(match self.e_machine {
+            EM_NONE => "no machine",
+            EM_SPARC => "SPARC",
+            EM_386 => "Intel 80386",
+            EM_MIPS => "MIPS",
+            EM_PPC => "PowerPC or cisco 4500",
+            EM_PPC64 => "64-bit PowerPC or cisco 7500",
+            EM_S390 => "IBM S/390",
+            EM_ARM => "ARM",
+            EM_SPARCV9 => "SPARC V9",
+            EM_IA_64 => "IA-64",
+            EM_X86_64 => "x86-64",
+            EM_AARCH64 => "ARM aarch64",
+            EM_RISCV => "UCB RISC-V",
+            EM_BPF => "eBPF",
+            EM_LOONGARCH => "LoongArch",
+            _ => return None,
+        })
+    }
+
     pub fn version(&self) -> u8 {
         self.e_ident[EI_VERSION]
     }
@@ -292,11 +347,21 @@ impl ElfHdr {
             0x2 => OsABI::NetBSD,
             0x3 => OsABI::Linux,
             0x6 => OsABI::Solaris,
+            0x7 => OsABI::Aix,
             0x8 => OsABI::Irix,
             0x9 => OsABI::FreeBSD,
             0x0A => OsABI::Tru64,
             0x0B => OsABI::Novell,
-            0x0C => OsABI::OpenVMS,
+            0x0C => OsABI::OpenBSD,
+            0x0D => OsABI::OpenVMS,
+            0x0E => OsABI::Nsk,
+            0x0F => OsABI::Aros,
+            0x10 => OsABI::FenixOS,
+            0x11 => OsABI::CloudABI,
+            0x12 => OsABI::OpenVOS,
+            0x40 => OsABI::ArmAeabi,
+            0x61 => OsABI::Arm,
+            0xFF => OsABI::Standalone,
             i => OsABI::Unknown(i),
         }
     }
@@ -313,14 +378,31 @@ impl ElfHdr {
             0x2 => Some(ObjectType::Exec),
             0x3 => Some(ObjectType::Dyn),
             0x4 => Some(ObjectType::Core),
-            0xFE00 => Some(ObjectType::Loos),
-            0xFEFF => Some(ObjectType::HiOS),
-            0xFF00 => Some(ObjectType::LoProc),
-            0xFFFF => Some(ObjectType::HiProc),
+            0xFE00..=0xFEFF => Some(ObjectType::Os(self.e_type)),
+            0xFF00..=0xFFFF => Some(ObjectType::Proc(self.e_type)),
             _ => None,
         }
     }
 
+    /// [`ElfHdr::ftype`] as readelf prints it, `<unknown>: 0x..` for values outside any
+    /// range
+    pub fn type_name(&self) -> String {
+        match self.ftype() {
+            Some(ftype) => ftype.to_string(),
+            None => format!("<unknown>: 0x{:x}", self.e_type),
+        }
+    }
+
+    /// [`ElfHdr::type_name`], with `ET_DYN` told apart into position independent
+    /// executables and shared objects, which takes the dynamic section
+    /// ([`FileData::is_pie`](super::core::FileData::is_pie))
+    pub fn describe_type(&self, pie: bool) -> String {
+        match self.ftype() {
+            Some(ObjectType::Dyn) if pie => "DYN (Position-Independent Executable file)".into(),
+            _ => self.type_name(),
+        }
+    }
+
     pub fn upcast_elf32(hdr: &Elf32Hdr) -> Self {
         Self {
             e_ident: hdr.e_ident,
@@ -411,8 +493,8 @@ impl From<&Elf64Hdr> for ElfHdr {
 }
 
 impl Display for OsABI {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(match self {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
             Self::None => "UNIX - System V",
             Self::HpUX => "HP-UX",
             Self::NetBSD => "NetBSD",
@@ -425,13 +507,86 @@ impl Display for OsABI {
             Self::OpenVMS => "OpenVMS",
             Self::FreeBSD => "FreeBSD",
             Self::Tru64 => "UNIX - Tru64",
-            Self::Unknown(_) => "Unknown",
+            Self::Nsk => "HP - Non-Stop Kernel",
+            Self::Aros => "AROS",
+            Self::FenixOS => "FenixOS",
+            Self::CloudABI => "Nuxi CloudABI",
+            Self::OpenVOS => "Stratus Technologies OpenVOS",
+            Self::ArmAeabi => "ARM EABI",
+            Self::Arm => "ARM",
+            Self::Standalone => "Standalone App",
+            Self::Unknown(abi) => return f.pad(&format!("<unknown: 0x{:x}>", abi)),
+        })
+    }
+}
+
+/// Looks up an `e_machine` value by a short architecture name (`aarch64`, `x86-64`, ...),
+/// an `EM_*` constant name or a plain number
+pub fn machine_by_name(name: &str) -> Option<Elf64Half> {
+    let name = name.to_ascii_lowercase();
+    let name = name.strip_prefix("em_").unwrap_or(&name);
+    Some(match name {
+        "none" => EM_NONE,
+        "sparc" => EM_SPARC,
+        "386" | "i386" | "x86" => EM_386,
+        "mips" => EM_MIPS,
+        "ppc" | "powerpc" => EM_PPC,
+        "ppc64" | "powerpc64" => EM_PPC64,
+        "s390" | "s390x" => EM_S390,
+        "arm" => EM_ARM,
+        "sparcv9" => EM_SPARCV9,
+        "ia_64" | "ia64" => EM_IA_64,
+        "x86_64" | "x86-64" | "amd64" => EM_X86_64,
+        "aarch64" | "arm64" => EM_AARCH64,
+        "riscv" => EM_RISCV,
+        "bpf" => EM_BPF,
+        "loongarch" => EM_LOONGARCH,
+        number => match number.strip_prefix("0x") {
+            Some(hex) => Elf64Half::from_str_radix(hex, 16).ok()?,
+            None => number.parse().ok()?,
+        },
+    })
+}
+
+impl FromStr for ObjectType {
+    type Err = String;
+
+    /// Parses `rel`, `exec`, `dyn`, `core` or `none`, with or without the `ET_` prefix
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.to_ascii_lowercase();
+        match name.strip_prefix("et_").unwrap_or(&name) {
+            "none" => Ok(Self::None),
+            "rel" => Ok(Self::Rel),
+            "exec" => Ok(Self::Exec),
+            "dyn" => Ok(Self::Dyn),
+            "core" => Ok(Self::Core),
+            _ => Err(format!("unknown object type '{}'", s)),
+        }
+    }
+}
+
+impl Display for ObjectType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Self::None => "NONE (None)",
+            Self::Rel => "REL (Relocatable file)",
+            Self::Exec => "EXEC (Executable file)",
+            Self::Dyn => "DYN (Shared object file)",
+            Self::Core => "CORE (Core file)",
+            Self::Os(e_type) => return f.pad(&format!("OS Specific: (0x{:x})", e_type)),
+            Self::Proc(e_type) => return f.pad(&format!("Processor Specific: (0x{:x})", e_type)),
         })
     }
 }
 
-impl ElfType {
-    pub fn display(&self) -> String {
-        format!("{:?}", self).to_uppercase()
+impl Display for ElfType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Self::None => "NONE (None)",
+            Self::Rel => "REL (Relocatable file)",
+            Self::Exec => "EXEC (Executable file)",
+            Self::Dyn => "DYN (Shared object file)",
+            Self::Core => "CORE (Core file)",
+        })
     }
 }
@@ -0,0 +1,97 @@
+use std::io;
+
+use super::{
+    bytes::{string_at, ByteReader},
+    core::FileData,
+    hdr::{ElfClass, Endian},
+    rel::irelative_type,
+    shdr::SectionType,
+    sym::SymbolType,
+};
+
+#[derive(Debug, Clone)]
+pub struct IrelativeReloc {
+    pub section: String,
+    pub offset: u64,
+    /// Address of the resolver, the addend for `SHT_RELA` or the value stored in place
+    /// for `SHT_REL`
+    pub resolver: Option<u64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct IfuncSymbol {
+    pub name: String,
+    /// For `STT_GNU_IFUNC` symbols the value is the resolver, not the implementation
+    pub resolver: u64,
+    pub binding: String,
+    /// Whether the symbol comes from `.dynsym` rather than `.symtab`
+    pub dynamic: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct IfuncReport {
+    pub relocs: Vec<IrelativeReloc>,
+    pub symbols: Vec<IfuncSymbol>,
+}
+
+impl IfuncReport {
+    pub fn read(elf: &FileData) -> io::Result<Self> {
+        let class = elf.header().class().unwrap_or(ElfClass::ElfClass64);
+        let endian = elf.header().endian().unwrap_or(Endian::Little);
+        let word = match class {
+            ElfClass::ElfClass32 => 4,
+            _ => 8,
+        };
+        let mut report = IfuncReport::default();
+
+        if let Some(irelative) = irelative_type(elf.header().machine()) {
+            for (shdr, rels) in elf.relocations()? {
+                let section = elf.section_name(&shdr).unwrap_or_default();
+                for rel in rels.iter().filter(|rel| rel.rtype() == irelative) {
+                    let resolver = match rel.addend() {
+                        Some(addend) => Some(addend as u64),
+                        None => elf
+                            .read_vaddr(rel.offset(), word)
+                            .ok()
+                            .and_then(|data| ByteReader::new(&data, endian).word(word)),
+                    };
+                    report.relocs.push(IrelativeReloc {
+                        section: section.clone(),
+                        offset: rel.offset(),
+                        resolver,
+                    });
+                }
+            }
+        }
+
+        let tables = elf
+            .section_headers()
+            .iter()
+            .filter(|shdr| {
+                matches!(
+                    shdr.section_type(),
+                    Some(SectionType::SymTab | SectionType::DynSym)
+                )
+            })
+            .copied()
+            .collect::<Vec<_>>();
+        for shdr in tables {
+            let (strtab, syms) = elf.symbol_table(&shdr)?;
+            for sym in syms
+                .iter()
+                .filter(|sym| matches!(sym.symbol_type(), Some(SymbolType::GnuIFunc)))
+            {
+                report.symbols.push(IfuncSymbol {
+                    name: string_at(strtab, sym.name() as usize),
+                    resolver: sym.value(),
+                    binding: sym
+                        .binding()
+                        .map_or("UNKNOWN".to_string(), |b| b.to_string()),
+                    dynamic: shdr.section_type() == Some(SectionType::DynSym),
+                });
+            }
+        }
+
+        Ok(report)
+    }
+}
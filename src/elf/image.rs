@@ -0,0 +1,280 @@
+use alloc::vec::Vec;
+use core::{
+    mem::{align_of, size_of},
+    ptr, slice, str,
+};
+
+use super::{
+    dynamic::{Dyn, DynamicTag, Elf32Dyn, Elf64Dyn},
+    hdr::{Elf32Hdr, Elf64Hdr, ElfClass},
+    note::Note,
+    phdr::{Elf32Phdr, Elf64Phdr, ProgramType},
+    shdr::{Elf32Shdr, Elf64Shdr, ElfShdr, SectionType},
+    sym::{Elf32Sym, Elf64Sym, ElfSym},
+    ElfHdr, ElfPhdr, EI_CLASS, EI_NINDENT, ELFMAG,
+};
+
+/// Reads a `#[repr(C)]` ELF structure at `offset`, `None` when it does not fit
+///
+/// # Safety
+/// `T` must be plain old data, valid for any bit pattern.
+pub(crate) unsafe fn read_struct<T>(data: &[u8], offset: usize) -> Option<T> {
+    let end = offset.checked_add(size_of::<T>())?;
+    if end > data.len() {
+        return None;
+    }
+    Some(ptr::read_unaligned(data.as_ptr().add(offset) as *const T))
+}
+
+/// Reads the section header at `offset` of a section header table
+pub(crate) fn section_header_at(data: &[u8], offset: usize, elf64: bool) -> Option<ElfShdr> {
+    unsafe {
+        Some(match elf64 {
+            true => read_struct::<Elf64Shdr>(data, offset)?.into(),
+            false => read_struct::<Elf32Shdr>(data, offset)?.into(),
+        })
+    }
+}
+
+/// Reads the program header at `offset` of a program header table
+pub(crate) fn program_header_at(data: &[u8], offset: usize, elf64: bool) -> Option<ElfPhdr> {
+    unsafe {
+        match elf64 {
+            true => read_struct::<Elf64Phdr>(data, offset).map(|phdr| (&phdr).into()),
+            false => {
+                read_struct::<Elf32Phdr>(data, offset).and_then(|phdr| (&phdr).try_into().ok())
+            }
+        }
+    }
+}
+
+/// An ELF image borrowed from memory, the parsing layer that works without `std`.
+///
+/// Like [`FileData`](super::core::FileData) this reads structures in host byte order, and
+/// every accessor returns `None` or stops iterating instead of panicking on truncated input.
+#[derive(Debug, Clone, Copy)]
+pub struct ElfImage<'a> {
+    data: &'a [u8],
+    header: ElfHdr,
+}
+
+impl<'a> ElfImage<'a> {
+    /// Validates the magic and class and reads the file header
+    pub fn parse(data: &'a [u8]) -> Option<Self> {
+        if data.len() < EI_NINDENT || !data.starts_with(ELFMAG.as_bytes()) {
+            return None;
+        }
+
+        let header = unsafe {
+            match data[EI_CLASS] {
+                1 => ElfHdr::upcast_elf32(&read_struct::<Elf32Hdr>(data, 0)?),
+                2 => ElfHdr::upcast_elf64(&read_struct::<Elf64Hdr>(data, 0)?),
+                _ => return None,
+            }
+        };
+
+        Some(Self { data, header })
+    }
+
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    pub fn header(&self) -> &ElfHdr {
+        &self.header
+    }
+
+    fn is_elf64(&self) -> bool {
+        self.header.class() == Some(ElfClass::ElfClass64)
+    }
+
+    pub fn section_header(&self, index: usize) -> Option<ElfShdr> {
+        if index >= self.header.e_shnum as usize {
+            return None;
+        }
+        let offset = (self.header.e_shoff as usize)
+            .checked_add(index.checked_mul(self.header.e_shentsize as usize)?)?;
+
+        section_header_at(self.data, offset, self.is_elf64())
+    }
+
+    pub fn section_headers(&self) -> impl Iterator<Item = ElfShdr> + 'a {
+        let image = *self;
+        (0..self.header.e_shnum as usize).map_while(move |index| image.section_header(index))
+    }
+
+    /// Contents of a section, empty for `SHT_NOBITS`
+    pub fn section_data(&self, shdr: &ElfShdr) -> Option<&'a [u8]> {
+        if shdr.section_type() == Some(SectionType::NoBits) {
+            return Some(&[]);
+        }
+        let start = shdr.offset() as usize;
+        self.data
+            .get(start..start.checked_add(shdr.size() as usize)?)
+    }
+
+    /// The NUL terminated string at `index` of the string table `strtab`, borrowed in place
+    pub fn string_at(&self, strtab: &ElfShdr, index: usize) -> Option<&'a str> {
+        let strings = self.section_data(strtab)?.get(index..)?;
+        let len = strings.iter().position(|&c| c == 0)?;
+        str::from_utf8(&strings[..len]).ok()
+    }
+
+    pub fn section_name(&self, shdr: &ElfShdr) -> Option<&'a str> {
+        let strtab = self.section_header(self.header.e_shstrndx as usize)?;
+        self.string_at(&strtab, shdr.name() as usize)
+    }
+
+    pub fn section_by_name(&self, name: &str) -> Option<ElfShdr> {
+        self.section_headers()
+            .find(|shdr| self.section_name(shdr) == Some(name))
+    }
+
+    pub fn program_headers(&self) -> impl Iterator<Item = ElfPhdr> + 'a {
+        let (data, elf64) = (self.data, self.is_elf64());
+        let (start, entsize) = (
+            self.header.e_phoff as usize,
+            self.header.e_phentsize as usize,
+        );
+        (0..self.header.e_phnum as usize).map_while(move |index| {
+            program_header_at(data, start.checked_add(index.checked_mul(entsize)?)?, elf64)
+        })
+    }
+
+    /// Entries of a `SHT_SYMTAB` or `SHT_DYNSYM` section
+    pub fn symbols(&self, shdr: &ElfShdr) -> impl Iterator<Item = ElfSym> + 'a {
+        let data = self.section_data(shdr).unwrap_or_default();
+        let elf64 = self.is_elf64();
+        let entsize = match elf64 {
+            true => size_of::<Elf64Sym>(),
+            false => size_of::<Elf32Sym>(),
+        };
+        (0..data.len() / entsize).map_while(move |index| unsafe {
+            match elf64 {
+                true => read_struct::<Elf64Sym>(data, index * entsize).map(ElfSym::from),
+                false => read_struct::<Elf32Sym>(data, index * entsize).map(ElfSym::from),
+            }
+        })
+    }
+
+    /// Borrows the entries of a symbol table of an `ELFCLASS64` image without decoding them,
+    /// `None` for the other class or when `sh_entsize` does not match the raw structure
+    pub fn elf64_symbols(&self, shdr: &ElfShdr) -> Option<&'a [Elf64Sym]> {
+        match self.is_elf64() {
+            true => self.table(shdr),
+            false => None,
+        }
+    }
+
+    /// The `ELFCLASS32` counterpart of [`elf64_symbols`](Self::elf64_symbols)
+    pub fn elf32_symbols(&self, shdr: &ElfShdr) -> Option<&'a [Elf32Sym]> {
+        match self.is_elf64() {
+            true => None,
+            false => self.table(shdr),
+        }
+    }
+
+    /// Reinterprets a section as a slice of `T` when its entry size and alignment permit
+    fn table<T>(&self, shdr: &ElfShdr) -> Option<&'a [T]> {
+        let data = self.section_data(shdr)?;
+        if shdr.entsize() as usize != size_of::<T>()
+            || data.as_ptr().align_offset(align_of::<T>()) != 0
+        {
+            return None;
+        }
+        Some(unsafe {
+            slice::from_raw_parts(data.as_ptr() as *const T, data.len() / size_of::<T>())
+        })
+    }
+
+    /// The `PT_DYNAMIC` entries up to `DT_NULL`
+    pub fn dynamic(&self) -> impl Iterator<Item = Dyn> + 'a {
+        let data = self
+            .program_headers()
+            .find(|phdr| phdr.program_type() == Some(ProgramType::Dynamic))
+            .and_then(|phdr| {
+                let start = phdr.offset() as usize;
+                self.data
+                    .get(start..start.checked_add(phdr.filesz() as usize)?)
+            })
+            .unwrap_or_default();
+        let elf64 = self.is_elf64();
+        let entsize = match elf64 {
+            true => size_of::<Elf64Dyn>(),
+            false => size_of::<Elf32Dyn>(),
+        };
+        (0..data.len() / entsize)
+            .map_while(move |index| unsafe {
+                match elf64 {
+                    true => read_struct::<Elf64Dyn>(data, index * entsize).map(|d| Dyn::from(&d)),
+                    false => read_struct::<Elf32Dyn>(data, index * entsize).map(|d| Dyn::from(&d)),
+                }
+            })
+            .take_while(|entry| entry.tag != DynamicTag::Null as u64)
+    }
+
+    /// Notes of the `SHT_NOTE` sections, or of the `PT_NOTE` segments without sections
+    pub fn notes(&self) -> Vec<Note> {
+        let endian = match self.header.endian() {
+            Some(endian) => endian,
+            None => return Vec::new(),
+        };
+
+        let mut sections = self
+            .section_headers()
+            .filter(|shdr| shdr.section_type() == Some(SectionType::Note))
+            .peekable();
+        if sections.peek().is_some() {
+            return sections
+                .filter_map(|shdr| Some((self.section_data(&shdr)?, shdr.addralign())))
+                .flat_map(|(data, align)| Note::parse_aligned(data, endian, align))
+                .collect();
+        }
+
+        self.program_headers()
+            .filter(|phdr| phdr.program_type() == Some(ProgramType::Note))
+            .filter_map(|phdr| {
+                let start = phdr.offset() as usize;
+                let data = self
+                    .data
+                    .get(start..start.checked_add(phdr.filesz() as usize)?)?;
+                Some((data, phdr.align()))
+            })
+            .flat_map(|(data, align)| Note::parse_aligned(data, endian, align))
+            .collect()
+    }
+}
+
+#[cfg(all(test, feature = "std", target_os = "linux"))]
+mod test {
+    use super::ElfImage;
+    use crate::elf::core::FileData;
+
+    #[test]
+    fn matches_file_backend() {
+        let path = std::env::current_exe().unwrap();
+        let data = std::fs::read(&path).unwrap();
+        let image = ElfImage::parse(&data).unwrap();
+        let elf = FileData::new(&path).unwrap();
+
+        let names = image
+            .section_headers()
+            .map(|shdr| image.section_name(&shdr).unwrap_or_default().to_string())
+            .collect::<Vec<_>>();
+        let expected = elf
+            .section_headers()
+            .iter()
+            .map(|shdr| elf.section_name(shdr).unwrap_or_default())
+            .collect::<Vec<_>>();
+        assert_eq!(names, expected);
+        assert_eq!(image.program_headers().count(), elf.program_headers().len());
+        assert!(ElfImage::parse(b"\x7fELF").is_none());
+
+        let symtab = image.section_by_name(".symtab").unwrap();
+        let count = match cfg!(target_pointer_width = "64") {
+            true => image.elf64_symbols(&symtab).unwrap().len(),
+            false => image.elf32_symbols(&symtab).unwrap().len(),
+        };
+        assert_eq!(count, image.symbols(&symtab).count());
+    }
+}
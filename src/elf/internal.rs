@@ -5,8 +5,8 @@ This is where all the evil lies
 */
 
 use std::{
-    io::{self, Read, Seek, SeekFrom},
-    mem, slice,
+    io::{self, BufReader, Read, Seek, SeekFrom},
+    mem, ptr, slice,
 };
 
 use super::{
@@ -19,8 +19,8 @@ use super::{
 #[inline]
 fn elf_tbss_special(shdr: &ElfShdr, segment: &ElfPhdr) -> bool {
     shdr.flags() & SectionFlag::Tls as u64 != 0
-        && shdr.section_type().unwrap() == SectionType::NoBits
-        && segment.program_type().unwrap() != ProgramType::Tls
+        && shdr.section_type() == Some(SectionType::NoBits)
+        && segment.program_type() != Some(ProgramType::Tls)
 }
 
 #[inline]
@@ -32,6 +32,26 @@ fn elf_section_size(shdr: &ElfShdr, segment: &ElfPhdr) -> u64 {
     }
 }
 
+/// Seeks to `offset`, failing with `UnexpectedEof` when fewer than `size` bytes follow it,
+/// so corrupt size and count fields are caught before anything is allocated for them
+pub fn seek_to_range<R: Seek>(
+    file: &mut R,
+    offset: SeekFrom,
+    size: Option<u64>,
+) -> io::Result<u64> {
+    let start = file.seek(offset)?;
+    let len = file.seek(SeekFrom::End(0))?;
+    match size {
+        Some(size) if size <= len.saturating_sub(start) => file.seek(SeekFrom::Start(start)),
+        _ => Err(io::ErrorKind::UnexpectedEof.into()),
+    }
+}
+
+/// Reads `nmemb` raw class specific entries at `offset` and widens them into `E`.
+///
+/// # Safety
+///
+/// `E32` and `E64` must be plain `repr(C)` structs for which every bit pattern is valid.
 pub unsafe fn get_data<'a, R: Read + Seek, E32, E64, E>(
     file: &mut R,
     hdr: &ElfHdr,
@@ -44,7 +64,12 @@ where
     E: From<&'a E32>,
     E: From<&'a E64>,
 {
-    file.seek(offset)?;
+    let entsize = match hdr.class() {
+        Some(ElfClass::ElfClass64) => mem::size_of::<E64>(),
+        _ => mem::size_of::<E32>(),
+    };
+    let size = nmemb.checked_mul(entsize).map(|size| size as u64);
+    seek_to_range(file, offset, size)?;
 
     match hdr.class().unwrap() {
         ElfClass::ElfClass32 => {
@@ -78,9 +103,83 @@ where
     }
 }
 
+/// Decodes `nmemb` raw class specific entries one at a time through a buffered reader, the
+/// streaming counterpart of [`get_data`]
+pub struct Entries<R: Read, E> {
+    reader: BufReader<R>,
+    entry: Vec<u8>,
+    decode: fn(&[u8]) -> E,
+    remaining: usize,
+}
+
+fn decode<T, E>(bytes: &[u8]) -> E
+where
+    for<'b> E: From<&'b T>,
+{
+    let raw = unsafe { ptr::read_unaligned(bytes.as_ptr() as *const T) };
+    E::from(&raw)
+}
+
+impl<R: Read + Seek, E> Entries<R, E> {
+    /// # Safety
+    ///
+    /// `E32` and `E64` must be plain `repr(C)` structs for which every bit pattern is valid.
+    pub unsafe fn new<E32, E64>(
+        mut file: R,
+        hdr: &ElfHdr,
+        nmemb: usize,
+        offset: u64,
+    ) -> io::Result<Self>
+    where
+        for<'b> E: From<&'b E32> + From<&'b E64>,
+    {
+        let (entsize, decode): (usize, fn(&[u8]) -> E) = match hdr.class() {
+            Some(ElfClass::ElfClass32) => (mem::size_of::<E32>(), decode::<E32, E>),
+            Some(ElfClass::ElfClass64) => (mem::size_of::<E64>(), decode::<E64, E>),
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "Unsupported elf class",
+                ))
+            }
+        };
+        file.seek(SeekFrom::Start(offset))?;
+
+        Ok(Self {
+            reader: BufReader::new(file),
+            entry: vec![0; entsize],
+            decode,
+            remaining: nmemb,
+        })
+    }
+}
+
+impl<R: Read, E> Iterator for Entries<R, E> {
+    type Item = io::Result<E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+
+        match self.reader.read_exact(&mut self.entry) {
+            Ok(()) => Some(Ok((self.decode)(&self.entry))),
+            Err(err) => {
+                self.remaining = 0;
+                Some(Err(err))
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.remaining))
+    }
+}
+
 pub fn offset_from_vma(phdrs: &[ElfPhdr], vma: u64, size: u64) -> u64 {
     for phdr in phdrs {
-        if phdr.program_type().unwrap() != ProgramType::Load {
+        if phdr.program_type() != Some(ProgramType::Load) {
             continue;
         }
 
@@ -101,7 +200,8 @@ pub fn elf_section_in_segment(
     check_vma: bool,
     strict: bool,
 ) -> bool {
-    let ptype = segment.program_type().unwrap();
+    // Processor specific types take the same path as any segment the checks do not name
+    let ptype = segment.program_type().unwrap_or(ProgramType::LoProc);
     ((((shdr.flags() & SectionFlag::Tls as u64) != 0)
         && (ptype == ProgramType::Tls
             || ptype == ProgramType::GnuRelro
@@ -115,7 +215,7 @@ pub fn elf_section_in_segment(
                 || ptype == ProgramType::GnuEhFrame
                 || ptype == ProgramType::GnuRelro
                 || ptype >= ProgramType::GnuMbindLo && ptype <= ProgramType::GnuMbindHi))
-        && (shdr.section_type().unwrap() == SectionType::NoBits
+        && (shdr.section_type() == Some(SectionType::NoBits)
             || shdr.offset() >= segment.offset()
                 && (!strict || shdr.offset() - segment.offset() < segment.filesz())
                 && (shdr.offset() - segment.offset() + elf_section_size(shdr, segment)
@@ -127,7 +227,7 @@ pub fn elf_section_in_segment(
         && ((ptype != ProgramType::Dynamic && ptype != ProgramType::Note)
             || shdr.size() != 0
             || segment.memsz() == 0
-            || (shdr.section_type().unwrap() == SectionType::NoBits
+            || (shdr.section_type() == Some(SectionType::NoBits)
                 || shdr.offset() > segment.offset()
                     && (shdr.offset() - segment.offset() < segment.filesz())
                     && (shdr.flags() & SectionFlag::Alloc as u64 == 0
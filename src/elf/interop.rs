@@ -0,0 +1,136 @@
+//! Adapters to the [`object`] crate, enabled with the `object` feature
+//!
+//! `object::read::Object` is sealed, so instead of implementing it this module lets `object`
+//! read through a [`FileData`] source and maps the parsed types onto the `object` vocabulary:
+//!
+//! ```no_run
+//! use object::{Object, ObjectSection};
+//! use readelf_rs::elf::core::FileData;
+//!
+//! let elf = FileData::new("/bin/ls").unwrap();
+//! let cache = elf.into_read_cache();
+//! let file = object::File::parse(&cache).unwrap();
+//! for section in file.sections() {
+//!     println!("{:?}", section.name());
+//! }
+//! ```
+//!
+//! [`FileData::load_section`] has the shape `gimli::Dwarf::load` expects from its loader.
+
+use std::{borrow::Cow, io};
+
+use object::{read::ReadCache, SectionKind, SymbolKind, SymbolScope};
+
+use super::{
+    core::{FileData, Source},
+    shdr::{ElfShdr, SectionFlag, SectionType},
+    source::SourceReader,
+    sym::{ElfSym, SymbolBinding, SymbolType, SymbolVis},
+};
+
+impl FileData {
+    /// Hands the underlying source over to `object`, ready for `object::File::parse(&cache)`
+    pub fn into_read_cache(self) -> ReadCache<SourceReader<Box<dyn Source>>> {
+        ReadCache::new(SourceReader::new(self.into_source()))
+    }
+
+    /// Decompressed contents of the named section, empty when it does not exist
+    pub fn load_section(&self, name: &str) -> io::Result<Cow<'static, [u8]>> {
+        match self.debug_section_by_name(name) {
+            Some(shdr) => self.decompressed_section_data(&shdr).map(Cow::Owned),
+            None => Ok(Cow::Borrowed(&[])),
+        }
+    }
+}
+
+/// The [`SectionKind`] `object` assigns to a section
+pub fn section_kind(shdr: &ElfShdr) -> SectionKind {
+    let flag = |flag: SectionFlag| shdr.flags() & flag as u64 != 0;
+
+    match shdr.section_type() {
+        Some(SectionType::ProgBits) if flag(SectionFlag::Alloc) => {
+            if flag(SectionFlag::ExecInstr) {
+                SectionKind::Text
+            } else if flag(SectionFlag::Tls) {
+                SectionKind::Tls
+            } else if flag(SectionFlag::Write) {
+                SectionKind::Data
+            } else if flag(SectionFlag::Strings) {
+                SectionKind::ReadOnlyString
+            } else {
+                SectionKind::ReadOnlyData
+            }
+        }
+        Some(SectionType::ProgBits) if flag(SectionFlag::Strings) => SectionKind::OtherString,
+        Some(SectionType::ProgBits) => SectionKind::Other,
+        Some(SectionType::NoBits) if flag(SectionFlag::Tls) => SectionKind::UninitializedTls,
+        Some(SectionType::NoBits) => SectionKind::UninitializedData,
+        Some(SectionType::Note) => SectionKind::Note,
+        Some(
+            SectionType::Null
+            | SectionType::SymTab
+            | SectionType::StrTab
+            | SectionType::Rela
+            | SectionType::Hash
+            | SectionType::Dynamic
+            | SectionType::Rel
+            | SectionType::DynSym
+            | SectionType::Group
+            | SectionType::SymTabShndx,
+        ) => SectionKind::Metadata,
+        _ => SectionKind::Elf(shdr.raw_type()),
+    }
+}
+
+/// The [`SymbolKind`] `object` assigns to a symbol
+pub fn symbol_kind(sym: &ElfSym) -> SymbolKind {
+    match sym.symbol_type() {
+        Some(SymbolType::Object | SymbolType::Common) => SymbolKind::Data,
+        Some(SymbolType::Func | SymbolType::GnuIFunc) => SymbolKind::Text,
+        Some(SymbolType::Section) => SymbolKind::Section,
+        Some(SymbolType::File) => SymbolKind::File,
+        Some(SymbolType::Tls) => SymbolKind::Tls,
+        _ => SymbolKind::Unknown,
+    }
+}
+
+/// The [`SymbolScope`] `object` assigns to a symbol
+pub fn symbol_scope(sym: &ElfSym) -> SymbolScope {
+    if sym.shndx() == 0 {
+        return SymbolScope::Unknown;
+    }
+
+    match sym.binding() {
+        Some(SymbolBinding::Local) => SymbolScope::Compilation,
+        Some(SymbolBinding::Global | SymbolBinding::Weak) => match sym.visibility() {
+            Some(SymbolVis::Hidden) => SymbolScope::Linkage,
+            _ => SymbolScope::Dynamic,
+        },
+        _ => SymbolScope::Unknown,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use object::{Object, ObjectSection};
+
+    use super::section_kind;
+    use crate::elf::core::FileData;
+
+    #[test]
+    fn agrees_with_object() {
+        let path = std::env::current_exe().unwrap();
+        let elf = FileData::new(&path).unwrap();
+        let kinds = elf
+            .section_headers()
+            .iter()
+            .skip(1)
+            .map(section_kind)
+            .collect::<Vec<_>>();
+
+        let cache = elf.into_read_cache();
+        let file = object::File::parse(&cache).unwrap();
+        let expected = file.sections().map(|s| s.kind()).collect::<Vec<_>>();
+        assert_eq!(kinds, expected);
+    }
+}
@@ -0,0 +1,91 @@
+use alloc::{format, string::String, vec::Vec};
+
+use super::{
+    bytes::{string_at, ByteReader},
+    hdr::Endian,
+};
+
+pub const LL_EXACT_MATCH: u32 = 0x1;
+pub const LL_IGNORE_INT_VER: u32 = 0x2;
+pub const LL_REQUIRE_MINOR: u32 = 0x4;
+pub const LL_EXPORTS: u32 = 0x8;
+pub const LL_DELAY_LOAD: u32 = 0x10;
+pub const LL_DELTA: u32 = 0x20;
+
+/// One entry of a `SHT_GNU_LIBLIST` section, a library prelink resolved the file against.
+/// `Elf32_Lib` and `Elf64_Lib` share the same 20 byte layout.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Library {
+    pub name: String,
+    pub time_stamp: u32,
+    pub checksum: u32,
+    pub version: u32,
+    pub flags: u32,
+}
+
+impl Library {
+    /// Decodes a `.gnu.liblist` section, `strtab` is the string table it links to
+    pub fn parse_section(data: &[u8], strtab: &[u8], endian: Endian) -> Vec<Self> {
+        let mut reader = ByteReader::new(data, endian);
+        core::iter::from_fn(|| {
+            Some(Self {
+                name: string_at(strtab, reader.u32()? as usize),
+                time_stamp: reader.u32()?,
+                checksum: reader.u32()?,
+                version: reader.u32()?,
+                flags: reader.u32()?,
+            })
+        })
+        .collect()
+    }
+
+    /// The time stamp as a UTC `YYYY-MM-DDTHH:MM:SS` date
+    pub fn time(&self) -> String {
+        let secs = self.time_stamp as u64;
+        let (days, rem) = (secs / 86400, secs % 86400);
+
+        // Howard Hinnant's days to civil date conversion
+        let z = days + 719468;
+        let era = z / 146097;
+        let doe = z % 146097;
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + (month <= 2) as u64;
+
+        format!(
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            year,
+            month,
+            day,
+            rem / 3600,
+            rem % 3600 / 60,
+            rem % 60
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::vec::Vec;
+
+    use super::{Library, LL_EXACT_MATCH};
+    use crate::elf::hdr::Endian;
+
+    #[test]
+    fn parses_liblist() {
+        let strtab = b"\0libc.so.6\0";
+        let mut data = Vec::new();
+        for field in [1u32, 1_104_635_045, 0x1234_5678, 0, LL_EXACT_MATCH] {
+            data.extend(field.to_le_bytes());
+        }
+
+        let libs = Library::parse_section(&data, strtab, Endian::Little);
+        assert_eq!(libs.len(), 1);
+        assert_eq!(libs[0].name, "libc.so.6");
+        assert_eq!(libs[0].checksum, 0x1234_5678);
+        assert_eq!(libs[0].time(), "2005-01-02T03:04:05");
+    }
+}
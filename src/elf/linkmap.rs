@@ -0,0 +1,114 @@
+use std::io;
+
+use super::{
+    bytes::string_at,
+    core::FileData,
+    hdr::ObjectType,
+    shdr::{SectionFlag, SectionType},
+    sym::SymbolType,
+};
+
+/// One line under an output section of a [`OutputSection`] map
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MapItem {
+    Symbol {
+        addr: u64,
+        size: u64,
+        name: String,
+    },
+    /// Bytes no symbol accounts for, alignment padding or code from stripped objects
+    Fill {
+        addr: u64,
+        size: u64,
+    },
+}
+
+/// An allocated section with the symbols laid out in it, like an output section in a
+/// linker map file
+#[derive(Debug, Clone)]
+pub struct OutputSection {
+    pub name: String,
+    pub addr: u64,
+    pub size: u64,
+    pub items: Vec<MapItem>,
+}
+
+impl OutputSection {
+    /// Rebuilds the map from the section headers and `.symtab`, or `.dynsym` when the file
+    /// is stripped
+    pub fn read(elf: &FileData) -> io::Result<Vec<Self>> {
+        let tables = elf.table_symbols()?;
+        let (strtab, symbols) = match tables
+            .iter()
+            .find(|(name, _, _)| name == ".symtab")
+            .or_else(|| tables.first())
+        {
+            Some((_, strtab, symbols)) => (*strtab, *symbols),
+            None => (&[][..], &[][..]),
+        };
+        let relocatable = elf.header().ftype() == Some(ObjectType::Rel);
+
+        let mut sections = Vec::new();
+        for (index, shdr) in elf.section_headers().iter().enumerate() {
+            if shdr.flags() & SectionFlag::Alloc as u64 == 0 || shdr.size() == 0 {
+                continue;
+            }
+            // Thread local symbols hold offsets into the TLS block, not addresses
+            if shdr.flags() & SectionFlag::Tls as u64 != 0
+                && shdr.section_type() == Some(SectionType::NoBits)
+            {
+                continue;
+            }
+
+            let mut placed = symbols
+                .iter()
+                .filter(|sym| {
+                    sym.shndx() as usize == index
+                        && matches!(
+                            sym.symbol_type(),
+                            Some(SymbolType::Func | SymbolType::Object | SymbolType::GnuIFunc)
+                        )
+                })
+                .map(|sym| {
+                    let addr = match relocatable {
+                        true => shdr.addr() + sym.value(),
+                        false => sym.value(),
+                    };
+                    (addr, sym.size(), string_at(strtab, sym.name() as usize))
+                })
+                .collect::<Vec<_>>();
+            placed.sort();
+            placed.dedup();
+
+            let end = shdr.addr() + shdr.size();
+            let mut cursor = shdr.addr();
+            let mut items = Vec::new();
+            for (addr, size, name) in placed {
+                if addr > cursor {
+                    items.push(MapItem::Fill {
+                        addr: cursor,
+                        size: addr - cursor,
+                    });
+                }
+                cursor = cursor.max(addr + size);
+                items.push(MapItem::Symbol { addr, size, name });
+            }
+            // A section without any symbols is listed on its own, not as one big fill
+            if cursor < end && !items.is_empty() {
+                items.push(MapItem::Fill {
+                    addr: cursor,
+                    size: end - cursor,
+                });
+            }
+
+            sections.push(Self {
+                name: elf.section_name(shdr).unwrap_or_default(),
+                addr: shdr.addr(),
+                size: shdr.size(),
+                items,
+            });
+        }
+
+        Ok(sections)
+    }
+}
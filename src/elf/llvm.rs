@@ -0,0 +1,51 @@
+use alloc::{string::String, vec::Vec};
+
+use super::{bytes::ByteReader, hdr::Endian};
+
+/// Decodes a `SHT_LLVM_ADDRSIG` section, the ULEB128 indices of the address significant
+/// symbols in the symbol table the section links to
+pub fn parse_addrsig(data: &[u8]) -> Option<Vec<u64>> {
+    // Only single bytes are read, the byte order does not matter
+    let mut reader = ByteReader::new(data, Endian::Little);
+    let mut indices = Vec::new();
+    while !reader.is_empty() {
+        indices.push(reader.uleb128()?);
+    }
+    Some(indices)
+}
+
+/// Decodes a `SHT_LLVM_LINKER_OPTIONS` section, NUL-terminated strings taken pairwise as
+/// key and value
+pub fn parse_linker_options(data: &[u8]) -> Option<Vec<(String, String)>> {
+    let mut reader = ByteReader::new(data, Endian::Little);
+    let mut options = Vec::new();
+    while !reader.is_empty() {
+        let key = String::from_utf8_lossy(reader.cstr()?).into_owned();
+        let value = String::from_utf8_lossy(reader.cstr()?).into_owned();
+        options.push((key, value));
+    }
+    Some(options)
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::{string::ToString, vec};
+
+    use super::{parse_addrsig, parse_linker_options};
+
+    #[test]
+    fn decodes_llvm_sections() {
+        assert_eq!(parse_addrsig(&[1, 0x85, 0x01, 7]), Some(vec![1, 133, 7]));
+        assert_eq!(parse_addrsig(&[0x80]), None);
+
+        let options = parse_linker_options(b"lib\0m\0/DEFAULTLIB:\0libcmt\0").unwrap();
+        assert_eq!(
+            options,
+            vec![
+                ("lib".to_string(), "m".to_string()),
+                ("/DEFAULTLIB:".to_string(), "libcmt".to_string())
+            ]
+        );
+        assert_eq!(parse_linker_options(b"key\0"), None);
+    }
+}
@@ -0,0 +1,281 @@
+use super::{
+    bytes::{string_at, ByteReader},
+    dwarf::{read_form, Encoding},
+    hdr::Endian,
+};
+
+/// A record of `.debug_macro` or `.debug_macinfo`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MacroEntry {
+    /// `#define`, the text is the name, parameters and replacement, `SQUARE(x) ((x) * (x))`
+    Define {
+        line: u64,
+        text: String,
+    },
+    Undef {
+        line: u64,
+        text: String,
+    },
+    /// Entering the file with the given number in the unit's line table, included at `line`
+    StartFile {
+        line: u64,
+        file: u64,
+    },
+    EndFile,
+    /// `DW_MACRO_import`, the GNU transparent include: the records of the unit at `offset`
+    /// belong here. Producers share units holding the predefined macros this way.
+    Import {
+        offset: u64,
+    },
+    /// `DW_MACINFO_vendor_ext`
+    Vendor {
+        constant: u64,
+        text: String,
+    },
+    /// An opcode this reader skipped through the operand table of the unit
+    Unknown,
+}
+
+/// A record and the opcode it was read from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MacroOp {
+    pub opcode: u8,
+    pub entry: MacroEntry,
+}
+
+/// The name of a `DW_MACRO_*` opcode, or of a `DW_MACINFO_*` one with `macinfo`
+pub fn macro_op_name(opcode: u8, macinfo: bool) -> String {
+    let name = match (opcode, macinfo) {
+        (0x01, true) => "DW_MACINFO_define",
+        (0x02, true) => "DW_MACINFO_undef",
+        (0x03, true) => "DW_MACINFO_start_file",
+        (0x04, true) => "DW_MACINFO_end_file",
+        (0xff, true) => "DW_MACINFO_vendor_ext",
+        (0x01, false) => "DW_MACRO_define",
+        (0x02, false) => "DW_MACRO_undef",
+        (0x03, false) => "DW_MACRO_start_file",
+        (0x04, false) => "DW_MACRO_end_file",
+        (0x05, false) => "DW_MACRO_define_strp",
+        (0x06, false) => "DW_MACRO_undef_strp",
+        (0x07, false) => "DW_MACRO_import",
+        (0x08, false) => "DW_MACRO_define_sup",
+        (0x09, false) => "DW_MACRO_undef_sup",
+        (0x0a, false) => "DW_MACRO_import_sup",
+        (0x0b, false) => "DW_MACRO_define_strx",
+        (0x0c, false) => "DW_MACRO_undef_strx",
+        (_, true) => return format!("DW_MACINFO_<0x{:x}>", opcode),
+        (_, false) => return format!("DW_MACRO_<0x{:x}>", opcode),
+    };
+    name.to_string()
+}
+
+/// A unit of `.debug_macro`, the DWARF 5 format GCC also emits for DWARF 4 as an extension
+#[derive(Debug, Clone)]
+pub struct MacroUnit {
+    /// Offset of the unit in the section, what `DW_AT_macros` and imports refer to
+    pub offset: usize,
+    pub version: u16,
+    pub offset_size: usize,
+    /// Offset in `.debug_line` of the line table numbering the files
+    pub line_offset: Option<u64>,
+    pub ops: Vec<MacroOp>,
+}
+
+impl MacroUnit {
+    /// Parses every unit of `.debug_macro`, strings are looked up in `debug_str`
+    pub fn parse(data: &[u8], debug_str: &[u8], endian: Endian) -> Vec<Self> {
+        let mut units = Vec::new();
+        let mut reader = ByteReader::new(data, endian);
+
+        while !reader.is_empty() {
+            match Self::parse_unit(&mut reader, debug_str) {
+                Some(unit) => units.push(unit),
+                None => break,
+            }
+        }
+
+        units
+    }
+
+    fn parse_unit(reader: &mut ByteReader, debug_str: &[u8]) -> Option<Self> {
+        let offset = reader.pos();
+        let version = reader.u16()?;
+        let flags = reader.u8()?;
+        let offset_size = if flags & 1 != 0 { 8 } else { 4 };
+        let line_offset = match flags & 2 {
+            0 => None,
+            _ => Some(reader.word(offset_size)?),
+        };
+        // Operand forms of opcodes a consumer may not know, so it can skip them
+        let mut operands = Vec::new();
+        if flags & 4 != 0 {
+            for _ in 0..reader.u8()? {
+                let opcode = reader.u8()?;
+                let forms = (0..reader.uleb128()?)
+                    .map(|_| reader.u8().map(u64::from))
+                    .collect::<Option<Vec<_>>>()?;
+                operands.push((opcode, forms));
+            }
+        }
+
+        let encoding = Encoding {
+            version,
+            offset_size,
+            address_size: offset_size,
+        };
+        let string = |offset: u64| string_at(debug_str, offset as usize);
+        let mut ops = Vec::new();
+        loop {
+            let opcode = reader.u8()?;
+            let entry = match opcode {
+                0 => break,
+                0x01 | 0x02 => {
+                    let line = reader.uleb128()?;
+                    let text = String::from_utf8_lossy(reader.cstr()?).into_owned();
+                    define(opcode == 0x01, line, text)
+                }
+                0x03 => MacroEntry::StartFile {
+                    line: reader.uleb128()?,
+                    file: reader.uleb128()?,
+                },
+                0x04 => MacroEntry::EndFile,
+                0x05 | 0x06 => {
+                    let line = reader.uleb128()?;
+                    let text = string(reader.word(offset_size)?);
+                    define(opcode == 0x05, line, text)
+                }
+                0x07 => MacroEntry::Import {
+                    offset: reader.word(offset_size)?,
+                },
+                // Strings and units in the supplementary object file, or behind the unit's
+                // string offsets table
+                0x08 | 0x09 | 0x0b | 0x0c => {
+                    let line = reader.uleb128()?;
+                    let text = match opcode {
+                        0x08 | 0x09 => format!("<sup 0x{:x}>", reader.word(offset_size)?),
+                        _ => format!("<strx {}>", reader.uleb128()?),
+                    };
+                    define(matches!(opcode, 0x08 | 0x0b), line, text)
+                }
+                0x0a => {
+                    reader.word(offset_size)?;
+                    MacroEntry::Unknown
+                }
+                _ => {
+                    let (_, forms) = operands.iter().find(|(op, _)| *op == opcode)?;
+                    for &form in forms {
+                        read_form(reader, form, encoding, 0)?;
+                    }
+                    MacroEntry::Unknown
+                }
+            };
+            ops.push(MacroOp { opcode, entry });
+        }
+
+        Some(Self {
+            offset,
+            version,
+            offset_size,
+            line_offset,
+            ops,
+        })
+    }
+}
+
+fn define(define: bool, line: u64, text: String) -> MacroEntry {
+    match define {
+        true => MacroEntry::Define { line, text },
+        false => MacroEntry::Undef { line, text },
+    }
+}
+
+/// Parses `.debug_macinfo`, the macro format before DWARF 5, into the lists of each
+/// compilation unit with the offset of each list
+pub fn parse_macinfo(data: &[u8], endian: Endian) -> Vec<(usize, Vec<MacroOp>)> {
+    let mut lists = Vec::new();
+    let mut reader = ByteReader::new(data, endian);
+
+    while !reader.is_empty() {
+        let offset = reader.pos();
+        let list = (|| {
+            let mut ops = Vec::new();
+            loop {
+                let opcode = reader.u8()?;
+                let entry = match opcode {
+                    0 => break,
+                    0x01 | 0x02 => {
+                        let line = reader.uleb128()?;
+                        let text = String::from_utf8_lossy(reader.cstr()?).into_owned();
+                        define(opcode == 0x01, line, text)
+                    }
+                    0x03 => MacroEntry::StartFile {
+                        line: reader.uleb128()?,
+                        file: reader.uleb128()?,
+                    },
+                    0x04 => MacroEntry::EndFile,
+                    0xff => MacroEntry::Vendor {
+                        constant: reader.uleb128()?,
+                        text: String::from_utf8_lossy(reader.cstr()?).into_owned(),
+                    },
+                    _ => return None,
+                };
+                ops.push(MacroOp { opcode, entry });
+            }
+            Some(ops)
+        })();
+
+        match list {
+            Some(ops) => lists.push((offset, ops)),
+            None => break,
+        }
+    }
+
+    lists
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_macinfo, MacroEntry, MacroUnit};
+    use crate::elf::hdr::Endian;
+
+    #[test]
+    fn parses_macro_units() {
+        let debug_str = b"\0LOCAL 1\0";
+        let data = [
+            5, 0, 2, 0, 0, 0, 0, // version 5, line table at 0
+            7, 0x20, 0, 0, 0, // import of the unit at 0x20
+            3, 0, 1, // start_file 1
+            5, 2, 1, 0, 0, 0, // define_strp "LOCAL 1" at line 2
+            2, 8, b'L', b'O', b'C', b'A', b'L', 0, // undef at line 8
+            4, 0,
+        ];
+        let units = MacroUnit::parse(&data, debug_str, Endian::Little);
+        assert_eq!(units.len(), 1);
+        assert_eq!(units[0].line_offset, Some(0));
+        let entries = units[0]
+            .ops
+            .iter()
+            .map(|op| op.entry.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            entries,
+            vec![
+                MacroEntry::Import { offset: 0x20 },
+                MacroEntry::StartFile { line: 0, file: 1 },
+                MacroEntry::Define {
+                    line: 2,
+                    text: "LOCAL 1".to_string()
+                },
+                MacroEntry::Undef {
+                    line: 8,
+                    text: "LOCAL".to_string()
+                },
+                MacroEntry::EndFile,
+            ]
+        );
+
+        let lists = parse_macinfo(&[3, 0, 1, 1, 1, b'A', 0, 4, 0], Endian::Little);
+        assert_eq!(lists.len(), 1);
+        assert_eq!(lists[0].1.len(), 3);
+    }
+}
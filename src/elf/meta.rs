@@ -0,0 +1,157 @@
+use std::io;
+
+use super::{
+    core::FileData,
+    hdr::{ElfClass, Endian, ObjectType},
+    phdr::ProgramType,
+    shdr::SectionType,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Linking {
+    Static,
+    StaticPie,
+    Dynamic,
+}
+
+impl Linking {
+    pub fn display(&self) -> &'static str {
+        match self {
+            Self::Static => "statically linked",
+            Self::StaticPie => "static-pie linked",
+            Self::Dynamic => "dynamically linked",
+        }
+    }
+}
+
+/// The digest printed by `--meta`, gathered from the header, segments, dynamic section
+/// and notes
+#[derive(Debug, Clone)]
+pub struct FileMeta {
+    pub class: Option<ElfClass>,
+    pub endian: Option<Endian>,
+    pub machine: String,
+    pub object_type: Option<ObjectType>,
+    pub pie: bool,
+    /// `None` for relocatable objects and core files, which are never linked
+    pub linking: Option<Linking>,
+    pub interpreter: Option<String>,
+    pub build_id: Option<String>,
+    /// OS and minimum kernel version of the `NT_GNU_ABI_TAG` note
+    pub abi_tag: Option<(&'static str, String)>,
+    pub stripped: bool,
+    pub debug_info: bool,
+}
+
+impl FileMeta {
+    pub fn read(elf: &FileData) -> io::Result<Self> {
+        let hdr = elf.header();
+        let endian = hdr.endian();
+        let mut meta = FileMeta {
+            class: hdr.class(),
+            endian,
+            machine: hdr
+                .machine_name()
+                .map_or_else(|| format!("machine {}", hdr.machine()), str::to_string),
+            object_type: hdr.ftype(),
+            pie: false,
+            linking: None,
+            interpreter: None,
+            build_id: None,
+            abi_tag: None,
+            stripped: !elf
+                .section_headers()
+                .iter()
+                .any(|shdr| shdr.section_type() == Some(SectionType::SymTab)),
+            debug_info: elf.debug_section_by_name(".debug_info").is_some(),
+        };
+
+        let interp = elf
+            .program_headers()
+            .iter()
+            .find(|phdr| phdr.program_type() == Some(ProgramType::Interp))
+            .map(|phdr| (phdr.offset(), phdr.filesz()));
+        if let Some((offset, size)) = interp {
+            let data = elf.read_at(offset, size as usize)?;
+            let path = data.split(|&c| c == 0).next().unwrap_or_default();
+            meta.interpreter = Some(String::from_utf8_lossy(path).into_owned());
+        }
+
+        let has_dynamic = elf.has_dynamic_section();
+        if let Some(ObjectType::Exec | ObjectType::Dyn) = meta.object_type {
+            meta.pie = elf.is_pie();
+            meta.linking = Some(
+                if meta.interpreter.is_some() || (has_dynamic && !meta.pie) {
+                    Linking::Dynamic
+                } else if meta.pie {
+                    Linking::StaticPie
+                } else {
+                    Linking::Static
+                },
+            );
+        }
+
+        for note in elf.notes()? {
+            if let Some(build_id) = note.build_id() {
+                meta.build_id = Some(build_id);
+            }
+            if let Some(abi_tag) = note.abi_tag(endian.unwrap_or(Endian::Little)) {
+                meta.abi_tag = Some(abi_tag);
+            }
+        }
+
+        Ok(meta)
+    }
+
+    /// One line summary in the spirit of file(1)
+    pub fn display(&self) -> String {
+        let mut parts = vec![format!(
+            "ELF {} {} {}",
+            match self.class {
+                Some(ElfClass::ElfClass32) => "32-bit",
+                Some(ElfClass::ElfClass64) => "64-bit",
+                _ => "invalid class",
+            },
+            match self.endian {
+                Some(Endian::Little) => "LSB",
+                Some(Endian::Big) => "MSB",
+                None => "invalid byte order",
+            },
+            match self.object_type {
+                Some(ObjectType::Rel) => "relocatable",
+                Some(ObjectType::Exec) => "executable",
+                Some(ObjectType::Dyn) if self.pie => "pie executable",
+                Some(ObjectType::Dyn) => "shared object",
+                Some(ObjectType::Core) => "core file",
+                _ => "unknown type",
+            }
+        )];
+        parts.push(self.machine.clone());
+
+        if let Some(linking) = self.linking {
+            parts.push(linking.display().to_string());
+        }
+        if let Some(interpreter) = &self.interpreter {
+            parts.push(format!("interpreter {}", interpreter));
+        }
+        if let Some(build_id) = &self.build_id {
+            parts.push(format!("BuildID={}", build_id));
+        }
+        if let Some((os, version)) = &self.abi_tag {
+            parts.push(format!("for GNU/{} {}", os, version));
+        }
+        if self.debug_info {
+            parts.push("with debug_info".to_string());
+        }
+        parts.push(
+            if self.stripped {
+                "stripped"
+            } else {
+                "not stripped"
+            }
+            .to_string(),
+        );
+
+        parts.join(", ")
+    }
+}
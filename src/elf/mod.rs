@@ -1,13 +1,112 @@
 #![allow(clippy::unused_io_amount)]
 
+#[cfg(feature = "dwarf")]
+pub mod addr2line;
+#[cfg(feature = "std")]
+pub mod analyze;
+pub mod archive;
+#[cfg(feature = "tokio")]
+pub mod async_file;
+#[cfg(feature = "std")]
+pub mod bpf;
+#[cfg(feature = "std")]
+pub mod branchprot;
+#[cfg(feature = "std")]
+pub mod btf;
+pub mod bytes;
+#[cfg(feature = "std")]
+pub mod check;
+#[cfg(feature = "std")]
+pub mod compress;
+#[cfg(feature = "std")]
+pub mod copyrel;
+#[cfg(feature = "std")]
 pub mod core;
+#[cfg(feature = "dwarf")]
+pub mod debugstats;
+#[cfg(feature = "demangle")]
+pub mod demangle;
+#[cfg(feature = "std")]
+pub mod deps;
+#[cfg(feature = "std")]
+pub mod diag;
+#[cfg(feature = "disasm")]
+pub mod disasm;
+#[cfg(feature = "dwarf")]
+pub mod dwarf;
+#[cfg(feature = "dwarf")]
+pub mod dwo;
 pub mod dynamic;
+#[cfg(feature = "std")]
+pub mod except;
+#[cfg(feature = "std")]
+pub mod export;
+#[cfg(feature = "dwarf")]
+pub mod gdbindex;
+#[cfg(feature = "std")]
+pub mod golang;
+pub mod hash;
 pub mod hdr;
+#[cfg(feature = "std")]
+pub mod ifunc;
+pub mod image;
+#[cfg(feature = "std")]
 pub mod internal;
+#[cfg(feature = "object")]
+pub mod interop;
+pub mod liblist;
+#[cfg(feature = "std")]
+pub mod linkmap;
+pub mod llvm;
+#[cfg(feature = "dwarf")]
+pub mod macros;
+#[cfg(feature = "std")]
+pub mod meta;
+#[cfg(all(feature = "std", unix))]
+pub mod mmap;
+#[cfg(feature = "std")]
+pub mod modinfo;
+pub mod note;
+#[cfg(feature = "std")]
+pub mod notedecode;
 pub mod phdr;
+#[cfg(all(feature = "std", target_os = "linux"))]
+pub mod process;
+#[cfg(feature = "std")]
+pub mod property;
+#[cfg(feature = "dwarf")]
+pub mod pubnames;
+#[cfg(feature = "std")]
+pub mod rel;
+#[cfg(feature = "std")]
+pub mod relocate;
+#[cfg(feature = "std")]
+pub mod resolve;
+#[cfg(feature = "std")]
+pub mod rust;
+#[cfg(feature = "std")]
+pub mod search;
+#[cfg(feature = "std")]
+pub mod section;
+#[cfg(feature = "std")]
+pub mod segalign;
 pub mod shdr;
+#[cfg(feature = "std")]
+pub mod source;
 pub mod sym;
+pub mod syminfo;
+#[cfg(feature = "std")]
+pub mod tls;
+#[cfg(feature = "std")]
+pub mod unwind;
+#[cfg(feature = "std")]
 pub mod ver;
+#[cfg(feature = "std")]
+pub mod visit;
+#[cfg(feature = "std")]
+pub mod weak;
+#[cfg(feature = "std")]
+pub mod xref;
 
 pub use hdr::ElfHdr;
 pub use phdr::ElfPhdr;
@@ -28,6 +127,10 @@ pub const EI_OSABI: usize = 7;
 pub const EI_ABIVERSION: usize = 8;
 pub const EI_PAD: usize = 9;
 
+pub const ELFOSABI_NONE: u8 = 0;
+pub const ELFOSABI_GNU: u8 = 3;
+pub const ELFOSABI_FREEBSD: u8 = 9;
+
 pub const ELFMAG0: usize = 0x7f;
 pub const ELFMAG1: usize = 'E' as usize;
 pub const ELFMAG2: usize = 'L' as usize;
@@ -36,6 +139,22 @@ pub const ELFMAG: &str = "\x7fELF";
 
 pub const ELFVER: u8 = 1;
 
+pub const EM_NONE: u16 = 0;
+pub const EM_SPARC: u16 = 2;
+pub const EM_386: u16 = 3;
+pub const EM_MIPS: u16 = 8;
+pub const EM_PPC: u16 = 20;
+pub const EM_PPC64: u16 = 21;
+pub const EM_S390: u16 = 22;
+pub const EM_ARM: u16 = 40;
+pub const EM_SPARCV9: u16 = 43;
+pub const EM_IA_64: u16 = 50;
+pub const EM_X86_64: u16 = 62;
+pub const EM_AARCH64: u16 = 183;
+pub const EM_RISCV: u16 = 243;
+pub const EM_BPF: u16 = 247;
+pub const EM_LOONGARCH: u16 = 258;
+
 type Elf32Addr = u32;
 type Elf32Half = u16;
 type Elf32Off = u32;
@@ -49,4 +168,4 @@ type Elf64Off = u64;
 type Elf64Sword = i32;
 type Elf64Word = u32;
 type Elf64Xword = u64;
-type Elf64Sxword = u64;
+type Elf64Sxword = i64;
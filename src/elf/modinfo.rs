@@ -0,0 +1,145 @@
+use std::io;
+
+use super::{
+    bytes::{string_at, ByteReader},
+    core::FileData,
+    hdr::{ElfClass, Endian},
+};
+
+/// `MODULE_NAME_LEN` is `64 - sizeof(unsigned long)`, which makes every
+/// `struct modversion_info` 64 bytes wide
+pub const MODVERSION_INFO_SIZE: usize = 64;
+
+#[derive(Debug, Clone)]
+pub struct ModVersion {
+    pub crc: u64,
+    pub name: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct ModParam {
+    pub name: String,
+    pub description: Option<String>,
+    pub param_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct KernelModule {
+    /// `key=value` pairs of `.modinfo` in file order, `parm`/`parmtype` excluded
+    pub info: Vec<(String, String)>,
+    pub params: Vec<ModParam>,
+    pub versions: Vec<ModVersion>,
+    /// `name` field of the `struct module` in `.gnu.linkonce.this_module`
+    pub name: Option<String>,
+    /// Relocations against `struct module`, i.e. the init and exit hooks
+    pub hooks: Vec<(u64, String)>,
+}
+
+/// Splits the NUL separated `key=value` strings of `.modinfo`
+pub fn parse_modinfo(data: &[u8]) -> Vec<(String, String)> {
+    data.split(|&c| c == 0)
+        .filter(|s| !s.is_empty())
+        .filter_map(|s| {
+            let s = String::from_utf8_lossy(s);
+            let (key, value) = s.split_once('=')?;
+            Some((key.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+pub fn parse_versions(data: &[u8], class: ElfClass, endian: Endian) -> Vec<ModVersion> {
+    data.chunks_exact(MODVERSION_INFO_SIZE)
+        .filter_map(|entry| {
+            let mut reader = ByteReader::new(entry, endian);
+            let crc = match class {
+                ElfClass::ElfClass64 => reader.u64()?,
+                _ => reader.u32()? as u64,
+            };
+            Some(ModVersion {
+                crc,
+                name: string_at(&entry[reader.pos()..], 0),
+            })
+        })
+        .collect()
+}
+
+impl KernelModule {
+    /// Returns `None` when the file has no `.modinfo` and is therefore not a kernel module
+    pub fn read(elf: &FileData) -> io::Result<Option<Self>> {
+        let class = elf.header().class().unwrap_or(ElfClass::ElfClass64);
+        let endian = elf.header().endian().unwrap_or(Endian::Little);
+
+        let modinfo = match elf.section_by_name(".modinfo") {
+            Some(shdr) => parse_modinfo(&elf.section_data(&shdr)?),
+            None => return Ok(None),
+        };
+
+        let mut module = KernelModule::default();
+        for (key, value) in modinfo {
+            let (name, rest) = match value.split_once(':') {
+                Some((name, rest)) if key == "parm" || key == "parmtype" => {
+                    (name.to_string(), rest.to_string())
+                }
+                _ => {
+                    module.info.push((key, value));
+                    continue;
+                }
+            };
+
+            let param = match module.params.iter_mut().find(|p| p.name == name) {
+                Some(param) => param,
+                None => {
+                    module.params.push(ModParam {
+                        name,
+                        description: None,
+                        param_type: None,
+                    });
+                    module.params.last_mut().unwrap()
+                }
+            };
+
+            if key == "parm" {
+                param.description = Some(rest);
+            } else {
+                param.param_type = Some(rest);
+            }
+        }
+
+        if let Some(shdr) = elf.section_by_name("__versions") {
+            module.versions = parse_versions(&elf.section_data(&shdr)?, class, endian);
+        }
+
+        if let Some(index) = elf.section_index(".gnu.linkonce.this_module") {
+            let shdr = elf.section_headers()[index];
+            let data = elf.section_data(&shdr)?;
+
+            // struct module { enum module_state state; struct list_head list; char name[]; ... }
+            let name_off = match class {
+                ElfClass::ElfClass64 => 24,
+                _ => 12,
+            };
+            module.name = data.get(name_off..).map(|name| string_at(name, 0));
+
+            for (rel_shdr, rels) in elf.relocations()? {
+                if rel_shdr.info() as usize != index {
+                    continue;
+                }
+
+                let symtab = match elf.section_headers().get(rel_shdr.link() as usize) {
+                    Some(symtab) => *symtab,
+                    None => continue,
+                };
+                let (strtab, syms) = elf.symbol_table(&symtab)?;
+                for rel in rels {
+                    if let Some(sym) = syms.get(rel.sym() as usize) {
+                        module
+                            .hooks
+                            .push((rel.offset(), string_at(strtab, sym.name() as usize)));
+                    }
+                }
+            }
+        }
+
+        Ok(Some(module))
+    }
+}
@@ -0,0 +1,311 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use super::{bytes::ByteReader, hdr::Endian};
+
+pub const NT_GNU_ABI_TAG: u32 = 1;
+pub const NT_GNU_BUILD_ID: u32 = 3;
+pub const NT_GNU_GOLD_VERSION: u32 = 4;
+pub const NT_GNU_PROPERTY_TYPE_0: u32 = 5;
+/// Note type of the `FDO` owned package metadata note, see
+/// <https://systemd.io/ELF_PACKAGE_METADATA/>
+pub const NT_FDO_PACKAGING_METADATA: u32 = 0xcafe1a7e;
+/// Note type of the `Go` owned build ID note emitted by `cmd/link`
+pub const NT_GO_BUILD_ID: u32 = 4;
+/// Note type of the `Android` owned note selecting the memory tagging mode of an AArch64
+/// executable, read by the bionic loader
+pub const NT_ANDROID_TYPE_MEMTAG: u32 = 4;
+/// Core file note of the `CORE` owner holding memory tags
+pub const NT_MEMTAG: u32 = 0x400;
+/// Core file note of the `LINUX` owner with the `PR_SET_TAGGED_ADDR_CTRL` state of a thread
+pub const NT_ARM_TAGGED_ADDR_CTRL: u32 = 0x409;
+
+/// The settings of an `NT_ANDROID_TYPE_MEMTAG` note
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemtagMode {
+    /// `NT_MEMTAG_LEVEL_*`: tag checks off, reported asynchronously or faulting at once
+    pub level: u32,
+    pub heap: bool,
+    pub stack: bool,
+}
+
+impl MemtagMode {
+    pub fn level_name(&self) -> &'static str {
+        match self.level {
+            0 => "NONE",
+            1 => "ASYNC",
+            2 => "SYNC",
+            _ => "Unknown",
+        }
+    }
+}
+
+/// Where a run of notes was read from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NoteContainer {
+    /// An `SHT_NOTE` section, by name
+    Section(String),
+    /// A `PT_NOTE` segment of a file without section headers
+    Segment { offset: u64, size: u64 },
+}
+
+#[derive(Debug, Clone)]
+pub struct Note {
+    /// Owner of the note, e.g. `GNU` or `Go`, without the terminating NUL
+    pub name: String,
+    pub ntype: u32,
+    pub desc: Vec<u8>,
+}
+
+impl Note {
+    /// Parses the `Elf_Nhdr` records of a note section or segment with the usual 4 byte
+    /// padding, see [`Note::parse_aligned`]
+    pub fn parse(data: &[u8], endian: Endian) -> Vec<Self> {
+        Self::parse_aligned(data, endian, 4)
+    }
+
+    /// Parses the `Elf_Nhdr` records of a note section or segment whose `sh_addralign` or
+    /// `p_align` is `align`. Core files and some systems pad the name and descriptor to 8
+    /// bytes, anything but 8 is read with the 4 byte padding of object files.
+    pub fn parse_aligned(data: &[u8], endian: Endian, align: u64) -> Vec<Self> {
+        let align = match align {
+            8 => 8,
+            _ => 4,
+        };
+        let mut reader = ByteReader::new(data, endian);
+        let mut notes = Vec::new();
+
+        while reader.remaining() >= 12 {
+            // Segments may be padded past their last record
+            if reader.data()[reader.pos()..].iter().all(|&c| c == 0) {
+                break;
+            }
+
+            let note = (|| {
+                let start = reader.pos();
+                let namesz = reader.u32()? as usize;
+                let descsz = reader.u32()? as usize;
+                let ntype = reader.u32()?;
+                let name = reader.bytes(namesz)?;
+                reader.seek(start + align_to(12 + namesz, align))?;
+                let desc = reader.bytes(descsz)?.to_vec();
+                // The final record may not be padded
+                let end = align_to(reader.pos() - start, align);
+                reader
+                    .seek(start + end)
+                    .or_else(|| reader.seek(reader.data().len()))?;
+
+                let name = name.split(|&c| c == 0).next().unwrap_or_default();
+                Some(Note {
+                    name: String::from_utf8_lossy(name).into_owned(),
+                    ntype,
+                    desc,
+                })
+            })();
+
+            match note {
+                Some(note) => notes.push(note),
+                None => break,
+            }
+        }
+
+        notes
+    }
+
+    pub fn is_gnu(&self, ntype: u32) -> bool {
+        self.name == "GNU" && self.ntype == ntype
+    }
+
+    /// The note type as spelled by readelf's `--notes`
+    pub fn type_name(&self) -> String {
+        match (self.name.as_str(), self.ntype) {
+            ("GNU", NT_GNU_ABI_TAG) => "NT_GNU_ABI_TAG (ABI version tag)".to_string(),
+            ("GNU", 2) => "NT_GNU_HWCAP (DSO-supplied software HWCAP info)".to_string(),
+            ("GNU", NT_GNU_BUILD_ID) => "NT_GNU_BUILD_ID (unique build ID bitstring)".to_string(),
+            ("GNU", NT_GNU_GOLD_VERSION) => "NT_GNU_GOLD_VERSION (gold version)".to_string(),
+            ("GNU", NT_GNU_PROPERTY_TYPE_0) => "NT_GNU_PROPERTY_TYPE_0".to_string(),
+            ("Go", NT_GO_BUILD_ID) => "GO BUILDID".to_string(),
+            ("FDO", NT_FDO_PACKAGING_METADATA) => "FDO_PACKAGING_METADATA".to_string(),
+            ("Android", NT_ANDROID_TYPE_MEMTAG) => {
+                "NT_ANDROID_TYPE_MEMTAG (Android memory tagging settings)".to_string()
+            }
+            ("CORE", NT_MEMTAG) => "NT_MEMTAG (memory tags)".to_string(),
+            ("LINUX", NT_ARM_TAGGED_ADDR_CTRL) => {
+                "NT_ARM_TAGGED_ADDR_CTRL (AArch tagged address control)".to_string()
+            }
+            (_, ntype) => format!("Unknown note type: (0x{:08x})", ntype),
+        }
+    }
+
+    /// Hex rendering of an `NT_GNU_BUILD_ID` descriptor
+    pub fn build_id(&self) -> Option<String> {
+        if !self.is_gnu(NT_GNU_BUILD_ID) {
+            return None;
+        }
+
+        Some(self.desc.iter().map(|b| format!("{:02x}", b)).collect())
+    }
+
+    /// The linker version string of an `NT_GNU_GOLD_VERSION` note, such as `gold 1.16`
+    pub fn gold_version(&self) -> Option<String> {
+        if !self.is_gnu(NT_GNU_GOLD_VERSION) {
+            return None;
+        }
+
+        Some(desc_string(&self.desc))
+    }
+
+    /// The JSON document of an `FDO_PACKAGING_METADATA` note, the distribution package the
+    /// file was built for
+    pub fn package_metadata(&self) -> Option<String> {
+        if self.name != "FDO" || self.ntype != NT_FDO_PACKAGING_METADATA {
+            return None;
+        }
+
+        Some(desc_string(&self.desc))
+    }
+
+    /// Decodes an `NT_GNU_ABI_TAG` descriptor into the OS and minimum kernel version
+    pub fn abi_tag(&self, endian: Endian) -> Option<(&'static str, String)> {
+        if !self.is_gnu(NT_GNU_ABI_TAG) {
+            return None;
+        }
+
+        let mut reader = ByteReader::new(&self.desc, endian);
+        let os = match reader.u32()? {
+            0 => "Linux",
+            1 => "Hurd",
+            2 => "Solaris",
+            3 => "FreeBSD",
+            4 => "NetBSD",
+            5 => "Syllable",
+            _ => "Unknown",
+        };
+        let version = format!("{}.{}.{}", reader.u32()?, reader.u32()?, reader.u32()?);
+
+        Some((os, version))
+    }
+
+    /// Decodes an `NT_ANDROID_TYPE_MEMTAG` descriptor
+    pub fn memtag(&self, endian: Endian) -> Option<MemtagMode> {
+        if self.name != "Android" || self.ntype != NT_ANDROID_TYPE_MEMTAG {
+            return None;
+        }
+
+        let flags = ByteReader::new(&self.desc, endian).u32()?;
+        Some(MemtagMode {
+            level: flags & 3,
+            heap: flags & 4 != 0,
+            stack: flags & 8 != 0,
+        })
+    }
+
+    /// Describes the `prctl(PR_SET_TAGGED_ADDR_CTRL)` value of an `NT_ARM_TAGGED_ADDR_CTRL`
+    /// core note: whether the thread passes tagged pointers to the kernel, how MTE tag
+    /// check faults are reported and which tags `irg` may pick
+    pub fn tagged_addr_ctrl(&self, endian: Endian) -> Option<String> {
+        if self.name != "LINUX" || self.ntype != NT_ARM_TAGGED_ADDR_CTRL {
+            return None;
+        }
+
+        let ctrl = ByteReader::new(&self.desc, endian).word(self.desc.len().min(8))?;
+        let enabled = if ctrl & 1 != 0 { "enabled" } else { "disabled" };
+        let faults = match (ctrl >> 1) & 3 {
+            0 => "none",
+            1 => "sync",
+            2 => "async",
+            _ => "sync, async",
+        };
+        Some(format!(
+            "tagged addresses {}, tag check faults: {}, allowed tags: 0x{:04x}",
+            enabled,
+            faults,
+            (ctrl >> 3) & 0xffff
+        ))
+    }
+}
+
+/// A NUL-terminated string descriptor, padding after the terminator dropped
+fn desc_string(desc: &[u8]) -> String {
+    let text = desc.split(|&c| c == 0).next().unwrap_or_default();
+    String::from_utf8_lossy(text).into_owned()
+}
+
+fn align_to(n: usize, align: usize) -> usize {
+    (n + align - 1) & !(align - 1)
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::{vec, vec::Vec};
+
+    use super::{Note, NT_ANDROID_TYPE_MEMTAG, NT_FDO_PACKAGING_METADATA, NT_GNU_BUILD_ID};
+    use crate::elf::hdr::Endian;
+
+    #[test]
+    fn parses_build_id() {
+        let mut data = vec![4, 0, 0, 0, 4, 0, 0, 0, 3, 0, 0, 0];
+        data.extend(b"GNU\0");
+        data.extend([0xde, 0xad, 0xbe, 0xef]);
+
+        let notes = Note::parse(&data, Endian::Little);
+        assert_eq!(notes.len(), 1);
+        assert!(notes[0].is_gnu(NT_GNU_BUILD_ID));
+        assert_eq!(notes[0].build_id().as_deref(), Some("deadbeef"));
+    }
+
+    #[test]
+    fn parses_package_metadata() {
+        let json = b"{\"type\":\"rpm\",\"name\":\"coreutils\"}\0\0\0";
+        let mut data = vec![4, 0, 0, 0, json.len() as u8, 0, 0, 0];
+        data.extend(NT_FDO_PACKAGING_METADATA.to_le_bytes());
+        data.extend(b"FDO\0");
+        data.extend(json);
+
+        let notes = Note::parse(&data, Endian::Little);
+        assert_eq!(notes[0].type_name(), "FDO_PACKAGING_METADATA");
+        assert_eq!(
+            notes[0].package_metadata().as_deref(),
+            Some("{\"type\":\"rpm\",\"name\":\"coreutils\"}")
+        );
+    }
+
+    #[test]
+    fn decodes_memtag_notes() {
+        let mut data = vec![8, 0, 0, 0, 4, 0, 0, 0];
+        data.extend(NT_ANDROID_TYPE_MEMTAG.to_le_bytes());
+        data.extend(b"Android\0");
+        data.extend(6u32.to_le_bytes());
+
+        let notes = Note::parse(&data, Endian::Little);
+        let mode = notes[0].memtag(Endian::Little).unwrap();
+        assert_eq!(
+            (mode.level_name(), mode.heap, mode.stack),
+            ("SYNC", true, false)
+        );
+        assert_eq!(notes[0].tagged_addr_ctrl(Endian::Little), None);
+    }
+
+    #[test]
+    fn parses_8_byte_aligned_notes() {
+        // Two records padded to 8 bytes, then the padding the segment ends with
+        let mut data = Vec::new();
+        for (ntype, desc) in [(1u32, &[1u8, 2, 3][..]), (3, &[0xab; 4][..])] {
+            data.extend(4u32.to_le_bytes());
+            data.extend((desc.len() as u32).to_le_bytes());
+            data.extend(ntype.to_le_bytes());
+            data.extend(b"GNU\0");
+            data.extend(desc);
+            data.resize((data.len() + 7) & !7, 0);
+        }
+        data.extend([0; 16]);
+
+        let notes = Note::parse_aligned(&data, Endian::Little, 8);
+        assert_eq!(notes.len(), 2);
+        assert_eq!(notes[0].desc, [1, 2, 3]);
+        assert_eq!(notes[1].build_id().as_deref(), Some("abababab"));
+    }
+}
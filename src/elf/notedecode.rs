@@ -0,0 +1,211 @@
+use std::sync::{Arc, PoisonError, RwLock};
+
+use super::{
+    hdr::{ElfClass, Endian},
+    note::{Note, NT_GNU_PROPERTY_TYPE_0},
+    property::GnuProperty,
+};
+
+/// A field decoded from a note descriptor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NoteField {
+    /// Key in the JSON and YAML output, `firmware_version`
+    pub key: String,
+    /// Label in the text output, `Firmware Version`
+    pub label: String,
+    pub value: String,
+}
+
+impl NoteField {
+    pub fn new(key: &str, label: &str, value: impl Into<String>) -> Self {
+        Self {
+            key: key.to_string(),
+            label: label.to_string(),
+            value: value.into(),
+        }
+    }
+}
+
+/// Decodes the notes of one owner and type, such as the vendor notes of a firmware build,
+/// for every output format once registered with [`register_note_decoder`].
+///
+/// Closures taking the note and the file's endianness implement it too.
+pub trait NoteDecoder: Send + Sync {
+    /// The description shown after the descriptor size, `None` keeps the default
+    /// `Unknown note type` one
+    fn type_name(&self, _note: &Note) -> Option<String> {
+        None
+    }
+
+    /// The fields of the descriptor, empty when it could not be decoded
+    fn decode(&self, note: &Note, endian: Endian) -> Vec<NoteField>;
+}
+
+impl<F> NoteDecoder for F
+where
+    F: Fn(&Note, Endian) -> Vec<NoteField> + Send + Sync,
+{
+    fn decode(&self, note: &Note, endian: Endian) -> Vec<NoteField> {
+        self(note, endian)
+    }
+}
+
+type Registry = Vec<(String, u32, Arc<dyn NoteDecoder>)>;
+
+static DECODERS: RwLock<Registry> = RwLock::new(Vec::new());
+
+/// Registers `decoder` for the notes of owner `name` and type `ntype`. It takes over from
+/// the decoding built into this crate for that key, and from an earlier registration.
+pub fn register_note_decoder(name: &str, ntype: u32, decoder: impl NoteDecoder + 'static) {
+    let mut decoders = DECODERS.write().unwrap_or_else(PoisonError::into_inner);
+    decoders.retain(|(owner, t, _)| (owner.as_str(), *t) != (name, ntype));
+    decoders.push((name.to_string(), ntype, Arc::new(decoder)));
+}
+
+/// Removes the decoder registered for `name` and `ntype`, returning whether there was one
+pub fn unregister_note_decoder(name: &str, ntype: u32) -> bool {
+    let mut decoders = DECODERS.write().unwrap_or_else(PoisonError::into_inner);
+    let count = decoders.len();
+    decoders.retain(|(owner, t, _)| (owner.as_str(), *t) != (name, ntype));
+    decoders.len() != count
+}
+
+/// The decoder registered for the owner and type of `note`
+pub fn note_decoder(note: &Note) -> Option<Arc<dyn NoteDecoder>> {
+    DECODERS
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .iter()
+        .find(|(owner, ntype, _)| *owner == note.name && *ntype == note.ntype)
+        .map(|(_, _, decoder)| decoder.clone())
+}
+
+/// The description of `note`, from its registered decoder when it has one
+pub fn note_type_name(note: &Note) -> String {
+    note_decoder(note)
+        .and_then(|decoder| decoder.type_name(note))
+        .unwrap_or_else(|| note.type_name())
+}
+
+/// The fields of `note` every renderer prints, from its registered decoder when it has
+/// one and otherwise from the notes this crate decodes itself. `class` and `machine` are
+/// those of the file, GNU properties are laid out and decoded after them.
+pub fn decode_note(note: &Note, class: ElfClass, endian: Endian, machine: u16) -> Vec<NoteField> {
+    if let Some(decoder) = note_decoder(note) {
+        return decoder.decode(note, endian);
+    }
+
+    if note.is_gnu(NT_GNU_PROPERTY_TYPE_0) {
+        // Comma separated on one line, the way binutils -W prints them
+        let properties = GnuProperty::parse(note, class, endian)
+            .iter()
+            .map(|property| property.describe(machine, endian))
+            .collect::<Vec<_>>();
+        vec![NoteField::new(
+            "properties",
+            "Properties",
+            properties.join(", "),
+        )]
+    } else if let Some(build_id) = note.build_id() {
+        vec![NoteField::new("build_id", "Build ID", build_id)]
+    } else if let Some((os, version)) = note.abi_tag(endian) {
+        // One line in binutils, `OS: Linux, ABI: 3.2.0`
+        let abi = format!("{}, ABI: {}", os, version);
+        vec![NoteField::new("abi_tag", "OS", abi)]
+    } else if let Some(version) = note.gold_version() {
+        vec![NoteField::new("version", "Version", version)]
+    } else if let Some(metadata) = note.package_metadata() {
+        vec![NoteField::new(
+            "package_metadata",
+            "Packaging Metadata",
+            metadata,
+        )]
+    } else if let Some(mode) = note.memtag(endian) {
+        let enabled = |on: bool| if on { "Enabled" } else { "Disabled" };
+        vec![
+            NoteField::new("tagging_mode", "Tagging Mode", mode.level_name()),
+            NoteField::new("heap", "Heap", enabled(mode.heap)),
+            NoteField::new("stack", "Stack", enabled(mode.stack)),
+        ]
+    } else if let Some(ctrl) = note.tagged_addr_ctrl(endian) {
+        vec![NoteField::new(
+            "tagged_addr_ctrl",
+            "Tagged Address Control",
+            ctrl,
+        )]
+    } else {
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        decode_note, note_decoder, note_type_name, register_note_decoder, unregister_note_decoder,
+        NoteDecoder, NoteField,
+    };
+    use crate::elf::{
+        hdr::{ElfClass, Endian},
+        note::{Note, NT_GNU_PROPERTY_TYPE_0},
+        EM_X86_64,
+    };
+
+    struct Firmware;
+
+    impl NoteDecoder for Firmware {
+        fn type_name(&self, _note: &Note) -> Option<String> {
+            Some("ACME_FIRMWARE (firmware version)".to_string())
+        }
+
+        fn decode(&self, note: &Note, _endian: Endian) -> Vec<NoteField> {
+            let version = String::from_utf8_lossy(&note.desc).into_owned();
+            vec![NoteField::new(
+                "firmware_version",
+                "Firmware Version",
+                version,
+            )]
+        }
+    }
+
+    #[test]
+    fn registers_note_decoders() {
+        let note = Note {
+            name: "ACME".to_string(),
+            ntype: 0x100,
+            desc: b"1.2.3".to_vec(),
+        };
+        assert!(note_decoder(&note).is_none());
+
+        register_note_decoder("ACME", 0x100, Firmware);
+        assert_eq!(note_type_name(&note), "ACME_FIRMWARE (firmware version)");
+        let fields = note_decoder(&note).unwrap().decode(&note, Endian::Little);
+        assert_eq!(fields[0].value, "1.2.3");
+
+        register_note_decoder("ACME", 0x100, |note: &Note, _| {
+            vec![NoteField::new("size", "Size", note.desc.len().to_string())]
+        });
+        assert_eq!(note_type_name(&note), "Unknown note type: (0x00000100)");
+        assert!(unregister_note_decoder("ACME", 0x100));
+        assert!(note_decoder(&note).is_none());
+    }
+
+    #[test]
+    fn decodes_gnu_properties_per_machine() {
+        // AArch64 feature 1 and x86 feature 1, both with their two low bits set
+        let mut desc = Vec::new();
+        for ptype in [0xc000_0000u32, 0xc000_0002] {
+            desc.extend(ptype.to_le_bytes());
+            desc.extend([4, 0, 0, 0, 3, 0, 0, 0, 0, 0, 0, 0]);
+        }
+        let note = Note {
+            name: "GNU".to_string(),
+            ntype: NT_GNU_PROPERTY_TYPE_0,
+            desc,
+        };
+        let fields = decode_note(&note, ElfClass::ElfClass64, Endian::Little, EM_X86_64);
+        assert_eq!(
+            fields[0].value,
+            "<processor-specific type 0xc0000000 data: 03 00 00 00 >, x86 feature: IBT, SHSTK"
+        );
+    }
+}
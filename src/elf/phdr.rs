@@ -1,17 +1,26 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+};
+use core::{
+    borrow::Borrow,
+    fmt::{self, Display},
+};
+#[cfg(feature = "std")]
 use std::{
     alloc::{alloc, dealloc, Layout},
-    borrow::Borrow,
     io::{self, Read, Seek, SeekFrom},
     mem::size_of,
     slice,
 };
 
-use num::{FromPrimitive, ToPrimitive};
 use num_derive::FromPrimitive;
+use num_traits::{FromPrimitive, ToPrimitive};
 
+#[cfg(feature = "std")]
+use super::{hdr::ElfClass, ElfHdr};
 use super::{
-    hdr::ElfClass, Elf32Addr, Elf32Off, Elf32Word, Elf64Addr, Elf64Off, Elf64Word, Elf64Xword,
-    ElfHdr,
+    Elf32Addr, Elf32Off, Elf32Word, Elf64Addr, Elf64Off, Elf64Word, Elf64Xword, EM_AARCH64,
 };
 
 #[derive(FromPrimitive, PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Copy)]
@@ -36,6 +45,29 @@ pub enum ProgramType {
     GnuMbindHi = 0x60000000 + 0x474e555 + 4096 - 1,
 }
 
+/// `PT_AARCH64_MEMTAG_MTE`, a core file segment holding the MTE allocation tags of the
+/// mapping at the same address, two 4-bit tags per byte
+pub const PT_AARCH64_MEMTAG_MTE: u32 = 0x70000002;
+
+/// Granule of memory each MTE allocation tag covers
+pub const MTE_GRANULE_SIZE: u64 = 16;
+
+/// The name readelf gives a segment type, processor specific ones depending on `machine`
+pub fn program_type_name(machine: u16, p_type: u32) -> String {
+    if let Some(ptype) = ProgramType::from_u32(p_type) {
+        return ptype.to_string();
+    }
+    let name = match (machine, p_type) {
+        (EM_AARCH64, 0x70000000) => "AARCH64_ARCHEXT",
+        (EM_AARCH64, 0x70000001) => "AARCH64_UNWIND",
+        (EM_AARCH64, PT_AARCH64_MEMTAG_MTE) => "AARCH64_MEMTAG_MTE",
+        (_, 0x70000000..=0x7fffffff) => return format!("LOPROC+0x{:x}", p_type - 0x70000000),
+        (_, 0x60000000..=0x6fffffff) => return format!("LOOS+0x{:x}", p_type - 0x60000000),
+        _ => return format!("<unknown>: {:x}", p_type),
+    };
+    name.to_string()
+}
+
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Debug, Copy)]
 pub struct ProgramFlags {
     read: bool,
@@ -100,6 +132,7 @@ pub struct Elf64Phdr {
 }
 
 impl ElfPhdr {
+    #[cfg(feature = "std")]
     pub fn read<R: Read + Seek>(hdr: &ElfHdr, file: &mut R) -> io::Result<Vec<Self>> {
         file.seek(SeekFrom::Start(hdr.e_phoff)).unwrap();
 
@@ -135,6 +168,11 @@ impl ElfPhdr {
         ProgramType::from_u32(self.p_type)
     }
 
+    /// The raw `p_type`, for the processor and OS specific types [`ProgramType`] leaves out
+    pub fn p_type(&self) -> Elf64Word {
+        self.p_type
+    }
+
     pub fn offset(&self) -> Elf64Off {
         self.p_offset
     }
@@ -200,21 +238,44 @@ impl TryFrom<&Elf32Phdr> for ElfPhdr {
     }
 }
 
-impl ProgramType {
-    pub fn display(&self) -> String {
-        format!("{:?}", self)
-            .chars()
-            .fold(String::new(), |mut s, c| {
-                if !s.is_empty() && c.is_uppercase() {
-                    s.push('_');
-                }
-                s.push_str(&c.to_uppercase().to_string());
-                s
-            })
+impl Display for ProgramType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Self::Null => "NULL",
+            Self::Load => "LOAD",
+            Self::Dynamic => "DYNAMIC",
+            Self::Interp => "INTERP",
+            Self::Note => "NOTE",
+            Self::ShLib => "SHLIB",
+            Self::Phdr => "PHDR",
+            Self::Tls => "TLS",
+            Self::LoOS => "LOOS+0x0",
+            Self::HiOS => "LOOS+0xfffffff",
+            Self::LoProc => "LOPROC+0x0",
+            Self::HiProc => "LOPROC+0xfffffff",
+            Self::GnuEhFrame => "GNU_EH_FRAME",
+            Self::GnuStack => "GNU_STACK",
+            Self::GnuRelro => "GNU_RELRO",
+            Self::GnuProperty => "GNU_PROPERTY",
+            Self::GnuMbindLo => "GNU_MBIND+0x0",
+            Self::GnuMbindHi => "GNU_MBIND+0xfff",
+        })
     }
 }
 
 impl ProgramFlags {
+    pub fn read(&self) -> bool {
+        self.read
+    }
+
+    pub fn write(&self) -> bool {
+        self.write
+    }
+
+    pub fn execute(&self) -> bool {
+        self.execute
+    }
+
     pub fn display(&self) -> String {
         let mut s = String::with_capacity(3);
         if self.read {
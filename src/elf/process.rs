@@ -0,0 +1,295 @@
+use std::{
+    fs::{self, File},
+    io,
+    os::unix::fs::FileExt,
+    path::{Path, PathBuf},
+};
+
+use super::{
+    core::{FileData, ParseLevel, ParseOptions, Source},
+    dynamic::DynamicTag,
+    hdr::{ElfClass, Endian},
+    image::ElfImage,
+    phdr::ProgramType,
+    rel::relr_addresses,
+};
+
+/// One line of `/proc/<pid>/maps`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MapEntry {
+    pub start: u64,
+    pub end: u64,
+    pub perms: String,
+    pub offset: u64,
+    pub path: Option<PathBuf>,
+}
+
+impl MapEntry {
+    fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.split_whitespace();
+        let (start, end) = fields.next()?.split_once('-')?;
+        let perms = fields.next()?.to_string();
+        let offset = u64::from_str_radix(fields.next()?, 16).ok()?;
+        let _dev = fields.next()?;
+        let _inode = fields.next()?;
+        // Paths may contain spaces, everything after the inode is the path
+        let path = fields.collect::<Vec<_>>().join(" ");
+
+        Some(Self {
+            start: u64::from_str_radix(start, 16).ok()?,
+            end: u64::from_str_radix(end, 16).ok()?,
+            perms,
+            offset,
+            path: (!path.is_empty()).then(|| PathBuf::from(path)),
+        })
+    }
+}
+
+/// The memory mappings of process `pid`
+pub fn read_maps(pid: u32) -> io::Result<Vec<MapEntry>> {
+    Ok(fs::read_to_string(format!("/proc/{}/maps", pid))?
+        .lines()
+        .filter_map(MapEntry::parse)
+        .collect())
+}
+
+struct Segment {
+    offset: u64,
+    filesz: u64,
+    addr: u64,
+}
+
+/// A process image presented as the file it was loaded from: file offsets inside a
+/// `PT_LOAD` segment are read from `/proc/<pid>/mem` where the segment is mapped, anything
+/// else (usually the section headers) reads as the end of the file
+pub struct ProcessMemory {
+    mem: File,
+    segments: Vec<Segment>,
+}
+
+impl Source for ProcessMemory {
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+        match self
+            .segments
+            .iter()
+            .find(|seg| offset >= seg.offset && offset < seg.offset + seg.filesz)
+        {
+            Some(seg) => {
+                let len = buf.len().min((seg.offset + seg.filesz - offset) as usize);
+                FileExt::read_at(&self.mem, &mut buf[..len], seg.addr + (offset - seg.offset))
+            }
+            None => Ok(0),
+        }
+    }
+
+    fn size(&self) -> io::Result<u64> {
+        Ok(self
+            .segments
+            .iter()
+            .map(|seg| seg.offset + seg.filesz)
+            .max()
+            .unwrap_or(0))
+    }
+}
+
+impl FileData {
+    /// Parses the main executable of process `pid` out of its memory, see
+    /// [`FileData::from_process_object`]
+    pub fn from_process(pid: u32) -> io::Result<Self> {
+        let exe = fs::read_link(format!("/proc/{}/exe", pid))?;
+        Self::from_process_object(pid, &exe)
+    }
+
+    /// Parses the object mapped from `path` in process `pid`, such as one of its shared
+    /// libraries, out of the process memory.
+    ///
+    /// Only what the loader maps is available: the file and program headers, the dynamic
+    /// section and the notes, but usually no section headers. Addresses the dynamic linker
+    /// relocated in the dynamic section have [`FileData::load_bias`] taken back off.
+    pub fn from_process_object(pid: u32, path: &Path) -> io::Result<Self> {
+        let base = read_maps(pid)?
+            .into_iter()
+            .find(|map| map.offset == 0 && map.path.as_deref() == Some(path))
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("{} is not mapped in process {}", path.display(), pid),
+                )
+            })?;
+        let mem = File::open(format!("/proc/{}/mem", pid))?;
+
+        // The headers sit at the start of the first mapping, read enough of it to find
+        // where every other segment went
+        let mut head = vec![0; (base.end - base.start).min(0x10000) as usize];
+        let read = FileExt::read_at(&mem, &mut head, base.start)?;
+        let image = ElfImage::parse(&head[..read]).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Not an ELF file - it has the wrong magic bytes at the start",
+            )
+        })?;
+        let loads = image
+            .program_headers()
+            .filter(|phdr| phdr.program_type() == Some(ProgramType::Load))
+            .collect::<Vec<_>>();
+        let bias = match loads.iter().min_by_key(|phdr| phdr.offset()) {
+            Some(first) => base
+                .start
+                .wrapping_sub(first.vaddr().wrapping_sub(first.offset())),
+            None => 0,
+        };
+
+        let memory = ProcessMemory {
+            mem,
+            segments: loads
+                .iter()
+                .map(|phdr| Segment {
+                    offset: phdr.offset(),
+                    filesz: phdr.filesz(),
+                    addr: bias.wrapping_add(phdr.vaddr()),
+                })
+                .collect(),
+        };
+        let mut elf = Self::from_source(
+            Box::new(memory),
+            path.to_path_buf(),
+            ParseOptions::default().with_level(ParseLevel::Headers),
+        )?;
+        elf.set_load_bias(bias);
+
+        Ok(elf)
+    }
+}
+
+/// A run of bytes that differ between a file and its image in a process
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Modification {
+    pub vaddr: u64,
+    pub disk: Vec<u8>,
+    pub memory: Vec<u8>,
+}
+
+impl FileData {
+    /// The address ranges the dynamic linker is expected to write when loading the file:
+    /// the targets of the `SHT_REL`/`SHT_RELA` sections and of the packed `DT_RELR` table,
+    /// the dynamic section itself and the reserved `DT_PLTGOT` entries for lazy binding
+    fn expected_writes(&self) -> io::Result<Vec<(u64, u64)>> {
+        let word = match self.header().class() {
+            Some(ElfClass::ElfClass64) => 8,
+            _ => 4,
+        };
+        let mut sites = self
+            .relocations()?
+            .iter()
+            .flat_map(|(_, rels)| rels.iter().map(|rel| rel.offset()))
+            .collect::<Vec<_>>();
+
+        let dynamic = self.dynamic_section();
+        let value = |tag: DynamicTag| {
+            dynamic
+                .iter()
+                .find(|d| d.tag == tag as u64)
+                .map(|d| unsafe { d.value.val })
+        };
+        if let (Some(addr), Some(size)) = (value(DynamicTag::RelR), value(DynamicTag::RelrSz)) {
+            let class = self.header().class().unwrap_or(ElfClass::ElfClass64);
+            let endian = self.header().endian().unwrap_or(Endian::Little);
+            sites.extend(relr_addresses(
+                &self.read_vaddr(addr, size as usize)?,
+                class,
+                endian,
+            ));
+        }
+
+        let mut ranges = sites
+            .into_iter()
+            .map(|site| (site, site + word))
+            .collect::<Vec<_>>();
+        if let Some(pltgot) = value(DynamicTag::PltGot) {
+            ranges.push((pltgot, pltgot + 3 * word));
+        }
+        ranges.extend(
+            self.program_headers()
+                .iter()
+                .filter(|phdr| phdr.program_type() == Some(ProgramType::Dynamic))
+                .map(|phdr| (phdr.vaddr(), phdr.vaddr() + phdr.memsz())),
+        );
+
+        Ok(ranges)
+    }
+
+    /// Compares `image`, this file as read out of a process with
+    /// [`FileData::from_process`], against the file itself.
+    ///
+    /// Only what should not change after loading is compared, the read-only `PT_LOAD`
+    /// segments and the `PT_GNU_RELRO` range, and relocation sites are skipped, so any
+    /// difference left points at the image being patched at runtime.
+    pub fn diff_image(&self, image: &FileData) -> io::Result<Vec<Modification>> {
+        let expected_writes = self.expected_writes()?;
+        let regions = self
+            .program_headers()
+            .iter()
+            .filter(|phdr| match phdr.program_type() {
+                Some(ProgramType::Load) => !phdr.flags().write(),
+                Some(ProgramType::GnuRelro) => true,
+                _ => false,
+            })
+            .map(|phdr| (phdr.vaddr(), phdr.filesz()))
+            .collect::<Vec<_>>();
+
+        let mut modifications = Vec::<Modification>::new();
+        for (vaddr, size) in regions {
+            let disk = self.read_vaddr(vaddr, size as usize)?;
+            let memory = image.read_vaddr(vaddr, size as usize)?;
+
+            let mut expected = vec![false; disk.len()];
+            let end = vaddr + size;
+            for &(start, stop) in &expected_writes {
+                if start < end && stop > vaddr {
+                    let from = start.max(vaddr) - vaddr;
+                    let to = stop.min(end) - vaddr;
+                    expected[from as usize..to as usize].fill(true);
+                }
+            }
+
+            for i in (0..disk.len()).filter(|&i| disk[i] != memory[i] && !expected[i]) {
+                let addr = vaddr + i as u64;
+                match modifications.last_mut() {
+                    Some(last) if last.vaddr + last.disk.len() as u64 == addr => {
+                        last.disk.push(disk[i]);
+                        last.memory.push(memory[i]);
+                    }
+                    _ => modifications.push(Modification {
+                        vaddr: addr,
+                        disk: vec![disk[i]],
+                        memory: vec![memory[i]],
+                    }),
+                }
+            }
+        }
+
+        Ok(modifications)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::elf::{core::FileData, dynamic::DynamicTag};
+
+    #[test]
+    fn reads_own_image() {
+        let disk = FileData::new(std::env::current_exe().unwrap()).unwrap();
+        let memory = FileData::from_process(std::process::id()).unwrap();
+
+        assert_eq!(memory.header().e_phnum, disk.header().e_phnum);
+        let entries = |elf: &FileData| {
+            elf.dynamic_section()
+                .iter()
+                .filter(|d| d.tag != DynamicTag::Debug as u64)
+                .map(|d| (d.tag, unsafe { d.value.val }))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(entries(&memory), entries(&disk));
+        assert_eq!(disk.diff_image(&memory).unwrap(), Vec::new());
+    }
+}
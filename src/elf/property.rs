@@ -0,0 +1,244 @@
+use super::{
+    bytes::ByteReader,
+    hdr::{ElfClass, Endian},
+    note::{Note, NT_GNU_PROPERTY_TYPE_0},
+    EM_386, EM_AARCH64, EM_X86_64,
+};
+
+pub const GNU_PROPERTY_STACK_SIZE: u32 = 1;
+pub const GNU_PROPERTY_NO_COPY_ON_PROTECTED: u32 = 2;
+pub const GNU_PROPERTY_1_NEEDED: u32 = 0xb0008000;
+pub const GNU_PROPERTY_LOPROC: u32 = 0xc0000000;
+pub const GNU_PROPERTY_HIPROC: u32 = 0xdfffffff;
+pub const GNU_PROPERTY_LOUSER: u32 = 0xe0000000;
+pub const GNU_PROPERTY_HIUSER: u32 = 0xffffffff;
+
+pub const GNU_PROPERTY_AARCH64_FEATURE_1_AND: u32 = 0xc0000000;
+pub const GNU_PROPERTY_AARCH64_FEATURE_1_BTI: u32 = 1;
+pub const GNU_PROPERTY_AARCH64_FEATURE_1_PAC: u32 = 2;
+pub const GNU_PROPERTY_X86_ISA_1_USED: u32 = 0xc0010002;
+pub const GNU_PROPERTY_X86_ISA_1_NEEDED: u32 = 0xc0008002;
+pub const GNU_PROPERTY_X86_FEATURE_1_AND: u32 = 0xc0000002;
+pub const GNU_PROPERTY_X86_FEATURE_2_USED: u32 = 0xc0010001;
+pub const GNU_PROPERTY_X86_FEATURE_2_NEEDED: u32 = 0xc0008001;
+
+/// Bits of `GNU_PROPERTY_X86_FEATURE_1_AND`, the CET features every input object supports
+const X86_FEATURE_1: [&str; 4] = ["IBT", "SHSTK", "LAM_U48", "LAM_U57"];
+/// Bits of `GNU_PROPERTY_X86_FEATURE_2_*`, the register sets the code touches
+const X86_FEATURE_2: [&str; 12] = [
+    "x86", "x87", "MMX", "XMM", "YMM", "ZMM", "FXSR", "XSAVE", "XSAVEOPT", "XSAVEC", "TMM", "MASK",
+];
+
+/// Microarchitecture levels of the x86-64 psABI, indexed by their `GNU_PROPERTY_X86_ISA_1_*` bit
+pub const X86_ISA_LEVELS: [&str; 4] = ["x86-64-baseline", "x86-64-v2", "x86-64-v3", "x86-64-v4"];
+
+#[derive(Debug, Clone)]
+pub struct GnuProperty {
+    pub ptype: u32,
+    pub data: Vec<u8>,
+}
+
+impl GnuProperty {
+    /// Splits the descriptor of an `NT_GNU_PROPERTY_TYPE_0` note, whose entries are padded
+    /// to the pointer size of the file
+    pub fn parse(note: &Note, class: ElfClass, endian: Endian) -> Vec<Self> {
+        if !note.is_gnu(NT_GNU_PROPERTY_TYPE_0) {
+            return Vec::new();
+        }
+
+        let align = match class {
+            ElfClass::ElfClass32 => 4,
+            _ => 8,
+        };
+        let mut reader = ByteReader::new(&note.desc, endian);
+        let mut properties = Vec::new();
+
+        while reader.remaining() >= 8 {
+            let property = (|| {
+                let ptype = reader.u32()?;
+                let size = reader.u32()? as usize;
+                let data = reader.bytes(size)?.to_vec();
+                let padding = (align - size % align) % align;
+                reader
+                    .skip(padding)
+                    .or_else(|| reader.seek(reader.data().len()))?;
+                Some(GnuProperty { ptype, data })
+            })();
+
+            match property {
+                Some(property) => properties.push(property),
+                None => break,
+            }
+        }
+
+        properties
+    }
+
+    /// The 4 byte bitmask carried by most processor specific properties
+    pub fn bitmask(&self, endian: Endian) -> Option<u32> {
+        ByteReader::new(&self.data, endian).u32()
+    }
+
+    /// A one line description of the property the way binutils words it. Processor
+    /// specific types go through the decoder of `machine`, and types nothing decodes are
+    /// dumped in hex.
+    pub fn describe(&self, machine: u16, endian: Endian) -> String {
+        let decoded = match self.ptype {
+            GNU_PROPERTY_STACK_SIZE => {
+                let size = ByteReader::new(&self.data, endian).word(self.data.len());
+                Some(match size {
+                    Some(size) => format!("stack size: {:#x}", size),
+                    None => "stack size: <corrupt>".to_string(),
+                })
+            }
+            GNU_PROPERTY_NO_COPY_ON_PROTECTED => Some("no copy on protected".to_string()),
+            GNU_PROPERTY_1_NEEDED => Some(match self.bitmask(endian) {
+                Some(0) => "1_needed: <None>".to_string(),
+                Some(1) => "1_needed: indirect external access".to_string(),
+                Some(bitmask) => format!("1_needed: <unknown: {:x}>", bitmask),
+                None => "1_needed: <corrupt>".to_string(),
+            }),
+            GNU_PROPERTY_LOPROC..=GNU_PROPERTY_HIPROC => {
+                property_decoder(machine).and_then(|decode| decode(self, endian))
+            }
+            _ => None,
+        };
+
+        decoded.unwrap_or_else(|| {
+            let kind = match self.ptype {
+                GNU_PROPERTY_LOPROC..=GNU_PROPERTY_HIPROC => "processor-specific",
+                GNU_PROPERTY_LOUSER..=GNU_PROPERTY_HIUSER => "application-specific",
+                _ => "unknown",
+            };
+            let data = self
+                .data
+                .iter()
+                .map(|byte| format!("{:02x} ", byte))
+                .collect::<String>();
+            format!("<{} type 0x{:x} data: {}>", kind, self.ptype, data)
+        })
+    }
+}
+
+/// Decodes the processor specific properties of one architecture, `None` for the types it
+/// does not know
+pub type PropertyDecoder = fn(&GnuProperty, Endian) -> Option<String>;
+
+/// The decoder of the processor specific properties of `machine`. Supporting another
+/// architecture takes one more arm here.
+pub fn property_decoder(machine: u16) -> Option<PropertyDecoder> {
+    match machine {
+        EM_AARCH64 => Some(describe_aarch64),
+        EM_386 | EM_X86_64 => Some(describe_x86),
+        _ => None,
+    }
+}
+
+fn describe_aarch64(property: &GnuProperty, endian: Endian) -> Option<String> {
+    let bitmask = property.bitmask(endian);
+    match property.ptype {
+        GNU_PROPERTY_AARCH64_FEATURE_1_AND => Some(match bitmask {
+            Some(bitmask) => format!("AArch64 feature: {}", aarch64_features(bitmask)),
+            None => "AArch64 feature: <corrupt>".to_string(),
+        }),
+        _ => None,
+    }
+}
+
+fn describe_x86(property: &GnuProperty, endian: Endian) -> Option<String> {
+    let bitmask = property.bitmask(endian);
+    let levels = |label: &str| match bitmask {
+        Some(bitmask) => format!("{}: {}", label, x86_isa_levels(bitmask).join(", ")),
+        None => format!("{}: <corrupt>", label),
+    };
+    let features = |label: &str, names: &[&str]| match bitmask {
+        Some(bitmask) => format!("{}: {}", label, bit_names(bitmask, names)),
+        None => format!("{}: <corrupt>", label),
+    };
+
+    Some(match property.ptype {
+        GNU_PROPERTY_X86_ISA_1_NEEDED => levels("x86 ISA needed"),
+        GNU_PROPERTY_X86_ISA_1_USED => levels("x86 ISA used"),
+        GNU_PROPERTY_X86_FEATURE_1_AND => features("x86 feature", &X86_FEATURE_1),
+        GNU_PROPERTY_X86_FEATURE_2_NEEDED => features("x86 feature needed", &X86_FEATURE_2),
+        GNU_PROPERTY_X86_FEATURE_2_USED => features("x86 feature used", &X86_FEATURE_2),
+        _ => return None,
+    })
+}
+
+/// Joins the names of the bits set in `bitmask`, `<None>` when none is
+fn bit_names(bitmask: u32, names: &[&str]) -> String {
+    let mut set = names
+        .iter()
+        .enumerate()
+        .filter(|(bit, _)| bitmask & (1 << bit) != 0)
+        .map(|(_, name)| name.to_string())
+        .collect::<Vec<_>>();
+    let unknown = bitmask & !((1u64 << names.len()) - 1) as u32;
+    if unknown != 0 {
+        set.push(format!("<unknown: {:x}>", unknown));
+    }
+    match set.is_empty() {
+        true => "<None>".to_string(),
+        false => set.join(", "),
+    }
+}
+
+/// The features of a `GNU_PROPERTY_AARCH64_FEATURE_1_AND` bitmask the way binutils lists
+/// them, `BTI, PAC`
+pub fn aarch64_features(bitmask: u32) -> String {
+    bit_names(bitmask, &["BTI", "PAC"])
+}
+
+/// Names the x86-64 microarchitecture levels set in an ISA bitmask
+pub fn x86_isa_levels(bitmask: u32) -> Vec<&'static str> {
+    X86_ISA_LEVELS
+        .iter()
+        .enumerate()
+        .filter(|(bit, _)| bitmask & (1 << bit) != 0)
+        .map(|(_, name)| *name)
+        .collect()
+}
+
+/// The highest level in the bitmask, which is what a CPU has to support to run the binary
+pub fn x86_isa_minimum(bitmask: u32) -> Option<&'static str> {
+    x86_isa_levels(bitmask).last().copied()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{x86_isa_levels, x86_isa_minimum, GnuProperty};
+    use crate::elf::{hdr::Endian, EM_AARCH64, EM_RISCV, EM_X86_64};
+
+    #[test]
+    fn describes_properties_by_machine() {
+        let property = GnuProperty {
+            ptype: 0xc0000000,
+            data: vec![3, 0, 0, 0],
+        };
+        assert_eq!(
+            property.describe(EM_AARCH64, Endian::Little),
+            "AArch64 feature: BTI, PAC"
+        );
+        assert_eq!(
+            property.describe(EM_RISCV, Endian::Little),
+            "<processor-specific type 0xc0000000 data: 03 00 00 00 >"
+        );
+
+        let property = GnuProperty {
+            ptype: 0xc0000002,
+            data: vec![3, 0, 0, 0],
+        };
+        assert_eq!(
+            property.describe(EM_X86_64, Endian::Little),
+            "x86 feature: IBT, SHSTK"
+        );
+    }
+
+    #[test]
+    fn decodes_isa_levels() {
+        assert_eq!(x86_isa_levels(0b101), vec!["x86-64-baseline", "x86-64-v3"]);
+        assert_eq!(x86_isa_minimum(0b101), Some("x86-64-v3"));
+        assert_eq!(x86_isa_minimum(0), None);
+    }
+}
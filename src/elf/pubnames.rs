@@ -0,0 +1,378 @@
+use super::{
+    bytes::{string_at, ByteReader},
+    dwarf::{read_form, unit_length, Encoding},
+    hdr::Endian,
+};
+
+/// An entry of a `.debug_pubnames` or `.debug_gnu_pubnames` set
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PubName {
+    /// Offset of the DIE from the start of its compilation unit
+    pub die_offset: u64,
+    /// The GNU flags byte, only present in `.debug_gnu_pubnames`
+    pub flags: Option<u8>,
+    pub name: String,
+}
+
+impl PubName {
+    /// The GNU flags the way binutils prints them, `g,function` for a global function
+    pub fn kind(&self) -> Option<String> {
+        let flags = self.flags?;
+        let kind = match (flags >> 4) & 0x7 {
+            0 => "none",
+            1 => "type",
+            2 => "variable",
+            3 => "function",
+            4 => "other",
+            _ => "unknown",
+        };
+        let scope = if flags & 0x80 != 0 { "s" } else { "g" };
+        Some(format!("{},{}", scope, kind))
+    }
+}
+
+/// The names of one compilation unit in `.debug_pubnames`, the lookup table of global
+/// names DWARF 2 to 4 producers emit with `-gpubnames`
+#[derive(Debug, Clone)]
+pub struct PubNameSet {
+    /// Offset of the set in the section
+    pub offset: usize,
+    pub length: u64,
+    pub version: u16,
+    /// Offset of the compilation unit in `.debug_info`
+    pub info_offset: u64,
+    pub info_size: u64,
+    pub names: Vec<PubName>,
+}
+
+impl PubNameSet {
+    /// Parses every set of a `.debug_pubnames` section, or of `.debug_gnu_pubnames` when
+    /// `gnu` is set, whose entries carry a flags byte
+    pub fn parse(data: &[u8], endian: Endian, gnu: bool) -> Vec<Self> {
+        let mut sets = Vec::new();
+        let mut reader = ByteReader::new(data, endian);
+
+        while !reader.is_empty() {
+            let offset = reader.pos();
+            let set = (|| {
+                let (length, offset_size) = unit_length(&mut reader)?;
+                let end = reader.pos().checked_add(length as usize)?;
+                let version = reader.u16()?;
+                let info_offset = reader.word(offset_size)?;
+                let info_size = reader.word(offset_size)?;
+
+                let mut names = Vec::new();
+                while reader.pos() < end {
+                    let die_offset = reader.word(offset_size)?;
+                    if die_offset == 0 {
+                        break;
+                    }
+                    let flags = match gnu {
+                        true => Some(reader.u8()?),
+                        false => None,
+                    };
+                    let name = String::from_utf8_lossy(reader.cstr()?).into_owned();
+                    names.push(PubName {
+                        die_offset,
+                        flags,
+                        name,
+                    });
+                }
+                reader.seek(end)?;
+
+                Some(PubNameSet {
+                    offset,
+                    length,
+                    version,
+                    info_offset,
+                    info_size,
+                    names,
+                })
+            })();
+
+            match set {
+                Some(set) => sets.push(set),
+                None => break,
+            }
+        }
+
+        sets
+    }
+}
+
+pub const DW_IDX_COMPILE_UNIT: u64 = 1;
+pub const DW_IDX_TYPE_UNIT: u64 = 2;
+pub const DW_IDX_DIE_OFFSET: u64 = 3;
+
+/// The name of a `DW_IDX_*` index attribute of `.debug_names`
+pub fn index_attribute_name(idx: u64) -> String {
+    match idx {
+        DW_IDX_COMPILE_UNIT => "DW_IDX_compile_unit".to_string(),
+        DW_IDX_TYPE_UNIT => "DW_IDX_type_unit".to_string(),
+        DW_IDX_DIE_OFFSET => "DW_IDX_die_offset".to_string(),
+        4 => "DW_IDX_parent".to_string(),
+        5 => "DW_IDX_type_hash".to_string(),
+        0x2000 => "DW_IDX_GNU_internal".to_string(),
+        0x2001 => "DW_IDX_GNU_external".to_string(),
+        _ => format!("DW_IDX_<0x{:x}>", idx),
+    }
+}
+
+/// One DIE a `.debug_names` name refers to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexEntry {
+    /// Offset of the entry in the entry pool
+    pub offset: u64,
+    pub abbrev: u64,
+    pub tag: u64,
+    /// `(DW_IDX_*, value)` pairs in abbreviation order
+    pub attributes: Vec<(u64, u64)>,
+}
+
+impl IndexEntry {
+    pub fn attribute(&self, idx: u64) -> Option<u64> {
+        self.attributes
+            .iter()
+            .find(|(attribute, _)| *attribute == idx)
+            .map(|(_, value)| *value)
+    }
+}
+
+/// A name of the `.debug_names` name table with the entries for it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexName {
+    /// Hash of the name, absent when the table has no hash lookup
+    pub hash: Option<u32>,
+    /// Offset of the name in `.debug_str`
+    pub string_offset: u64,
+    pub name: String,
+    pub entries: Vec<IndexEntry>,
+}
+
+/// An abbreviation of the `.debug_names` entry pool: code, tag and the `(DW_IDX_*,
+/// DW_FORM_*)` pairs of its attributes
+type IndexAbbrev = (u64, u64, Vec<(u64, u64)>);
+
+/// A DWARF 5 `.debug_names` accelerated lookup table, the successor of
+/// `.debug_pubnames` which also indexes types and static functions
+#[derive(Debug, Clone)]
+pub struct DebugNames {
+    /// Offset of the table in the section
+    pub offset: usize,
+    pub length: u64,
+    pub version: u16,
+    pub augmentation: Vec<u8>,
+    /// Offsets of the compilation units in `.debug_info`
+    pub comp_units: Vec<u64>,
+    pub local_type_units: Vec<u64>,
+    /// Type signatures of type units in other files
+    pub foreign_type_units: Vec<u64>,
+    /// Index into `names`, counting from 1, of the first name hashing to each bucket, 0 for
+    /// empty buckets
+    pub buckets: Vec<u32>,
+    pub abbrev_table_size: u32,
+    pub names: Vec<IndexName>,
+}
+
+impl DebugNames {
+    /// Parses the tables of a `.debug_names` section, names are looked up in `strtab`, the
+    /// contents of `.debug_str`
+    pub fn parse(data: &[u8], strtab: &[u8], endian: Endian) -> Vec<Self> {
+        let mut tables = Vec::new();
+        let mut reader = ByteReader::new(data, endian);
+
+        while !reader.is_empty() {
+            let offset = reader.pos();
+            match Self::parse_table(&mut reader, strtab) {
+                Some(table) => tables.push(table),
+                None => break,
+            }
+            if reader.pos() <= offset {
+                break;
+            }
+        }
+
+        tables
+    }
+
+    fn parse_table(reader: &mut ByteReader, strtab: &[u8]) -> Option<Self> {
+        let offset = reader.pos();
+        let (length, offset_size) = unit_length(reader)?;
+        let end = reader.pos().checked_add(length as usize)?;
+        let version = reader.u16()?;
+        reader.skip(2)?;
+        let comp_unit_count = reader.u32()?;
+        let local_type_unit_count = reader.u32()?;
+        let foreign_type_unit_count = reader.u32()?;
+        let bucket_count = reader.u32()?;
+        let name_count = reader.u32()?;
+        let abbrev_table_size = reader.u32()?;
+        let augmentation_size = reader.u32()? as usize;
+        // Padded to a multiple of 4, the size includes the padding
+        let augmentation = reader.bytes(augmentation_size)?.to_vec();
+
+        let comp_units = (0..comp_unit_count)
+            .map(|_| reader.word(offset_size))
+            .collect::<Option<Vec<_>>>()?;
+        let local_type_units = (0..local_type_unit_count)
+            .map(|_| reader.word(offset_size))
+            .collect::<Option<Vec<_>>>()?;
+        let foreign_type_units = (0..foreign_type_unit_count)
+            .map(|_| reader.u64())
+            .collect::<Option<Vec<_>>>()?;
+        let buckets = (0..bucket_count)
+            .map(|_| reader.u32())
+            .collect::<Option<Vec<_>>>()?;
+        let hashes = match bucket_count {
+            0 => Vec::new(),
+            _ => (0..name_count)
+                .map(|_| reader.u32())
+                .collect::<Option<Vec<_>>>()?,
+        };
+        let string_offsets = (0..name_count)
+            .map(|_| reader.word(offset_size))
+            .collect::<Option<Vec<_>>>()?;
+        let entry_offsets = (0..name_count)
+            .map(|_| reader.word(offset_size))
+            .collect::<Option<Vec<_>>>()?;
+
+        let abbrev_data = reader.bytes(abbrev_table_size as usize)?;
+        let mut abbrevs = Vec::new();
+        let mut abbrev_reader = ByteReader::new(abbrev_data, reader.endian());
+        loop {
+            let code = abbrev_reader.uleb128()?;
+            if code == 0 {
+                break;
+            }
+            let tag = abbrev_reader.uleb128()?;
+            let mut attributes = Vec::new();
+            loop {
+                let idx = abbrev_reader.uleb128()?;
+                let form = abbrev_reader.uleb128()?;
+                if idx == 0 && form == 0 {
+                    break;
+                }
+                attributes.push((idx, form));
+            }
+            abbrevs.push((code, tag, attributes));
+        }
+
+        let pool = reader.data().get(reader.pos()..end)?;
+        let encoding = Encoding {
+            version,
+            offset_size,
+            address_size: offset_size,
+        };
+        let names = string_offsets
+            .iter()
+            .zip(&entry_offsets)
+            .enumerate()
+            .map(|(i, (&string_offset, &entry_offset))| {
+                let mut entries = Vec::new();
+                let mut pool_reader = ByteReader::new(pool, reader.endian());
+                if pool_reader.seek(entry_offset as usize).is_some() {
+                    // A name's entries run until an abbreviation code of 0
+                    while let Some(entry) = Self::parse_entry(&mut pool_reader, &abbrevs, encoding)
+                    {
+                        entries.push(entry);
+                    }
+                }
+                IndexName {
+                    hash: hashes.get(i).copied(),
+                    string_offset,
+                    name: string_at(strtab, string_offset as usize),
+                    entries,
+                }
+            })
+            .collect();
+
+        reader.seek(end)?;
+
+        Some(Self {
+            offset,
+            length,
+            version,
+            augmentation,
+            comp_units,
+            local_type_units,
+            foreign_type_units,
+            buckets,
+            abbrev_table_size,
+            names,
+        })
+    }
+
+    fn parse_entry(
+        reader: &mut ByteReader,
+        abbrevs: &[IndexAbbrev],
+        encoding: Encoding,
+    ) -> Option<IndexEntry> {
+        let offset = reader.pos() as u64;
+        let abbrev = reader.uleb128()?;
+        let (_, tag, forms) = abbrevs.iter().find(|(code, _, _)| *code == abbrev)?;
+        let attributes = forms
+            .iter()
+            .map(|&(idx, form)| Some((idx, read_form(reader, form, encoding, 0)?.as_u64()?)))
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(IndexEntry {
+            offset,
+            abbrev,
+            tag: *tag,
+            attributes,
+        })
+    }
+
+    /// The `.debug_info` offset of the compilation unit an entry belongs to. Tables covering
+    /// a single unit may leave `DW_IDX_compile_unit` out.
+    pub fn comp_unit(&self, entry: &IndexEntry) -> Option<u64> {
+        match entry.attribute(DW_IDX_COMPILE_UNIT) {
+            Some(index) => self.comp_units.get(index as usize).copied(),
+            None if self.comp_units.len() == 1 && entry.attribute(DW_IDX_TYPE_UNIT).is_none() => {
+                self.comp_units.first().copied()
+            }
+            None => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{DebugNames, PubNameSet, DW_IDX_DIE_OFFSET};
+    use crate::elf::hdr::Endian;
+
+    #[test]
+    fn parses_name_indexes() {
+        let mut set = vec![0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0x40, 0, 0, 0];
+        set.extend_from_slice(&[0x2e, 0, 0, 0, 0x30, b'f', b'o', b'o', 0, 0, 0, 0, 0]);
+        let length = (set.len() - 4) as u32;
+        set[..4].copy_from_slice(&length.to_le_bytes());
+        let sets = PubNameSet::parse(&set, Endian::Little, true);
+        assert_eq!(sets.len(), 1);
+        assert_eq!(sets[0].info_size, 0x40);
+        assert_eq!(sets[0].names[0].die_offset, 0x2e);
+        assert_eq!(sets[0].names[0].kind(), Some("g,function".to_string()));
+
+        let strtab = b"\0main\0";
+        // One CU, one bucket, one name with a subprogram entry at DIE offset 0x2b
+        let mut table = vec![0, 0, 0, 0, 5, 0, 0, 0];
+        for field in [1u32, 0, 0, 1, 1, 7, 0, 0, 1, 0x7c9a7f6a, 1, 0] {
+            table.extend_from_slice(&field.to_le_bytes());
+        }
+        table.extend_from_slice(&[1, 0x2e, 3, 0x13, 0, 0, 0]);
+        table.extend_from_slice(&[1, 0x2b, 0, 0, 0, 0]);
+        let length = (table.len() - 4) as u32;
+        table[..4].copy_from_slice(&length.to_le_bytes());
+
+        let tables = DebugNames::parse(&table, strtab, Endian::Little);
+        assert_eq!(tables.len(), 1);
+        assert_eq!(tables[0].buckets, vec![1]);
+        let name = &tables[0].names[0];
+        assert_eq!(name.name, "main");
+        assert_eq!(name.hash, Some(0x7c9a7f6a));
+        assert_eq!(name.entries.len(), 1);
+        assert_eq!(name.entries[0].tag, 0x2e);
+        assert_eq!(name.entries[0].attribute(DW_IDX_DIE_OFFSET), Some(0x2b));
+        assert_eq!(tables[0].comp_unit(&name.entries[0]), Some(0));
+    }
+}
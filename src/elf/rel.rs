@@ -1 +1,283 @@
+use std::io::{self, Read, Seek, SeekFrom};
 
+use super::{
+    bytes::ByteReader,
+    hdr::{ElfClass, Endian},
+    internal::get_data,
+    shdr::{ElfShdr, SectionType},
+    Elf32Addr, Elf32Sword, Elf32Word, Elf64Addr, Elf64Sxword, Elf64Xword, ElfHdr, EM_386,
+    EM_AARCH64, EM_ARM, EM_BPF, EM_LOONGARCH, EM_PPC, EM_PPC64, EM_RISCV, EM_S390, EM_SPARC,
+    EM_SPARCV9, EM_X86_64,
+};
+
+#[repr(C)]
+pub struct Elf32Rel {
+    r_offset: Elf32Addr,
+    r_info: Elf32Word,
+}
+
+#[repr(C)]
+pub struct Elf32Rela {
+    r_offset: Elf32Addr,
+    r_info: Elf32Word,
+    r_addend: Elf32Sword,
+}
+
+#[repr(C)]
+pub struct Elf64Rel {
+    r_offset: Elf64Addr,
+    r_info: Elf64Xword,
+}
+
+#[repr(C)]
+pub struct Elf64Rela {
+    r_offset: Elf64Addr,
+    r_info: Elf64Xword,
+    r_addend: Elf64Sxword,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ElfRel {
+    /// Location to apply the relocation to, a section offset in relocatable files and a
+    /// virtual address otherwise
+    offset: Elf64Addr,
+    /// Raw `r_info`, widened to 64 bits
+    info: Elf64Xword,
+    /// Index into the symbol table referenced by the relocation section's `sh_link`
+    sym: u32,
+    /// Processor specific relocation type
+    rtype: u32,
+    /// Explicit addend, only present in `SHT_RELA` entries
+    addend: Option<Elf64Sxword>,
+}
+
+impl ElfRel {
+    pub fn read_relocations<R: Seek + Read>(
+        file: &mut R,
+        hdr: &ElfHdr,
+        shdr: &ElfShdr,
+    ) -> Option<io::Result<Vec<Self>>> {
+        if shdr.size() == 0 || shdr.entsize() == 0 {
+            return None;
+        }
+
+        let nmemb = (shdr.size() / shdr.entsize()) as usize;
+        let offset = SeekFrom::Start(shdr.offset());
+
+        let rels = unsafe {
+            match shdr.section_type()? {
+                SectionType::Rela => {
+                    get_data::<_, Elf32Rela, Elf64Rela, ElfRel>(file, hdr, nmemb, offset)
+                }
+                SectionType::Rel => {
+                    get_data::<_, Elf32Rel, Elf64Rel, ElfRel>(file, hdr, nmemb, offset)
+                }
+                _ => return None,
+            }
+        };
+
+        Some(rels)
+    }
+
+    /// Builds a relocation from raw fields, splitting `r_info` according to the file class
+    pub fn new(class: ElfClass, offset: u64, info: u64, addend: Option<i64>) -> Self {
+        let (sym, rtype) = match class {
+            ElfClass::ElfClass64 => ((info >> 32) as u32, info as u32),
+            _ => ((info >> 8) as u32, (info & 0xff) as u32),
+        };
+
+        Self {
+            offset,
+            info,
+            sym,
+            rtype,
+            addend,
+        }
+    }
+
+    pub fn offset(&self) -> Elf64Addr {
+        self.offset
+    }
+
+    pub fn info(&self) -> Elf64Xword {
+        self.info
+    }
+
+    pub fn sym(&self) -> u32 {
+        self.sym
+    }
+
+    pub fn rtype(&self) -> u32 {
+        self.rtype
+    }
+
+    pub fn addend(&self) -> Option<Elf64Sxword> {
+        self.addend
+    }
+}
+
+impl From<&Elf32Rel> for ElfRel {
+    fn from(rel: &Elf32Rel) -> Self {
+        Self::new(
+            ElfClass::ElfClass32,
+            rel.r_offset.into(),
+            rel.r_info.into(),
+            None,
+        )
+    }
+}
+
+impl From<&Elf32Rela> for ElfRel {
+    fn from(rel: &Elf32Rela) -> Self {
+        Self::new(
+            ElfClass::ElfClass32,
+            rel.r_offset.into(),
+            rel.r_info.into(),
+            Some(rel.r_addend.into()),
+        )
+    }
+}
+
+impl From<&Elf64Rel> for ElfRel {
+    fn from(rel: &Elf64Rel) -> Self {
+        Self::new(ElfClass::ElfClass64, rel.r_offset, rel.r_info, None)
+    }
+}
+
+impl From<&Elf64Rela> for ElfRel {
+    fn from(rel: &Elf64Rela) -> Self {
+        Self::new(
+            ElfClass::ElfClass64,
+            rel.r_offset,
+            rel.r_info,
+            Some(rel.r_addend),
+        )
+    }
+}
+
+/// Returns the binutils spelling of a relocation type for the given `e_machine`
+pub fn reloc_type_name(machine: u16, rtype: u32) -> Option<&'static str> {
+    match machine {
+        EM_X86_64 => Some(match rtype {
+            0 => "R_X86_64_NONE",
+            1 => "R_X86_64_64",
+            2 => "R_X86_64_PC32",
+            3 => "R_X86_64_GOT32",
+            4 => "R_X86_64_PLT32",
+            5 => "R_X86_64_COPY",
+            6 => "R_X86_64_GLOB_DAT",
+            7 => "R_X86_64_JUMP_SLOT",
+            8 => "R_X86_64_RELATIVE",
+            9 => "R_X86_64_GOTPCREL",
+            10 => "R_X86_64_32",
+            11 => "R_X86_64_32S",
+            12 => "R_X86_64_16",
+            13 => "R_X86_64_PC16",
+            14 => "R_X86_64_8",
+            15 => "R_X86_64_PC8",
+            16 => "R_X86_64_DTPMOD64",
+            17 => "R_X86_64_DTPOFF64",
+            18 => "R_X86_64_TPOFF64",
+            19 => "R_X86_64_TLSGD",
+            20 => "R_X86_64_TLSLD",
+            21 => "R_X86_64_DTPOFF32",
+            22 => "R_X86_64_GOTTPOFF",
+            23 => "R_X86_64_TPOFF32",
+            24 => "R_X86_64_PC64",
+            25 => "R_X86_64_GOTOFF64",
+            26 => "R_X86_64_GOTPC32",
+            32 => "R_X86_64_SIZE32",
+            33 => "R_X86_64_SIZE64",
+            34 => "R_X86_64_GOTPC32_TLSDESC",
+            35 => "R_X86_64_TLSDESC_CALL",
+            36 => "R_X86_64_TLSDESC",
+            37 => "R_X86_64_IRELATIVE",
+            38 => "R_X86_64_RELATIVE64",
+            41 => "R_X86_64_GOTPCRELX",
+            42 => "R_X86_64_REX_GOTPCRELX",
+            _ => return None,
+        }),
+        EM_BPF => Some(match rtype {
+            0 => "R_BPF_NONE",
+            1 => "R_BPF_64_64",
+            2 => "R_BPF_64_ABS64",
+            3 => "R_BPF_64_ABS32",
+            4 => "R_BPF_64_NODYLD32",
+            10 => "R_BPF_64_32",
+            _ => return None,
+        }),
+        _ => None,
+    }
+}
+
+/// The `R_*_IRELATIVE` type of the given `e_machine`, whose addend is an ifunc resolver
+pub fn irelative_type(machine: u16) -> Option<u32> {
+    Some(match machine {
+        EM_386 => 42,
+        EM_X86_64 => 37,
+        EM_ARM => 160,
+        EM_AARCH64 => 1032,
+        EM_PPC | EM_PPC64 => 248,
+        EM_S390 => 61,
+        EM_SPARC | EM_SPARCV9 => 249,
+        EM_RISCV => 58,
+        EM_LOONGARCH => 12,
+        _ => return None,
+    })
+}
+
+/// The `R_*_RELATIVE` type of the given `e_machine`, the base address plus the addend.
+/// `DT_RELACOUNT` and `DT_RELCOUNT` count the leading run of these in their table.
+pub fn relative_type(machine: u16) -> Option<u32> {
+    Some(match machine {
+        EM_386 | EM_X86_64 => 8,
+        EM_ARM => 23,
+        EM_AARCH64 => 1027,
+        EM_PPC | EM_PPC64 | EM_SPARC | EM_SPARCV9 => 22,
+        EM_S390 => 12,
+        EM_RISCV | EM_LOONGARCH => 3,
+        _ => return None,
+    })
+}
+
+/// The `R_*_COPY` type of the given `e_machine`
+pub fn copy_type(machine: u16) -> Option<u32> {
+    Some(match machine {
+        EM_386 | EM_X86_64 => 5,
+        EM_ARM => 20,
+        EM_AARCH64 => 1024,
+        EM_PPC | EM_PPC64 | EM_SPARC | EM_SPARCV9 => 19,
+        EM_S390 => 9,
+        EM_RISCV | EM_LOONGARCH => 4,
+        _ => return None,
+    })
+}
+
+/// Expands a packed `DT_RELR` table into the addresses it relocates: an even entry is an
+/// address, an odd one a bitmap of which of the following words are relocated as well
+pub fn relr_addresses(data: &[u8], class: ElfClass, endian: Endian) -> Vec<u64> {
+    let word = match class {
+        ElfClass::ElfClass64 => 8,
+        _ => 4,
+    };
+    let bits = word as u64 * 8 - 1;
+    let mut reader = ByteReader::new(data, endian);
+    let mut addresses = Vec::new();
+    let mut next = 0;
+
+    while let Some(entry) = reader.word(word) {
+        if entry & 1 == 0 {
+            addresses.push(entry);
+            next = entry + word as u64;
+        } else {
+            addresses.extend(
+                (0..bits)
+                    .filter(|bit| entry >> (bit + 1) & 1 != 0)
+                    .map(|bit| next + bit * word as u64),
+            );
+            next += bits * word as u64;
+        }
+    }
+
+    addresses
+}
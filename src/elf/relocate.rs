@@ -0,0 +1,239 @@
+use std::io;
+
+use super::{
+    core::FileData,
+    hdr::{Endian, ObjectType},
+    rel::ElfRel,
+    EM_386, EM_AARCH64, EM_ARM, EM_LOONGARCH, EM_PPC64, EM_RISCV, EM_S390, EM_X86_64,
+};
+
+/// What a relocation does to the `width` bytes at its offset
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RelocAction {
+    /// Store `S + A`
+    Absolute(usize),
+    /// Store `S + A - P`
+    PcRelative(usize),
+    /// Add `S + A` to the value in place, as in RISC-V and LoongArch label differences
+    Add(usize),
+    /// Subtract `S + A` from the value in place
+    Sub(usize),
+    /// Leave the bytes alone
+    None,
+}
+
+/// The action of the relocation types that show up in data and debug sections, `None` for
+/// code relocations which need instruction encoding knowledge
+pub fn reloc_action(machine: u16, rtype: u32) -> Option<RelocAction> {
+    use RelocAction::*;
+
+    Some(match (machine, rtype) {
+        (EM_X86_64, 0) => None,
+        (EM_X86_64, 1 | 17) => Absolute(8),
+        (EM_X86_64, 2) => PcRelative(4),
+        (EM_X86_64, 10 | 11 | 21) => Absolute(4),
+        (EM_X86_64, 24) => PcRelative(8),
+        (EM_386, 0) => None,
+        (EM_386, 1 | 32) => Absolute(4),
+        (EM_386, 2) => PcRelative(4),
+        (EM_AARCH64, 0 | 256) => None,
+        (EM_AARCH64, 257) => Absolute(8),
+        (EM_AARCH64, 258) => Absolute(4),
+        (EM_AARCH64, 259) => Absolute(2),
+        (EM_AARCH64, 260) => PcRelative(8),
+        (EM_AARCH64, 261) => PcRelative(4),
+        (EM_AARCH64, 262) => PcRelative(2),
+        (EM_ARM, 0) => None,
+        (EM_ARM, 2) => Absolute(4),
+        (EM_ARM, 3) => PcRelative(4),
+        (EM_RISCV, 0) => None,
+        (EM_RISCV, 1) => Absolute(4),
+        (EM_RISCV, 2) => Absolute(8),
+        (EM_RISCV, 33) => Add(1),
+        (EM_RISCV, 34) => Add(2),
+        (EM_RISCV, 35) => Add(4),
+        (EM_RISCV, 36) => Add(8),
+        (EM_RISCV, 37) => Sub(1),
+        (EM_RISCV, 38) => Sub(2),
+        (EM_RISCV, 39) => Sub(4),
+        (EM_RISCV, 40) => Sub(8),
+        (EM_RISCV, 57) => PcRelative(4),
+        (EM_LOONGARCH, 0) => None,
+        (EM_LOONGARCH, 1) => Absolute(4),
+        (EM_LOONGARCH, 2) => Absolute(8),
+        (EM_LOONGARCH, 47) => Add(1),
+        (EM_LOONGARCH, 48) => Add(2),
+        (EM_LOONGARCH, 50) => Add(4),
+        (EM_LOONGARCH, 51) => Add(8),
+        (EM_LOONGARCH, 52) => Sub(1),
+        (EM_LOONGARCH, 53) => Sub(2),
+        (EM_LOONGARCH, 55) => Sub(4),
+        (EM_LOONGARCH, 56) => Sub(8),
+        (EM_PPC64, 0) => None,
+        (EM_PPC64, 1) => Absolute(4),
+        (EM_PPC64, 38) => Absolute(8),
+        (EM_PPC64, 26) => PcRelative(4),
+        (EM_PPC64, 44) => PcRelative(8),
+        (EM_S390, 0) => None,
+        (EM_S390, 4) => Absolute(4),
+        (EM_S390, 5) => PcRelative(4),
+        (EM_S390, 22) => Absolute(8),
+        (EM_S390, 23) => PcRelative(8),
+        _ => return Option::None,
+    })
+}
+
+fn read_uint(bytes: &[u8], endian: Endian) -> u64 {
+    let mut value = 0;
+    for i in 0..bytes.len() {
+        let byte = match endian {
+            Endian::Little => bytes[bytes.len() - 1 - i],
+            Endian::Big => bytes[i],
+        };
+        value = value << 8 | byte as u64;
+    }
+    value
+}
+
+fn write_uint(bytes: &mut [u8], value: u64, endian: Endian) {
+    let len = bytes.len();
+    for i in 0..len {
+        let byte = (value >> (8 * i)) as u8;
+        match endian {
+            Endian::Little => bytes[i] = byte,
+            Endian::Big => bytes[len - 1 - i] = byte,
+        }
+    }
+}
+
+/// Applies one relocation to `data`, the contents of the section it targets starting at
+/// address `base`. `symbol` is the value of the referenced symbol. Returns whether the
+/// relocation type is understood and fits in the section.
+pub fn apply_relocation(
+    data: &mut [u8],
+    base: u64,
+    rel: &ElfRel,
+    symbol: u64,
+    machine: u16,
+    endian: Endian,
+) -> bool {
+    let action = match reloc_action(machine, rel.rtype()) {
+        Some(action) => action,
+        None => return false,
+    };
+    let width = match action {
+        RelocAction::Absolute(width)
+        | RelocAction::PcRelative(width)
+        | RelocAction::Add(width)
+        | RelocAction::Sub(width) => width,
+        RelocAction::None => return true,
+    };
+    let offset = rel.offset() as usize;
+    let location = match data.get_mut(offset..offset.saturating_add(width)) {
+        Some(location) => location,
+        None => return false,
+    };
+
+    // REL entries keep their addend in the bytes being relocated
+    let current = read_uint(location, endian);
+    let addend = rel.addend().map_or(current, |addend| addend as u64);
+    let value = symbol.wrapping_add(addend);
+    let place = base.wrapping_add(rel.offset());
+
+    write_uint(
+        location,
+        match action {
+            RelocAction::PcRelative(_) => value.wrapping_sub(place),
+            RelocAction::Add(_) => current.wrapping_add(value),
+            RelocAction::Sub(_) => current.wrapping_sub(value),
+            _ => value,
+        },
+        endian,
+    );
+    true
+}
+
+impl FileData {
+    /// Contents of section `index` with the relocations against it applied, what
+    /// `readelf --relocated-dump` shows. Only relocatable objects are relocated, the
+    /// sections of linked files already hold their final values.
+    ///
+    /// Returns the data and the number of relocations that could not be applied, because
+    /// their type is not a data relocation this knows about.
+    pub fn relocated_section_data(&self, index: usize) -> io::Result<(Vec<u8>, usize)> {
+        let shdr = match self.section_headers().get(index) {
+            Some(shdr) => *shdr,
+            None => {
+                return Err(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("section {} does not exist", index),
+                ))
+            }
+        };
+        let mut data = self.decompressed_section_data(&shdr)?;
+        if self.header().ftype() != Some(ObjectType::Rel) {
+            return Ok((data, 0));
+        }
+
+        let machine = self.header().machine();
+        let endian = self.header().endian().unwrap_or(Endian::Little);
+        let mut skipped = 0;
+        for (rel_shdr, rels) in self.relocations()? {
+            if rel_shdr.info() as usize != index {
+                continue;
+            }
+
+            let symbols = match self.section_headers().get(rel_shdr.link() as usize) {
+                Some(symtab) => self.symbol_table(symtab)?.1,
+                None => &[],
+            };
+            for rel in &rels {
+                let symbol = symbols.get(rel.sym() as usize).map_or(0, |sym| sym.value());
+                if !apply_relocation(&mut data, shdr.addr(), rel, symbol, machine, endian) {
+                    skipped += 1;
+                }
+            }
+        }
+
+        Ok((data, skipped))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::apply_relocation;
+    use crate::elf::{hdr::ElfClass, hdr::Endian, rel::ElfRel, EM_RISCV, EM_X86_64};
+
+    #[test]
+    fn applies_data_relocations() {
+        let mut data = vec![0; 16];
+        let abs = ElfRel::new(ElfClass::ElfClass64, 0, 1, Some(0x10));
+        let pc = ElfRel::new(ElfClass::ElfClass64, 8, 2, Some(-4));
+        assert!(apply_relocation(
+            &mut data,
+            0x1000,
+            &abs,
+            0x2000,
+            EM_X86_64,
+            Endian::Little
+        ));
+        assert!(apply_relocation(
+            &mut data,
+            0x1000,
+            &pc,
+            0x2000,
+            EM_X86_64,
+            Endian::Little
+        ));
+        assert_eq!(data[..8], 0x2010u64.to_le_bytes());
+        assert_eq!(data[8..12], (0x2000u32 - 4 - 0x1008).to_le_bytes());
+
+        // R_RISCV_ADD32 then R_RISCV_SUB32, a label difference
+        let mut data = 5u32.to_le_bytes().to_vec();
+        let add = ElfRel::new(ElfClass::ElfClass64, 0, 35, Some(0));
+        let sub = ElfRel::new(ElfClass::ElfClass64, 0, 39, Some(0));
+        apply_relocation(&mut data, 0, &add, 0x30, EM_RISCV, Endian::Little);
+        apply_relocation(&mut data, 0, &sub, 0x10, EM_RISCV, Endian::Little);
+        assert_eq!(data, (5u32 + 0x20).to_le_bytes());
+    }
+}
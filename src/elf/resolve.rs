@@ -0,0 +1,231 @@
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use super::{
+    bytes::string_at, core::FileData, deps::DepGraph, shdr::SectionType, sym::SymbolBinding,
+};
+
+#[derive(Debug, Clone)]
+pub struct Definition {
+    pub version: Option<String>,
+    pub hidden: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct Reference {
+    pub name: String,
+    pub version: Option<String>,
+    pub weak: bool,
+}
+
+/// A loaded object of the simulated global scope
+#[derive(Debug, Clone)]
+pub struct ScopeObject {
+    pub name: String,
+    pub path: PathBuf,
+    pub definitions: HashMap<String, Vec<Definition>>,
+    pub references: Vec<Reference>,
+}
+
+impl ScopeObject {
+    pub fn read(name: String, path: &Path) -> io::Result<Self> {
+        let elf = FileData::new(path)?;
+        let versions = elf.symbol_versions()?;
+        let mut object = ScopeObject {
+            name,
+            path: path.to_path_buf(),
+            definitions: HashMap::new(),
+            references: Vec::new(),
+        };
+
+        let dynsym = elf
+            .section_headers()
+            .iter()
+            .find(|shdr| shdr.section_type() == Some(SectionType::DynSym))
+            .copied();
+        let (strtab, syms) = match dynsym {
+            Some(dynsym) => elf.symbol_table(&dynsym)?,
+            None => return Ok(object),
+        };
+
+        for (index, sym) in syms.iter().enumerate().skip(1) {
+            let weak = match sym.binding() {
+                Some(SymbolBinding::Global) => false,
+                Some(SymbolBinding::Weak) => true,
+                _ => continue,
+            };
+            let name = string_at(strtab, sym.name() as usize);
+            if name.is_empty() {
+                continue;
+            }
+
+            if sym.shndx() == 0 {
+                object.references.push(Reference {
+                    name,
+                    version: versions
+                        .needed(index)
+                        .map(|(_, version)| version.to_string()),
+                    weak,
+                });
+            } else {
+                let defined = versions.defined(index);
+                object
+                    .definitions
+                    .entry(name)
+                    .or_default()
+                    .push(Definition {
+                        version: defined.map(|(version, _)| version.to_string()),
+                        hidden: matches!(defined, Some((_, true))),
+                    });
+            }
+        }
+
+        Ok(object)
+    }
+
+    /// Whether this object satisfies a reference to `name` with the required `version`.
+    ///
+    /// Unversioned references bind to the default (non-hidden) version, versioned references
+    /// need an exact match but accept unversioned definitions, as the dynamic linker does.
+    pub fn defines(&self, name: &str, version: Option<&str>) -> bool {
+        let defs = match self.definitions.get(name) {
+            Some(defs) => defs,
+            None => return false,
+        };
+
+        defs.iter()
+            .any(|def| match (version, def.version.as_deref()) {
+                (Some(wanted), Some(defined)) => wanted == defined,
+                (None, Some(_)) => !def.hidden,
+                (_, None) => true,
+            })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Binding {
+    pub symbol: String,
+    pub version: Option<String>,
+    pub weak: bool,
+    /// Index into [`Resolution::objects`] of the object holding the reference
+    pub referrer: usize,
+    /// Index of the first object in scope order providing the definition
+    pub provider: Option<usize>,
+}
+
+/// An offline `ldd -r`, binding every undefined dynamic symbol against the global scope
+#[derive(Debug, Clone)]
+pub struct Resolution {
+    /// Scope in lookup order: the executable, preloaded libraries, then the breadth first
+    /// `DT_NEEDED` closure
+    pub objects: Vec<ScopeObject>,
+    pub bindings: Vec<Binding>,
+    /// `DT_NEEDED` entries that could not be located
+    pub missing: Vec<String>,
+}
+
+impl Resolution {
+    pub fn simulate<P: AsRef<Path>>(path: P, preload: &[String]) -> io::Result<Self> {
+        let path = path.as_ref();
+        let graph = DepGraph::resolve(path)?;
+        let mut resolution = Resolution {
+            objects: Vec::new(),
+            bindings: Vec::new(),
+            missing: Vec::new(),
+        };
+
+        let mut scope = vec![(path.to_string_lossy().into_owned(), path.to_path_buf())];
+        scope.extend(preload.iter().map(|lib| (lib.clone(), PathBuf::from(lib))));
+        for node in graph.nodes.iter().skip(1) {
+            match &node.path {
+                Some(path) => scope.push((node.name.clone(), path.clone())),
+                None => resolution.missing.push(node.name.clone()),
+            }
+        }
+
+        resolution.load(scope)?;
+        resolution.bind(resolution.objects.len());
+
+        Ok(resolution)
+    }
+
+    /// Binds the undefined dynamic symbols of `path` against `libraries` alone, without
+    /// looking for its `DT_NEEDED` entries: whether those libraries are enough for it
+    pub fn against<P: AsRef<Path>>(path: P, libraries: &[String]) -> io::Result<Self> {
+        let path = path.as_ref();
+        let mut resolution = Resolution {
+            objects: Vec::new(),
+            bindings: Vec::new(),
+            missing: Vec::new(),
+        };
+
+        let mut scope = vec![(path.to_string_lossy().into_owned(), path.to_path_buf())];
+        scope.extend(
+            libraries
+                .iter()
+                .map(|lib| (lib.clone(), PathBuf::from(lib))),
+        );
+        resolution.load(scope)?;
+        resolution.bind(1);
+
+        Ok(resolution)
+    }
+
+    /// Reads the objects of `scope` in order, skipping any loaded already
+    fn load(&mut self, scope: Vec<(String, PathBuf)>) -> io::Result<()> {
+        let mut loaded = Vec::new();
+        for (name, path) in scope {
+            let canonical = fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+            if loaded.contains(&canonical) {
+                continue;
+            }
+            loaded.push(canonical);
+            self.objects.push(ScopeObject::read(name, &path)?);
+        }
+        Ok(())
+    }
+
+    /// Binds the references of the first `referrers` objects against the whole scope
+    fn bind(&mut self, referrers: usize) {
+        for (referrer, object) in self.objects.iter().enumerate().take(referrers) {
+            for reference in &object.references {
+                let provider = self.objects.iter().position(|candidate| {
+                    candidate.defines(&reference.name, reference.version.as_deref())
+                });
+                self.bindings.push(Binding {
+                    symbol: reference.name.clone(),
+                    version: reference.version.clone(),
+                    weak: reference.weak,
+                    referrer,
+                    provider,
+                });
+            }
+        }
+    }
+
+    /// The versions objects in scope define `symbol` with, whatever the reference asks
+    /// for: what is there instead when a versioned reference stays unresolved
+    pub fn versions_defined(&self, symbol: &str) -> Vec<(&ScopeObject, Option<&str>)> {
+        self.objects
+            .iter()
+            .flat_map(|object| {
+                object
+                    .definitions
+                    .get(symbol)
+                    .into_iter()
+                    .flatten()
+                    .map(move |def| (object, def.version.as_deref()))
+            })
+            .collect()
+    }
+
+    /// Strong references nothing in scope defines, these abort loading with `RTLD_NOW`
+    pub fn unresolved(&self) -> impl Iterator<Item = &Binding> {
+        self.bindings
+            .iter()
+            .filter(|binding| binding.provider.is_none() && !binding.weak)
+    }
+}
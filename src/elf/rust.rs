@@ -0,0 +1,241 @@
+use std::{collections::BTreeMap, io};
+
+use super::{bytes::string_at, core::FileData, shdr::SectionType};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PanicStrategy {
+    Unwind,
+    Abort,
+    Unknown,
+}
+
+impl PanicStrategy {
+    pub fn display(&self) -> &'static str {
+        match self {
+            Self::Unwind => "unwind",
+            Self::Abort => "abort",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StdHint {
+    Std,
+    NoStd,
+    Unknown,
+}
+
+impl StdHint {
+    pub fn display(&self) -> &'static str {
+        match self {
+            Self::Std => "std",
+            Self::NoStd => "no_std",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RustInfo {
+    /// `rustc version ...` entries of `.comment`
+    pub compilers: Vec<String>,
+    pub panic: PanicStrategy,
+    pub std: StdHint,
+    /// Crate name to the number of symbols mangled under it
+    pub crates: BTreeMap<String, usize>,
+    pub v0_symbols: usize,
+    pub legacy_symbols: usize,
+}
+
+/// Skips a `s<base62>_` disambiguator or `B<base62>_` backref
+fn skip_base62(sym: &[u8], pos: usize) -> usize {
+    match sym[pos..].iter().position(|&c| c == b'_') {
+        Some(end) => pos + end + 1,
+        None => sym.len(),
+    }
+}
+
+/// Reads a `[u]<decimal>[_]<bytes>` identifier, returning it and the position after it
+fn v0_ident(sym: &[u8], mut pos: usize) -> Option<(&[u8], usize)> {
+    if sym.get(pos) == Some(&b'u') {
+        pos += 1;
+    }
+    let start = pos;
+    while sym.get(pos)?.is_ascii_digit() {
+        pos += 1;
+    }
+    let len = std::str::from_utf8(&sym[start..pos])
+        .ok()?
+        .parse::<usize>()
+        .ok()?;
+    if sym.get(pos) == Some(&b'_') {
+        pos += 1;
+    }
+    Some((sym.get(pos..pos + len)?, pos + len))
+}
+
+/// Recovers the crate roots (`C` productions) of a v0 mangled symbol.
+///
+/// This is a scanner rather than a full demangler, it only tracks enough of the grammar
+/// to step over identifiers and base-62 numbers so their contents are not mistaken for tags.
+pub fn v0_crates(sym: &str) -> Vec<String> {
+    let sym = sym.as_bytes();
+    let mut crates = Vec::new();
+    let mut pos = 2;
+
+    while pos < sym.len() {
+        match sym[pos] {
+            b'C' => {
+                pos += 1;
+                if sym.get(pos) == Some(&b's') {
+                    pos = skip_base62(sym, pos);
+                }
+                match v0_ident(sym, pos) {
+                    Some((name, next)) => {
+                        crates.push(String::from_utf8_lossy(name).into_owned());
+                        pos = next;
+                    }
+                    None => break,
+                }
+            }
+            b's' | b'B' => pos = skip_base62(sym, pos),
+            b'0'..=b'9' | b'u' => match v0_ident(sym, pos) {
+                Some((_, next)) => pos = next,
+                None => pos += 1,
+            },
+            // Vendor specific suffix
+            b'.' => break,
+            _ => pos += 1,
+        }
+    }
+
+    crates
+}
+
+/// Returns the leading path component of a legacy `_ZN...17h<hash>E` symbol
+pub fn legacy_crate(sym: &str) -> Option<String> {
+    let body = sym.strip_prefix("_ZN")?;
+    let hash = body
+        .len()
+        .checked_sub(20)
+        .and_then(|start| body.get(start..))?;
+    if !hash.starts_with("17h") || !hash.ends_with('E') {
+        return None;
+    }
+    let digits = body.bytes().take_while(u8::is_ascii_digit).count();
+    let len = body[..digits].parse::<usize>().ok()?;
+    let name = body.get(digits..digits + len)?;
+    // Trait impls such as `_<T as Trait>` do not start with a crate name
+    if name.starts_with('_') || name.starts_with('$') {
+        return None;
+    }
+    Some(name.to_string())
+}
+
+impl RustInfo {
+    /// Returns `None` when neither `.comment` nor the symbol names point to rustc
+    pub fn read(elf: &FileData) -> io::Result<Option<Self>> {
+        let mut info = RustInfo {
+            compilers: Vec::new(),
+            panic: PanicStrategy::Unknown,
+            std: StdHint::Unknown,
+            crates: BTreeMap::new(),
+            v0_symbols: 0,
+            legacy_symbols: 0,
+        };
+
+        if let Some(shdr) = elf.section_by_name(".comment") {
+            info.compilers = elf
+                .section_data(&shdr)?
+                .split(|&c| c == 0)
+                .map(String::from_utf8_lossy)
+                .filter(|s| s.starts_with("rustc "))
+                .map(|s| s.into_owned())
+                .collect();
+        }
+
+        let symtab = elf
+            .section_headers()
+            .iter()
+            .find(|shdr| shdr.section_type() == Some(SectionType::SymTab))
+            .or_else(|| {
+                elf.section_headers()
+                    .iter()
+                    .find(|shdr| shdr.section_type() == Some(SectionType::DynSym))
+            })
+            .copied();
+
+        let mut has_unwinder = false;
+        if let Some(symtab) = symtab {
+            let (strtab, syms) = elf.symbol_table(&symtab)?;
+            for sym in syms {
+                let name = string_at(strtab, sym.name() as usize);
+                let crates = if name.starts_with("_R") {
+                    info.v0_symbols += 1;
+                    v0_crates(&name)
+                } else if let Some(name) = legacy_crate(&name) {
+                    info.legacy_symbols += 1;
+                    vec![name]
+                } else {
+                    has_unwinder |= name == "_Unwind_Resume" || name == "rust_eh_personality";
+                    continue;
+                };
+
+                for name in crates {
+                    *info.crates.entry(name).or_insert(0) += 1;
+                }
+            }
+        }
+
+        if info.compilers.is_empty() && info.crates.is_empty() {
+            return Ok(None);
+        }
+
+        info.panic = if info.crates.contains_key("panic_unwind") {
+            PanicStrategy::Unwind
+        } else if info.crates.contains_key("panic_abort") {
+            PanicStrategy::Abort
+        } else if has_unwinder {
+            PanicStrategy::Unwind
+        } else {
+            PanicStrategy::Unknown
+        };
+
+        info.std = if info.crates.contains_key("std") {
+            StdHint::Std
+        } else if info.crates.contains_key("core") {
+            StdHint::NoStd
+        } else {
+            StdHint::Unknown
+        };
+
+        Ok(Some(info))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{legacy_crate, v0_crates};
+
+    #[test]
+    fn recovers_v0_crates() {
+        assert_eq!(
+            v0_crates("_RNvNtCskveIEWJzp2i_12panic_unwind3imp6CANARY"),
+            vec!["panic_unwind"]
+        );
+        assert_eq!(
+            v0_crates("_RINvNtCs8NwYtU1Mohg_4core3ptr9drop_glueINtNtCsbEht8wFNRx7_5alloc5boxed3BoxNtNtCskveIEWJzp2i_12panic_unwind3imp9ExceptionEEB1e_"),
+            vec!["core", "alloc", "panic_unwind"]
+        );
+    }
+
+    #[test]
+    fn recovers_legacy_crate() {
+        assert_eq!(
+            legacy_crate("_ZN3std2rt10lang_start17h0123456789abcdefE").as_deref(),
+            Some("std")
+        );
+        assert_eq!(legacy_crate("_ZN4core3fmt5write"), None);
+    }
+}
@@ -0,0 +1,151 @@
+use std::io;
+
+use super::{
+    bytes::string_at,
+    core::FileData,
+    hdr::ObjectType,
+    phdr::ProgramType,
+    shdr::SectionType,
+    sym::{SymbolMap, SymbolType},
+};
+
+/// Parses a hex byte pattern such as `e8 ?? ?? ?? ?? c3` or `deadbeef`, where `??` matches
+/// any byte
+pub fn parse_pattern(pattern: &str) -> Result<Vec<Option<u8>>, String> {
+    let digits = pattern
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<Vec<_>>();
+    if digits.is_empty() || digits.len() % 2 != 0 {
+        return Err(format!("'{}' is not a whole number of hex bytes", pattern));
+    }
+
+    digits
+        .chunks(2)
+        .map(|pair| match pair {
+            ['?', '?'] => Ok(None),
+            [hi, lo] => match (hi.to_digit(16), lo.to_digit(16)) {
+                (Some(hi), Some(lo)) => Ok(Some((hi << 4 | lo) as u8)),
+                _ => Err(format!("'{}{}' is not a hex byte", hi, lo)),
+            },
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
+/// Where a match of [`FileData::find_bytes`] lands in the file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ByteMatch {
+    pub offset: u64,
+    /// Section whose contents contain the match
+    pub section: Option<String>,
+    /// Address the match is loaded at, when a `PT_LOAD` segment maps it
+    pub vaddr: Option<u64>,
+    /// Function or object containing the match, as `name+offset`
+    pub symbol: Option<String>,
+}
+
+impl FileData {
+    /// Every file offset the pattern from [`parse_pattern`] matches at, with the section,
+    /// address and symbol it belongs to
+    pub fn find_bytes(&self, pattern: &[Option<u8>]) -> io::Result<Vec<ByteMatch>> {
+        let data = self.read_at(0, self.file_size()? as usize)?;
+        let relocatable = self.header().ftype() == Some(ObjectType::Rel);
+        let empty = SymbolMap::default();
+        let global_map = match relocatable {
+            true => &empty,
+            false => self.symbol_map()?,
+        };
+        let symtab = match relocatable {
+            true => self
+                .table_symbols()?
+                .into_iter()
+                .find(|(name, _, _)| name == ".symtab"),
+            false => None,
+        };
+
+        let offsets = match pattern.len() {
+            0 => Vec::new(),
+            len => data
+                .windows(len)
+                .enumerate()
+                .filter(|(_, window)| {
+                    window
+                        .iter()
+                        .zip(pattern)
+                        .all(|(byte, expected)| expected.is_none() || *expected == Some(*byte))
+                })
+                .map(|(offset, _)| offset as u64)
+                .collect(),
+        };
+
+        let mut matches = Vec::new();
+        for offset in offsets {
+            let section = self.section_headers().iter().enumerate().find(|(_, shdr)| {
+                shdr.section_type() != Some(SectionType::NoBits)
+                    && offset >= shdr.offset()
+                    && offset < shdr.offset() + shdr.size()
+            });
+            let vaddr = self
+                .program_headers()
+                .iter()
+                .find(|phdr| {
+                    phdr.program_type() == Some(ProgramType::Load)
+                        && offset >= phdr.offset()
+                        && offset < phdr.offset() + phdr.filesz()
+                })
+                .map(|phdr| offset - phdr.offset() + phdr.vaddr());
+
+            // Relocatable objects only have section relative symbol values
+            let symbol = match (section, &symtab, vaddr) {
+                (Some((index, shdr)), Some((_, strtab, syms)), _) => SymbolMap::new(
+                    syms.iter()
+                        .filter(|sym| sym.shndx() as usize == index)
+                        .filter(|sym| {
+                            matches!(
+                                sym.symbol_type(),
+                                Some(SymbolType::Func | SymbolType::Object)
+                            )
+                        })
+                        .map(|sym| {
+                            (
+                                sym.value(),
+                                sym.size(),
+                                string_at(strtab, sym.name() as usize),
+                            )
+                        }),
+                )
+                .describe(offset - shdr.offset()),
+                (Some((_, shdr)), None, Some(vaddr)) => {
+                    global_map.describe_from(vaddr, shdr.addr())
+                }
+                (None, None, Some(vaddr)) => global_map.describe(vaddr),
+                _ => None,
+            };
+
+            matches.push(ByteMatch {
+                offset,
+                section: section.and_then(|(_, shdr)| self.section_name(shdr)),
+                vaddr,
+                symbol,
+            });
+        }
+
+        Ok(matches)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse_pattern;
+
+    #[test]
+    fn parses_patterns() {
+        assert_eq!(
+            parse_pattern("e8 ?? 00ff").unwrap(),
+            vec![Some(0xe8), None, Some(0x00), Some(0xff)]
+        );
+        assert!(parse_pattern("e8 0").is_err());
+        assert!(parse_pattern("zz").is_err());
+    }
+}
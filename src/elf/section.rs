@@ -0,0 +1,275 @@
+use std::{mem::size_of, str, vec};
+
+use super::{
+    dynamic::{Dyn, DynamicTag, Elf32Dyn, Elf64Dyn},
+    hdr::{ElfClass, Endian},
+    image::read_struct,
+    note::Note,
+    rel::{Elf32Rel, Elf32Rela, Elf64Rel, Elf64Rela, ElfRel},
+    shdr::{ElfShdr, SectionType},
+    sym::{Elf32Sym, Elf64Sym, ElfSym},
+    ElfHdr,
+};
+
+/// A section decoded according to its `sh_type`, built from its header and contents
+#[derive(Clone, Copy)]
+pub enum Section<'a> {
+    /// `SHT_SYMTAB` and `SHT_DYNSYM`
+    Symtab(SymtabSection<'a>),
+    Strtab(StringTable<'a>),
+    Note(NoteSection<'a>),
+    /// `SHT_REL` and `SHT_RELA`
+    Rela(RelaSection<'a>),
+    Dynamic(DynamicSection<'a>),
+    Other(ElfShdr, &'a [u8]),
+}
+
+impl<'a> Section<'a> {
+    pub fn new(hdr: &ElfHdr, shdr: ElfShdr, data: &'a [u8]) -> Self {
+        match shdr.section_type() {
+            Some(SectionType::SymTab | SectionType::DynSym) => {
+                Self::Symtab(SymtabSection::new(hdr, shdr, data))
+            }
+            Some(SectionType::StrTab) => Self::Strtab(StringTable::new(data)),
+            Some(SectionType::Note) => Self::Note(NoteSection::new(hdr, shdr, data)),
+            Some(SectionType::Rel | SectionType::Rela) => {
+                Self::Rela(RelaSection::new(hdr, shdr, data))
+            }
+            Some(SectionType::Dynamic) => Self::Dynamic(DynamicSection::new(hdr, data)),
+            _ => Self::Other(shdr, data),
+        }
+    }
+
+    pub fn data(&self) -> &'a [u8] {
+        match self {
+            Self::Symtab(section) => section.data,
+            Self::Strtab(section) => section.data,
+            Self::Note(section) => section.data,
+            Self::Rela(section) => section.data,
+            Self::Dynamic(section) => section.data,
+            Self::Other(_, data) => data,
+        }
+    }
+}
+
+fn is_elf64(hdr: &ElfHdr) -> bool {
+    hdr.class() == Some(ElfClass::ElfClass64)
+}
+
+/// Decodes the `index`th `T32`/`T64` entry of `data`
+fn entry<T32, T64, E>(data: &[u8], elf64: bool, index: usize) -> Option<E>
+where
+    for<'b> E: From<&'b T32> + From<&'b T64>,
+{
+    unsafe {
+        match elf64 {
+            true => read_struct::<T64>(data, index.checked_mul(size_of::<T64>())?)
+                .map(|raw| E::from(&raw)),
+            false => read_struct::<T32>(data, index.checked_mul(size_of::<T32>())?)
+                .map(|raw| E::from(&raw)),
+        }
+    }
+}
+
+/// The NUL terminated strings of a `SHT_STRTAB` section
+#[derive(Clone, Copy)]
+pub struct StringTable<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> StringTable<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    /// The string starting at byte `index`, `None` past the end or for invalid UTF-8
+    pub fn get(&self, index: usize) -> Option<&'a str> {
+        let strings = self.data.get(index..)?;
+        let len = strings
+            .iter()
+            .position(|&c| c == 0)
+            .unwrap_or(strings.len());
+        str::from_utf8(&strings[..len]).ok()
+    }
+
+    /// Every string with its offset, the leading empty string included
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &'a str)> + 'a {
+        let data = self.data;
+        data.split(|&c| c == 0)
+            .scan(0, |offset, s| {
+                let start = *offset;
+                *offset += s.len() + 1;
+                Some((start, s))
+            })
+            .take_while(move |(start, _)| *start < data.len())
+            .filter_map(|(start, s)| Some((start, str::from_utf8(s).ok()?)))
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct SymtabSection<'a> {
+    shdr: ElfShdr,
+    data: &'a [u8],
+    elf64: bool,
+}
+
+impl<'a> SymtabSection<'a> {
+    pub fn new(hdr: &ElfHdr, shdr: ElfShdr, data: &'a [u8]) -> Self {
+        Self {
+            shdr,
+            data,
+            elf64: is_elf64(hdr),
+        }
+    }
+
+    /// Index of the string table holding the symbol names
+    pub fn strtab_index(&self) -> u32 {
+        self.shdr.link()
+    }
+
+    pub fn len(&self) -> usize {
+        match self.elf64 {
+            true => self.data.len() / size_of::<Elf64Sym>(),
+            false => self.data.len() / size_of::<Elf32Sym>(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<ElfSym> {
+        entry::<Elf32Sym, Elf64Sym, ElfSym>(self.data, self.elf64, index)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = ElfSym> + 'a {
+        let this = *self;
+        (0..self.len()).filter_map(move |index| this.get(index))
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct RelaSection<'a> {
+    shdr: ElfShdr,
+    data: &'a [u8],
+    elf64: bool,
+}
+
+impl<'a> RelaSection<'a> {
+    pub fn new(hdr: &ElfHdr, shdr: ElfShdr, data: &'a [u8]) -> Self {
+        Self {
+            shdr,
+            data,
+            elf64: is_elf64(hdr),
+        }
+    }
+
+    /// Whether the entries carry explicit addends, `SHT_RELA` as opposed to `SHT_REL`
+    pub fn has_addends(&self) -> bool {
+        self.shdr.section_type() == Some(SectionType::Rela)
+    }
+
+    /// Index of the symbol table the entries refer to
+    pub fn symtab_index(&self) -> u32 {
+        self.shdr.link()
+    }
+
+    /// Index of the section the relocations apply to, 0 for dynamic relocations
+    pub fn target_index(&self) -> u32 {
+        self.shdr.info()
+    }
+
+    pub fn len(&self) -> usize {
+        let entsize = match (self.elf64, self.has_addends()) {
+            (true, true) => size_of::<Elf64Rela>(),
+            (true, false) => size_of::<Elf64Rel>(),
+            (false, true) => size_of::<Elf32Rela>(),
+            (false, false) => size_of::<Elf32Rel>(),
+        };
+        self.data.len() / entsize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<ElfRel> {
+        match self.has_addends() {
+            true => entry::<Elf32Rela, Elf64Rela, ElfRel>(self.data, self.elf64, index),
+            false => entry::<Elf32Rel, Elf64Rel, ElfRel>(self.data, self.elf64, index),
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = ElfRel> + 'a {
+        let this = *self;
+        (0..self.len()).filter_map(move |index| this.get(index))
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct DynamicSection<'a> {
+    data: &'a [u8],
+    elf64: bool,
+}
+
+impl<'a> DynamicSection<'a> {
+    pub fn new(hdr: &ElfHdr, data: &'a [u8]) -> Self {
+        Self {
+            data,
+            elf64: is_elf64(hdr),
+        }
+    }
+
+    /// The entries up to, and not including, `DT_NULL`
+    pub fn iter(&self) -> impl Iterator<Item = Dyn> + 'a {
+        let (data, elf64) = (self.data, self.elf64);
+        (0..)
+            .map_while(move |index| entry::<Elf32Dyn, Elf64Dyn, Dyn>(data, elf64, index))
+            .take_while(|entry| entry.tag != DynamicTag::Null as u64)
+    }
+
+    /// The value of the first entry tagged `tag`
+    pub fn find(&self, tag: DynamicTag) -> Option<u64> {
+        self.iter()
+            .find(|entry| entry.tag == tag as u64)
+            .map(|entry| unsafe { entry.value.val })
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct NoteSection<'a> {
+    data: &'a [u8],
+    endian: Endian,
+    align: u64,
+}
+
+impl<'a> NoteSection<'a> {
+    pub fn new(hdr: &ElfHdr, shdr: ElfShdr, data: &'a [u8]) -> Self {
+        Self {
+            data,
+            endian: hdr.endian().unwrap_or(Endian::Little),
+            align: shdr.addralign(),
+        }
+    }
+
+    pub fn iter(&self) -> vec::IntoIter<Note> {
+        Note::parse_aligned(self.data, self.endian, self.align).into_iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::StringTable;
+
+    #[test]
+    fn splits_string_table() {
+        let table = StringTable::new(b"\0.text\0.data\0");
+        assert_eq!(table.get(1), Some(".text"));
+        assert_eq!(table.get(3), Some("ext"));
+        assert_eq!(table.get(14), None);
+        assert_eq!(
+            table.iter().collect::<Vec<_>>(),
+            [(0, ""), (1, ".text"), (7, ".data")]
+        );
+    }
+}
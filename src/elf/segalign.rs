@@ -0,0 +1,162 @@
+use super::{
+    core::FileData, phdr::ProgramType, EM_386, EM_AARCH64, EM_ARM, EM_IA_64, EM_LOONGARCH, EM_MIPS,
+    EM_PPC, EM_PPC64, EM_RISCV, EM_S390, EM_SPARC, EM_SPARCV9, EM_X86_64,
+};
+
+/// Page sizes the kernels of an architecture can be configured with, smallest first
+pub fn page_sizes(machine: u16) -> &'static [u64] {
+    match machine {
+        EM_X86_64 | EM_386 | EM_S390 | EM_RISCV | EM_ARM => &[0x1000],
+        EM_AARCH64 | EM_MIPS | EM_PPC | EM_PPC64 => &[0x1000, 0x4000, 0x10000],
+        EM_LOONGARCH => &[0x1000, 0x4000, 0x10000],
+        EM_IA_64 => &[0x1000, 0x2000, 0x4000, 0x10000],
+        EM_SPARC | EM_SPARCV9 => &[0x2000],
+        _ => &[0x1000],
+    }
+}
+
+/// The smallest huge page of an architecture with its smallest base page, what a segment
+/// has to be aligned to for the kernel to back it with huge pages
+pub fn huge_page_size(machine: u16) -> Option<u64> {
+    match machine {
+        EM_X86_64 | EM_AARCH64 | EM_RISCV | EM_PPC64 => Some(0x200000),
+        EM_S390 => Some(0x100000),
+        EM_LOONGARCH => Some(0x2000000),
+        _ => None,
+    }
+}
+
+/// Something about a `PT_LOAD` alignment that stops or hampers loading
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlignIssue {
+    /// `p_align` is neither 0, 1 nor a power of two
+    NotPowerOfTwo,
+    /// `p_vaddr` and `p_offset` differ modulo `p_align`, the segment cannot be mapped
+    Incongruent,
+    /// `p_align` is below this page size. Kernels running with it refuse the segment when
+    /// its address and offset differ modulo the page, and otherwise may map bytes of the
+    /// neighbouring segments with the wrong permissions.
+    BelowPageSize(u64),
+    /// `p_align` is above the largest page size, Linux before 5.10 ignores the extra
+    /// alignment of shared objects and PIEs
+    AbovePageSize(u64),
+}
+
+impl AlignIssue {
+    /// Whether the loader rejects the segment outright
+    pub fn is_fatal(&self) -> bool {
+        matches!(self, Self::NotPowerOfTwo | Self::Incongruent)
+    }
+}
+
+impl std::fmt::Display for AlignIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotPowerOfTwo => write!(f, "alignment is not a power of two"),
+            Self::Incongruent => write!(f, "address and offset differ modulo the alignment"),
+            Self::BelowPageSize(page) => write!(f, "aligned below the {:#x} page size", page),
+            Self::AbovePageSize(page) => {
+                write!(
+                    f,
+                    "aligned above the {:#x} page size, older kernels ignore it",
+                    page
+                )
+            }
+        }
+    }
+}
+
+/// The problems with one `PT_LOAD` segment placed at `vaddr`, from file offset `offset`
+pub fn check_segment(vaddr: u64, offset: u64, align: u64, machine: u16) -> Vec<AlignIssue> {
+    let mut issues = Vec::new();
+    if align <= 1 {
+        return issues;
+    }
+    if !align.is_power_of_two() {
+        issues.push(AlignIssue::NotPowerOfTwo);
+        return issues;
+    }
+    if vaddr % align != offset % align {
+        issues.push(AlignIssue::Incongruent);
+    }
+
+    let pages = page_sizes(machine);
+    if let Some(&page) = pages.iter().rev().find(|&&page| page > align) {
+        issues.push(AlignIssue::BelowPageSize(page));
+    }
+    match pages.last() {
+        Some(&page) if align > page && huge_page_size(machine) != Some(align) => {
+            issues.push(AlignIssue::AbovePageSize(page))
+        }
+        _ => {}
+    }
+
+    issues
+}
+
+/// The alignment of one `PT_LOAD` segment and what is wrong with it
+#[derive(Debug, Clone)]
+pub struct SegmentAlignment {
+    /// Index in the program header table
+    pub index: usize,
+    pub offset: u64,
+    pub vaddr: u64,
+    pub align: u64,
+    pub issues: Vec<AlignIssue>,
+    /// Whether the kernel can back the segment with huge pages, its alignment and
+    /// placement being multiples of [`huge_page_size`]
+    pub huge_pages: bool,
+}
+
+impl SegmentAlignment {
+    pub fn read(elf: &FileData) -> Vec<Self> {
+        let machine = elf.header().machine();
+        let huge = huge_page_size(machine);
+
+        elf.program_headers()
+            .iter()
+            .enumerate()
+            .filter(|(_, phdr)| phdr.program_type() == Some(ProgramType::Load))
+            .map(|(index, phdr)| Self {
+                index,
+                offset: phdr.offset(),
+                vaddr: phdr.vaddr(),
+                align: phdr.align(),
+                issues: check_segment(phdr.vaddr(), phdr.offset(), phdr.align(), machine),
+                huge_pages: matches!(huge, Some(huge) if phdr.align() % huge == 0
+                    && phdr.vaddr() % huge == phdr.offset() % huge),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{check_segment, AlignIssue};
+    use crate::elf::{EM_AARCH64, EM_X86_64};
+
+    #[test]
+    fn checks_load_alignment() {
+        assert_eq!(check_segment(0x401000, 0x1000, 0x1000, EM_X86_64), vec![]);
+        assert_eq!(
+            check_segment(0x200000, 0x200000, 0x200000, EM_X86_64),
+            vec![]
+        );
+        assert_eq!(
+            check_segment(0x402010, 0x1000, 0x1000, EM_X86_64),
+            vec![AlignIssue::Incongruent]
+        );
+        assert_eq!(
+            check_segment(0, 0, 0x1800, EM_X86_64),
+            vec![AlignIssue::NotPowerOfTwo]
+        );
+        assert_eq!(
+            check_segment(0x11000, 0x1000, 0x1000, EM_AARCH64),
+            vec![AlignIssue::BelowPageSize(0x10000)]
+        );
+        assert_eq!(
+            check_segment(0, 0, 0x100000, EM_AARCH64),
+            vec![AlignIssue::AbovePageSize(0x10000)]
+        );
+    }
+}
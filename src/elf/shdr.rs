@@ -1,5 +1,7 @@
+use alloc::{format, string::String};
+use core::fmt::{self, Debug, Display};
+#[cfg(feature = "std")]
 use std::{
-    fmt::Debug,
     fs::{File, OpenOptions},
     io::{self, Read, Seek, SeekFrom},
     mem::{self, transmute, MaybeUninit},
@@ -7,13 +9,13 @@ use std::{
     ptr, slice,
 };
 
-use num::FromPrimitive;
 use num_derive::FromPrimitive;
+use num_traits::FromPrimitive;
 
-use super::{
-    hdr::ElfClass, Elf32Addr, Elf32Off, Elf32Word, Elf64Addr, Elf64Off, Elf64Word, Elf64Xword,
-    ElfHdr,
-};
+#[cfg(feature = "std")]
+use super::{hdr::ElfClass, internal::seek_to_range, ElfHdr};
+use super::{Elf32Addr, Elf32Off, Elf32Word, Elf64Addr, Elf64Off, Elf64Word, Elf64Xword};
+use super::{ELFOSABI_FREEBSD, ELFOSABI_GNU, ELFOSABI_NONE, EM_ARM, EM_PPC, EM_X86_64};
 
 macro_rules! trivial_convert {
     ($self:expr => $field:ident, $variant32:ident, $variant64:ident) => {
@@ -24,14 +26,14 @@ macro_rules! trivial_convert {
     };
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ElfShdr {
     Elf32Shdr(Elf32Shdr),
     Elf64Shdr(Elf64Shdr),
 }
 
 #[repr(C)]
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Elf32Shdr {
     name: Elf32Word,
     section_type: Elf32Word,
@@ -46,7 +48,7 @@ pub struct Elf32Shdr {
 }
 
 #[repr(C)]
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Elf64Shdr {
     /// The name of this section in index of the string table
     pub name: Elf64Word,
@@ -71,6 +73,7 @@ pub struct Elf64Shdr {
     pub entsize: Elf64Xword,
 }
 
+#[cfg(feature = "std")]
 pub struct ElfShdrIter {
     file: File,
     remaining: usize,
@@ -78,6 +81,29 @@ pub struct ElfShdrIter {
 }
 
 impl ElfShdr {
+    /// A header for a table located some other way than the section header table, such as
+    /// through the dynamic section, so the section readers can decode it
+    pub fn synthetic(
+        section_type: SectionType,
+        addr: u64,
+        offset: u64,
+        size: u64,
+        entsize: u64,
+    ) -> Self {
+        Self::Elf64Shdr(Elf64Shdr {
+            name: 0,
+            section_type: section_type as Elf64Word,
+            flags: SectionFlag::Alloc as Elf64Xword,
+            addr,
+            offset,
+            size,
+            link: 0,
+            info: 0,
+            addralign: 0,
+            entsize,
+        })
+    }
+
     pub fn name(&self) -> Elf64Word {
         trivial_convert!(self => name, Elf32Shdr, Elf64Shdr)
     }
@@ -86,6 +112,26 @@ impl ElfShdr {
         SectionType::from_u32(trivial_convert!(self => section_type, Elf32Shdr, Elf64Shdr))
     }
 
+    /// `sh_type` as stored, including values [`SectionType`] has no variant for
+    pub fn raw_type(&self) -> Elf64Word {
+        trivial_convert!(self => section_type, Elf32Shdr, Elf64Shdr)
+    }
+
+    /// The `sh_type` name readelf prints, with types [`SectionType`] has no variant for
+    /// shown in hex relative to the range they fall in
+    pub fn type_name(&self) -> String {
+        let raw = self.raw_type();
+        match self.section_type() {
+            Some(stype) => format!("{}", stype),
+            None if (SHT_LOPROC..=SHT_HIPROC).contains(&raw) => {
+                format!("LOPROC+{:#x}", raw - SHT_LOPROC)
+            }
+            None if (SHT_LOOS..=SHT_HIOS).contains(&raw) => format!("LOOS+{:#x}", raw - SHT_LOOS),
+            None if raw >= SHT_LOUSER => format!("LOUSER+{:#x}", raw - SHT_LOUSER),
+            None => format!("{:08x}: <unknown>", raw),
+        }
+    }
+
     pub fn flags(&self) -> u64 {
         trivial_convert!(self => flags, Elf32Shdr, Elf64Shdr)
     }
@@ -94,6 +140,15 @@ impl ElfShdr {
         trivial_convert!(self => addr, Elf32Shdr, Elf64Shdr)
     }
 
+    /// The address in a copy of the file loaded with `bias`, sections that are not loaded
+    /// keep their address
+    pub fn rebased_addr(&self, bias: u64) -> Elf64Addr {
+        match self.flags() & SectionFlag::Alloc as u64 {
+            0 => self.addr(),
+            _ => self.addr().wrapping_add(bias),
+        }
+    }
+
     pub fn offset(&self) -> Elf64Off {
         trivial_convert!(self => offset, Elf32Shdr, Elf64Shdr)
     }
@@ -118,6 +173,43 @@ impl ElfShdr {
         trivial_convert!(self => entsize, Elf32Shdr, Elf64Shdr)
     }
 
+    /// The `sh_flags` key letters used by readelf, e.g. `WA` or `AX`. Which OS and
+    /// processor specific bits have a letter of their own depends on the `e_machine` and
+    /// `EI_OSABI` of the file, the rest show up as `o` or `p`.
+    pub fn flag_letters(&self, machine: u16, os_abi: u8) -> String {
+        let mut letters = String::with_capacity(14);
+        let mut sh_flags = self.flags();
+        while sh_flags != 0 {
+            let flag = sh_flags & sh_flags.wrapping_neg();
+            sh_flags &= !flag;
+            letters.push(match flag {
+                flag if flag == SectionFlag::Write as u64 => 'W',
+                flag if flag == SectionFlag::Alloc as u64 => 'A',
+                flag if flag == SectionFlag::ExecInstr as u64 => 'X',
+                flag if flag == SectionFlag::Merge as u64 => 'M',
+                flag if flag == SectionFlag::Strings as u64 => 'S',
+                flag if flag == SectionFlag::InfoLink as u64 => 'I',
+                flag if flag == SectionFlag::LinkOrder as u64 => 'L',
+                flag if flag == SectionFlag::OsNonConforming as u64 => 'O',
+                flag if flag == SectionFlag::Group as u64 => 'G',
+                flag if flag == SectionFlag::Tls as u64 => 'T',
+                flag if flag == SectionFlag::Exclude as u64 => 'E',
+                flag if flag == SectionFlag::Compressed as u64 => 'C',
+                SHF_X86_64_LARGE if machine == EM_X86_64 => 'l',
+                SHF_ARM_PURECODE if machine == EM_ARM => 'y',
+                SHF_PPC_VLE if machine == EM_PPC => 'v',
+                flag if flag == SectionFlag::GnuRetain as u64 && retain_flag(os_abi) => 'R',
+                flag if flag == SectionFlag::GnuMbind as u64 && mbind_flag(os_abi) => 'D',
+                flag if flag & SHF_MASKOS != 0 => 'o',
+                flag if flag & SHF_MASKPROC != 0 => 'p',
+                _ => 'x',
+            });
+        }
+
+        letters
+    }
+
+    #[cfg(feature = "std")]
     pub fn read_string_table<P: AsRef<Path>>(path: P) -> Result<Vec<u8>, io::Error> {
         let hdr = ElfHdr::read(&path)?;
         let index = (hdr.e_shentsize as u64 * hdr.e_shstrndx as u64) + hdr.e_shoff;
@@ -147,7 +239,7 @@ impl ElfShdr {
             },
         };
 
-        file.seek(SeekFrom::Start(shdr.offset()))?;
+        seek_to_range(&mut file, SeekFrom::Start(shdr.offset()), Some(shdr.size()))?;
         let mut buf = vec![0; shdr.size() as usize];
 
         file.read(&mut buf)?;
@@ -155,6 +247,7 @@ impl ElfShdr {
         Ok(buf)
     }
 
+    #[cfg(feature = "std")]
     #[inline]
     pub fn get_string_table<R: Read + Seek>(
         file: &mut R,
@@ -163,6 +256,7 @@ impl ElfShdr {
         Self::get_data(file, hdr, hdr.e_shstrndx.into(), hdr.e_shoff)
     }
 
+    #[cfg(feature = "std")]
     pub fn get_data<R: Read + Seek>(
         file: &mut R,
         hdr: &ElfHdr,
@@ -189,13 +283,43 @@ impl ElfShdr {
             }
         };
 
+        seek_to_range(file, SeekFrom::Start(shdr.offset()), Some(shdr.size()))?;
         let mut buf = vec![0; shdr.size() as usize];
-        file.seek(SeekFrom::Start(shdr.offset()))?;
         file.read(&mut buf)?;
 
         Ok(buf)
     }
 
+    /// Reads every section header described by `hdr`
+    #[cfg(feature = "std")]
+    pub fn read_all<R: Read + Seek>(hdr: &ElfHdr, file: &mut R) -> io::Result<Vec<Self>> {
+        file.seek(SeekFrom::Start(hdr.e_shoff))?;
+
+        (0..hdr.e_shnum)
+            .map(|_| unsafe {
+                Ok(match hdr.class() {
+                    Some(ElfClass::ElfClass64) => {
+                        let mut buf = MaybeUninit::<Elf64Shdr>::uninit();
+                        file.read_exact(slice::from_raw_parts_mut(
+                            buf.as_mut_ptr() as *mut u8,
+                            mem::size_of::<Elf64Shdr>(),
+                        ))?;
+                        buf.assume_init().into()
+                    }
+                    _ => {
+                        let mut buf = MaybeUninit::<Elf32Shdr>::uninit();
+                        file.read_exact(slice::from_raw_parts_mut(
+                            buf.as_mut_ptr() as *mut u8,
+                            mem::size_of::<Elf32Shdr>(),
+                        ))?;
+                        buf.assume_init().into()
+                    }
+                })
+            })
+            .collect()
+    }
+
+    #[cfg(feature = "std")]
     pub fn iter<P: AsRef<Path>>(path: P) -> Result<ElfShdrIter, io::Error> {
         let mut file = OpenOptions::new().read(true).open(&path)?;
         let hdr = ElfHdr::read(&path)?;
@@ -226,6 +350,7 @@ impl From<Elf64Shdr> for ElfShdr {
     }
 }
 
+#[cfg(feature = "std")]
 impl Iterator for ElfShdrIter {
     type Item = ElfShdr;
     fn next(&mut self) -> Option<Self::Item> {
@@ -265,8 +390,24 @@ impl Iterator for ElfShdrIter {
     }
 }
 
+/// Prefix of the sections GCC writes its LTO bytecode to
+pub const LTO_SECTION_PREFIX: &str = ".gnu.lto_";
+
+/// Splits a GCC LTO section name into the stream it holds and the object ID GCC appends:
+/// `.gnu.lto_.symtab.c85a4eca95e54f14` gives `(".symtab", Some("c85a4eca95e54f14"))`.
+/// Function bodies are named after their function, as in `.gnu.lto_main.0.<id>`.
+pub fn split_lto_section_name(name: &str) -> Option<(&str, Option<&str>)> {
+    let rest = name.strip_prefix(LTO_SECTION_PREFIX)?;
+    Some(match rest.rsplit_once('.') {
+        Some((stream, id)) if id.len() == 16 && id.bytes().all(|c| c.is_ascii_hexdigit()) => {
+            (stream, Some(id))
+        }
+        _ => (rest, None),
+    })
+}
+
 #[repr(usize)]
-#[derive(Clone, PartialEq, Eq, Debug, FromPrimitive)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, FromPrimitive)]
 pub enum SectionType {
     Null = 0x0,
     ProgBits = 0x1,
@@ -285,18 +426,82 @@ pub enum SectionType {
     PreInitArray = 0x10,
     Group = 0x11,
     SymTabShndx = 0x12,
+    Relr = 0x13,
     LoProc = 0x70000000,
     HiProc = 0x7FFFFFFF,
     LoUser = 0x80000000,
     HiUser = 0xFFFFFFFF,
 
+    // LLVM additional section types
+    LlvmOdrTab = 0x6fff4c00,
+    LlvmLinkerOptions = 0x6fff4c01,
+    LlvmAddrsig = 0x6fff4c03,
+    LlvmDependentLibraries = 0x6fff4c04,
+    LlvmSymPart = 0x6fff4c05,
+    LlvmPartEhdr = 0x6fff4c06,
+    LlvmPartPhdr = 0x6fff4c07,
+    LlvmBbAddrMapV0 = 0x6fff4c08,
+    LlvmCallGraphProfile = 0x6fff4c09,
+    LlvmBbAddrMap = 0x6fff4c0a,
+    LlvmOffloading = 0x6fff4c0b,
+    LlvmLto = 0x6fff4c0c,
+
     // GNU additional section types
     GnuHash = 0x6ffffff6,
+    GnuLibList = 0x6ffffff7,
+    SunwSyminfo = 0x6ffffffc,
     VerDef = 0x6FFFFFFD,
     VerNeed = 0x6FFFFFFE,
     VerSym = 0x6FFFFFFF,
 }
 
+impl Display for SectionType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Self::Null => "NULL",
+            Self::ProgBits => "PROGBITS",
+            Self::SymTab => "SYMTAB",
+            Self::StrTab => "STRTAB",
+            Self::Rela => "RELA",
+            Self::Hash => "HASH",
+            Self::Dynamic => "DYNAMIC",
+            Self::Note => "NOTE",
+            Self::NoBits => "NOBITS",
+            Self::Rel => "REL",
+            Self::SHLib => "SHLIB",
+            Self::DynSym => "DYNSYM",
+            Self::InitArray => "INIT_ARRAY",
+            Self::FiniArray => "FINI_ARRAY",
+            Self::PreInitArray => "PREINIT_ARRAY",
+            Self::Group => "GROUP",
+            Self::SymTabShndx => "SYMTAB SECTION INDICES",
+            Self::Relr => "RELR",
+            Self::LoProc => "LOPROC",
+            Self::HiProc => "HIPROC",
+            Self::LoUser => "LOUSER",
+            Self::HiUser => "HIUSER",
+            Self::LlvmOdrTab => "LLVM_ODRTAB",
+            Self::LlvmLinkerOptions => "LLVM_LINKER_OPTIONS",
+            Self::LlvmAddrsig => "LLVM_ADDRSIG",
+            Self::LlvmDependentLibraries => "LLVM_DEPENDENT_LIBRARIES",
+            Self::LlvmSymPart => "LLVM_SYMPART",
+            Self::LlvmPartEhdr => "LLVM_PART_EHDR",
+            Self::LlvmPartPhdr => "LLVM_PART_PHDR",
+            Self::LlvmBbAddrMapV0 => "LLVM_BB_ADDR_MAP_V0",
+            Self::LlvmCallGraphProfile => "LLVM_CALL_GRAPH_PROFILE",
+            Self::LlvmBbAddrMap => "LLVM_BB_ADDR_MAP",
+            Self::LlvmOffloading => "LLVM_OFFLOADING",
+            Self::LlvmLto => "LLVM_LTO",
+            Self::GnuHash => "GNU_HASH",
+            Self::GnuLibList => "GNU_LIBLIST",
+            Self::SunwSyminfo => "SUNW_SYMINFO",
+            Self::VerDef => "VERDEF",
+            Self::VerNeed => "VERNEED",
+            Self::VerSym => "VERSYM",
+        })
+    }
+}
+
 #[repr(u64)]
 pub enum SectionFlag {
     Write = 1 << 0,
@@ -311,5 +516,94 @@ pub enum SectionFlag {
     Tls = 1 << 10,
     Exclude = 0x80000000,
     Compressed = 1 << 11,
+    /// `SHF_GNU_RETAIN`, the section is kept by `--gc-sections`
+    GnuRetain = 0x00200000,
     GnuMbind = 0x01000000,
+    /// Solaris `SHF_ORDERED`, in the processor specific range
+    Ordered = 0x40000000,
+}
+
+/// Ranges of `sh_type` values reserved for OS, processor and application specific types
+pub const SHT_LOOS: u32 = 0x60000000;
+pub const SHT_HIOS: u32 = 0x6fffffff;
+pub const SHT_LOPROC: u32 = 0x70000000;
+pub const SHT_HIPROC: u32 = 0x7fffffff;
+pub const SHT_LOUSER: u32 = 0x80000000;
+
+/// Bits of `sh_flags` reserved for OS specific semantics
+pub const SHF_MASKOS: u64 = 0x0ff00000;
+/// Bits of `sh_flags` reserved for processor specific semantics
+pub const SHF_MASKPROC: u64 = 0xf0000000;
+pub const SHF_X86_64_LARGE: u64 = 0x10000000;
+pub const SHF_ARM_PURECODE: u64 = 0x20000000;
+pub const SHF_PPC_VLE: u64 = 0x10000000;
+
+/// `SHF_GNU_RETAIN` is only given a letter for GNU and FreeBSD objects
+fn retain_flag(os_abi: u8) -> bool {
+    matches!(os_abi, ELFOSABI_GNU | ELFOSABI_FREEBSD)
+}
+
+/// `SHF_GNU_MBIND` is only given a letter for GNU, FreeBSD and unmarked objects
+fn mbind_flag(os_abi: u8) -> bool {
+    matches!(os_abi, ELFOSABI_NONE | ELFOSABI_GNU | ELFOSABI_FREEBSD)
+}
+
+/// The "Key to Flags" legend readelf prints under the section headers, listing the
+/// letters [`ElfShdr::flag_letters`] can produce for the given file
+pub fn flag_key(machine: u16, os_abi: u8) -> String {
+    let mut key = String::from(
+        "Key to Flags:\n  W (write), A (alloc), X (execute), M (merge), S (strings), I (info),\n  \
+         L (link order), O (extra OS processing required), G (group), T (TLS),\n  \
+         C (compressed), x (unknown), o (OS specific), E (exclude),\n  ",
+    );
+    if retain_flag(os_abi) {
+        key.push_str("R (retain), ");
+    }
+    if mbind_flag(os_abi) {
+        key.push_str("D (mbind), ");
+    }
+    match machine {
+        EM_X86_64 => key.push_str("l (large), "),
+        EM_ARM => key.push_str("y (purecode), "),
+        EM_PPC => key.push_str("v (VLE), "),
+        _ => {}
+    }
+    key.push_str("p (processor specific)");
+    key
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ElfShdr, SectionFlag, SectionType, SHF_X86_64_LARGE};
+    use crate::elf::{ELFOSABI_GNU, ELFOSABI_NONE, EM_AARCH64, EM_X86_64};
+
+    #[test]
+    fn os_and_processor_flag_letters() {
+        let mut shdr = ElfShdr::synthetic(SectionType::ProgBits, 0, 0, 0, 0);
+        if let ElfShdr::Elf64Shdr(shdr) = &mut shdr {
+            shdr.flags |= SectionFlag::Write as u64
+                | SectionFlag::GnuRetain as u64
+                | 0x0400_0000
+                | SHF_X86_64_LARGE;
+        }
+
+        assert_eq!(shdr.flag_letters(EM_X86_64, ELFOSABI_GNU), "WARol");
+        assert_eq!(shdr.flag_letters(EM_AARCH64, ELFOSABI_NONE), "WAoop");
+    }
+
+    #[test]
+    fn names_unknown_section_types() {
+        let mut shdr = ElfShdr::synthetic(SectionType::Relr, 0, 0, 0, 0);
+        assert_eq!(shdr.type_name(), "RELR");
+        for (raw, name) in [
+            (0x7000_0005, "LOPROC+0x5"),
+            (0x6fff_f000, "LOOS+0xffff000"),
+            (0x1_2345, "00012345: <unknown>"),
+        ] {
+            if let ElfShdr::Elf64Shdr(shdr) = &mut shdr {
+                shdr.section_type = raw;
+            }
+            assert_eq!(shdr.type_name(), name);
+        }
+    }
 }
@@ -0,0 +1,173 @@
+use std::{
+    fs::File,
+    io::{self, Read, Seek, SeekFrom},
+    ops::Deref,
+    sync::{Mutex, PoisonError},
+};
+
+/// Anything an ELF image can be read from: a file, a mapping, an in-memory buffer on targets
+/// without a filesystem such as wasm32, or any reader wrapped in [`Locked`].
+///
+/// Reads are positioned instead of seek-then-read so a [`FileData`](super::core::FileData)
+/// needs no exclusive access and can be queried from several threads at once.
+pub trait Source: Send + Sync {
+    /// Reads at most `buf.len()` bytes at `offset`, like `pread(2)`
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize>;
+
+    /// Total size of the image in bytes
+    fn size(&self) -> io::Result<u64>;
+
+    /// Fills `buf` from `offset`, failing with `UnexpectedEof` when the image ends first
+    fn read_exact_at(&self, mut buf: &mut [u8], mut offset: u64) -> io::Result<()> {
+        while !buf.is_empty() {
+            match self.read_at(buf, offset) {
+                Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+                Ok(read) => {
+                    buf = &mut buf[read..];
+                    offset += read as u64;
+                }
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(())
+    }
+}
+
+fn read_slice(data: &[u8], buf: &mut [u8], offset: u64) -> usize {
+    let start = usize::try_from(offset)
+        .unwrap_or(usize::MAX)
+        .min(data.len());
+    let len = buf.len().min(data.len() - start);
+    buf[..len].copy_from_slice(&data[start..start + len]);
+    len
+}
+
+#[cfg(unix)]
+impl Source for File {
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+        std::os::unix::fs::FileExt::read_at(self, buf, offset)
+    }
+
+    fn size(&self) -> io::Result<u64> {
+        Ok(self.metadata()?.len())
+    }
+}
+
+#[cfg(windows)]
+impl Source for File {
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+        std::os::windows::fs::FileExt::seek_read(self, buf, offset)
+    }
+
+    fn size(&self) -> io::Result<u64> {
+        Ok(self.metadata()?.len())
+    }
+}
+
+impl Source for Vec<u8> {
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+        Ok(read_slice(self, buf, offset))
+    }
+
+    fn size(&self) -> io::Result<u64> {
+        Ok(self.len() as u64)
+    }
+}
+
+#[cfg(unix)]
+impl Source for super::mmap::Mmap {
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+        Ok(read_slice(self, buf, offset))
+    }
+
+    fn size(&self) -> io::Result<u64> {
+        Ok(self.len() as u64)
+    }
+}
+
+/// Shares a `Read + Seek` reader between threads by serializing access to it
+pub struct Locked<R>(Mutex<R>);
+
+impl<R> Locked<R> {
+    pub fn new(reader: R) -> Self {
+        Self(Mutex::new(reader))
+    }
+}
+
+impl<R: Read + Seek + Send> Source for Locked<R> {
+    fn read_at(&self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+        let mut reader = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        reader.seek(SeekFrom::Start(offset))?;
+        reader.read(buf)
+    }
+
+    fn size(&self) -> io::Result<u64> {
+        let mut reader = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        reader.seek(SeekFrom::End(0))
+    }
+}
+
+/// A `Read + Seek` cursor over a [`Source`], for the decoders that read sequentially
+pub struct SourceReader<S> {
+    source: S,
+    pos: u64,
+}
+
+impl<S: Deref> SourceReader<S>
+where
+    S::Target: Source,
+{
+    pub fn new(source: S) -> Self {
+        Self { source, pos: 0 }
+    }
+}
+
+impl<S: Deref> Read for SourceReader<S>
+where
+    S::Target: Source,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.source.read_at(buf, self.pos)?;
+        self.pos += read as u64;
+        Ok(read)
+    }
+}
+
+impl<S: Deref> Seek for SourceReader<S>
+where
+    S::Target: Source,
+{
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let (base, delta) = match pos {
+            SeekFrom::Start(offset) => (offset, 0),
+            SeekFrom::End(delta) => (self.source.size()?, delta),
+            SeekFrom::Current(delta) => (self.pos, delta),
+        };
+        self.pos = base.checked_add_signed(delta).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "seek to a negative offset")
+        })?;
+        Ok(self.pos)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{io::Cursor, thread};
+
+    use crate::elf::core::FileData;
+
+    #[test]
+    fn queries_from_several_threads() {
+        let path = std::env::current_exe().unwrap();
+        let elf = FileData::new(&path).unwrap();
+        let locked = FileData::from_reader(Cursor::new(std::fs::read(&path).unwrap())).unwrap();
+        let expected = elf.table_symbols().unwrap();
+
+        thread::scope(|scope| {
+            for elf in [&elf, &locked, &elf, &locked] {
+                scope.spawn(|| assert_eq!(elf.table_symbols().unwrap(), expected));
+            }
+        });
+    }
+}
@@ -1,16 +1,21 @@
-use std::{
-    io::{self, Read, Seek, SeekFrom},
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{
+    fmt::{self, Display},
     ptr,
 };
+#[cfg(feature = "std")]
+use std::io::{self, Read, Seek, SeekFrom};
 
-use num::ToPrimitive;
 use num_derive::{FromPrimitive, ToPrimitive};
-use num_traits::FromPrimitive;
+use num_traits::{FromPrimitive, ToPrimitive};
 
-use super::{
-    internal::get_data, shdr::ElfShdr, Elf32Addr, Elf32Half, Elf32Word, Elf64Addr, Elf64Half,
-    Elf64Word, Elf64Xword, ElfHdr,
-};
+#[cfg(feature = "std")]
+use super::{internal::get_data, shdr::ElfShdr, ElfHdr};
+use super::{Elf32Addr, Elf32Half, Elf32Word, Elf64Addr, Elf64Half, Elf64Word, Elf64Xword};
 
 #[repr(C, packed)]
 pub struct Elf32Sym {
@@ -48,7 +53,7 @@ pub struct ElfSym {
     other: u8,
 }
 
-#[derive(Debug, FromPrimitive, ToPrimitive)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SymbolType {
     NoType,
     Object,
@@ -59,22 +64,59 @@ pub enum SymbolType {
     Tls,
     Relc,
     SRelc,
-    Loos,
-    GnuIFunc = 10,
-    HiOS = 12,
-    LoProc,
-    HiProc = 15,
+    /// `STT_GNU_IFUNC`, the first of the OS specific types
+    GnuIFunc,
+    /// Any other type in `STT_LOOS..=STT_HIOS`
+    Os(u8),
+    /// A type in `STT_LOPROC..=STT_HIPROC`
+    Proc(u8),
 }
 
-#[derive(Debug, FromPrimitive, ToPrimitive)]
+impl SymbolType {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        Some(match value {
+            0 => Self::NoType,
+            1 => Self::Object,
+            2 => Self::Func,
+            3 => Self::Section,
+            4 => Self::File,
+            5 => Self::Common,
+            6 => Self::Tls,
+            8 => Self::Relc,
+            9 => Self::SRelc,
+            10 => Self::GnuIFunc,
+            11..=12 => Self::Os(value),
+            13..=15 => Self::Proc(value),
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SymbolBinding {
     Local,
-    Global = 1,
-    Weak = 2,
-    Loos = 10,
-    HiOS = 12,
-    LoPROC = 13,
-    HiPROC = 15,
+    Global,
+    Weak,
+    /// `STB_GNU_UNIQUE`, the first of the OS specific bindings
+    GnuUnique,
+    /// Any other binding in `STB_LOOS..=STB_HIOS`
+    Os(u8),
+    /// A binding in `STB_LOPROC..=STB_HIPROC`
+    Proc(u8),
+}
+
+impl SymbolBinding {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        Some(match value {
+            0 => Self::Local,
+            1 => Self::Global,
+            2 => Self::Weak,
+            10 => Self::GnuUnique,
+            11..=12 => Self::Os(value),
+            13..=15 => Self::Proc(value),
+            _ => return None,
+        })
+    }
 }
 
 #[derive(Debug, FromPrimitive, ToPrimitive)]
@@ -86,6 +128,7 @@ pub enum SymbolVis {
 }
 
 impl ElfSym {
+    #[cfg(feature = "std")]
     pub fn read_symbols<R: Seek + Read>(
         file: &mut R,
         hdr: &ElfHdr,
@@ -140,29 +183,145 @@ impl ElfSym {
         self.shndx
     }
 
+    /// The value as an address in a copy of the file loaded with `bias`. Undefined,
+    /// absolute and common symbols and thread local offsets are left alone.
+    pub fn rebased_value(&self, bias: u64) -> Elf64Addr {
+        match (self.shndx, self.symbol_type()) {
+            (0 | 0xff00..=0xfffe, _) | (_, Some(SymbolType::Tls)) => self.value,
+            _ => self.value.wrapping_add(bias),
+        }
+    }
+
     pub fn other(&self) -> u8 {
         self.other
     }
 }
 
-impl SymbolType {
-    pub fn display(&self) -> String {
-        format!("{:?}", self).to_uppercase()
+/// Defined symbols sorted by address, used to turn addresses back into `name+offset`
+#[derive(Debug, Clone, Default)]
+pub struct SymbolMap {
+    /// `(address, size, name)` entries
+    symbols: Vec<(Elf64Addr, Elf64Xword, String)>,
+}
+
+impl SymbolMap {
+    pub fn new<I: IntoIterator<Item = (Elf64Addr, Elf64Xword, String)>>(symbols: I) -> Self {
+        let mut symbols = symbols
+            .into_iter()
+            .filter(|(_, _, name)| !name.is_empty())
+            .collect::<Vec<_>>();
+        symbols.sort();
+        symbols.dedup_by(|a, b| a.0 == b.0 && a.2 == b.2);
+
+        Self { symbols }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+
+    /// Finds the symbol covering `addr`, preferring sized symbols over the closest
+    /// preceding label
+    pub fn lookup(&self, addr: Elf64Addr) -> Option<(&str, Elf64Addr)> {
+        let end = self.symbols.partition_point(|(start, _, _)| *start <= addr);
+        let candidates = &self.symbols[..end];
+
+        candidates
+            .iter()
+            .rev()
+            .find(|(start, size, _)| addr < start + size)
+            .or_else(|| candidates.last().filter(|(_, size, _)| *size == 0))
+            .map(|(start, _, name)| (name.as_str(), addr - start))
+    }
+
+    pub fn describe(&self, addr: Elf64Addr) -> Option<String> {
+        self.describe_from(addr, 0)
+    }
+
+    /// Like [`SymbolMap::describe`] but ignores symbols starting below `start`, typically
+    /// the base of the section containing `addr`
+    pub fn describe_from(&self, addr: Elf64Addr, start: Elf64Addr) -> Option<String> {
+        self.lookup(addr)
+            .filter(|(_, offset)| addr - offset >= start)
+            .map(|(name, offset)| match offset {
+                0 => name.to_string(),
+                _ => format!("{}+0x{:x}", name, offset),
+            })
     }
 }
 
-impl SymbolBinding {
-    pub fn display(&self) -> String {
-        format!("{:?}", self).to_uppercase()
+impl Display for SymbolType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Self::NoType => "NOTYPE",
+            Self::Object => "OBJECT",
+            Self::Func => "FUNC",
+            Self::Section => "SECTION",
+            Self::File => "FILE",
+            Self::Common => "COMMON",
+            Self::Tls => "TLS",
+            Self::Relc => "RELC",
+            Self::SRelc => "SRELC",
+            Self::GnuIFunc => "IFUNC",
+            Self::Os(value) => return f.pad(&format!("<OS: {}>", value)),
+            Self::Proc(value) => return f.pad(&format!("<PROC: {}>", value)),
+        })
+    }
+}
+
+impl Display for SymbolBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Self::Local => "LOCAL",
+            Self::Global => "GLOBAL",
+            Self::Weak => "WEAK",
+            Self::GnuUnique => "UNIQUE",
+            Self::Os(value) => return f.pad(&format!("<OS: {}>", value)),
+            Self::Proc(value) => return f.pad(&format!("<PROC: {}>", value)),
+        })
     }
 }
 
-impl SymbolVis {
-    pub fn display(&self) -> String {
-        format!("{:?}", self).to_uppercase()
+impl Display for SymbolVis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Self::Default => "DEFAULT",
+            Self::Internal => "INTERNAL",
+            Self::Hidden => "HIDDEN",
+            Self::Protected => "PROTECTED",
+        })
     }
 }
 
+/// Looks up a symbol type by the name it is displayed with (`func`, `object`, `ifunc`, ...)
+/// or its `STT_*` constant name
+pub fn symbol_type_by_name(name: &str) -> Option<SymbolType> {
+    let name = name.to_ascii_lowercase();
+    Some(match name.strip_prefix("stt_").unwrap_or(&name) {
+        "notype" => SymbolType::NoType,
+        "object" => SymbolType::Object,
+        "func" | "function" => SymbolType::Func,
+        "section" => SymbolType::Section,
+        "file" => SymbolType::File,
+        "common" => SymbolType::Common,
+        "tls" => SymbolType::Tls,
+        "ifunc" | "gnu_ifunc" => SymbolType::GnuIFunc,
+        _ => return None,
+    })
+}
+
+/// Looks up a symbol binding by the name it is displayed with or its `STB_*` constant name
+pub fn symbol_binding_by_name(name: &str) -> Option<SymbolBinding> {
+    let name = name.to_ascii_lowercase();
+    Some(match name.strip_prefix("stb_").unwrap_or(&name) {
+        "local" => SymbolBinding::Local,
+        "global" => SymbolBinding::Global,
+        "weak" => SymbolBinding::Weak,
+        "unique" | "gnu_unique" => SymbolBinding::GnuUnique,
+        _ => return None,
+    })
+}
+
 // See https://github.com/rust-lang/rust/issues/82523
 impl From<Elf32Sym> for ElfSym {
     fn from(sym: Elf32Sym) -> Self {
@@ -0,0 +1,65 @@
+use alloc::vec::Vec;
+
+use super::{bytes::ByteReader, hdr::Endian};
+
+/// `si_boundto` of a symbol bound to the object itself
+pub const SYMINFO_BT_SELF: u16 = 0xffff;
+/// `si_boundto` of a symbol bound to the parent object
+pub const SYMINFO_BT_PARENT: u16 = 0xfffe;
+/// `si_boundto` of a symbol with no special binding, Solaris only
+pub const SYMINFO_BT_NONE: u16 = 0xfffd;
+/// `si_boundto` of a symbol defined externally, Solaris only
+pub const SYMINFO_BT_EXTERN: u16 = 0xfffc;
+
+pub const SYMINFO_FLG_DIRECT: u16 = 0x0001;
+pub const SYMINFO_FLG_PASSTHRU: u16 = 0x0002;
+pub const SYMINFO_FLG_COPY: u16 = 0x0004;
+pub const SYMINFO_FLG_LAZYLOAD: u16 = 0x0008;
+pub const SYMINFO_FLG_DIRECTBIND: u16 = 0x0010;
+pub const SYMINFO_FLG_NOEXTDIRECT: u16 = 0x0020;
+pub const SYMINFO_FLG_AUXILIARY: u16 = 0x0040;
+pub const SYMINFO_FLG_INTERPOSE: u16 = 0x0080;
+pub const SYMINFO_FLG_CAP: u16 = 0x0100;
+pub const SYMINFO_FLG_DEFERRED: u16 = 0x0200;
+
+/// One `Elf_Syminfo` entry of a `SHT_SUNW_syminfo` section, describing the dynamic symbol
+/// at the same index. The layout is the same for both classes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Syminfo {
+    /// `SYMINFO_BT_*`, or the index of the `DT_NEEDED` entry the symbol binds to
+    pub boundto: u16,
+    pub flags: u16,
+}
+
+impl Syminfo {
+    pub fn parse(data: &[u8], endian: Endian) -> Vec<Self> {
+        let mut reader = ByteReader::new(data, endian);
+        core::iter::from_fn(|| {
+            Some(Self {
+                boundto: reader.u16()?,
+                flags: reader.u16()?,
+            })
+        })
+        .collect()
+    }
+
+    /// Names of the set `SYMINFO_FLG_*` bits
+    pub fn flag_names(&self) -> Vec<&'static str> {
+        [
+            (SYMINFO_FLG_DIRECT, "DIRECT"),
+            (SYMINFO_FLG_PASSTHRU, "PASSTHRU"),
+            (SYMINFO_FLG_COPY, "COPY"),
+            (SYMINFO_FLG_LAZYLOAD, "LAZYLOAD"),
+            (SYMINFO_FLG_DIRECTBIND, "DIRECTBIND"),
+            (SYMINFO_FLG_NOEXTDIRECT, "NOEXTDIRECT"),
+            (SYMINFO_FLG_AUXILIARY, "AUXILIARY"),
+            (SYMINFO_FLG_INTERPOSE, "INTERPOSE"),
+            (SYMINFO_FLG_CAP, "CAP"),
+            (SYMINFO_FLG_DEFERRED, "DEFERRED"),
+        ]
+        .iter()
+        .filter(|(flag, _)| self.flags & flag != 0)
+        .map(|&(_, name)| name)
+        .collect()
+    }
+}
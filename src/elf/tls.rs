@@ -0,0 +1,187 @@
+use std::io;
+
+use super::{
+    bytes::string_at, core::FileData, hdr::ObjectType, phdr::ProgramType, EM_AARCH64, EM_ARM,
+    EM_X86_64,
+};
+
+/// What the dynamic linker stores at a TLS relocation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TlsRelocKind {
+    /// `DTPMOD`: the module ID of the object defining the variable
+    ModuleId,
+    /// `DTPOFF`/`DTPREL`: the offset of the variable in its module's TLS block
+    ModuleOffset,
+    /// `TPOFF`/`TPREL`: the offset of the variable from the thread pointer
+    ThreadPointerOffset,
+    /// `TLSDESC`: a descriptor whose resolver returns the thread pointer offset
+    Descriptor,
+}
+
+impl TlsRelocKind {
+    /// What the value means to the code reading it, and the access model it belongs to
+    pub fn meaning(&self) -> &'static str {
+        match self {
+            Self::ModuleId => "module ID, first __tls_get_addr argument (dynamic TLS)",
+            Self::ModuleOffset => {
+                "offset in the module's TLS block, second __tls_get_addr argument"
+            }
+            Self::ThreadPointerOffset => {
+                "offset from the thread pointer (static TLS, initial exec)"
+            }
+            Self::Descriptor => "descriptor resolved to a thread pointer offset (TLS descriptors)",
+        }
+    }
+}
+
+/// The kind and binutils name of a dynamic TLS relocation type of the given `e_machine`
+pub fn tls_reloc(machine: u16, rtype: u32) -> Option<(TlsRelocKind, &'static str)> {
+    use TlsRelocKind::*;
+
+    Some(match (machine, rtype) {
+        (EM_X86_64, 16) => (ModuleId, "R_X86_64_DTPMOD64"),
+        (EM_X86_64, 17) => (ModuleOffset, "R_X86_64_DTPOFF64"),
+        (EM_X86_64, 18) => (ThreadPointerOffset, "R_X86_64_TPOFF64"),
+        (EM_X86_64, 21) => (ModuleOffset, "R_X86_64_DTPOFF32"),
+        (EM_X86_64, 23) => (ThreadPointerOffset, "R_X86_64_TPOFF32"),
+        (EM_X86_64, 36) => (Descriptor, "R_X86_64_TLSDESC"),
+        (EM_AARCH64, 1028) => (ModuleId, "R_AARCH64_TLS_DTPMOD"),
+        (EM_AARCH64, 1029) => (ModuleOffset, "R_AARCH64_TLS_DTPREL"),
+        (EM_AARCH64, 1030) => (ThreadPointerOffset, "R_AARCH64_TLS_TPREL"),
+        (EM_AARCH64, 1031) => (Descriptor, "R_AARCH64_TLSDESC"),
+        (EM_ARM, 13) => (Descriptor, "R_ARM_TLS_DESC"),
+        (EM_ARM, 17) => (ModuleId, "R_ARM_TLS_DTPMOD32"),
+        (EM_ARM, 18) => (ModuleOffset, "R_ARM_TLS_DTPOFF32"),
+        (EM_ARM, 19) => (ThreadPointerOffset, "R_ARM_TLS_TPOFF32"),
+        _ => return None,
+    })
+}
+
+/// The `PT_TLS` segment, the initialization image every thread's block is copied from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TlsTemplate {
+    pub vaddr: u64,
+    /// Bytes of initialized data (`.tdata`), the rest up to `memsz` is zeroed (`.tbss`)
+    pub filesz: u64,
+    pub memsz: u64,
+    pub align: u64,
+}
+
+impl TlsTemplate {
+    pub fn read(elf: &FileData) -> Option<Self> {
+        elf.program_headers()
+            .iter()
+            .find(|phdr| phdr.program_type() == Some(ProgramType::Tls))
+            .map(|phdr| Self {
+                vaddr: phdr.vaddr(),
+                filesz: phdr.filesz(),
+                memsz: phdr.memsz(),
+                align: phdr.align(),
+            })
+    }
+
+    /// Where the thread pointer sees offset `offset` of the main executable's block, whose
+    /// place in static TLS is fixed by the ABI. x86-64 puts the block just below the thread
+    /// pointer, AArch64 and ARM just above a 16 or 8 byte thread control block.
+    pub fn thread_pointer_offset(&self, machine: u16, offset: u64) -> Option<i64> {
+        let align = self.align.max(1);
+        let round = |n: u64| n.checked_add(align - 1).map(|n| n / align * align);
+        match machine {
+            EM_X86_64 => Some(offset as i64 - round(self.memsz)? as i64),
+            EM_AARCH64 => Some((round(16)? + offset) as i64),
+            EM_ARM => Some((round(8)? + offset) as i64),
+            _ => None,
+        }
+    }
+}
+
+/// A dynamic TLS relocation and the variable it refers to
+#[derive(Debug, Clone)]
+pub struct TlsReloc {
+    pub offset: u64,
+    pub kind: TlsRelocKind,
+    pub type_name: &'static str,
+    /// Empty for relocations against the module itself, with the offset in the addend
+    pub symbol: String,
+    /// Offset of the variable in this file's TLS template, when it is defined here
+    pub template_offset: Option<u64>,
+    /// Offset from the thread pointer, known up front for the main executable only
+    pub thread_pointer_offset: Option<i64>,
+}
+
+impl TlsReloc {
+    pub fn read(elf: &FileData) -> io::Result<Vec<Self>> {
+        let machine = elf.header().machine();
+        let template = TlsTemplate::read(elf);
+        // Only the executable's block sits at an offset the ABI fixes
+        let main_program = elf.header().ftype() == Some(ObjectType::Exec) || elf.is_pie();
+        let mut relocs = Vec::new();
+
+        for (shdr, rels) in elf.relocations()? {
+            if !rels
+                .iter()
+                .any(|rel| tls_reloc(machine, rel.rtype()).is_some())
+            {
+                continue;
+            }
+            let (strtab, syms) = match elf.section_headers().get(shdr.link() as usize) {
+                Some(symtab) if shdr.link() != 0 => elf.symbol_table(symtab)?,
+                _ => Default::default(),
+            };
+
+            for rel in &rels {
+                let (kind, type_name) = match tls_reloc(machine, rel.rtype()) {
+                    Some(tls) => tls,
+                    None => continue,
+                };
+                let sym = syms.get(rel.sym() as usize).filter(|_| rel.sym() != 0);
+                let addend = rel.addend().unwrap_or(0) as u64;
+                // TLS symbol values are offsets in the template already
+                let template_offset = match sym {
+                    _ if kind == TlsRelocKind::ModuleId => None,
+                    Some(sym) if sym.shndx() == 0 => None,
+                    Some(sym) => Some(sym.value().wrapping_add(addend)),
+                    None => Some(addend),
+                };
+                let thread_pointer_offset = match (template, template_offset) {
+                    (Some(template), Some(offset))
+                        if main_program && kind == TlsRelocKind::ThreadPointerOffset =>
+                    {
+                        template.thread_pointer_offset(machine, offset)
+                    }
+                    _ => None,
+                };
+
+                relocs.push(Self {
+                    offset: rel.offset(),
+                    kind,
+                    type_name,
+                    symbol: sym.map_or(String::new(), |sym| string_at(strtab, sym.name() as usize)),
+                    template_offset,
+                    thread_pointer_offset,
+                });
+            }
+        }
+
+        Ok(relocs)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::TlsTemplate;
+    use crate::elf::{EM_AARCH64, EM_X86_64};
+
+    #[test]
+    fn static_tls_offsets() {
+        let template = TlsTemplate {
+            vaddr: 0x3de8,
+            filesz: 0x4,
+            memsz: 0x14,
+            align: 0x8,
+        };
+        assert_eq!(template.thread_pointer_offset(EM_X86_64, 0), Some(-0x18));
+        assert_eq!(template.thread_pointer_offset(EM_X86_64, 0x10), Some(-0x8));
+        assert_eq!(template.thread_pointer_offset(EM_AARCH64, 0x4), Some(0x14));
+    }
+}
@@ -0,0 +1,334 @@
+use std::io;
+
+use super::{
+    bytes::ByteReader,
+    core::FileData,
+    hdr::{ElfClass, Endian},
+    phdr::ProgramType,
+};
+
+pub const DW_EH_PE_OMIT: u8 = 0xff;
+pub const DW_EH_PE_INDIRECT: u8 = 0x80;
+
+/// Describes a `DW_EH_PE_*` pointer encoding the way binutils spells it
+pub fn encoding_name(encoding: u8) -> String {
+    if encoding == DW_EH_PE_OMIT {
+        return "omit".to_string();
+    }
+
+    let format = match encoding & 0x0f {
+        0x00 => "absptr",
+        0x01 => "uleb128",
+        0x02 => "udata2",
+        0x03 => "udata4",
+        0x04 => "udata8",
+        0x09 => "sleb128",
+        0x0a => "sdata2",
+        0x0b => "sdata4",
+        0x0c => "sdata8",
+        _ => "unknown",
+    };
+    let application = match encoding & 0x70 {
+        0x00 => "",
+        0x10 => "pcrel ",
+        0x20 => "textrel ",
+        0x30 => "datarel ",
+        0x40 => "funcrel ",
+        0x50 => "aligned ",
+        _ => "unknown ",
+    };
+    let indirect = if encoding & DW_EH_PE_INDIRECT != 0 {
+        "indirect "
+    } else {
+        ""
+    };
+
+    format!("{}{}{}", indirect, application, format)
+}
+
+/// Bases the relative `DW_EH_PE_*` applications are computed against
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EncodingBases {
+    /// Virtual address of the first byte of the reader's data, for `pcrel`
+    pub section: u64,
+    pub data: u64,
+    pub text: u64,
+    pub func: u64,
+}
+
+/// Reads a pointer in the given `DW_EH_PE_*` encoding. `indirect` pointers are returned
+/// as the address of the slot holding the real value.
+pub fn read_encoded(
+    reader: &mut ByteReader,
+    encoding: u8,
+    ptr_size: usize,
+    bases: &EncodingBases,
+) -> Option<u64> {
+    if encoding == DW_EH_PE_OMIT {
+        return None;
+    }
+
+    let pc = bases.section.wrapping_add(reader.pos() as u64);
+    if encoding & 0x70 == 0x50 {
+        let aligned = (reader.pos() + ptr_size - 1) & !(ptr_size - 1);
+        reader.seek(aligned)?;
+    }
+
+    let value = match encoding & 0x0f {
+        0x00 => reader.word(ptr_size)?,
+        0x01 => reader.uleb128()?,
+        0x02 => reader.u16()? as u64,
+        0x03 => reader.u32()? as u64,
+        0x04 => reader.u64()?,
+        0x09 => reader.sleb128()? as u64,
+        0x0a => reader.u16()? as i16 as i64 as u64,
+        0x0b => reader.u32()? as i32 as i64 as u64,
+        0x0c => reader.u64()?,
+        _ => return None,
+    };
+
+    let base = match encoding & 0x70 {
+        0x10 => pc,
+        0x20 => bases.text,
+        0x30 => bases.data,
+        0x40 => bases.func,
+        _ => 0,
+    };
+
+    Some(base.wrapping_add(value))
+}
+
+#[derive(Debug, Clone)]
+pub struct EhFrameHdr {
+    /// Virtual address of `.eh_frame_hdr`
+    pub address: u64,
+    pub offset: u64,
+    pub size: u64,
+    pub version: u8,
+    pub eh_frame_ptr_enc: u8,
+    pub fde_count_enc: u8,
+    pub table_enc: u8,
+    pub eh_frame_ptr: Option<u64>,
+    /// `(initial location, FDE address)` pairs, sorted by initial location
+    pub table: Vec<(u64, u64)>,
+}
+
+impl EhFrameHdr {
+    pub fn parse(data: &[u8], address: u64, class: ElfClass, endian: Endian) -> Option<Self> {
+        let ptr_size = match class {
+            ElfClass::ElfClass32 => 4,
+            _ => 8,
+        };
+        let bases = EncodingBases {
+            section: address,
+            data: address,
+            ..Default::default()
+        };
+
+        let mut reader = ByteReader::new(data, endian);
+        let version = reader.u8()?;
+        let eh_frame_ptr_enc = reader.u8()?;
+        let fde_count_enc = reader.u8()?;
+        let table_enc = reader.u8()?;
+        let eh_frame_ptr = read_encoded(&mut reader, eh_frame_ptr_enc, ptr_size, &bases);
+        let fde_count = read_encoded(&mut reader, fde_count_enc, ptr_size, &bases).unwrap_or(0);
+
+        let mut table = Vec::new();
+        if table_enc != DW_EH_PE_OMIT {
+            for _ in 0..fde_count {
+                let initial = read_encoded(&mut reader, table_enc, ptr_size, &bases);
+                let fde = read_encoded(&mut reader, table_enc, ptr_size, &bases);
+                match (initial, fde) {
+                    (Some(initial), Some(fde)) => table.push((initial, fde)),
+                    _ => break,
+                }
+            }
+        }
+
+        Some(EhFrameHdr {
+            address,
+            offset: 0,
+            size: data.len() as u64,
+            version,
+            eh_frame_ptr_enc,
+            fde_count_enc,
+            table_enc,
+            eh_frame_ptr,
+            table,
+        })
+    }
+
+    /// Locates the header through `.eh_frame_hdr`, or `PT_GNU_EH_FRAME` without sections
+    pub fn read(elf: &FileData) -> io::Result<Option<Self>> {
+        let class = elf.header().class().unwrap_or(ElfClass::ElfClass64);
+        let endian = elf.header().endian().unwrap_or(Endian::Little);
+
+        let location = match elf.section_by_name(".eh_frame_hdr") {
+            Some(shdr) => Some((shdr.addr(), shdr.offset(), shdr.size())),
+            None => elf
+                .program_headers()
+                .iter()
+                .find(|phdr| phdr.program_type() == Some(ProgramType::GnuEhFrame))
+                .map(|phdr| (phdr.vaddr(), phdr.offset(), phdr.filesz())),
+        };
+        let (address, offset, size) = match location {
+            Some(location) => location,
+            None => return Ok(None),
+        };
+
+        let data = elf.read_at(offset, size as usize)?;
+        Ok(Self::parse(&data, address, class, endian).map(|hdr| EhFrameHdr { offset, ..hdr }))
+    }
+
+    /// Whether the search table is sorted, the unwinder relies on it for binary search
+    pub fn is_sorted(&self) -> bool {
+        self.table.windows(2).all(|pair| pair[0].0 <= pair[1].0)
+    }
+}
+
+/// The augmentation fields of a CIE that matter for locating FDE contents
+#[derive(Debug, Clone, Default)]
+pub struct Cie {
+    pub offset: usize,
+    pub version: u8,
+    pub augmentation: String,
+    pub fde_encoding: u8,
+    pub lsda_encoding: Option<u8>,
+    pub personality: Option<u64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Fde {
+    pub offset: usize,
+    pub cie: usize,
+    pub pc_begin: u64,
+    pub pc_range: u64,
+    /// Address of the language specific data area, for CIEs with an `L` augmentation
+    pub lsda: Option<u64>,
+    pub personality: Option<u64>,
+}
+
+/// Walks `.eh_frame`, returning every FDE with the CIE fields applied
+pub fn parse_eh_frame(data: &[u8], address: u64, class: ElfClass, endian: Endian) -> Vec<Fde> {
+    let ptr_size = match class {
+        ElfClass::ElfClass32 => 4,
+        _ => 8,
+    };
+    let bases = EncodingBases {
+        section: address,
+        ..Default::default()
+    };
+    let mut cies: Vec<Cie> = Vec::new();
+    let mut fdes = Vec::new();
+    let mut reader = ByteReader::new(data, endian);
+
+    while reader.remaining() >= 4 {
+        let offset = reader.pos();
+        let entry = (|| {
+            let mut length = reader.u32()? as u64;
+            if length == 0 {
+                return Some(reader.pos());
+            }
+            if length == 0xffffffff {
+                length = reader.u64()?;
+            }
+            let id_pos = reader.pos();
+            let end = id_pos.checked_add(length as usize)?;
+            let id = reader.u32()?;
+
+            if id == 0 {
+                let mut cie = Cie {
+                    offset,
+                    version: reader.u8()?,
+                    augmentation: String::from_utf8_lossy(reader.cstr()?).into_owned(),
+                    ..Default::default()
+                };
+                if cie.augmentation.contains("eh") {
+                    reader.word(ptr_size)?;
+                }
+                if cie.version >= 4 {
+                    reader.skip(2)?;
+                }
+                reader.uleb128()?;
+                reader.sleb128()?;
+                if cie.version == 1 {
+                    reader.u8()?;
+                } else {
+                    reader.uleb128()?;
+                }
+
+                if cie.augmentation.starts_with('z') {
+                    let length = reader.uleb128()? as usize;
+                    let aug_end = reader.pos() + length;
+                    for c in cie.augmentation.clone().chars().skip(1) {
+                        match c {
+                            'L' => cie.lsda_encoding = Some(reader.u8()?),
+                            'R' => cie.fde_encoding = reader.u8()?,
+                            'P' => {
+                                let encoding = reader.u8()?;
+                                cie.personality =
+                                    read_encoded(&mut reader, encoding, ptr_size, &bases);
+                            }
+                            _ => {}
+                        }
+                    }
+                    reader.seek(aug_end)?;
+                }
+                cies.push(cie);
+            } else {
+                let cie_offset = id_pos.checked_sub(id as usize)?;
+                let cie = cies.iter().find(|cie| cie.offset == cie_offset)?;
+                let pc_begin = read_encoded(&mut reader, cie.fde_encoding, ptr_size, &bases)?;
+                let pc_range =
+                    read_encoded(&mut reader, cie.fde_encoding & 0x0f, ptr_size, &bases)?;
+                let mut lsda = None;
+                if cie.augmentation.starts_with('z') {
+                    let length = reader.uleb128()? as usize;
+                    let aug_end = reader.pos() + length;
+                    if let Some(encoding) = cie.lsda_encoding {
+                        lsda = read_encoded(&mut reader, encoding, ptr_size, &bases)
+                            .filter(|&lsda| lsda != 0);
+                    }
+                    reader.seek(aug_end)?;
+                }
+                fdes.push(Fde {
+                    offset,
+                    cie: cie_offset,
+                    pc_begin,
+                    pc_range,
+                    lsda,
+                    personality: cie.personality,
+                });
+            }
+
+            Some(end)
+        })();
+
+        match entry.and_then(|end| reader.seek(end)) {
+            Some(()) if reader.pos() > offset => {}
+            _ => break,
+        }
+    }
+
+    fdes
+}
+
+#[cfg(test)]
+mod test {
+    use super::{encoding_name, EhFrameHdr};
+    use crate::elf::hdr::{ElfClass, Endian};
+
+    #[test]
+    fn parses_datarel_table() {
+        let mut data = vec![1, 0x1b, 0x03, 0x3b];
+        data.extend(0x40i32.to_le_bytes());
+        data.extend(1u32.to_le_bytes());
+        data.extend((-0x1000i32).to_le_bytes());
+        data.extend(0x58i32.to_le_bytes());
+
+        let hdr = EhFrameHdr::parse(&data, 0x2000, ElfClass::ElfClass64, Endian::Little).unwrap();
+        assert_eq!(hdr.eh_frame_ptr, Some(0x2044));
+        assert_eq!(hdr.table, vec![(0x1000, 0x2058)]);
+        assert_eq!(encoding_name(hdr.table_enc), "datarel sdata4");
+    }
+}
@@ -1,6 +1,8 @@
 use std::io::{Read, Seek};
 
 use super::{
+    bytes::{string_at, ByteReader},
+    hdr::Endian,
     shdr::{ElfShdr, SectionType},
     Elf32Half, Elf32Word, Elf64Half, Elf64Word, ElfHdr,
 };
@@ -48,7 +50,7 @@ impl ElfVerdef {
     ) -> Option<Vec<Self>> {
         let versym = match shdrs
             .iter()
-            .find(|shdr| shdr.section_type().unwrap() == SectionType::VerSym)
+            .find(|shdr| shdr.section_type() == Some(SectionType::VerSym))
         {
             Some(versym) => versym,
             _ => return None,
@@ -132,9 +134,363 @@ impl From<&Elf64Verdef> for ElfVerdef {
     }
 }
 
+/// `VER_NDX_LOCAL` and `VER_NDX_GLOBAL` are reserved versym values
+pub const VER_NDX_LOCAL: u16 = 0;
+pub const VER_NDX_GLOBAL: u16 = 1;
+/// Set in a versym entry when the symbol version is hidden
+pub const VERSYM_HIDDEN: u16 = 0x8000;
+
+/// `vd_flags`/`vna_flags` bits
+pub const VER_FLG_BASE: u16 = 0x1;
+pub const VER_FLG_WEAK: u16 = 0x2;
+pub const VER_FLG_INFO: u16 = 0x4;
+
+/// Version flags the way readelf prints them, `none` or e.g. `BASE | WEAK`
+pub fn version_flags_name(flags: u16) -> String {
+    if flags == 0 {
+        return "none".to_string();
+    }
+
+    let mut names = Vec::new();
+    for (flag, name) in [
+        (VER_FLG_BASE, "BASE"),
+        (VER_FLG_WEAK, "WEAK"),
+        (VER_FLG_INFO, "INFO"),
+    ] {
+        if flags & flag != 0 {
+            names.push(name);
+        }
+    }
+    if flags & !(VER_FLG_BASE | VER_FLG_WEAK | VER_FLG_INFO) != 0 {
+        names.push("<unknown>");
+    }
+    names.join(" | ")
+}
+
+#[derive(Debug, Clone)]
+pub struct ElfVernaux {
+    /// Offset of the entry in the section
+    pub offset: usize,
+    pub hash: Elf64Word,
+    pub flags: Elf64Half,
+    /// Version index referenced by `.gnu.version` entries
+    pub other: Elf64Half,
+    pub name: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct ElfVerneed {
+    /// Offset of the entry in the section
+    pub offset: usize,
+    pub version: Elf64Half,
+    /// Shared object the versions are needed from
+    pub file: String,
+    pub aux: Vec<ElfVernaux>,
+}
+
+impl ElfVerneed {
+    /// Walks the `vn_next`/`vna_next` chains of a `SHT_GNU_verneed` section holding
+    /// `count` entries (its `sh_info`)
+    pub fn parse(data: &[u8], strtab: &[u8], count: usize, endian: Endian) -> Vec<Self> {
+        let mut needs = Vec::new();
+        let mut offset = 0;
+
+        for _ in 0..count {
+            let mut reader = ByteReader::new(data, endian);
+            let need = (|| {
+                reader.seek(offset)?;
+                let version = reader.u16()?;
+                let cnt = reader.u16()?;
+                let file = reader.u32()?;
+                let aux = reader.u32()? as usize;
+                let next = reader.u32()? as usize;
+
+                let mut entries = Vec::new();
+                let mut aux_offset = offset + aux;
+                for _ in 0..cnt {
+                    reader.seek(aux_offset)?;
+                    let hash = reader.u32()?;
+                    let flags = reader.u16()?;
+                    let other = reader.u16()?;
+                    let name = reader.u32()?;
+                    let aux_next = reader.u32()? as usize;
+                    entries.push(ElfVernaux {
+                        offset: aux_offset,
+                        hash,
+                        flags,
+                        other,
+                        name: string_at(strtab, name as usize),
+                    });
+                    if aux_next == 0 {
+                        break;
+                    }
+                    aux_offset += aux_next;
+                }
+
+                Some((
+                    ElfVerneed {
+                        offset,
+                        version,
+                        file: string_at(strtab, file as usize),
+                        aux: entries,
+                    },
+                    next,
+                ))
+            })();
+
+            match need {
+                Some((need, next)) => {
+                    needs.push(need);
+                    if next == 0 {
+                        break;
+                    }
+                    offset += next;
+                }
+                None => break,
+            }
+        }
+
+        needs
+    }
+}
+
+/// A `SHT_GNU_verdef` entry with its `Elf_Verdaux` names resolved
+#[derive(Debug, Clone)]
+pub struct VersionDef {
+    /// Offset of the entry in the section
+    pub offset: usize,
+    /// `vd_version`, the revision of the structure
+    pub version: Elf64Half,
+    pub flags: Elf64Half,
+    /// Version index referenced by `.gnu.version` entries
+    pub ndx: Elf64Half,
+    pub hash: Elf64Word,
+    /// The version name followed by the names of its parents
+    pub names: Vec<String>,
+    /// Offsets of the `Elf_Verdaux` entries holding `names`
+    pub name_offsets: Vec<usize>,
+}
+
+impl VersionDef {
+    /// Walks the `vd_next`/`vda_next` chains of a `SHT_GNU_verdef` section holding
+    /// `count` entries (its `sh_info`)
+    pub fn parse(data: &[u8], strtab: &[u8], count: usize, endian: Endian) -> Vec<Self> {
+        let mut defs = Vec::new();
+        let mut offset = 0;
+
+        for _ in 0..count {
+            let mut reader = ByteReader::new(data, endian);
+            let def = (|| {
+                reader.seek(offset)?;
+                let version = reader.u16()?;
+                let flags = reader.u16()?;
+                let ndx = reader.u16()?;
+                let cnt = reader.u16()?;
+                let hash = reader.u32()?;
+                let aux = reader.u32()? as usize;
+                let next = reader.u32()? as usize;
+
+                let mut names = Vec::new();
+                let mut name_offsets = Vec::new();
+                let mut aux_offset = offset + aux;
+                for _ in 0..cnt {
+                    reader.seek(aux_offset)?;
+                    let name = reader.u32()?;
+                    let aux_next = reader.u32()? as usize;
+                    names.push(string_at(strtab, name as usize));
+                    name_offsets.push(aux_offset);
+                    if aux_next == 0 {
+                        break;
+                    }
+                    aux_offset += aux_next;
+                }
+
+                Some((
+                    VersionDef {
+                        offset,
+                        version,
+                        flags,
+                        ndx,
+                        hash,
+                        names,
+                        name_offsets,
+                    },
+                    next,
+                ))
+            })();
+
+            match def {
+                Some((def, next)) => {
+                    defs.push(def);
+                    if next == 0 {
+                        break;
+                    }
+                    offset += next;
+                }
+                None => break,
+            }
+        }
+
+        defs
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.names.first().map(String::as_str)
+    }
+
+    /// Whether this is the definition of the file itself, named after its soname
+    pub fn is_base(&self) -> bool {
+        self.flags & VER_FLG_BASE != 0
+    }
+
+    pub fn is_weak(&self) -> bool {
+        self.flags & VER_FLG_WEAK != 0
+    }
+}
+
+/// The `.gnu.version` table together with the version definitions and requirements it
+/// indexes into
+#[derive(Debug, Clone, Default)]
+pub struct SymbolVersions {
+    /// One entry per `.dynsym` symbol
+    pub versym: Vec<Elf64Half>,
+    pub verneed: Vec<ElfVerneed>,
+    pub verdef: Vec<VersionDef>,
+}
+
+impl SymbolVersions {
+    pub fn parse_versym(data: &[u8], endian: Endian) -> Vec<Elf64Half> {
+        let mut reader = ByteReader::new(data, endian);
+        std::iter::from_fn(|| reader.u16()).collect()
+    }
+
+    /// The version defined for the dynamic symbol at `index` and whether it is hidden,
+    /// `None` for references and unversioned symbols
+    pub fn defined(&self, index: usize) -> Option<(&str, bool)> {
+        let versym = *self.versym.get(index)?;
+        let ndx = versym & !VERSYM_HIDDEN;
+        if ndx == VER_NDX_LOCAL || ndx == VER_NDX_GLOBAL {
+            return None;
+        }
+
+        self.verdef
+            .iter()
+            .find(|def| def.ndx == ndx)
+            .and_then(VersionDef::name)
+            .map(|name| (name, versym & VERSYM_HIDDEN != 0))
+    }
+
+    /// The version the way binutils appends it to a symbol name, `@@VERSION` for the
+    /// default version of a definition and `@VERSION` for a hidden one or a requirement.
+    /// Copy relocated symbols are defined but still carry the version they require.
+    pub fn name_suffix(&self, index: usize, undefined: bool) -> Option<String> {
+        if let (false, Some((version, hidden))) = (undefined, self.defined(index)) {
+            return Some(match hidden {
+                true => format!("@{}", version),
+                false => format!("@@{}", version),
+            });
+        }
+        self.needed(index)
+            .map(|(_, version)| format!("@{}", version))
+    }
+
+    /// [`SymbolVersions::name_suffix`] the way readelf shows it in symbol tables, where a
+    /// requirement also carries its version index, `@VERSION (3)`. The symbols naming a
+    /// version definition, `name`, are left alone.
+    pub fn symbol_table_suffix(&self, index: usize, name: &str, undefined: bool) -> Option<String> {
+        if let (false, Some((version, _))) = (undefined, self.defined(index)) {
+            return match version == name {
+                true => None,
+                false => self.name_suffix(index, false),
+            };
+        }
+        let ndx = self.versym.get(index)? & !VERSYM_HIDDEN;
+        self.needed(index)
+            .map(|(_, version)| format!("@{} ({})", version, ndx))
+    }
+
+    /// The name a `.gnu.version` entry refers to with the hidden bit masked off,
+    /// `*local*` and `*global*` for the reserved indices
+    pub fn version_name(&self, versym: Elf64Half) -> Option<&str> {
+        let ndx = versym & !VERSYM_HIDDEN;
+        match ndx {
+            VER_NDX_LOCAL => Some("*local*"),
+            VER_NDX_GLOBAL => Some("*global*"),
+            _ => self
+                .verneed
+                .iter()
+                .flat_map(|need| need.aux.iter())
+                .find(|aux| aux.other == ndx)
+                .map(|aux| aux.name.as_str())
+                .or_else(|| {
+                    self.verdef
+                        .iter()
+                        .find(|def| def.ndx == ndx)
+                        .and_then(VersionDef::name)
+                }),
+        }
+    }
+
+    /// The `(file, version)` requirement of the dynamic symbol at `index`, if any
+    pub fn needed(&self, index: usize) -> Option<(&str, &str)> {
+        let ndx = self.versym.get(index)? & !VERSYM_HIDDEN;
+        if ndx == VER_NDX_LOCAL || ndx == VER_NDX_GLOBAL {
+            return None;
+        }
+
+        self.verneed.iter().find_map(|need| {
+            need.aux
+                .iter()
+                .find(|aux| aux.other == ndx)
+                .map(|aux| (need.file.as_str(), aux.name.as_str()))
+        })
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use crate::elf::core::FileData;
+    use super::{
+        version_flags_name, ElfVerneed, SymbolVersions, VersionDef, VER_FLG_BASE, VER_FLG_WEAK,
+    };
+    use crate::elf::{core::FileData, hdr::Endian};
+
+    #[test]
+    fn parses_verneed() {
+        let strtab = b"\0libc.so.6\0GLIBC_2.2.5\0";
+        let mut data = vec![1, 0, 1, 0, 1, 0, 0, 0, 16, 0, 0, 0, 0, 0, 0, 0];
+        data.extend([0x75, 0x1a, 0x69, 0x09, 0, 0, 2, 0, 11, 0, 0, 0, 0, 0, 0, 0]);
+
+        let versions = SymbolVersions {
+            versym: vec![0, 2, 1],
+            verneed: ElfVerneed::parse(&data, strtab, 1, Endian::Little),
+            verdef: Vec::new(),
+        };
+        assert_eq!(versions.needed(1), Some(("libc.so.6", "GLIBC_2.2.5")));
+        assert_eq!(versions.needed(2), None);
+        assert_eq!(versions.version_name(0x8002), Some("GLIBC_2.2.5"));
+        assert_eq!(versions.version_name(1), Some("*global*"));
+        assert_eq!(
+            versions.symbol_table_suffix(1, "puts", true).as_deref(),
+            Some("@GLIBC_2.2.5 (2)")
+        );
+    }
+
+    #[test]
+    fn parses_verdef_flags() {
+        let strtab = b"\0libfoo.so\0";
+        let mut data = vec![1, 0, 1, 0, 1, 0, 1, 0, 0, 0, 0, 0, 20, 0, 0, 0, 0, 0, 0, 0];
+        data.extend([1, 0, 0, 0, 0, 0, 0, 0]);
+
+        let defs = VersionDef::parse(&data, strtab, 1, Endian::Little);
+        assert!(defs[0].is_base());
+        assert_eq!(defs[0].name(), Some("libfoo.so"));
+        assert_eq!(defs[0].name_offsets, [20]);
+        assert_eq!(version_flags_name(0), "none");
+        assert_eq!(
+            version_flags_name(VER_FLG_BASE | VER_FLG_WEAK),
+            "BASE | WEAK"
+        );
+    }
 
     #[test]
     fn foo() {
@@ -0,0 +1,154 @@
+use std::io;
+
+use super::{
+    bytes::string_at,
+    core::FileData,
+    hdr::Endian,
+    note::Note,
+    phdr::ProgramType,
+    rel::ElfRel,
+    shdr::{ElfShdr, SectionType},
+    sym::ElfSym,
+    ElfHdr, ElfPhdr,
+};
+
+/// Whether [`FileData::walk`] should decode the entries of a section
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Walk {
+    Enter,
+    Skip,
+}
+
+/// Callbacks for [`FileData::walk`], every method defaults to doing nothing and entering
+/// every section.
+///
+/// Symbol, relocation and note tables are only read when [`visit_section`] returns
+/// [`Walk::Enter`] for their section, so skipping what is not needed keeps a walk cheap.
+///
+/// [`visit_section`]: ElfVisitor::visit_section
+pub trait ElfVisitor {
+    fn visit_header(&mut self, _hdr: &ElfHdr) {}
+
+    fn visit_segment(&mut self, _index: usize, _phdr: &ElfPhdr) {}
+
+    fn visit_section(&mut self, _index: usize, _shdr: &ElfShdr, _name: &str) -> Walk {
+        Walk::Enter
+    }
+
+    /// A symbol of the `SHT_SYMTAB`/`SHT_DYNSYM` section `section`
+    fn visit_symbol(&mut self, _section: usize, _index: usize, _sym: &ElfSym, _name: &str) {}
+
+    /// A relocation of the `SHT_REL`/`SHT_RELA` section `section`
+    fn visit_relocation(&mut self, _section: usize, _rel: &ElfRel) {}
+
+    /// A note of the `SHT_NOTE` section `section`, or of a `PT_NOTE` segment when the file
+    /// has no section headers, in which case `section` is `None`
+    fn visit_note(&mut self, _section: Option<usize>, _note: &Note) {}
+}
+
+impl FileData {
+    /// Traverses the file in one pass: the header, the segments, then each section followed
+    /// by its symbols, relocations or notes
+    pub fn walk<V: ElfVisitor>(&self, visitor: &mut V) -> io::Result<()> {
+        let header = *self.header();
+        let endian = header.endian().unwrap_or(Endian::Little);
+        visitor.visit_header(&header);
+
+        for (index, phdr) in self.program_headers().iter().enumerate() {
+            visitor.visit_segment(index, phdr);
+        }
+
+        if self.section_headers().is_empty() {
+            let segments = self
+                .program_headers()
+                .iter()
+                .filter(|phdr| phdr.program_type() == Some(ProgramType::Note))
+                .map(|phdr| (phdr.offset(), phdr.filesz(), phdr.align()))
+                .collect::<Vec<_>>();
+            for (offset, size, align) in segments {
+                let data = self.read_at(offset, size as usize)?;
+                for note in Note::parse_aligned(&data, endian, align) {
+                    visitor.visit_note(None, &note);
+                }
+            }
+        }
+
+        for index in 0..self.section_headers().len() {
+            let shdr = self.section_headers()[index];
+            let name = self.section_name(&shdr).unwrap_or_default();
+            if visitor.visit_section(index, &shdr, &name) == Walk::Skip {
+                continue;
+            }
+
+            match shdr.section_type() {
+                Some(SectionType::SymTab | SectionType::DynSym) => {
+                    let strtab = self.section_headers().get(shdr.link() as usize).copied();
+                    let strtab = match strtab {
+                        Some(strtab) => self.section_data(&strtab)?,
+                        None => Vec::new(),
+                    };
+                    for (i, sym) in self.symbols_iter(&shdr)?.enumerate() {
+                        let sym = sym?;
+                        visitor.visit_symbol(
+                            index,
+                            i,
+                            &sym,
+                            &string_at(&strtab, sym.name() as usize),
+                        );
+                    }
+                }
+                Some(SectionType::Rel | SectionType::Rela) => {
+                    for rel in self.relocations_iter(&shdr)? {
+                        visitor.visit_relocation(index, &rel?);
+                    }
+                }
+                Some(SectionType::Note) => {
+                    let data = self.section_data(&shdr)?;
+                    for note in Note::parse_aligned(&data, endian, shdr.addralign()) {
+                        visitor.visit_note(Some(index), &note);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod test {
+    use super::{ElfVisitor, Walk};
+    use crate::elf::{core::FileData, shdr::ElfShdr, sym::ElfSym};
+
+    #[derive(Default)]
+    struct Counter {
+        sections: usize,
+        symbols: usize,
+    }
+
+    impl ElfVisitor for Counter {
+        fn visit_section(&mut self, _: usize, _: &ElfShdr, name: &str) -> Walk {
+            self.sections += 1;
+            match name {
+                ".symtab" => Walk::Enter,
+                _ => Walk::Skip,
+            }
+        }
+
+        fn visit_symbol(&mut self, _: usize, _: usize, _: &ElfSym, _: &str) {
+            self.symbols += 1;
+        }
+    }
+
+    #[test]
+    fn counts_sections_and_symbols() {
+        let elf = FileData::new(std::env::current_exe().unwrap()).unwrap();
+        let mut counter = Counter::default();
+        elf.walk(&mut counter).unwrap();
+
+        let symtab = elf.section_by_name(".symtab").unwrap();
+        assert_eq!(counter.sections, elf.section_headers().len());
+        assert_eq!(counter.symbols, elf.symbol_table(&symtab).unwrap().1.len());
+    }
+}
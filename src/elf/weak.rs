@@ -0,0 +1,66 @@
+use std::io;
+
+use super::{
+    bytes::string_at,
+    core::FileData,
+    sym::{SymbolBinding, SymbolType},
+};
+
+/// A `STB_WEAK` symbol of one of the symbol tables
+#[derive(Debug, Clone)]
+pub struct WeakSymbol {
+    /// Name of the symbol table section, `.symtab` or `.dynsym`
+    pub table: String,
+    pub name: String,
+    /// `@VERSION` or `@@VERSION` for versioned `.dynsym` entries
+    pub version: Option<String>,
+    pub value: u64,
+    pub size: u64,
+    pub symbol_type: Option<SymbolType>,
+}
+
+/// The weak symbols of a file, split by whether they define or reference.
+///
+/// A weak definition gives way to any global one found earlier in lookup order, without
+/// an error, so two objects can silently disagree on which body wins. A weak undefined
+/// reference that nothing defines resolves to 0 instead of failing to link or load.
+#[derive(Debug, Clone, Default)]
+pub struct WeakReport {
+    pub definitions: Vec<WeakSymbol>,
+    pub undefined: Vec<WeakSymbol>,
+}
+
+impl WeakReport {
+    pub fn read(elf: &FileData) -> io::Result<Self> {
+        let versions = elf.symbol_versions().unwrap_or_default();
+        let mut report = Self::default();
+
+        for (table, strtab, syms) in elf.table_symbols()? {
+            for (i, sym) in syms.iter().enumerate() {
+                if sym.binding() != Some(SymbolBinding::Weak) {
+                    continue;
+                }
+                let undefined = sym.shndx() == 0;
+                let name = string_at(strtab, sym.name() as usize);
+                let version = match table.as_str() {
+                    ".dynsym" => versions.name_suffix(i, undefined),
+                    _ => None,
+                };
+                let weak = WeakSymbol {
+                    table: table.clone(),
+                    name,
+                    version,
+                    value: sym.value(),
+                    size: sym.size(),
+                    symbol_type: sym.symbol_type(),
+                };
+                match undefined {
+                    true => report.undefined.push(weak),
+                    false => report.definitions.push(weak),
+                }
+            }
+        }
+
+        Ok(report)
+    }
+}
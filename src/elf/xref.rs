@@ -0,0 +1,110 @@
+use std::io;
+
+use super::{
+    bytes::string_at,
+    core::FileData,
+    hdr::ObjectType,
+    rel::reloc_type_name,
+    sym::{SymbolMap, SymbolType},
+};
+
+#[derive(Debug, Clone)]
+pub struct XrefSite {
+    /// Relocation section the entry was found in
+    pub section: String,
+    pub offset: u64,
+    pub rtype: String,
+    pub addend: Option<i64>,
+    /// Section containing the patched location
+    pub target: Option<String>,
+    /// Function or object containing the patched location, as `name+offset`
+    pub function: Option<String>,
+}
+
+/// Finds every relocation referring to the symbol called `name`.
+///
+/// Relocatable objects store section relative offsets, so sites are resolved against the
+/// symbols of the section named by the relocation section's `sh_info`. Linked files use
+/// virtual addresses and are resolved against the whole symbol table.
+pub fn find_xrefs(elf: &FileData, name: &str) -> io::Result<Vec<XrefSite>> {
+    let machine = elf.header().machine();
+    let relocatable = elf.header().ftype() == Some(ObjectType::Rel);
+    let empty = SymbolMap::default();
+    let global_map = if relocatable {
+        &empty
+    } else {
+        elf.symbol_map()?
+    };
+    let mut sites = Vec::new();
+
+    for (shdr, rels) in elf.relocations()? {
+        let symtab = match elf.section_headers().get(shdr.link() as usize) {
+            Some(symtab) if shdr.link() != 0 => *symtab,
+            _ => continue,
+        };
+        let (strtab, syms) = elf.symbol_table(&symtab)?;
+        let matches = rels
+            .iter()
+            .filter(|rel| {
+                syms.get(rel.sym() as usize)
+                    .map(|sym| string_at(strtab, sym.name() as usize))
+                    .as_deref()
+                    == Some(name)
+            })
+            .collect::<Vec<_>>();
+        if matches.is_empty() {
+            continue;
+        }
+
+        let section = elf.section_name(&shdr).unwrap_or_default();
+        let target_index = shdr.info() as usize;
+        let local_map = SymbolMap::new(
+            syms.iter()
+                .filter(|sym| sym.shndx() as usize == target_index)
+                .filter(|sym| {
+                    matches!(
+                        sym.symbol_type(),
+                        Some(SymbolType::Func | SymbolType::Object)
+                    )
+                })
+                .map(|sym| {
+                    (
+                        sym.value(),
+                        sym.size(),
+                        string_at(strtab, sym.name() as usize),
+                    )
+                }),
+        );
+
+        for rel in matches {
+            let (target, function) = if relocatable {
+                let target = elf
+                    .section_headers()
+                    .get(target_index)
+                    .copied()
+                    .and_then(|target| elf.section_name(&target));
+                (target, local_map.describe(rel.offset()))
+            } else {
+                match elf.section_at_vaddr(rel.offset()) {
+                    Some(target) => (
+                        elf.section_name(&target),
+                        global_map.describe_from(rel.offset(), target.addr()),
+                    ),
+                    None => (None, global_map.describe(rel.offset())),
+                }
+            };
+
+            sites.push(XrefSite {
+                section: section.clone(),
+                offset: rel.offset(),
+                rtype: reloc_type_name(machine, rel.rtype())
+                    .map_or_else(|| rel.rtype().to_string(), str::to_string),
+                addend: rel.addend(),
+                target,
+                function,
+            });
+        }
+    }
+
+    Ok(sites)
+}
@@ -0,0 +1,253 @@
+//! C API over [`FileData`], see `include/readelf_rs.h`
+//!
+//! Every handle returned by `relr_open*` owns the parsed file and the strings handed out
+//! for it, so pointers in the returned structs stay valid until `relr_close`.
+
+use std::{
+    ffi::{CStr, CString},
+    io,
+    os::raw::{c_char, c_int},
+    panic::{self, UnwindSafe},
+    ptr, slice,
+};
+
+use crate::elf::{bytes::string_at, core::FileData, sym::ElfSym};
+
+#[repr(C)]
+pub struct RelrHeader {
+    pub class: u8,
+    pub data: u8,
+    pub os_abi: u8,
+    pub object_type: u16,
+    pub machine: u16,
+    pub entry: u64,
+    pub flags: u32,
+    pub phnum: u16,
+    pub shnum: u16,
+}
+
+#[repr(C)]
+pub struct RelrSection {
+    pub name: *const c_char,
+    pub section_type: u32,
+    pub flags: u64,
+    pub addr: u64,
+    pub offset: u64,
+    pub size: u64,
+    pub link: u32,
+    pub info: u32,
+    pub addralign: u64,
+    pub entsize: u64,
+}
+
+#[repr(C)]
+pub struct RelrSymbol {
+    pub name: *const c_char,
+    /// Name of the table the symbol comes from, `.symtab` or `.dynsym`
+    pub table: *const c_char,
+    pub value: u64,
+    pub size: u64,
+    pub info: u8,
+    pub other: u8,
+    pub shndx: u16,
+}
+
+/// Opaque handle behind the `relr_*` functions
+pub struct RelrFile {
+    elf: FileData,
+    section_names: Vec<CString>,
+    symbols: Vec<(usize, CString, ElfSym)>,
+    tables: Vec<CString>,
+}
+
+fn c_string(s: String) -> CString {
+    CString::new(s.replace('\0', "")).unwrap_or_default()
+}
+
+impl RelrFile {
+    fn new(elf: FileData) -> Option<Self> {
+        let section_names = elf
+            .section_headers()
+            .to_vec()
+            .iter()
+            .map(|shdr| c_string(elf.section_name(shdr).unwrap_or_default()))
+            .collect();
+
+        let mut symbols = Vec::new();
+        let mut tables = Vec::new();
+        for (index, (table, strtab, syms)) in elf.table_symbols().ok()?.into_iter().enumerate() {
+            tables.push(c_string(table));
+            symbols.extend(syms.iter().map(|sym| {
+                (
+                    index,
+                    c_string(string_at(strtab, sym.name() as usize)),
+                    sym.clone(),
+                )
+            }));
+        }
+
+        Some(Self {
+            elf,
+            section_names,
+            symbols,
+            tables,
+        })
+    }
+}
+
+/// Builds the handle for the file `open` parses. A panic on a malformed file must not unwind
+/// into the caller, it gives NULL like any other failure.
+fn into_handle(open: impl FnOnce() -> io::Result<FileData> + UnwindSafe) -> *mut RelrFile {
+    match panic::catch_unwind(|| open().ok().and_then(RelrFile::new)) {
+        Ok(Some(file)) => Box::into_raw(Box::new(file)),
+        _ => ptr::null_mut(),
+    }
+}
+
+/// Opens and parses the ELF file at `path`, returns NULL on failure
+///
+/// # Safety
+/// `path` must be a valid NUL terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn relr_open(path: *const c_char) -> *mut RelrFile {
+    if path.is_null() {
+        return ptr::null_mut();
+    }
+    let path = match CStr::from_ptr(path).to_str() {
+        Ok(path) => path,
+        Err(_) => return ptr::null_mut(),
+    };
+    into_handle(|| FileData::new(path))
+}
+
+/// Parses an ELF image from memory, the bytes are copied so the buffer can be freed after
+///
+/// # Safety
+/// `data` must point to at least `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn relr_open_bytes(data: *const u8, len: usize) -> *mut RelrFile {
+    if data.is_null() {
+        return ptr::null_mut();
+    }
+    let data = slice::from_raw_parts(data, len);
+    into_handle(|| FileData::from_bytes(data))
+}
+
+/// Releases a handle, passing NULL is a no-op
+///
+/// # Safety
+/// `file` must come from `relr_open*` and not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn relr_close(file: *mut RelrFile) {
+    if !file.is_null() {
+        drop(Box::from_raw(file));
+    }
+}
+
+/// Fills `out` with the file header, returns 0 on success
+///
+/// # Safety
+/// `file` must be a live handle and `out` writable.
+#[no_mangle]
+pub unsafe extern "C" fn relr_header(file: *const RelrFile, out: *mut RelrHeader) -> c_int {
+    let (file, out) = match (file.as_ref(), out.as_mut()) {
+        (Some(file), Some(out)) => (file, out),
+        _ => return -1,
+    };
+    let hdr = file.elf.header();
+    let ident = hdr.ident();
+    *out = RelrHeader {
+        class: ident[4],
+        data: ident[5],
+        os_abi: ident[7],
+        object_type: hdr.e_type,
+        machine: hdr.machine(),
+        entry: hdr.entry(),
+        flags: hdr.flags(),
+        phnum: hdr.nheaders(),
+        shnum: hdr.nsection_headers(),
+    };
+    0
+}
+
+/// Number of section headers, 0 for a NULL handle
+///
+/// # Safety
+/// `file` must be NULL or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn relr_section_count(file: *const RelrFile) -> usize {
+    file.as_ref()
+        .map_or(0, |file| file.elf.section_headers().len())
+}
+
+/// Fills `out` with section `index`, returns 0 on success and -1 when out of range
+///
+/// # Safety
+/// `file` must be a live handle and `out` writable.
+#[no_mangle]
+pub unsafe extern "C" fn relr_section_at(
+    file: *const RelrFile,
+    index: usize,
+    out: *mut RelrSection,
+) -> c_int {
+    let (file, out) = match (file.as_ref(), out.as_mut()) {
+        (Some(file), Some(out)) => (file, out),
+        _ => return -1,
+    };
+    let shdr = match file.elf.section_headers().get(index) {
+        Some(shdr) => shdr,
+        None => return -1,
+    };
+    *out = RelrSection {
+        name: file.section_names[index].as_ptr(),
+        section_type: shdr.raw_type(),
+        flags: shdr.flags(),
+        addr: shdr.addr(),
+        offset: shdr.offset(),
+        size: shdr.size(),
+        link: shdr.link(),
+        info: shdr.info(),
+        addralign: shdr.addralign(),
+        entsize: shdr.entsize(),
+    };
+    0
+}
+
+/// Number of symbols over `.symtab` and `.dynsym` together
+///
+/// # Safety
+/// `file` must be NULL or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn relr_symbol_count(file: *const RelrFile) -> usize {
+    file.as_ref().map_or(0, |file| file.symbols.len())
+}
+
+/// Fills `out` with symbol `index`, returns 0 on success and -1 when out of range
+///
+/// # Safety
+/// `file` must be a live handle and `out` writable.
+#[no_mangle]
+pub unsafe extern "C" fn relr_symbol_at(
+    file: *const RelrFile,
+    index: usize,
+    out: *mut RelrSymbol,
+) -> c_int {
+    let (file, out) = match (file.as_ref(), out.as_mut()) {
+        (Some(file), Some(out)) => (file, out),
+        _ => return -1,
+    };
+    let (table, name, sym) = match file.symbols.get(index) {
+        Some(symbol) => symbol,
+        None => return -1,
+    };
+    *out = RelrSymbol {
+        name: name.as_ptr(),
+        table: file.tables[*table].as_ptr(),
+        value: sym.value(),
+        size: sym.size(),
+        info: sym.info(),
+        other: sym.other(),
+        shndx: sym.shndx(),
+    };
+    0
+}
@@ -0,0 +1,13 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![feature(int_log)]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
+pub mod display;
+#[allow(dead_code)]
+pub mod elf;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "python")]
+pub mod python;
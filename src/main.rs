@@ -1,18 +1,118 @@
-#![feature(int_log)]
 #![feature(vec_into_raw_parts)]
 
-use clap::Parser;
+#[cfg(feature = "disasm")]
+use std::ops::Range;
+use std::{
+    borrow::Cow,
+    fmt::Display,
+    fs,
+    io::{self, Read},
+    process,
+    time::Instant,
+};
+#[cfg(feature = "dwarf")]
+use std::{collections::HashMap, path::Path};
+
+#[cfg(feature = "man")]
+use clap::CommandFactory;
+use clap::{ArgEnum, Parser, Subcommand};
 
-mod display;
-#[allow(dead_code)]
-mod elf;
+mod config;
+mod diff;
+#[cfg(feature = "man")]
+mod manpage;
+mod pager;
+mod response;
+#[cfg(feature = "script")]
+mod script;
+
+#[cfg(feature = "demangle")]
+use readelf_rs::elf::demangle::demangle;
+use readelf_rs::{
+    display::{Renderer, SymBase, TextRenderer},
+    elf,
+};
+#[cfg(feature = "json")]
+use readelf_rs::{
+    display::{TreeFormat, TreeRenderer, Value},
+    elf::diag::{Diagnostic, Severity},
+};
 use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
 
+use config::Config;
+use diff::DiffArgs;
+use pager::Pager;
+use response::expand_response_files;
+#[cfg(feature = "script")]
+use script::Script;
+
+#[cfg(feature = "dwarf")]
+use elf::{
+    addr2line::{Addr2Line, DebugInfo, Frame},
+    bytes::ByteReader,
+    debugstats::DebugStats,
+    dwarf::{
+        at_string, expression_string, is_addrx, is_location_attr, is_strx, is_unit_ref,
+        parse_abbrevs, read_dies, tag_string, unit_type_name, AttrValue, Attribute, Die,
+        DwarfSections, FileTable, LineProgram, ListEntry, ListTable, UnitBases, UnitHeader,
+        DW_AT_COMP_DIR, DW_AT_DWO_NAME, DW_AT_GNU_DWO_ID, DW_AT_GNU_DWO_NAME, DW_AT_GNU_LOCVIEWS,
+        DW_AT_HIGH_PC, DW_AT_RANGES, DW_AT_STMT_LIST, DW_FORM_ADDR, DW_FORM_LINE_STRP,
+        DW_FORM_LOCLISTX, DW_FORM_RNGLISTX, DW_FORM_SEC_OFFSET, DW_FORM_STRP,
+    },
+    dwo::{dwo_path, dwp_path, DwoSections},
+    gdbindex::GdbIndex,
+    macros::{macro_op_name, parse_macinfo, MacroEntry, MacroOp, MacroUnit},
+    pubnames::{index_attribute_name, DebugNames, PubNameSet, DW_IDX_DIE_OFFSET},
+};
+#[cfg(feature = "disasm")]
 use elf::{
-    hdr::{ElfClass, Endian},
+    addr2line::{LineTable, Location},
+    disasm::{bitness, disassemble},
+};
+use elf::{
+    analyze::analyze_sections,
+    archive::{Archive, ARMAG},
+    bpf::{map_type_name, BpfObject, BpfRelocTarget},
+    branchprot::BranchProtection,
+    btf::{Btf, BtfExt},
+    bytes::string_at,
+    copyrel::CopyReloc,
+    core::{FileData, ParseLevel, ParseOptions},
+    deps::DepGraph,
+    dynamic::{dynamic_tag_name, dynamic_value_string, DynamicTag},
+    except::{read_lsdas, typeinfo_names},
+    export::ExportList,
+    golang::GoBinary,
+    hdr::{machine_by_name, ElfClass, Endian, ObjectType},
+    ifunc::IfuncReport,
     internal::elf_section_in_segment,
-    shdr::SectionFlag,
-    ELFVER,
+    liblist::Library,
+    linkmap::{MapItem, OutputSection},
+    llvm::{parse_addrsig, parse_linker_options},
+    meta::FileMeta,
+    modinfo::KernelModule,
+    note::Note,
+    phdr::{program_type_name, ProgramType, MTE_GRANULE_SIZE, PT_AARCH64_MEMTAG_MTE},
+    property::{
+        aarch64_features, x86_isa_levels, x86_isa_minimum, GnuProperty,
+        GNU_PROPERTY_X86_ISA_1_NEEDED, GNU_PROPERTY_X86_ISA_1_USED,
+    },
+    rel::{relative_type, reloc_type_name},
+    resolve::Resolution,
+    rust::RustInfo,
+    search::parse_pattern,
+    segalign::{huge_page_size, page_sizes, SegmentAlignment},
+    shdr::SectionType,
+    sym::{
+        symbol_binding_by_name, symbol_type_by_name, ElfSym, SymbolBinding, SymbolMap, SymbolType,
+    },
+    syminfo::{Syminfo, SYMINFO_BT_EXTERN, SYMINFO_BT_NONE, SYMINFO_BT_PARENT, SYMINFO_BT_SELF},
+    tls::{TlsReloc, TlsTemplate},
+    unwind::{encoding_name, EhFrameHdr},
+    ver::{version_flags_name, SymbolVersions, VERSYM_HIDDEN},
+    weak::{WeakReport, WeakSymbol},
+    xref::find_xrefs,
+    EM_AARCH64, EM_BPF,
 };
 
 macro_rules! set_color {
@@ -42,26 +142,64 @@ macro_rules! print_color {
     };
 }
 
-macro_rules! attr_pad {
-    ($stdout:expr, $color:path, $attr:expr, $ctx:expr, $pad:expr) => {
-        set_color!($stdout, $color);
-        print!("{}", $attr);
-        set_color!($stdout, Color::White);
-        println!(
-            ":{ctx:>pad$}",
-            pad = $pad - $attr.len() + $ctx.len(),
-            ctx = $ctx
-        );
-    };
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum OutputFormat {
+    Text,
+    Gnu,
+    #[cfg(feature = "json")]
+    Json,
+    #[cfg(feature = "json")]
+    Yaml,
+}
+
+/// DWARF sections `--debug-dump` decodes
+#[cfg(feature = "dwarf")]
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum DebugDump {
+    /// .debug_info, the DIEs of every unit
+    Info,
+    /// .debug_line, the decoded line number matrix
+    #[clap(name = "decodedline")]
+    DecodedLine,
+    /// .debug_ranges and .debug_rnglists
+    #[clap(name = "Ranges")]
+    Ranges,
+    /// .debug_loc and .debug_loclists
+    Loc,
+    /// .debug_pubnames, .debug_gnu_pubnames and .debug_names
+    Pubnames,
+    /// .debug_macro and .debug_macinfo
+    Macro,
+    /// .gdb_index
+    #[clap(name = "gdb_index")]
+    GdbIndex,
+}
+
+/// Format of the `--report` document
+#[cfg(feature = "json")]
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum ReportFormat {
+    Json,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Compare two files
+    Diff(DiffArgs),
 }
 
 #[derive(Parser, Debug)]
 #[clap(
     author = "Xetera Mnemonics <grostaco@gmail.com>",
     version,
-    about = "A simple readelf implementation"
+    about = "A simple readelf implementation",
+    after_help = "Exit status is 0 when every file was read, 1 when some files could not be \
+                  parsed or --check-unresolved found unresolved symbols and 2 on usage errors."
 )]
 struct Args {
+    #[clap(subcommand)]
+    command: Option<Command>,
+
     /// ELF files
     files: Vec<String>,
 
@@ -74,14 +212,86 @@ struct Args {
     show_headers: bool,
 
     /// Display the section headers
+    ///
+    /// Columns: Nr is the section index, Name the name from the section header string
+    /// table, Type the sh_type, Address the virtual address when loaded, Offset the file
+    /// offset, Size and EntSize the section and table entry sizes in bytes, Flags the
+    /// sh_flags as letters (W write, A alloc, X execute, M merge, S strings, I info link,
+    /// L link order, O OS specific, G group, T TLS, C compressed, E exclude), Link and Info
+    /// the sh_link and sh_info fields and Align the required alignment.
     #[clap(short = 'S', long = "section-headers", alias = "sections")]
     show_sections: bool,
 
+    /// Display the section headers followed by the decoded contents of the sections with
+    /// a known layout: LLVM address significance tables and linker options
+    #[clap(short = 't', long = "section-details")]
+    show_section_details: bool,
+
+    /// Display architecture and OS specific information, such as the prelink library
+    /// list
+    #[clap(short = 'A', long = "arch-specific")]
+    show_arch_specific: bool,
+
     /// Display the symbol table
+    ///
+    /// Columns: Num is the index in the table, Value the symbol value (usually an address),
+    /// Size the object size in bytes, Type, Bind and Vis the symbol type, binding and
+    /// visibility, Ndx the section index (UND for undefined, ABS for absolute) and Name the
+    /// symbol name.
     #[clap(short = 's', long = "syms")]
     show_symbols: bool,
 
+    /// Show Rust and C++ symbol names demangled in -s, --dyn-syms and --weak
+    #[cfg(feature = "demangle")]
+    #[clap(short = 'C', long = "demangle")]
+    demangle: bool,
+
+    /// With -s, only list symbols of these types (comma separated: notype, object, func,
+    /// section, file, common, tls, ifunc)
+    #[clap(
+        long = "syms-type",
+        value_name = "TYPES",
+        use_value_delimiter = true,
+        parse(try_from_str = parse_symbol_type)
+    )]
+    syms_type: Vec<SymbolType>,
+
+    /// With -s, only list symbols with these bindings (comma separated: local, global,
+    /// weak, unique)
+    #[clap(
+        long = "syms-bind",
+        value_name = "BINDINGS",
+        use_value_delimiter = true,
+        parse(try_from_str = parse_symbol_binding)
+    )]
+    syms_bind: Vec<SymbolBinding>,
+
+    /// Allow output lines wider than the terminal, implies --no-truncate
+    #[clap(short = 'W', long = "wide")]
+    wide: bool,
+
+    /// Never cut section or symbol names to fit their column, let the lines wrap instead
+    #[clap(long = "no-truncate")]
+    no_truncate: bool,
+
+    /// Base symbol values are printed in by -s and -d: 16, 10 or 8. JSON output writes
+    /// them as prefixed strings unless the base is 10
+    #[clap(long = "sym-base", value_name = "BASE", parse(try_from_str = parse_sym_base))]
+    sym_base: Option<SymBase>,
+
+    /// With -s, only list symbols defined in the file
+    #[clap(long = "defined-only", conflicts_with = "undefined-only")]
+    defined_only: bool,
+
+    /// With -s, only list undefined symbols, the ones the file imports
+    #[clap(long = "undefined-only")]
+    undefined_only: bool,
+
     /// Display the program headers
+    ///
+    /// Columns: Type is the p_type, Offset the file offset, VirtAddr and PhysAddr the load
+    /// addresses, FileSiz and MemSiz the sizes in the file and in memory, Flags the R, W and
+    /// E permissions and Align the segment alignment.
     #[clap(short = 'l', long = "program-headers")]
     show_program_header: bool,
 
@@ -89,420 +299,4182 @@ struct Args {
     #[clap(short = 'r', long = "relocs")]
     show_relocations: bool,
 
+    /// With -r, list the relative relocations DT_RELACOUNT/DT_RELCOUNT count one by one
+    /// instead of summing them up in a line
+    #[clap(long = "expand-relative")]
+    expand_relative: bool,
+
+    /// With -r, name the section and the function or object each relocation patches
+    #[clap(long = "reloc-targets")]
+    reloc_targets: bool,
+
+    /// Use the dynamic section instead of the section headers when displaying relocations,
+    /// which also works on files whose section headers are stripped
+    #[clap(short = 'D', long = "use-dynamic")]
+    use_dynamic: bool,
+
     /// Display the dynamic symbol table
     #[clap(short = 'd', long = "dyn-syms")]
     show_dyn_syms: bool,
-}
 
-fn main() {
-    let args = Args::parse();
-    let mut should_pad = false;
-    let mut stdout = StandardStream::stdout(ColorChoice::Always);
+    /// Display the dynamic section
+    #[clap(long = "dynamic")]
+    show_dynamic: bool,
 
-    for f in args.files {
-        let mut elf = elf::core::FileData::new(&f).unwrap();
+    /// Display the symbol versioning sections: the version index of every dynamic symbol
+    /// and the versions required from other objects
+    #[clap(short = 'V', long = "version-info")]
+    show_version_info: bool,
 
-        if args.show_headers {
-            let hdr = elf.header();
-
-            set_color!(stdout, Color::Yellow);
-            print!("ELF Header");
-            set_color!(stdout, Color::Blue);
-            println!(" {}", f);
-            set_color!(stdout, Color::Magenta);
-            print!("Magic");
-            set_color!(stdout, Color::White);
-            print!(":\t\t");
-            for i in hdr.ident() {
-                print!(" {:02x}", i);
-            }
-            println!();
-            attr_pad!(
-                stdout,
-                Color::Green,
-                "Class",
-                match hdr.class().unwrap() {
-                    ElfClass::ElfClass32 => "ELF32",
-                    ElfClass::ElfClass64 => "ELF64",
-                    ElfClass::None => "Unknown",
-                },
-                36
-            );
+    /// Display the BPF type information in .BTF and .BTF.ext
+    #[clap(long = "btf")]
+    show_btf: bool,
 
-            attr_pad!(
-                stdout,
-                Color::Green,
-                "Data",
-                match hdr.endian() {
-                    Some(Endian::Big) => "2's complement, big endian",
-                    Some(Endian::Little) => "2's complement, little endian",
-                    _ => "Unknown",
-                },
-                36
-            );
+    /// Display the programs, maps and map relocations of an eBPF object
+    #[clap(long = "bpf")]
+    show_bpf: bool,
 
-            attr_pad!(
-                stdout,
-                Color::Green,
-                "Version",
-                format!(
-                    "{} {}",
-                    hdr.version(),
-                    match hdr.version() {
-                        ELFVER => "(current version)",
-                        _ => "",
-                    }
-                ),
-                36
-            );
+    /// Display kernel module information from .modinfo, __versions and .gnu.linkonce.this_module
+    #[clap(long = "modinfo")]
+    show_modinfo: bool,
 
-            attr_pad!(
-                stdout,
-                Color::Green,
-                "OS/ABI",
-                format!("{}", hdr.os_abi()),
-                36
-            );
+    /// Display the Go build information and a summary of .gopclntab
+    #[clap(long = "go-info")]
+    show_go: bool,
 
-            attr_pad!(
-                stdout,
-                Color::Green,
-                "ABI Version",
-                format!("{}", hdr.abi_version()),
-                36
-            );
+    /// Display rustc version, panic strategy, std usage and crates recovered from symbols
+    #[clap(long = "rust-info")]
+    show_rust: bool,
 
-            attr_pad!(
-                stdout,
-                Color::Green,
-                "Type",
-                format!("{:#?}", hdr.ftype().unwrap()),
-                36
-            );
+    /// Display a one line summary of each file, similar to file(1)
+    #[clap(long = "meta")]
+    show_meta: bool,
 
-            attr_pad!(
-                stdout,
-                Color::Green,
-                "Machine",
-                format!("{}", hdr.machine()),
-                36
-            );
+    /// Display the x86 ISA levels recorded in the GNU property notes
+    #[clap(long = "x86-isa")]
+    show_x86_isa: bool,
 
-            attr_pad!(
-                stdout,
-                Color::Green,
-                "Entry point addresss",
-                format!("0x{:x}", hdr.entry()),
-                36
-            );
+    /// Display the BTI and PAC adoption of an AArch64 file: property note, PLT and landing
+    /// pads
+    #[clap(long = "branch-protection")]
+    show_branch_protection: bool,
 
-            attr_pad!(
-                stdout,
-                Color::Green,
-                "Start of program headers",
-                format!("{} (bytes into file)", hdr.phstart()),
-                36
-            );
+    /// Run the section analyzers over the sections they recognize, the built-in ones and
+    /// those registered through the library
+    #[clap(long = "analyze")]
+    show_analyze: bool,
 
-            attr_pad!(
-                stdout,
-                Color::Green,
-                "Start of section headers",
-                format!("{} (bytes into file)", hdr.shstart()),
-                36
-            );
+    /// Display IRELATIVE relocations and STT_GNU_IFUNC symbols with their resolvers
+    #[clap(long = "ifunc")]
+    show_ifunc: bool,
 
-            attr_pad!(
-                stdout,
-                Color::Green,
-                "Flags",
-                format!("{}", hdr.flags()),
-                36
-            );
+    /// Check the alignment of the loadable segments against the page sizes of the machine
+    /// and report whether they can be backed by huge pages
+    #[clap(long = "segment-alignment")]
+    show_segment_alignment: bool,
 
-            attr_pad!(
-                stdout,
-                Color::Green,
-                "Size of this header",
-                format!("{} (bytes)", hdr.header_size()),
-                36
-            );
+    /// Display COPY relocations with the object size and the library expected to define them
+    #[clap(long = "copy-relocs")]
+    show_copy_relocs: bool,
 
-            attr_pad!(
-                stdout,
-                Color::Green,
-                "Size of program headers",
-                format!("{} (bytes)", hdr.program_headers_size()),
-                36
-            );
+    /// Display the TLS template and the dynamic TLS relocations: what each one stores, the
+    /// variable's offset in the template and, in executables, its thread pointer offset
+    #[clap(long = "tls")]
+    show_tls: bool,
 
-            attr_pad!(
-                stdout,
-                Color::Green,
-                "Number of program headers",
-                format!("{}", hdr.nheaders()),
-                36
-            );
+    /// Display the weak definitions and the weak undefined references, which resolve
+    /// differently from global symbols without any error when something goes wrong
+    #[clap(long = "weak")]
+    show_weak: bool,
 
-            attr_pad!(
-                stdout,
-                Color::Green,
-                "Size of program headers",
-                format!("{} (bytes)", hdr.section_size()),
-                36
-            );
+    /// Print a linker map style listing of every allocated section with the symbols placed
+    /// in it, their addresses and sizes, and the fill between them
+    #[clap(long = "map")]
+    show_map: bool,
 
-            attr_pad!(
-                stdout,
-                Color::Green,
-                "Number of section headers",
-                format!("{}", hdr.nsection_headers()),
-                36
-            );
+    /// Display every relocation referring to the given symbol
+    #[clap(long = "xref", value_name = "SYMBOL")]
+    xref: Option<String>,
 
-            attr_pad!(
-                stdout,
-                Color::Green,
-                "Section header string table index",
-                format!("{}", hdr.table_index()),
-                36
-            );
+    /// Search the whole file for a hex byte pattern such as "e8 ?? ?? ?? ?? c3", where ??
+    /// matches any byte, and show each match's offset, section, address and symbol
+    #[clap(long = "find-bytes", value_name = "HEX")]
+    find_bytes: Option<String>,
 
-            should_pad = true;
-        }
+    /// Print the function and source position of each ADDRESS (hex) from the DWARF, and
+    /// the functions inlined there innermost first, like addr2line -a -f -i (repeatable)
+    #[cfg(feature = "dwarf")]
+    #[clap(
+        long = "addr2line",
+        value_name = "ADDRESS",
+        parse(try_from_str = parse_address),
+        use_value_delimiter = true
+    )]
+    addr2line: Vec<u64>,
 
-        if args.show_sections {
-            if should_pad {
-                println!();
-            }
+    /// Disassemble the executable sections as objdump -d does, or with =SYMBOL only the
+    /// code of that symbol (x86 and x86-64 only)
+    #[cfg(feature = "disasm")]
+    #[clap(
+        long = "disassemble",
+        value_name = "SYMBOL",
+        min_values = 0,
+        require_equals = true
+    )]
+    disassemble: Option<Option<String>>,
 
-            print_color!(
-                stdout,
-                Color::White,
-                "There are {} section headers, starting at offset 0x{:x}\n",
-                elf.header().e_shnum,
-                elf.header().e_shoff
-            );
-            print_color!(stdout, Color::Yellow, "{}\n  ", "Section Headers");
+    /// Disassemble only the addresses from START up to END, both in hex (implies
+    /// --disassemble)
+    #[cfg(feature = "disasm")]
+    #[clap(
+        long = "disassemble-range",
+        value_name = "START,END",
+        parse(try_from_str = parse_address_range)
+    )]
+    disassemble_range: Option<Range<u64>>,
 
-            print_color!(stdout, Color::Blue, "{}", "[");
-            print_color!(stdout, Color::White, "{}", "Nr");
-            print_color!(stdout, Color::Blue, "{}", "]");
+    /// Interleave the source lines of the instructions into the disassembly, as objdump -S
+    /// does, reading the files the line tables name (implies --disassemble)
+    #[cfg(feature = "disasm")]
+    #[clap(long = "source")]
+    source: bool,
 
-            print_color!(stdout, Color::Green, " {:18}", "Name");
-            print_color!(stdout, Color::Green, " {:17}", "Type");
-            print_color!(stdout, Color::Green, " {:17}", "Address");
-            print_color!(stdout, Color::Green, " {:16}\n      ", "Offset");
+    /// Read the source files of --source from NEW where the line tables name a path
+    /// starting with OLD (repeatable, the first matching OLD is used)
+    #[cfg(feature = "disasm")]
+    #[clap(
+        long = "source-prefix-map",
+        value_name = "OLD=NEW",
+        parse(try_from_str = parse_prefix_map)
+    )]
+    source_prefix_map: Vec<(String, String)>,
 
-            print_color!(stdout, Color::Green, " {:18}", "Size");
-            print_color!(stdout, Color::Green, " {:17}", "EntSize");
-            print_color!(stdout, Color::Green, " {:18}", "Flags  Link  Info");
-            print_color!(stdout, Color::Green, " {:18}", "Align");
+    /// Run a Rhai script against each file, which sees its header, sections, segments,
+    /// symbols and dynamic entries as the constant `elf`
+    #[cfg(feature = "script")]
+    #[clap(long = "script", value_name = "FILE")]
+    script: Option<String>,
 
-            let max_pad = elf.section_headers().len().log10() as usize + 1;
+    /// Print a GNU ld version script reproducing the exported dynamic symbols and their
+    /// version nodes, for locking down the interface of a shared object
+    #[clap(long = "version-script")]
+    show_version_script: bool,
 
-            for (i, shdr) in elf.section_headers().iter().enumerate() {
-                print_color!(stdout, Color::Blue, "{}", "\n  [");
-                print_color!(
-                    stdout,
-                    Color::White,
-                    "{i:max_pad$}",
-                    i = i,
-                    max_pad = max_pad
-                );
+    /// Print the exported dynamic symbols one per line, as name@@VERSION when versioned
+    #[clap(long = "exports")]
+    show_exports: bool,
 
-                print_color!(stdout, Color::Blue, "{}", "] ");
-                set_color!(stdout, Color::White);
-                print!(
-                    "{:18}",
-                    &elf.string_lookup_iter(shdr.name() as usize)
-                        .unwrap()
-                        .take(16)
-                        .collect::<String>()
-                );
+    /// Print the exported dynamic symbols as a dynamic list for ld's
+    /// --export-dynamic-symbol-list, one per line with the version in a comment
+    #[clap(long = "export-dynamic-symbol-list")]
+    show_dynamic_list: bool,
 
-                print!(
-                    " {:18}",
-                    format!("{:?}", shdr.section_type().unwrap()).to_uppercase()
-                );
+    /// Print the recursive DT_NEEDED dependencies as a Graphviz dot graph
+    #[clap(long = "deps-dot")]
+    show_deps_dot: bool,
 
-                print!("{:016x}", shdr.addr());
-                println!("  {:08x}", shdr.offset());
-                print!(
-                    "{empt:pad$}{sz:016x}",
-                    empt = "",
-                    sz = shdr.size(),
-                    pad = 3 + 4
-                );
-                print!("   {:017x}", shdr.entsize());
-
-                let mut flags_buf = String::with_capacity(14);
-                let mut sh_flags = shdr.flags() as i64;
-                while sh_flags != 0 {
-                    let flag = sh_flags & -sh_flags;
-                    sh_flags &= !flag;
-                    let cflag = match flag {
-                        flag if flag == SectionFlag::Write as i64 => 'W',
-                        flag if flag == SectionFlag::Alloc as i64 => 'A',
-                        flag if flag == SectionFlag::ExecInstr as i64 => 'X',
-                        flag if flag == SectionFlag::Merge as i64 => 'M',
-                        flag if flag == SectionFlag::Strings as i64 => 'S',
-                        flag if flag == SectionFlag::InfoLink as i64 => 'I',
-                        flag if flag == SectionFlag::LinkOrder as i64 => 'L',
-                        flag if flag == SectionFlag::OsNonConforming as i64 => 'O',
-                        flag if flag == SectionFlag::Group as i64 => 'G',
-                        flag if flag == SectionFlag::Tls as i64 => 'T',
-                        flag if flag == SectionFlag::Exclude as i64 => 'E',
-                        flag if flag == SectionFlag::Compressed as i64 => 'C',
-                        flag if flag == SectionFlag::GnuMbind as i64 => 'D',
-                        _ => '?',
-                    };
-                    flags_buf.push(cflag);
-                }
+    /// Simulate dynamic linker symbol resolution over the dependency closure
+    #[clap(long = "resolve")]
+    show_resolve: bool,
 
-                print!(" {:^8}", flags_buf);
-                print!("{:>3}", shdr.link());
-                print!("{:>6}", shdr.info());
-                print!("{:>6}", shdr.addralign());
-            }
+    /// Add a shared object to the front of the --resolve scope, like LD_PRELOAD, or to the
+    /// libraries --check-unresolved checks against (repeatable)
+    #[clap(long = "with-lib", value_name = "PATH")]
+    with_libs: Vec<String>,
 
-            println!();
+    /// Check that the --with-lib libraries define every undefined dynamic symbol of the
+    /// file with a matching version, an offline `ldd -r` that does not search DT_NEEDED.
+    /// Leftover strong references make the exit status 1.
+    #[clap(long = "check-unresolved")]
+    check_unresolved: bool,
+
+    /// Display the unwind information in .eh_frame_hdr
+    #[clap(short = 'u', long = "unwind")]
+    show_unwind: bool,
+
+    /// Display the contents of DWARF debug sections (comma separated)
+    #[cfg(feature = "dwarf")]
+    #[clap(
+        long = "debug-dump",
+        value_name = "SECTIONS",
+        arg_enum,
+        use_value_delimiter = true
+    )]
+    debug_dump: Vec<DebugDump>,
+
+    /// Do not show DIEs at a depth of N or more in --debug-dump=info, the unit DIEs are
+    /// at depth 0
+    #[cfg(feature = "dwarf")]
+    #[clap(long = "dwarf-depth", value_name = "N")]
+    dwarf_depth: Option<usize>,
+
+    /// Start --debug-dump=info at the DIE at OFFSET in .debug_info, skipping the units and
+    /// DIEs before it
+    #[cfg(feature = "dwarf")]
+    #[clap(long = "dwarf-start", value_name = "OFFSET", parse(try_from_str = parse_offset))]
+    dwarf_start: Option<u64>,
+
+    /// Summarize the DWARF instead of dumping it: section sizes, units, DIEs by tag,
+    /// abbreviation reuse and string table sizes, for tracking debug info growth
+    #[cfg(feature = "dwarf")]
+    #[clap(long = "debug-stats")]
+    show_debug_stats: bool,
+
+    /// Display the notes
+    ///
+    /// Columns: Owner is the note name, Data size the length of the descriptor and
+    /// Description the note type. Build IDs and ABI tags are decoded below their note.
+    #[clap(short = 'n', long = "notes")]
+    show_notes: bool,
+
+    /// Output format of the header, section, symbol and note views: colored text, plain
+    /// text, one JSON object per file or one YAML document per file [default: text]
+    #[clap(long = "output-format", arg_enum)]
+    output_format: Option<OutputFormat>,
+
+    /// Only process files for this machine, e.g. aarch64, x86-64 or an EM_* number (repeatable)
+    #[clap(long = "filter-machine", value_name = "MACHINE", parse(try_from_str = parse_machine))]
+    filter_machine: Vec<u16>,
+
+    /// Only process files of this object type: rel, exec, dyn or core (repeatable)
+    #[clap(long = "filter-type", value_name = "TYPE")]
+    filter_type: Vec<ObjectType>,
+
+    /// Suppress all output, only report through the exit status whether the files parse
+    #[clap(short = 'q', long = "quiet")]
+    quiet: bool,
+
+    /// Report parse times on stderr.
+    /// Built with the tracing feature, this also prints the parser's spans (-vvv for all).
+    #[clap(short = 'v', long = "verbose", parse(from_occurrences))]
+    verbose: u64,
+
+    /// Pipe the output through $PAGER (less by default) when writing to a terminal
+    #[clap(long = "pager")]
+    pager: bool,
+
+    /// Write straight to stdout, even when the config file turns the pager on
+    #[clap(long = "no-pager", conflicts_with = "pager")]
+    no_pager: bool,
+
+    /// Display the C++ exception tables (LSDAs in .gcc_except_table) referenced from FDEs
+    #[clap(long = "lsda")]
+    show_lsda: bool,
+
+    /// Color theme of the text output, one of the [themes] in the config file
+    #[clap(long = "theme", value_name = "NAME")]
+    theme: Option<String>,
+
+    /// Ignore ~/.config/readelf-rs/config.toml
+    #[clap(long = "no-config")]
+    no_config: bool,
+
+    /// Print a roff man page generated from these options and exit
+    #[cfg(feature = "man")]
+    #[clap(long = "generate-man")]
+    generate_man: bool,
+
+    /// Also warn about an entry point outside the executable segments, a PT_PHDR that does
+    /// not match the program headers and dynamic entries pointing at unmapped memory
+    #[clap(long = "check")]
+    check: bool,
+
+    /// Instead of the usual output, print the parse warnings and --check findings of every
+    /// file as one document on stdout, each with its severity, rule ID and file offset
+    #[cfg(feature = "json")]
+    #[clap(long = "report", value_name = "FORMAT", arg_enum)]
+    report: Option<ReportFormat>,
+
+    /// Reject files with sections or segments past the end of the file or a bad section
+    /// name table index instead of showing what can be parsed
+    #[clap(long = "strict")]
+    strict: bool,
+
+    /// Read the main executable of a running process from its memory instead of a file
+    /// (Linux only, repeatable). Section headers are not mapped, so only the headers,
+    /// segments, notes and dynamic information are available
+    #[clap(long = "pid", value_name = "PID")]
+    pid: Vec<u32>,
+
+    /// Show section and symbol addresses as loaded at BASE, the hex start of the file's
+    /// first mapping in /proc/<pid>/maps or its base address in a crash log, and print the
+    /// resulting load bias
+    #[clap(long = "base", value_name = "ADDR", parse(try_from_str = parse_address))]
+    base: Option<u64>,
+
+    /// Hex dump a section, given by name or index, with its relocations applied (repeatable)
+    #[clap(short = 'R', long = "relocated-dump", value_name = "SECTION")]
+    relocated_dump: Vec<String>,
+
+    /// With --pid, compare the read-only segments and RELRO data of the process image
+    /// against its executable on disk and list bytes changed other than by relocations
+    #[clap(long = "diff-memory", requires = "pid")]
+    diff_memory: bool,
+}
+
+/// What a report is produced for, a file, the image of a process given with --pid or a
+/// member of an archive
+enum Target {
+    File(String),
+    Process(u32),
+    /// Named `lib.a(member.o)`, as readelf does
+    Member(String, Vec<u8>),
+}
+
+impl Target {
+    fn open(&self, options: ParseOptions) -> io::Result<FileData> {
+        match self {
+            Self::File(path) => FileData::open_with(path, options),
+            #[cfg(target_os = "linux")]
+            Self::Process(pid) => FileData::from_process(*pid),
+            #[cfg(not(target_os = "linux"))]
+            Self::Process(_) => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "reading process images is only supported on Linux",
+            )),
+            Self::Member(_, data) => FileData::from_bytes_with(data.clone(), options),
         }
+    }
 
-        if args.show_symbols {
-            let symbols = elf.table_symbols().unwrap();
-            for (section, table, symbols) in symbols {
-                set_color!(stdout);
-                print!("Symbol table");
-                set_color!(stdout, Color::Magenta);
-                print!(" {} ", section);
-                set_color!(stdout);
-                print!("contains");
-                set_color!(stdout, Color::Green);
-                print!(" {} ", symbols.len());
-                set_color!(stdout);
-                println!("entries");
-                if elf.header().class().unwrap() == ElfClass::ElfClass64 {
-                    println!("   Num:    Value          Size Type    Bind   Vis      Ndx Name");
-                } else {
-                    println!("   Num:    Value  Size Type    Bind   Vis      Ndx Name");
-                }
-                for (i, symbol) in symbols.iter().enumerate() {
-                    println!(
-                        "{:>6}: {:016x}  {:>4} {:7} {:6} {} {:>3} {}",
-                        i,
-                        symbol.value(),
-                        symbol.size(),
-                        symbol.symbol_type().unwrap().display(),
-                        symbol.binding().unwrap().display(),
-                        symbol.visibility().unwrap().display(),
-                        match symbol.shndx() {
-                            0 => "UND".to_string(),
-                            65521 => "ABS".to_string(),
-                            i => i.to_string(),
-                        },
-                        table
-                            .iter()
-                            .skip(symbol.name() as usize)
-                            .take_while(|&&p| p != 0)
-                            .map(|&c| c as char)
-                            .collect::<String>()
-                    );
-                }
-                println!("\n\n");
-            }
+    /// The name the target is reported under, for a process the path its executable can
+    /// also be opened at
+    fn name(&self) -> String {
+        match self {
+            Self::File(path) => path.clone(),
+            Self::Process(pid) => format!("/proc/{}/exe", pid),
+            Self::Member(name, _) => name.clone(),
         }
+    }
 
-        if args.show_program_header {
-            println!(
-                "ELF file type is {}",
-                elf.header().file_type().unwrap().display()
-            );
-            println!("Entry point at 0x{:x}", elf.header().e_entry);
-            println!(
-                "There are {} program headers, starting at offset {}\n",
-                elf.header().e_phnum,
-                elf.header().e_phoff
-            );
+    /// The file at `path`, or each member when it is an archive. A file that cannot be
+    /// read is left for opening it to report.
+    fn expand(path: String) -> Vec<Self> {
+        let mut magic = [0; ARMAG.len()];
+        let is_archive = fs::File::open(&path)
+            .and_then(|mut file| file.read_exact(&mut magic))
+            .is_ok()
+            && magic == ARMAG;
+        let data = match is_archive.then(|| fs::read(&path)) {
+            Some(Ok(data)) => data,
+            _ => return vec![Self::File(path)],
+        };
+        Archive::parse(&data)
+            .into_iter()
+            .flat_map(|archive| archive.members())
+            .map(|member| {
+                let name = format!("{}({})", path, member.name);
+                Self::Member(name, member.data.to_vec())
+            })
+            .collect()
+    }
+}
 
-            println!("Program Headers:");
-            println!("  Type           Offset             VirtAddr           PhysAddr");
-            println!("                 FileSiz            MemSiz              Flags Align");
+impl Args {
+    /// Whether nothing but the file header is asked for, in which case the section headers
+    /// are not read at all
+    fn headers_only(&self) -> bool {
+        self.show_headers
+            && self.verbose == 0
+            && !self.reporting()
+            && !(self.show_sections
+                || self.show_section_details
+                || self.show_symbols
+                || self.show_notes
+                || self.text_only_views())
+    }
 
-            for header in elf.program_headers() {
-                println!(
-                    "  {:15}0x{:016x} 0x{:016x} 0x{:016x}\n                 0x{:016x} 0x{:016x}{:^8}0x{:x}",
-                    header.program_type().unwrap().display(),
-                    header.offset(),
-                    header.vaddr(),
-                    header.paddr(),
-                    header.filesz(),
-                    header.filesz(),
-                    header.flags().display(),
-                    header.align()
-                )
-            }
+    /// Whether a view the renderers do not cover is asked for. These print text directly,
+    /// so they have no JSON or YAML form.
+    fn text_only_views(&self) -> bool {
+        self.show_arch_specific
+            || self.show_program_header
+            || self.show_relocations
+            || self.show_dyn_syms
+            || self.show_dynamic
+            || self.show_version_info
+            || self.show_btf
+            || self.show_bpf
+            || self.show_modinfo
+            || self.show_go
+            || self.show_rust
+            || self.show_meta
+            || self.show_x86_isa
+            || self.show_branch_protection
+            || self.show_analyze
+            || self.show_ifunc
+            || self.show_copy_relocs
+            || self.show_tls
+            || self.show_weak
+            || self.show_segment_alignment
+            || self.check
+            || self.show_map
+            || self.diff_memory
+            || !self.relocated_dump.is_empty()
+            || self.xref.is_some()
+            || self.find_bytes.is_some()
+            || self.show_deps_dot
+            || self.show_resolve
+            || self.check_unresolved
+            || self.show_version_script
+            || self.show_exports
+            || self.show_dynamic_list
+            || self.show_unwind
+            || self.reading_dwarf()
+            || self.show_lsda
+            || self.scripting()
+            || self.disassembling()
+    }
 
-            println!("Section to Segment mapping:");
-            println!(" Segment Sections...");
+    /// Whether --report replaces the usual output
+    #[cfg(feature = "json")]
+    fn reporting(&self) -> bool {
+        self.report.is_some()
+    }
 
-            for (i, phdr) in elf.program_headers().iter().enumerate() {
-                print!("  {i:02}     ");
+    #[cfg(not(feature = "json"))]
+    fn reporting(&self) -> bool {
+        false
+    }
 
-                let section = elf.section_headers().iter().skip(1);
+    #[cfg(feature = "script")]
+    fn scripting(&self) -> bool {
+        self.script.is_some()
+    }
 
-                for shdr in section {
-                    if elf_section_in_segment(shdr, phdr, true, true) {
-                        print!("{} ", elf.string_lookup(shdr.name() as usize).unwrap())
-                    }
+    #[cfg(not(feature = "script"))]
+    fn scripting(&self) -> bool {
+        false
+    }
+
+    #[cfg(feature = "dwarf")]
+    fn reading_dwarf(&self) -> bool {
+        !self.debug_dump.is_empty() || self.show_debug_stats || !self.addr2line.is_empty()
+    }
+
+    #[cfg(not(feature = "dwarf"))]
+    fn reading_dwarf(&self) -> bool {
+        false
+    }
+
+    #[cfg(feature = "disasm")]
+    fn disassembling(&self) -> bool {
+        self.disassemble.is_some() || self.disassemble_range.is_some() || self.source
+    }
+
+    #[cfg(not(feature = "disasm"))]
+    fn disassembling(&self) -> bool {
+        false
+    }
+
+    /// A symbol name as -s prints it, demangled with -C
+    #[cfg(feature = "demangle")]
+    fn symbol_name(&self, name: String) -> String {
+        match demangle(&name) {
+            Some((demangled, _)) if self.demangle => demangled,
+            _ => name,
+        }
+    }
+
+    #[cfg(not(feature = "demangle"))]
+    fn symbol_name(&self, name: String) -> String {
+        name
+    }
+
+    /// Whether a symbol passes the -s filters
+    fn wants_symbol(&self, sym: &ElfSym) -> bool {
+        (self.syms_type.is_empty()
+            || matches!(sym.symbol_type(), Some(t) if self.syms_type.contains(&t)))
+            && (self.syms_bind.is_empty()
+                || matches!(sym.binding(), Some(b) if self.syms_bind.contains(&b)))
+            && !(self.defined_only && sym.shndx() == 0)
+            && !(self.undefined_only && sym.shndx() != 0)
+    }
+}
+
+fn parse_machine(name: &str) -> Result<u16, String> {
+    machine_by_name(name).ok_or_else(|| format!("unknown machine '{}'", name))
+}
+
+fn parse_address(addr: &str) -> Result<u64, String> {
+    let digits = addr.trim_start_matches("0x").trim_start_matches("0X");
+    u64::from_str_radix(digits, 16).map_err(|_| format!("invalid address '{}'", addr))
+}
+
+/// An offset in decimal, or in hex with a `0x` prefix, as binutils reads them
+#[cfg(feature = "dwarf")]
+fn parse_offset(offset: &str) -> Result<u64, String> {
+    let parsed = match offset
+        .strip_prefix("0x")
+        .or_else(|| offset.strip_prefix("0X"))
+    {
+        Some(digits) => u64::from_str_radix(digits, 16),
+        None => offset.parse(),
+    };
+    parsed.map_err(|_| format!("invalid offset '{}'", offset))
+}
+
+#[cfg(feature = "disasm")]
+fn parse_address_range(range: &str) -> Result<Range<u64>, String> {
+    let (start, end) = range
+        .split_once(',')
+        .ok_or_else(|| format!("invalid range '{}', expected START,END", range))?;
+    let (start, end) = (parse_address(start)?, parse_address(end)?);
+    match start < end {
+        true => Ok(start..end),
+        false => Err(format!("empty range '{}'", range)),
+    }
+}
+
+#[cfg(feature = "disasm")]
+fn parse_prefix_map(map: &str) -> Result<(String, String), String> {
+    map.split_once('=')
+        .filter(|(old, _)| !old.is_empty())
+        .map(|(old, new)| (old.to_string(), new.to_string()))
+        .ok_or_else(|| format!("invalid prefix map '{}', expected OLD=NEW", map))
+}
+
+fn parse_sym_base(base: &str) -> Result<SymBase, String> {
+    base.parse()
+        .ok()
+        .and_then(SymBase::from_radix)
+        .ok_or_else(|| format!("unsupported base '{}', expected 16, 10 or 8", base))
+}
+
+fn parse_symbol_type(name: &str) -> Result<SymbolType, String> {
+    symbol_type_by_name(name).ok_or_else(|| format!("unknown symbol type '{}'", name))
+}
+
+fn parse_symbol_binding(name: &str) -> Result<SymbolBinding, String> {
+    symbol_binding_by_name(name).ok_or_else(|| format!("unknown symbol binding '{}'", name))
+}
+
+/// The data a view reads, or `None` once its error is printed and the file is marked as
+/// failed, so the views after it still run
+fn or_report<T, E: Display>(file: &str, result: Result<T, E>, failed: &mut bool) -> Option<T> {
+    match result {
+        Ok(value) => Some(value),
+        Err(err) => {
+            eprintln!("readelf-rs: Error: '{}': {}", file, err);
+            *failed = true;
+            None
+        }
+    }
+}
+
+/// A debug section, inflated when compressed, or `None` when it is missing or cannot be read,
+/// which is reported as the section is skipped
+#[cfg(feature = "dwarf")]
+fn debug_section_data(file: &str, elf: &FileData, name: &str) -> Option<Vec<u8>> {
+    let shdr = elf.debug_section_by_name(name)?;
+    match elf.decompressed_section_data(&shdr) {
+        Ok(data) => Some(data),
+        Err(err) => {
+            eprintln!(
+                "readelf-rs: Warning: '{}': {}: {}, skipped",
+                file, name, err
+            );
+            None
+        }
+    }
+}
+
+/// An attribute value the way `readelf --debug-dump=info` prints it
+#[cfg(feature = "dwarf")]
+fn attribute_string(
+    dwarf: &DwarfSections,
+    attr: &Attribute,
+    unit: &UnitHeader,
+    bases: &UnitBases,
+) -> String {
+    let encoding = unit.encoding;
+    let text = || {
+        dwarf
+            .string(attr, bases, encoding)
+            .unwrap_or_else(|| "<no string>".to_string())
+    };
+    match (attr.form, attr.value) {
+        (DW_FORM_STRP, AttrValue::Unsigned(offset)) => {
+            format!("(indirect string, offset: {}): {}", gnu_hex(offset), text())
+        }
+        (DW_FORM_LINE_STRP, AttrValue::Unsigned(offset)) => {
+            format!(
+                "(indirect line string, offset: {}): {}",
+                gnu_hex(offset),
+                text()
+            )
+        }
+        (form, AttrValue::Unsigned(index)) if is_strx(form) => {
+            format!("(indexed string: {:#x}): {}", index, text())
+        }
+        (form, AttrValue::Unsigned(index)) if is_addrx(form) => {
+            match dwarf.address(attr, bases, encoding) {
+                Some(address) => format!("(index: {:#x}): {:x}", index, address),
+                None => format!("(index: {:#x}): <no .debug_addr entry>", index),
+            }
+        }
+        (DW_FORM_ADDR, AttrValue::Unsigned(address)) => gnu_hex(address),
+        (form, AttrValue::Unsigned(offset)) if is_unit_ref(form) => {
+            format!("<{:#x}>", unit.offset as u64 + offset)
+        }
+        // DW_FORM_ref_addr and DW_FORM_ref_sig8
+        (0x10, AttrValue::Unsigned(offset)) => format!("<{:#x}>", offset),
+        (0x20, AttrValue::Unsigned(signature)) => format!("signature: {:#x}", signature),
+        (DW_FORM_RNGLISTX | DW_FORM_LOCLISTX, AttrValue::Unsigned(index)) => {
+            match dwarf.list_offset(attr, bases, encoding) {
+                Some(offset) => format!("(index: {:#x}): {:#x}", index, offset),
+                None => format!("(index: {:#x})", index),
+            }
+        }
+        (DW_FORM_SEC_OFFSET, AttrValue::Unsigned(offset)) if is_location_attr(attr.name) => {
+            format!("{:#x} (location list)", offset)
+        }
+        (DW_FORM_SEC_OFFSET, AttrValue::Unsigned(offset)) => gnu_hex(offset),
+        // The size of the unit when it is not an address
+        (_, AttrValue::Unsigned(size)) if attr.name == DW_AT_HIGH_PC => format!("{:#x}", size),
+        (_, AttrValue::Unsigned(value)) => value.to_string(),
+        (_, AttrValue::Signed(value)) => value.to_string(),
+        (_, AttrValue::Flag(value)) => (value as u8).to_string(),
+        (_, AttrValue::String(_)) => text(),
+        (form, AttrValue::Block(bytes)) => {
+            let mut block = format!("{} byte block: ", bytes.len());
+            for byte in bytes {
+                block += &format!("{:x} ", byte);
+            }
+            // DW_FORM_exprloc, and the blocks location attributes used before it
+            if form == 0x18 || is_location_attr(attr.name) {
+                block += &format!("\t({})", expression_string(bytes, encoding, dwarf.endian));
+            }
+            block
+        }
+    }
+}
+
+/// The DWARF of one file as the `--debug-dump` views need it, the dumped file's own or the
+/// `.dwo` sections of a split unit
+#[cfg(feature = "dwarf")]
+struct DwarfFile<'a> {
+    sections: DwarfSections<'a>,
+    debug_line: &'a [u8],
+    /// The units of `.debug_info`, with their DIEs and the bases of their indexed forms
+    units: Vec<(UnitHeader, Vec<Die<'a>>, UnitBases)>,
+    /// `.dwo` for the sections of split units
+    suffix: &'static str,
+    /// The `.dwo` or `.dwp` file the sections were read from
+    loaded_from: Option<String>,
+}
+
+#[cfg(feature = "dwarf")]
+impl<'a> DwarfFile<'a> {
+    /// Reads the units of `debug_info`, warning about those that cannot be read in full
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "dwarf", level = "debug", skip_all, fields(suffix = suffix))
+    )]
+    fn new(
+        file: &str,
+        sections: DwarfSections<'a>,
+        debug_info: &'a [u8],
+        debug_abbrev: &[u8],
+        debug_line: &'a [u8],
+        suffix: &'static str,
+    ) -> Self {
+        let endian = sections.endian;
+        // Every dump needs the DIEs, for the bases of indexed forms and for finding the
+        // line programs and lists the way binutils does
+        let mut tables = HashMap::new();
+        let mut units = Vec::new();
+        let mut reader = ByteReader::new(debug_info, endian);
+        while !reader.is_empty() {
+            let offset = reader.pos();
+            let unit = match UnitHeader::parse(&mut reader, false) {
+                Some(unit) if unit.end <= debug_info.len() => unit,
+                _ => {
+                    eprintln!(
+                        "readelf-rs: Warning: '{}': .debug_info{}: unit at offset {:#x} is truncated",
+                        file, suffix, offset
+                    );
+                    break;
                 }
-                println!()
+            };
+            let abbrevs = tables
+                .entry(unit.abbrev_offset)
+                .or_insert_with(|| parse_abbrevs(debug_abbrev, unit.abbrev_offset, endian));
+            let (dies, complete) = read_dies(debug_info, &unit, abbrevs, endian);
+            if !complete {
+                eprintln!(
+                    "readelf-rs: Warning: '{}': .debug_info{}: the DIEs of the unit at offset {:#x} stop at an unknown abbreviation or form",
+                    file, suffix, unit.offset
+                );
             }
+            let bases = dies
+                .first()
+                .map(|die| sections.unit_bases(die, unit.encoding))
+                .unwrap_or_default();
+            units.push((unit, dies, bases));
+            reader.seek(unit.end);
         }
 
-        if args.show_relocations {
-            elf.process_relocs();
+        Self {
+            sections,
+            debug_line,
+            units,
+            suffix,
+            loaded_from: None,
         }
+    }
+}
 
-        if args.show_dyn_syms {
-            println!("Symbol table '.dynsym' contains 24 entries:");
-            println!("   Num:    Value          Size Type    Bind   Vis      Ndx Name");
+/// Prints the `Contents of the <name> section:` line the DWARF views start with
+#[cfg(feature = "dwarf")]
+fn print_section_heading(stdout: &mut StandardStream, name: &str, dwarf: &DwarfFile) {
+    set_color!(stdout);
+    print!("\nContents of the ");
+    set_color!(stdout, Color::Magenta);
+    print!("{}{}", name, dwarf.suffix);
+    set_color!(stdout);
+    match &dwarf.loaded_from {
+        Some(path) => println!(" section (loaded from {}):\n", path),
+        None => println!(" section:\n"),
+    }
+}
 
-            let dyn_syms = match elf.dynamic_symbols() {
-                Some(Ok(syms)) => syms,
-                _ => panic!("Cannot load dynamic symbols"),
-            };
+/// Prints the header of a unit the way `readelf --debug-dump=info` does
+#[cfg(feature = "dwarf")]
+fn print_unit_header(unit: &UnitHeader) {
+    println!(
+        "  Compilation Unit @ offset {}:",
+        gnu_hex(unit.offset as u64)
+    );
+    println!(
+        "   Length:        {:#x} ({}-bit)",
+        unit.length,
+        unit.encoding.offset_size * 8
+    );
+    println!("   Version:       {}", unit.encoding.version);
+    if unit.encoding.version >= 5 {
+        println!(
+            "   Unit Type:     DW_UT_{} ({})",
+            unit_type_name(unit.unit_type).unwrap_or("unknown"),
+            unit.unit_type
+        );
+    }
+    println!("   Abbrev Offset: {}", gnu_hex(unit.abbrev_offset));
+    println!("   Pointer Size:  {}", unit.encoding.address_size);
+    if let Some(id) = unit.dwo_id {
+        println!("   DWO ID:        {:#x}", id);
+    }
+    if let Some((signature, offset)) = unit.type_signature {
+        println!("   Signature:     {:#018x}", signature);
+        println!("   Type Offset:   {:#x}", offset);
+    }
+}
 
-            let table = elf
-                .table_symbols()
-                .unwrap()
-                .iter()
-                .find(|(name, _, _)| name == ".dynsym")
-                .unwrap()
-                .clone();
-            for (i, sym) in dyn_syms.iter().enumerate() {
-                println!(
-                    "{:>6}: {:016} {:>5} {:<8}{:<7}{:<8} {} {}",
-                    i,
-                    sym.value(),
-                    sym.size(),
-                    sym.symbol_type().unwrap().display(),
-                    sym.binding().unwrap().display(),
-                    sym.visibility().unwrap().display(),
-                    match sym.shndx() {
-                        0 => "UND".to_string(),
-                        65521 => "ABS".to_string(),
-                        i => i.to_string(),
-                    },
-                    table
-                        .1
-                        .iter()
-                        .skip(sym.name() as usize)
-                        .take_while(|&&p| p != 0)
-                        .map(|&c| c as char)
-                        .collect::<String>(),
+/// Prints the `--debug-dump` views of the DIEs, line programs and lists of one file
+#[cfg(feature = "dwarf")]
+fn print_dwarf(stdout: &mut StandardStream, file: &str, args: &Args, dwarf: &DwarfFile) {
+    let DwarfFile {
+        sections,
+        debug_line,
+        units,
+        ..
+    } = dwarf;
+    let endian = sections.endian;
+
+    if args.debug_dump.contains(&DebugDump::Info) && !units.is_empty() {
+        print_section_heading(stdout, ".debug_info", dwarf);
+
+        // --dwarf-start skips whole units before the offset and the start of the unit
+        // holding it, header included
+        let start = args.dwarf_start.unwrap_or(0);
+        for (unit, dies, bases) in units {
+            if (unit.end as u64) <= start {
+                continue;
+            }
+            if (unit.offset as u64) >= start {
+                print_unit_header(unit);
+            }
+            // Runs of DIEs too deep for --dwarf-depth are shown as a single `...`
+            let mut elided = false;
+            for die in dies.iter().filter(|die| die.offset as u64 >= start) {
+                if matches!(args.dwarf_depth, Some(depth) if die.depth >= depth) {
+                    if !elided {
+                        println!(" <{}><{:x}>: ...", die.depth, die.offset);
+                    }
+                    elided = true;
+                    continue;
+                }
+                elided = false;
+                print!(
+                    " <{}><{:x}>: Abbrev Number: {}",
+                    die.depth, die.offset, die.code
                 );
+                match die.code {
+                    0 => println!(),
+                    _ => println!(" ({})", tag_string(die.tag)),
+                }
+                for attr in &die.attributes {
+                    println!(
+                        "    <{:x}>   {:<18}: {}",
+                        attr.offset,
+                        at_string(attr.name),
+                        attribute_string(sections, attr, unit, bases)
+                    );
+                }
             }
         }
+        println!();
+    }
+    if args.debug_dump.contains(&DebugDump::DecodedLine) && !debug_line.is_empty() {
+        print_section_heading(stdout, ".debug_line", dwarf);
+
+        let mut offsets: Vec<_> = units
+            .iter()
+            .filter_map(|(_, dies, _)| dies.first()?.value(DW_AT_STMT_LIST))
+            .collect();
+        offsets.sort_unstable();
+        offsets.dedup();
+        for offset in offsets {
+            let program = match LineProgram::parse(
+                debug_line,
+                offset,
+                endian,
+                sections.debug_str,
+                sections.line_str,
+            ) {
+                Some(program) => program,
+                None => {
+                    eprintln!(
+                        "readelf-rs: Warning: '{}': .debug_line: cannot read the line number program at offset {:#x}",
+                        file, offset
+                    );
+                    continue;
+                }
+            };
+            let (mut file, mut last, mut view) = (None, None, 0);
+            for row in program.rows(debug_line, endian) {
+                let path = program.files.name(row.file).unwrap_or("<unknown>");
+                if file != Some(row.file) {
+                    match file {
+                        Some(_) => println!("\n{}:", path),
+                        None => {
+                            println!("{}:", path);
+                            println!("File name                            Line number    Starting address    View    Stmt");
+                        }
+                    }
+                    file = Some(row.file);
+                }
+                let name = path.rsplit('/').next().unwrap_or(path);
+                if row.end_sequence {
+                    println!("{:<35}  {:>11}  {:>#18x}\n", name, "-", row.address);
+                    last = None;
+                    continue;
+                }
+                // Views number the rows sharing an address
+                view = match last == Some(row.address) {
+                    true => view + 1,
+                    false => 0,
+                };
+                last = Some(row.address);
+                print!("{:<35}  {:>11}  {:>#18x}", name, row.line, row.address);
+                match view {
+                    0 => print!("        "),
+                    view => print!("  {:>6}", view),
+                }
+                match row.is_stmt {
+                    true => println!("       x"),
+                    false => println!(),
+                }
+            }
+            println!();
+        }
+    }
+
+    if args.debug_dump.contains(&DebugDump::Ranges) {
+        let mut lists = Vec::new();
+        for (index, (unit, dies, bases)) in units.iter().enumerate() {
+            for attr in dies.iter().filter_map(|die| die.attr(DW_AT_RANGES)) {
+                if let Some(offset) = sections.list_offset(attr, bases, unit.encoding) {
+                    lists.push((unit.encoding.version >= 5, offset, index));
+                }
+            }
+        }
+        lists.sort_unstable();
+        lists.dedup_by_key(|&mut (v5, offset, _)| (v5, offset));
+
+        for (name, data, v5) in [
+            (".debug_ranges", sections.ranges, false),
+            (".debug_rnglists", sections.rnglists, true),
+        ] {
+            if data.is_empty() {
+                continue;
+            }
+            print_section_heading(stdout, name, dwarf);
+
+            let tables = match v5 {
+                true => ListTable::parse_all(data, endian),
+                false => Vec::new(),
+            };
+            if !v5 {
+                println!("\n    Offset   Begin    End");
+            }
+            for table in &tables {
+                println!(" Table at Offset: {}:", gnu_hex(table.offset as u64));
+                println!("  Length:          {:#x}", table.length);
+                println!("  DWARF version:   {}", table.encoding.version);
+                println!("  Address size:    {}", table.encoding.address_size);
+                println!("  Segment size:    {}", table.segment_size);
+                println!("  Offset entries:  {}", table.offset_entries);
+            }
+            let in_section = lists.iter().filter(|&&(list_v5, _, _)| list_v5 == v5);
+            for (index, &(_, offset, unit)) in in_section.enumerate() {
+                let (unit, _, bases) = &units[unit];
+                let entries = match sections.range_list(offset, bases, unit.encoding) {
+                    Some(entries) => entries,
+                    None => {
+                        eprintln!(
+                            "readelf-rs: Warning: '{}': {}: cannot read the range list at offset {:#x}",
+                            file, name, offset
+                        );
+                        continue;
+                    }
+                };
+                if v5 {
+                    println!("\n  Offset: {:#x}, Index: {}", offset, index);
+                    println!("    Offset   Begin    End");
+                }
+                for (at, entry) in entries {
+                    // binutils tags the entries of .debug_ranges with the list's offset
+                    let at = if v5 { at as u64 } else { offset };
+                    match entry {
+                        ListEntry::Base(base) => {
+                            println!("    {:08x} {:016x} (base address)", at, base)
+                        }
+                        ListEntry::Range { begin, end, .. } => println!(
+                            "    {:08x} {:016x} {:016x} {}",
+                            at,
+                            begin,
+                            end,
+                            if begin == end { "(start == end)" } else { "" }
+                        ),
+                        ListEntry::End => println!("    {:08x} <End of list>", at),
+                        _ => {}
+                    }
+                }
+            }
+            println!();
+        }
+    }
+
+    if args.debug_dump.contains(&DebugDump::Loc) {
+        let mut lists = Vec::new();
+        for (index, (unit, dies, bases)) in units.iter().enumerate() {
+            for die in dies {
+                let views = die.value(DW_AT_GNU_LOCVIEWS);
+                for attr in &die.attributes {
+                    // DWARF 2 and 3 had no section offset form, their data4 and
+                    // data8 locations are list offsets too
+                    let list = match attr.form {
+                        DW_FORM_SEC_OFFSET | DW_FORM_LOCLISTX => true,
+                        0x06 | 0x07 => unit.encoding.version < 4,
+                        _ => false,
+                    };
+                    if !list || !is_location_attr(attr.name) {
+                        continue;
+                    }
+                    if let Some(offset) = sections.list_offset(attr, bases, unit.encoding) {
+                        let views = views
+                            .map(|views| sections.views_offset(views, attr, bases, unit.encoding));
+                        lists.push((unit.encoding.version >= 5, offset, index, views));
+                    }
+                }
+            }
+        }
+        lists.sort_unstable();
+        lists.dedup_by_key(|&mut (v5, offset, _, _)| (v5, offset));
+
+        for (name, data, v5) in [
+            (".debug_loc", sections.loc, false),
+            (".debug_loclists", sections.loclists, true),
+        ] {
+            if data.is_empty() {
+                continue;
+            }
+            print_section_heading(stdout, name, dwarf);
+            println!("    Offset   Begin            End              Expression");
+
+            for &(_, offset, unit, views) in lists.iter().filter(|list| list.0 == v5) {
+                let (unit, _, bases) = &units[unit];
+                let encoding = unit.encoding;
+                let entries = match sections.location_list(offset, bases, encoding) {
+                    Some(entries) => entries,
+                    None => {
+                        eprintln!(
+                            "readelf-rs: Warning: '{}': {}: cannot read the location list at offset {:#x}",
+                            file, name, offset
+                        );
+                        continue;
+                    }
+                };
+                let views = views
+                    .and_then(|views| sections.location_views(views, offset, encoding))
+                    .unwrap_or_default();
+                println!();
+                for (at, begin, end) in &views {
+                    println!(
+                        "    {:08x} v{:015x} v{:015x} location view pair",
+                        at, begin, end
+                    );
+                }
+                if !views.is_empty() {
+                    println!();
+                }
+
+                let expression = |expr| expression_string(expr, encoding, endian);
+                let mut views = views.iter();
+                for (at, entry) in entries {
+                    match entry {
+                        ListEntry::Base(base) => {
+                            println!("    {:08x} {:016x} (base address)", at, base)
+                        }
+                        ListEntry::Range { begin, end, expr } => {
+                            let expr = expression(expr.unwrap_or_default());
+                            match views.next() {
+                                Some((view_at, view_begin, view_end)) => {
+                                    println!(
+                                        "    {:08x} v{:015x} v{:015x} views at {:08x} for:",
+                                        at, view_begin, view_end, view_at
+                                    );
+                                    println!("             {:016x} {:016x} ({})", begin, end, expr);
+                                }
+                                None => println!(
+                                    "    {:08x} {:016x} {:016x} ({})",
+                                    at, begin, end, expr
+                                ),
+                            }
+                        }
+                        ListEntry::Default(expr) => {
+                            println!("    {:08x} (default location) ({})", at, expression(expr))
+                        }
+                        ListEntry::ViewPair(begin, end) => println!(
+                            "    {:08x} v{:015x} v{:015x} location view pair",
+                            at, begin, end
+                        ),
+                        ListEntry::End => println!("    {:08x} <End of list>", at),
+                    }
+                }
+            }
+            println!();
+        }
+    }
+}
+
+/// The source lines `--source` prints above the instructions, read once per file
+#[cfg(feature = "disasm")]
+struct SourceLines<'a> {
+    file: &'a str,
+    prefix_map: &'a [(String, String)],
+    /// The lines of each source file, `None` when it could not be read
+    sources: HashMap<String, Option<Vec<String>>>,
+    /// The highest line printed and the last line shown of each source file
+    printed: HashMap<String, (u64, u64)>,
+    /// The position of the previous instruction
+    last: Option<(String, u64)>,
+}
+
+#[cfg(feature = "disasm")]
+impl<'a> SourceLines<'a> {
+    fn new(file: &'a str, prefix_map: &'a [(String, String)]) -> Self {
+        Self {
+            file,
+            prefix_map,
+            sources: HashMap::new(),
+            printed: HashMap::new(),
+            last: None,
+        }
+    }
+
+    /// Prints the lines leading up to `location` when the previous instruction was on
+    /// another line. Like objdump -S, up to 5 lines of context are printed the first time
+    /// a file is shown and the lines skipped since the highest one printed when moving
+    /// forward.
+    fn print(&mut self, location: &Location) {
+        let position = (location.file.clone(), location.line);
+        if location.line == 0 || self.last.as_ref() == Some(&position) {
+            return;
+        }
+        self.last = Some(position);
+
+        let (file, prefix_map) = (self.file, self.prefix_map);
+        let lines = self
+            .sources
+            .entry(location.file.clone())
+            .or_insert_with(|| {
+                let path = prefix_map
+                    .iter()
+                    .find_map(|(old, new)| {
+                        let rest = location.file.strip_prefix(old.as_str())?;
+                        Some(format!("{}{}", new, rest))
+                    })
+                    .unwrap_or_else(|| location.file.clone());
+                match std::fs::read(&path) {
+                    Ok(data) => Some(
+                        String::from_utf8_lossy(&data)
+                            .lines()
+                            .map(str::to_string)
+                            .collect(),
+                    ),
+                    Err(err) => {
+                        eprintln!(
+                            "readelf-rs: Warning: '{}': {}: {}, skipped",
+                            file, path, err
+                        );
+                        None
+                    }
+                }
+            });
+        let lines = match lines {
+            Some(lines) => lines,
+            None => return,
+        };
+
+        let (max_printed, last_line) = self.printed.entry(location.file.clone()).or_default();
+        let line = location.line;
+        if *last_line == line {
+            return;
+        }
+        let mut first = line.saturating_sub(5).max(1);
+        if *max_printed >= first {
+            first = if *max_printed < line {
+                *max_printed + 1
+            } else {
+                line
+            };
+        }
+        for number in first..=line {
+            if let Some(text) = lines.get(number as usize - 1) {
+                println!("{}", text);
+            }
+        }
+        *max_printed = (*max_printed).max(line);
+        *last_line = line;
+    }
+}
+
+/// The section index and the addresses of the symbol `name`, from the symbol table or
+/// else the dynamic symbol table. A symbol without a size runs up to the next symbol of
+/// its section.
+#[cfg(feature = "disasm")]
+fn symbol_region(elf: &FileData, name: &str) -> io::Result<Option<(usize, Range<u64>)>> {
+    let sections = elf.section_headers();
+    for table in [SectionType::SymTab, SectionType::DynSym] {
+        for shdr in sections
+            .iter()
+            .filter(|shdr| shdr.section_type() == Some(table))
+        {
+            let (strtab, syms) = elf.symbol_table(shdr)?;
+            let sym = syms.iter().find(|sym| {
+                sym.shndx() != 0
+                    && (sym.shndx() as usize) < sections.len()
+                    && string_at(strtab, sym.name() as usize) == name
+            });
+            let sym = match sym {
+                Some(sym) => sym,
+                None => continue,
+            };
+            let start = sym.value();
+            let end = match sym.size() {
+                0 => syms
+                    .iter()
+                    .filter(|other| other.shndx() == sym.shndx() && other.value() > start)
+                    .map(|other| other.value())
+                    .min()
+                    .unwrap_or(u64::MAX),
+                size => start.saturating_add(size),
+            };
+            return Ok(Some((sym.shndx() as usize, start..end)));
+        }
+    }
+    Ok(None)
+}
+
+/// Prints the executable sections the way `objdump -d` does, with the source lines of
+/// the instructions for `--source`. `--disassemble=SYMBOL` and `--disassemble-range`
+/// narrow it down to the part of a section they name.
+#[cfg(feature = "disasm")]
+fn print_disassembly(
+    stdout: &mut StandardStream,
+    file: &str,
+    elf: &FileData,
+    args: &Args,
+) -> io::Result<()> {
+    let bitness = bitness(elf.header().machine()).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::Unsupported,
+            "disassembly is only supported for x86 and x86-64",
+        )
+    })?;
+    let symbols = elf.symbol_map()?;
+    let width = match elf.header().class() {
+        Some(ElfClass::ElfClass32) => 8,
+        _ => 16,
+    };
+    // The addresses to disassemble and the section they are in for a symbol, whose
+    // address is ambiguous in relocatable files
+    let (section, mut region) = match &args.disassemble {
+        Some(Some(name)) => {
+            let (index, region) = symbol_region(elf, name)?.ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("no symbol named '{}'", name),
+                )
+            })?;
+            (Some(index), region)
+        }
+        _ => (None, 0..u64::MAX),
+    };
+    if let Some(range) = &args.disassemble_range {
+        region = region.start.max(range.start)..region.end.min(range.end);
+    }
+    let mut found = false;
+    let lines = match args.source {
+        true => LineTable::new(&DebugInfo::read(elf)?),
+        false => LineTable::default(),
+    };
+    let mut source = SourceLines::new(file, &args.source_prefix_map);
+    let hex = |bytes: &[u8]| {
+        bytes
+            .iter()
+            .map(|byte| format!("{:02x} ", byte))
+            .collect::<String>()
+    };
+
+    for (index, shdr) in elf.section_headers().iter().enumerate() {
+        let start = shdr.addr().max(region.start);
+        let end = shdr.addr().saturating_add(shdr.size()).min(region.end);
+        if shdr.flags() & elf::shdr::SectionFlag::ExecInstr as u64 == 0
+            || shdr.section_type() == Some(SectionType::NoBits)
+            || matches!(section, Some(section) if section != index)
+            || start >= end
+        {
+            continue;
+        }
+        let name = elf.section_name(shdr).unwrap_or_default();
+        let data = elf.section_data(shdr)?;
+        let offset = (start - shdr.addr()) as usize;
+        let code = match data.get(offset..offset + (end - start) as usize) {
+            Some(code) if !code.is_empty() => code,
+            _ => continue,
+        };
+        found = true;
+        set_color!(stdout);
+        print!("\nDisassembly of section ");
+        set_color!(stdout, Color::Magenta);
+        print!("{}", name);
+        set_color!(stdout);
+        println!(":");
+
+        for insn in disassemble(code, start, bitness) {
+            let label = match symbols.lookup(insn.address) {
+                Some((symbol, 0)) => Some(args.symbol_name(symbol.to_string())),
+                // Starting inside a symbol, the label is the offset in it
+                Some((symbol, offset))
+                    if insn.address == start && insn.address - offset >= shdr.addr() =>
+                {
+                    Some(format!(
+                        "{}+0x{:x}",
+                        args.symbol_name(symbol.to_string()),
+                        offset
+                    ))
+                }
+                _ if insn.address == start => Some(name.clone()),
+                _ => None,
+            };
+            if let Some(label) = label {
+                print!("\n{:0width$x} <", insn.address, width = width);
+                set_color!(stdout, Color::Magenta);
+                print!("{}", label);
+                set_color!(stdout);
+                println!(">:");
+            }
+            if let Some(location) = lines.find(insn.address) {
+                source.print(&location);
+            }
+
+            let (bytes, rest) = insn.bytes.split_at(insn.bytes.len().min(7));
+            print!("{:>8x}:\t{:<21}\t{}", insn.address, hex(bytes), insn.text);
+            if let Some(symbol) = insn.target.and_then(|target| symbols.describe(target)) {
+                print!(" <{}>", symbol);
+            }
+            if let Some(memory) = insn.memory {
+                print!("        # {:x}", memory);
+                if let Some(symbol) = symbols.describe(memory) {
+                    print!(" <{}>", symbol);
+                }
+            }
+            println!();
+            // Bytes past the seventh go on lines of their own
+            for (index, chunk) in rest.chunks(7).enumerate() {
+                let address = insn.address + 7 * (index as u64 + 1);
+                println!("{:>8x}:\t{}", address, hex(chunk));
+            }
+        }
+    }
+
+    match found || (section.is_none() && args.disassemble_range.is_none()) {
+        true => Ok(()),
+        false => Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!(
+                "no executable section contains 0x{:x}-0x{:x}",
+                region.start, region.end
+            ),
+        )),
+    }
+}
+
+/// A value the way binutils prints it with `%#lx`, which leaves 0 without the prefix
+#[cfg(feature = "dwarf")]
+fn gnu_hex(value: u64) -> String {
+    match value {
+        0 => "0".to_string(),
+        _ => format!("{:#x}", value),
+    }
+}
+
+#[cfg(feature = "tracing")]
+fn init_tracing(verbose: u64) {
+    use tracing_subscriber::fmt::format::FmtSpan;
+
+    let level = match verbose {
+        1 => tracing::Level::INFO,
+        2 => tracing::Level::DEBUG,
+        _ => tracing::Level::TRACE,
+    };
+    tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_span_events(FmtSpan::CLOSE)
+        .with_writer(io::stderr)
+        .init();
+}
+
+fn main() {
+    process::exit(run());
+}
+
+fn run() -> i32 {
+    let mut args = Args::parse_from(expand_response_files(std::env::args()));
+    #[cfg(feature = "man")]
+    if args.generate_man {
+        print!("{}", manpage::render(&Args::command()));
+        return 0;
+    }
+    #[cfg(feature = "tracing")]
+    if args.verbose > 0 {
+        init_tracing(args.verbose);
+    }
+    if let Some(Command::Diff(diff)) = &args.command {
+        return diff::run(diff);
+    }
+
+    let config = match Config::path().filter(|_| !args.no_config) {
+        Some(path) => match Config::load(&path) {
+            Ok(config) => config,
+            Err(err) => {
+                eprintln!("readelf-rs: Error: '{}': {}", path.display(), err);
+                return 2;
+            }
+        },
+        None => Config::default(),
+    };
+    args.wide |= config.defaults.wide;
+    #[cfg(feature = "demangle")]
+    {
+        args.demangle |= config.defaults.demangle;
+    }
+    let theme = match config.theme(args.theme.as_deref()) {
+        Ok(theme) => theme,
+        Err(err) => {
+            eprintln!("readelf-rs: Error: {}", err);
+            return 2;
+        }
+    };
+    let output_format = match (args.output_format, &config.defaults.output_format) {
+        (Some(format), _) => format,
+        (None, Some(name)) => match OutputFormat::from_str(name, true) {
+            Ok(format) => format,
+            Err(err) => {
+                eprintln!("readelf-rs: Error: config output-format: {}", err);
+                return 2;
+            }
+        },
+        (None, None) => OutputFormat::Text,
+    };
+    #[cfg(feature = "json")]
+    if let (true, OutputFormat::Json | OutputFormat::Yaml) =
+        (args.text_only_views() && !args.reporting(), output_format)
+    {
+        eprintln!(
+            "readelf-rs: Error: --output-format: only -h, -S, -t, -s and -n have a JSON or \
+             YAML form"
+        );
+        return 2;
+    }
+
+    let find_bytes = match args.find_bytes.as_deref().map(parse_pattern) {
+        Some(Ok(pattern)) => Some(pattern),
+        Some(Err(err)) => {
+            eprintln!("readelf-rs: Error: --find-bytes: {}", err);
+            return 2;
+        }
+        None => None,
+    };
+
+    #[cfg(feature = "script")]
+    let script = match args.script.as_deref().map(Script::load) {
+        Some(Ok(script)) => Some(script),
+        Some(Err(err)) => {
+            eprintln!("readelf-rs: Error: --script: {}", err);
+            return 2;
+        }
+        None => None,
+    };
+
+    let _pager = if (args.pager || config.defaults.pager) && !args.no_pager {
+        Pager::start()
+    } else {
+        None
+    };
+    let mut stdout = StandardStream::stdout(match output_format {
+        OutputFormat::Text => ColorChoice::Always,
+        _ => ColorChoice::Never,
+    });
+    let mut failed = false;
+    let targets = args
+        .files
+        .iter()
+        .cloned()
+        .flat_map(Target::expand)
+        .chain(args.pid.iter().copied().map(Target::Process))
+        .collect::<Vec<_>>();
+    // readelf names every member of an archive, even one given alone
+    let banners = targets.len() > 1
+        || targets
+            .iter()
+            .any(|target| matches!(target, Target::Member(..)));
+    let sym_base = args.sym_base.unwrap_or_default();
+    let truncate = !(args.no_truncate || args.wide);
+    #[cfg(feature = "json")]
+    let tree = |format: TreeFormat| {
+        let renderer = TreeRenderer::new(io::stdout(), format);
+        match args.sym_base {
+            Some(base) => renderer.with_sym_base(base),
+            None => renderer,
+        }
+    };
+    let mut renderer: Box<dyn Renderer> = match output_format {
+        OutputFormat::Text => Box::new(
+            TextRenderer::new(StandardStream::stdout(ColorChoice::Always))
+                .with_banners(banners)
+                .with_theme(theme)
+                .with_sym_base(sym_base)
+                .with_truncation(truncate),
+        ),
+        OutputFormat::Gnu => Box::new(
+            TextRenderer::new(StandardStream::stdout(ColorChoice::Never))
+                .with_banners(banners)
+                .with_sym_base(sym_base)
+                .with_truncation(truncate),
+        ),
+        #[cfg(feature = "json")]
+        OutputFormat::Json => Box::new(tree(TreeFormat::Json)),
+        #[cfg(feature = "json")]
+        OutputFormat::Yaml => Box::new(tree(TreeFormat::Yaml)),
+    };
+
+    let options =
+        ParseOptions::default()
+            .with_strict(args.strict)
+            .with_level(match args.headers_only() {
+                true => ParseLevel::Headers,
+                false => ParseLevel::Sections,
+            });
+
+    #[cfg(feature = "json")]
+    let mut report = Vec::new();
+    for target in &targets {
+        let f = &target.name();
+        let started = Instant::now();
+        let elf = match target.open(options) {
+            Ok(elf) => elf,
+            Err(err) => {
+                #[cfg(feature = "json")]
+                if args.reporting() {
+                    let diagnostic = Diagnostic::error("unreadable-file", err.to_string());
+                    report.push((f.clone(), vec![diagnostic]));
+                    failed = true;
+                    continue;
+                }
+                if !args.quiet {
+                    eprintln!("readelf-rs: Error: '{}': {}", f, err);
+                }
+                failed = true;
+                continue;
+            }
+        };
+        if args.verbose > 0 {
+            eprintln!(
+                "readelf-rs: '{}': {} segments, {} sections, parsed in {:.2?}",
+                f,
+                elf.program_headers().len(),
+                elf.section_headers().len(),
+                started.elapsed()
+            );
+        }
+        if !args.filter_machine.is_empty() && !args.filter_machine.contains(&elf.header().machine())
+        {
+            continue;
+        }
+        if !args.filter_type.is_empty()
+            && !matches!(elf.header().ftype(), Some(ftype) if args.filter_type.contains(&ftype))
+        {
+            continue;
+        }
+
+        #[cfg(feature = "json")]
+        if args.reporting() {
+            let mut diagnostics = elf.diagnostics();
+            diagnostics.extend(elf.pointer_diagnostics());
+            report.push((f.clone(), diagnostics));
+            continue;
+        }
+        if args.quiet {
+            continue;
+        }
+
+        renderer.begin_file(f).unwrap();
+
+        let bias = args
+            .base
+            .and_then(|base| elf.bias_for_base(base))
+            .unwrap_or(0);
+        renderer.set_load_bias(bias);
+        if let (Some(base), OutputFormat::Text | OutputFormat::Gnu) = (args.base, output_format) {
+            println!("Load bias for base {:#x}: {:#x}", base, bias);
+        }
+
+        if args.show_meta {
+            if let Some(meta) = or_report(f, FileMeta::read(&elf), &mut failed) {
+                println!("{}: {}", f, meta.display());
+            }
+        }
+
+        if args.show_headers {
+            renderer.set_position_independent(elf.is_pie());
+            renderer.file_header(elf.header()).unwrap();
+        }
+
+        if args.show_sections || args.show_section_details {
+            renderer
+                .begin_sections(
+                    elf.header(),
+                    elf.section_headers().len(),
+                    elf.header().shstart(),
+                )
+                .unwrap();
+            for (i, shdr) in elf.section_headers().iter().enumerate() {
+                let name = elf.section_name(shdr).unwrap_or_default();
+                renderer.section_row(i, &name, shdr).unwrap();
+            }
+            renderer.end_sections().unwrap();
+        }
+
+        // The decoded contents have no structured form, JSON and YAML carry the table alone
+        if let (true, OutputFormat::Text | OutputFormat::Gnu) =
+            (args.show_section_details, output_format)
+        {
+            for shdr in elf.section_headers() {
+                let name = elf.section_name(shdr).unwrap_or_default();
+                match shdr.section_type() {
+                    Some(SectionType::LlvmAddrsig) => {
+                        let strtab_syms = elf
+                            .section_headers()
+                            .get(shdr.link() as usize)
+                            .and_then(|symtab| elf.symbol_table(symtab).ok());
+                        let data = match or_report(f, elf.section_data(shdr), &mut failed) {
+                            Some(data) => data,
+                            None => continue,
+                        };
+                        let indices = parse_addrsig(&data);
+
+                        set_color!(stdout);
+                        print!("\nAddress significant symbols in section ");
+                        set_color!(stdout, Color::Magenta);
+                        print!("'{}'", name);
+                        set_color!(stdout);
+                        match indices {
+                            None => println!(" are malformed"),
+                            Some(indices) => {
+                                println!(": {}", indices.len());
+                                for index in indices {
+                                    let sym_name =
+                                        strtab_syms.as_ref().and_then(|(strtab, syms)| {
+                                            syms.get(index as usize)
+                                                .map(|sym| string_at(strtab, sym.name() as usize))
+                                        });
+                                    println!(
+                                        "  {:>6}: {}",
+                                        index,
+                                        sym_name.as_deref().unwrap_or("<invalid index>")
+                                    );
+                                }
+                            }
+                        }
+                    }
+                    Some(SectionType::LlvmLinkerOptions) => {
+                        set_color!(stdout);
+                        print!("\nLinker options in section ");
+                        set_color!(stdout, Color::Magenta);
+                        print!("'{}'", name);
+                        set_color!(stdout);
+                        let data = match or_report(f, elf.section_data(shdr), &mut failed) {
+                            Some(data) => data,
+                            None => continue,
+                        };
+                        match parse_linker_options(&data) {
+                            None => println!(" are malformed"),
+                            Some(options) => {
+                                println!(": {}", options.len());
+                                for (key, value) in options {
+                                    println!("  {}: {}", key, value);
+                                }
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        if args.show_symbols {
+            let class = elf.header().class().unwrap_or(ElfClass::ElfClass64);
+            let versions = elf.symbol_versions().unwrap_or_default();
+            let tables = or_report(f, elf.table_symbols(), &mut failed).unwrap_or_default();
+            for (section, table, symbols) in tables {
+                renderer
+                    .begin_symbols(&section, symbols.len(), class)
+                    .unwrap();
+                for (i, symbol) in symbols.iter().enumerate() {
+                    if !args.wants_symbol(symbol) {
+                        continue;
+                    }
+                    let raw = string_at(table, symbol.name() as usize);
+                    let suffix = match section.as_str() {
+                        ".dynsym" => versions.symbol_table_suffix(i, &raw, symbol.shndx() == 0),
+                        _ => None,
+                    };
+                    let name = args.symbol_name(raw) + suffix.as_deref().unwrap_or_default();
+                    renderer.symbol_row(i, &name, symbol).unwrap();
+                }
+                renderer.end_symbols().unwrap();
+            }
+        }
+
+        if args.show_notes {
+            let endian = elf.header().endian().unwrap_or(Endian::Little);
+            let class = elf.header().class().unwrap_or(ElfClass::ElfClass64);
+            let machine = elf.header().machine();
+            let containers = or_report(f, elf.note_containers(), &mut failed).unwrap_or_default();
+            for (container, notes) in containers {
+                renderer.begin_notes(&container, notes.len()).unwrap();
+                for note in &notes {
+                    renderer.note(note, endian, class, machine).unwrap();
+                }
+                renderer.end_notes().unwrap();
+            }
+        }
+
+        if args.show_program_header {
+            println!(
+                "Elf file type is {}",
+                elf.header().describe_type(elf.is_pie())
+            );
+            println!("Entry point at 0x{:x}", elf.header().e_entry);
+            println!(
+                "There are {} program headers, starting at offset {}\n",
+                elf.header().e_phnum,
+                elf.header().e_phoff
+            );
+
+            println!("Program Headers:");
+            println!("  Type           Offset             VirtAddr           PhysAddr");
+            println!("                 FileSiz            MemSiz              Flags Align");
+
+            for header in elf.program_headers() {
+                println!(
+                    "  {:<14.14} 0x{:016x} 0x{:016x} 0x{:016x}\n                 0x{:016x} 0x{:016x}{:^8}0x{:x}",
+                    program_type_name(elf.header().machine(), header.p_type()),
+                    header.offset(),
+                    header.vaddr(),
+                    header.paddr(),
+                    header.filesz(),
+                    header.memsz(),
+                    header.flags().display(),
+                    header.align()
+                );
+
+                let payload = || elf.read_at(header.offset(), header.filesz() as usize);
+                match header.program_type() {
+                    Some(ProgramType::Interp) => {
+                        if let Ok(data) = payload() {
+                            let path = data.split(|&c| c == 0).next().unwrap_or_default();
+                            println!(
+                                "      [Requesting program interpreter: {}]",
+                                String::from_utf8_lossy(path)
+                            );
+                        }
+                    }
+                    Some(ProgramType::GnuEhFrame) => {
+                        let class = elf.header().class().unwrap_or(ElfClass::ElfClass64);
+                        let endian = elf.header().endian().unwrap_or(Endian::Little);
+                        let hdr = payload().ok().and_then(|data| {
+                            EhFrameHdr::parse(&data, header.vaddr(), class, endian)
+                        });
+                        if let Some(hdr) = hdr {
+                            print!("      [.eh_frame_hdr: {} FDEs", hdr.table.len());
+                            match hdr.eh_frame_ptr {
+                                Some(ptr) => println!(", .eh_frame at {:#x}]", ptr),
+                                None => println!("]"),
+                            }
+                        }
+                    }
+                    Some(ProgramType::Note) => {
+                        let endian = elf.header().endian().unwrap_or(Endian::Little);
+                        let memtag = payload()
+                            .map(|data| Note::parse_aligned(&data, endian, header.align()))
+                            .unwrap_or_default()
+                            .iter()
+                            .find_map(|note| note.memtag(endian));
+                        if let Some(mode) = memtag {
+                            let enabled = |on: bool| if on { "enabled" } else { "disabled" };
+                            println!(
+                                "      [Memory tagging: {}, heap {}, stack {}]",
+                                mode.level_name(),
+                                enabled(mode.heap),
+                                enabled(mode.stack)
+                            );
+                        }
+                    }
+                    None if elf.header().machine() == EM_AARCH64
+                        && header.p_type() == PT_AARCH64_MEMTAG_MTE =>
+                    {
+                        println!(
+                            "      [MTE tags of 0x{:x}-0x{:x}: {} granules]",
+                            header.vaddr(),
+                            header.vaddr() + header.memsz(),
+                            header.memsz() / MTE_GRANULE_SIZE
+                        );
+                    }
+                    Some(ProgramType::GnuProperty) => {
+                        let class = elf.header().class().unwrap_or(ElfClass::ElfClass64);
+                        let endian = elf.header().endian().unwrap_or(Endian::Little);
+                        let properties = payload()
+                            .map(|data| Note::parse_aligned(&data, endian, header.align()))
+                            .unwrap_or_default()
+                            .iter()
+                            .flat_map(|note| GnuProperty::parse(note, class, endian))
+                            .map(|property| property.describe(elf.header().machine(), endian))
+                            .collect::<Vec<_>>();
+                        if !properties.is_empty() {
+                            println!("      [Properties: {}]", properties.join("; "));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+
+            println!("Section to Segment mapping:");
+            println!(" Segment Sections...");
+
+            for (i, phdr) in elf.program_headers().iter().enumerate() {
+                print!("  {i:02}     ");
+
+                let section = elf.section_headers().iter().skip(1);
+
+                for shdr in section {
+                    if elf_section_in_segment(shdr, phdr, true, true) {
+                        print!(
+                            "{} ",
+                            elf.string_lookup(shdr.name() as usize).unwrap_or_default()
+                        )
+                    }
+                }
+                println!()
+            }
+        }
+
+        if args.show_dynamic {
+            let dynamic = elf.dynamic_section();
+            let count = elf.dynamic_entry_count();
+            if count == 0 {
+                println!("\nThere is no dynamic section in this file.");
+            } else {
+                let strtab = match (
+                    elf.dynamic_value(DynamicTag::StrTab),
+                    elf.dynamic_value(DynamicTag::StrSz),
+                ) {
+                    (Some(addr), Some(size)) => {
+                        elf.read_vaddr(addr, size as usize).unwrap_or_default()
+                    }
+                    _ => Vec::new(),
+                };
+                let is64 = elf.header().class() == Some(ElfClass::ElfClass64);
+
+                set_color!(stdout);
+                print!("\nDynamic section at offset ");
+                set_color!(stdout, Color::Magenta);
+                print!("{:#x}", elf.dynamic_offset());
+                set_color!(stdout);
+                println!(
+                    " contains {} {}:",
+                    count,
+                    if count == 1 { "entry" } else { "entries" }
+                );
+                println!("  Tag        Type                         Name/Value");
+                // The DT_NULL terminator is listed too
+                let entries = dynamic
+                    .iter()
+                    .map(|entry| (entry.tag, unsafe { entry.value.val }))
+                    .chain([(DynamicTag::Null as u64, 0)])
+                    .take(count);
+                for (tag, value) in entries {
+                    let name = format!("({})", dynamic_tag_name(tag));
+                    let value =
+                        dynamic_value_string(tag, value, |at| string_at(&strtab, at as usize));
+                    match is64 {
+                        true => println!(" 0x{:016x} {:<20} {}", tag, name, value),
+                        false => println!(" 0x{:08x} {:<28} {}", tag, name, value),
+                    }
+                }
+            }
+        }
+
+        if args.show_relocations {
+            let has_sections = elf.section_headers().iter().any(|shdr| {
+                matches!(
+                    shdr.section_type(),
+                    Some(SectionType::Rel | SectionType::Rela)
+                )
+            });
+            let use_sections = has_sections && !args.use_dynamic;
+            let is64 = elf.header().class() == Some(ElfClass::ElfClass64);
+            let (width, pad) = if is64 { (12, 20) } else { (8, 12) };
+            let value_width = if is64 { 16 } else { 8 };
+            let targets = match args.reloc_targets {
+                true => or_report(f, elf.symbol_map(), &mut failed),
+                false => None,
+            };
+
+            // Without section headers the tables and their symbols come from the dynamic section
+            let ((dynamic_strtab, dynamic_syms), versions) = match use_sections {
+                true => (
+                    Default::default(),
+                    elf.symbol_versions().unwrap_or_default(),
+                ),
+                false => (
+                    elf.dynamic_segment_symbols().unwrap_or_default(),
+                    elf.dynamic_symbol_versions().unwrap_or_default(),
+                ),
+            };
+            let no_versions = SymbolVersions::default();
+
+            let mut tables = Vec::new();
+            if use_sections {
+                for shdr in elf.section_headers().iter().filter(|shdr| {
+                    matches!(
+                        shdr.section_type(),
+                        Some(SectionType::Rel | SectionType::Rela)
+                    )
+                }) {
+                    let rels = elf
+                        .relocations_iter(shdr)
+                        .and_then(Iterator::collect::<io::Result<Vec<_>>>);
+                    let rels = match or_report(f, rels, &mut failed) {
+                        Some(rels) => rels,
+                        None => continue,
+                    };
+                    let symtab = match shdr.link() {
+                        0 => None,
+                        link => elf.section_headers().get(link as usize),
+                    };
+                    let (strtab, syms) = symtab
+                        .and_then(|symtab| or_report(f, elf.symbol_table(symtab), &mut failed))
+                        .unwrap_or_default();
+                    let versions = match symtab.and_then(|symtab| symtab.section_type()) {
+                        Some(SectionType::DynSym) => &versions,
+                        _ => &no_versions,
+                    };
+                    // The table the dynamic section points at is the one its count applies to
+                    let count_tag = [
+                        (DynamicTag::Rela, DynamicTag::Relacount),
+                        (DynamicTag::Rel, DynamicTag::Relcount),
+                    ]
+                    .into_iter()
+                    .find(|(table, _)| {
+                        shdr.addr() != 0 && elf.dynamic_value(*table) == Some(shdr.addr())
+                    })
+                    .map(|(_, count)| count);
+                    let name = elf.section_name(shdr).unwrap_or_default();
+                    tables.push((name, *shdr, rels, strtab, syms, versions, count_tag));
+                }
+            } else {
+                let dynamic =
+                    or_report(f, elf.dynamic_relocations(), &mut failed).unwrap_or_default();
+                if dynamic.is_empty() {
+                    match args.use_dynamic {
+                        true => println!("\nThere are no dynamic relocations in this file."),
+                        false => println!("\nThere are no relocations in this file."),
+                    }
+                }
+                for (name, shdr, rels) in dynamic {
+                    // Linkers sort the relative relocations first and count them
+                    let count_tag = match name {
+                        "RELA" => Some(DynamicTag::Relacount),
+                        "REL" => Some(DynamicTag::Relcount),
+                        _ => None,
+                    };
+                    tables.push((
+                        name.to_string(),
+                        shdr,
+                        rels,
+                        &dynamic_strtab[..],
+                        &dynamic_syms[..],
+                        &versions,
+                        count_tag,
+                    ));
+                }
+            }
+
+            for (name, shdr, rels, strtab, syms, versions, count_tag) in &tables {
+                let rela = shdr.section_type() == Some(SectionType::Rela);
+                set_color!(stdout);
+                match use_sections {
+                    true => {
+                        print!("\nRelocation section '");
+                        set_color!(stdout, Color::Magenta);
+                        print!("{}", name);
+                        set_color!(stdout);
+                        println!(
+                            "' at offset 0x{:x} contains {} {}:",
+                            shdr.offset(),
+                            rels.len(),
+                            if rels.len() == 1 { "entry" } else { "entries" }
+                        );
+                    }
+                    false => {
+                        print!("\n'");
+                        set_color!(stdout, Color::Magenta);
+                        print!("{}", name);
+                        set_color!(stdout);
+                        println!(
+                            "' relocation section at offset 0x{:x} contains {} bytes:",
+                            // binutils reports the address the dynamic section gives here
+                            shdr.addr(),
+                            shdr.size()
+                        );
+                    }
+                }
+
+                // Only the run at the start that really is relative gets folded
+                let count = count_tag.and_then(|tag| elf.dynamic_value(tag));
+                let relative = relative_type(elf.header().machine());
+                let folded = match count {
+                    Some(count) if !args.expand_relative => rels
+                        .iter()
+                        .take(count as usize)
+                        .take_while(|rel| Some(rel.rtype()) == relative)
+                        .count(),
+                    _ => 0,
+                };
+                if let (Some(count), Some(tag)) = (count, count_tag) {
+                    println!(
+                        " {}: {} relative relocations",
+                        dynamic_tag_name(*tag as u64),
+                        count
+                    );
+                }
+                println!(
+                    "{}{}",
+                    match is64 {
+                        true => "  Offset          Info           Type           Sym. Value    Sym. Name",
+                        false => " Offset     Info    Type            Sym.Value  Sym. Name",
+                    },
+                    if rela { " + Addend" } else { "" }
+                );
+                if folded > 0 {
+                    println!(
+                        "  [{} {} relocations at 0x{:x} to 0x{:x}, --expand-relative lists them]",
+                        folded,
+                        reloc_type_name(elf.header().machine(), relative.unwrap_or(0))
+                            .unwrap_or("relative"),
+                        rels[0].offset(),
+                        rels[folded - 1].offset()
+                    );
+                }
+
+                for rel in &rels[folded..] {
+                    print!(
+                        "{:0width$x}  {:0width$x} {:<17.17}",
+                        rel.offset(),
+                        rel.info(),
+                        reloc_type_name(elf.header().machine(), rel.rtype()).map_or_else(
+                            || format!("unrecognized: {:x}", rel.rtype()),
+                            String::from
+                        ),
+                        width = width
+                    );
+                    match syms.get(rel.sym() as usize).filter(|_| rel.sym() != 0) {
+                        Some(sym) => {
+                            let mut name = string_at(strtab, sym.name() as usize);
+                            // Section symbols go by the name of their section
+                            if name.is_empty() && sym.symbol_type() == Some(SymbolType::Section) {
+                                name = elf
+                                    .section_headers()
+                                    .get(sym.shndx() as usize)
+                                    .and_then(|shdr| elf.section_name(shdr))
+                                    .unwrap_or_default();
+                            }
+                            let name = match truncate && name.chars().count() > 22 {
+                                true => {
+                                    format!("{}[...]", name.chars().take(17).collect::<String>())
+                                }
+                                false => name,
+                            };
+                            let version = versions
+                                .name_suffix(rel.sym() as usize, sym.shndx() == 0)
+                                .unwrap_or_default();
+                            print!(
+                                " {:0value_width$x} {}{}",
+                                sym.value(),
+                                name,
+                                version,
+                                value_width = value_width
+                            );
+                            match rel.addend() {
+                                Some(addend) if addend < 0 => {
+                                    print!(" - {:x}", addend.unsigned_abs())
+                                }
+                                Some(addend) => print!(" + {:x}", addend),
+                                None => {}
+                            }
+                        }
+                        None => {
+                            if let Some(addend) = rel.addend() {
+                                print!("{:pad$}{:x}", "", addend, pad = pad);
+                            }
+                        }
+                    }
+                    if let Some(symbols) = &targets {
+                        let target = elf.describe_vaddr(symbols, rel.offset());
+                        set_color!(stdout, Color::Cyan);
+                        print!("  in {}", target.as_deref().unwrap_or("<unmapped>"));
+                        set_color!(stdout);
+                    }
+                    println!();
+                }
+            }
+        }
+
+        if args.show_dyn_syms {
+            let dynsym = elf
+                .section_headers()
+                .iter()
+                .find(|shdr| shdr.section_type() == Some(SectionType::DynSym))
+                .copied();
+            // Without the section the table can still be found through the dynamic section
+            let table = match dynsym {
+                Some(shdr) if !args.use_dynamic => {
+                    elf.symbol_table(&shdr).ok().map(|(strtab, syms)| {
+                        let versions = elf.symbol_versions().unwrap_or_default();
+                        let table = (Cow::Borrowed(strtab), Cow::Borrowed(syms));
+                        ("'.dynsym'".to_string(), table, versions)
+                    })
+                }
+                _ if elf.has_dynamic_section() => elf
+                    .dynamic_segment_symbols()
+                    .ok()
+                    .filter(|(_, syms)| !syms.is_empty())
+                    .map(|(strtab, syms)| {
+                        let versions = elf.dynamic_symbol_versions().unwrap_or_default();
+                        let table = (Cow::Owned(strtab), Cow::Owned(syms));
+                        ("for image".to_string(), table, versions)
+                    }),
+                _ => None,
+            };
+
+            match table {
+                None => {
+                    println!(
+                        "\nDynamic symbol information is not available for displaying symbols."
+                    )
+                }
+                Some((name, (strtab, syms), versions)) => {
+                    let is64 = elf.header().class() == Some(ElfClass::ElfClass64);
+                    let width = if is64 { 16 } else { 8 };
+
+                    println!("\nSymbol table {} contains {} entries:", name, syms.len());
+                    println!(
+                        "{}",
+                        match is64 {
+                            true =>
+                                "   Num:    Value          Size Type    Bind   Vis      Ndx Name",
+                            false => "   Num:    Value  Size Type    Bind   Vis      Ndx Name",
+                        }
+                    );
+                    for (i, sym) in syms.iter().enumerate() {
+                        println!(
+                            "{:>6}: {} {:>5} {:<7} {:<6} {:<7} {:>4} {}",
+                            i,
+                            sym_base.column(sym.rebased_value(bias), width),
+                            sym.size(),
+                            sym.symbol_type().map_or_else(
+                                || format!("<unknown>: {}", sym.info() & 0xf),
+                                |t| t.to_string()
+                            ),
+                            sym.binding().map_or_else(
+                                || format!("<unknown>: {}", sym.info() >> 4),
+                                |b| b.to_string()
+                            ),
+                            sym.visibility().map_or(String::new(), |v| v.to_string()),
+                            match sym.shndx() {
+                                0 => "UND".to_string(),
+                                65521 => "ABS".to_string(),
+                                65522 => "COM".to_string(),
+                                i => i.to_string(),
+                            },
+                            {
+                                let name = string_at(&strtab, sym.name() as usize);
+                                let suffix =
+                                    versions.symbol_table_suffix(i, &name, sym.shndx() == 0);
+                                args.symbol_name(name) + suffix.as_deref().unwrap_or_default()
+                            },
+                        );
+                    }
+                }
+            }
+        }
+
+        if args.show_version_info {
+            let versions = elf.symbol_versions().unwrap_or_default();
+            if !elf.section_headers().iter().any(|shdr| {
+                matches!(
+                    shdr.section_type(),
+                    Some(SectionType::VerSym | SectionType::VerNeed | SectionType::VerDef)
+                )
+            }) {
+                set_color!(stdout);
+                println!("\nNo version information found in this file.");
+            }
+            let width = match elf.header().class() {
+                Some(ElfClass::ElfClass32) => 8,
+                _ => 16,
+            };
+            let entries = |count: usize| match count {
+                1 => "entry",
+                _ => "entries",
+            };
+            // readelf prints offsets with C's %#06x, which leaves the 0x off zero
+            let offset = |offset: usize| match offset {
+                0 => "000000".to_string(),
+                _ => format!("{:#06x}", offset),
+            };
+
+            for shdr in elf.section_headers() {
+                let link = elf
+                    .section_headers()
+                    .get(shdr.link() as usize)
+                    .and_then(|link| elf.section_name(link))
+                    .unwrap_or_default();
+                let location = format!(
+                    " Addr: 0x{:0w$x}  Offset: 0x{:08x}  Link: {} ({})",
+                    shdr.addr(),
+                    shdr.offset(),
+                    shdr.link(),
+                    link,
+                    w = width
+                );
+                let name = elf.section_name(shdr).unwrap_or_default();
+
+                match shdr.section_type() {
+                    Some(SectionType::VerSym) => {
+                        let endian = elf.header().endian().unwrap_or(Endian::Little);
+                        let data = match or_report(f, elf.section_data(shdr), &mut failed) {
+                            Some(data) => data,
+                            None => continue,
+                        };
+                        let versym = SymbolVersions::parse_versym(&data, endian);
+                        set_color!(stdout);
+                        println!(
+                            "\nVersion symbols section '{}' contains {} {}:",
+                            name,
+                            versym.len(),
+                            entries(versym.len())
+                        );
+                        println!("{}", location);
+                        for (row, chunk) in versym.chunks(4).enumerate() {
+                            let mut line = format!("  {:03x}:", row * 4);
+                            for &value in chunk {
+                                let entry = match value {
+                                    0 => "   0 (*local*)    ".to_string(),
+                                    1 => "   1 (*global*)   ".to_string(),
+                                    _ => {
+                                        let version = versions.version_name(value).unwrap_or("???");
+                                        format!(
+                                            "{:4x}{}({}{:<w$}",
+                                            value & !VERSYM_HIDDEN,
+                                            if value & VERSYM_HIDDEN != 0 { 'h' } else { ' ' },
+                                            version,
+                                            ")",
+                                            // readelf pads with a negative width past 12
+                                            w = version.len().abs_diff(12)
+                                        )
+                                    }
+                                };
+                                line.push_str(&format!("{:<18}", entry));
+                            }
+                            println!("{}", line);
+                        }
+                    }
+                    Some(SectionType::VerDef) => {
+                        set_color!(stdout);
+                        println!(
+                            "\nVersion definition section '{}' contains {} {}:",
+                            name,
+                            versions.verdef.len(),
+                            entries(versions.verdef.len())
+                        );
+                        println!("{}", location);
+                        for def in &versions.verdef {
+                            print!(
+                                "  {}: Rev: {}  Flags: {}  Index: {}  Cnt: {}  Name: ",
+                                offset(def.offset),
+                                def.version,
+                                version_flags_name(def.flags),
+                                def.ndx,
+                                def.names.len()
+                            );
+                            // The base definition names the file itself rather than a version
+                            if def.is_base() {
+                                set_color!(stdout, Color::Magenta);
+                            }
+                            println!("{}", def.name().unwrap_or_default());
+                            set_color!(stdout);
+                            for (i, (parent, at)) in
+                                def.names.iter().zip(&def.name_offsets).enumerate().skip(1)
+                            {
+                                println!("  {}: Parent {}: {}", offset(*at), i, parent);
+                            }
+                        }
+                    }
+                    Some(SectionType::VerNeed) => {
+                        set_color!(stdout);
+                        println!(
+                            "\nVersion needs section '{}' contains {} {}:",
+                            name,
+                            versions.verneed.len(),
+                            entries(versions.verneed.len())
+                        );
+                        println!("{}", location);
+                        for need in &versions.verneed {
+                            println!(
+                                "  {}: Version: {}  File: {}  Cnt: {}",
+                                offset(need.offset),
+                                need.version,
+                                need.file,
+                                need.aux.len()
+                            );
+                            for aux in &need.aux {
+                                println!(
+                                    "  {}:   Name: {}  Flags: {}  Version: {}",
+                                    offset(aux.offset),
+                                    aux.name,
+                                    version_flags_name(aux.flags),
+                                    aux.other
+                                );
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        if args.show_arch_specific {
+            let endian = elf.header().endian().unwrap_or(Endian::Little);
+            for shdr in elf
+                .section_headers()
+                .iter()
+                .filter(|shdr| shdr.section_type() == Some(SectionType::GnuLibList))
+            {
+                let strtab = match elf.section_headers().get(shdr.link() as usize) {
+                    Some(strtab) => or_report(f, elf.section_data(strtab), &mut failed),
+                    None => Some(Vec::new()),
+                };
+                let (strtab, data) =
+                    match (strtab, or_report(f, elf.section_data(shdr), &mut failed)) {
+                        (Some(strtab), Some(data)) => (strtab, data),
+                        _ => continue,
+                    };
+                let libs = Library::parse_section(&data, &strtab, endian);
+
+                set_color!(stdout);
+                print!("\nLibrary list section ");
+                set_color!(stdout, Color::Magenta);
+                print!("'{}'", elf.section_name(shdr).unwrap_or_default());
+                set_color!(stdout);
+                println!(" contains {} entries:", libs.len());
+                println!("     Library              Time Stamp          Checksum   Version Flags");
+                for (i, lib) in libs.iter().enumerate() {
+                    let name = match truncate {
+                        true => lib.name.chars().take(20).collect(),
+                        false => lib.name.clone(),
+                    };
+                    println!(
+                        "{:>3}: {:<20} {} {:#010x} {:<7} {:<7}",
+                        i,
+                        name,
+                        lib.time(),
+                        lib.checksum,
+                        lib.version,
+                        lib.flags
+                    );
+                }
+            }
+
+            let dynamic = elf.dynamic_section();
+            for shdr in elf
+                .section_headers()
+                .iter()
+                .filter(|shdr| shdr.section_type() == Some(SectionType::SunwSyminfo))
+            {
+                let data = match or_report(f, elf.section_data(shdr), &mut failed) {
+                    Some(data) => data,
+                    None => continue,
+                };
+                let infos = Syminfo::parse(&data, endian);
+                let (strtab, syms) = elf
+                    .section_headers()
+                    .get(shdr.link() as usize)
+                    .and_then(|dynsym| elf.symbol_table(dynsym).ok())
+                    .unwrap_or_default();
+                let cut = |name: String, width: usize| match truncate {
+                    true => name.chars().take(width).collect(),
+                    false => name,
+                };
+
+                set_color!(stdout);
+                println!(
+                    "\nDynamic info segment at offset 0x{:x} contains {} entries:",
+                    shdr.offset(),
+                    infos.len()
+                );
+                println!(" Num: Name                           BoundTo     Flags");
+                for (i, info) in infos.iter().enumerate() {
+                    let name = match syms.get(i) {
+                        Some(sym) => cut(string_at(strtab, sym.name() as usize), 30),
+                        None => "<corrupt index>".to_string(),
+                    };
+                    // Other values index the DT_NEEDED entry of the library providing it
+                    let bound = match info.boundto {
+                        SYMINFO_BT_SELF => "SELF".to_string(),
+                        SYMINFO_BT_PARENT => "PARENT".to_string(),
+                        SYMINFO_BT_NONE => "NONE".to_string(),
+                        SYMINFO_BT_EXTERN => "EXTERN".to_string(),
+                        index => match dynamic.get(index as usize) {
+                            Some(d) if index > 0 && d.tag == DynamicTag::Needed as u64 => {
+                                cut(string_at(strtab, unsafe { d.value.val } as usize), 10)
+                            }
+                            _ => index.to_string(),
+                        },
+                    };
+
+                    print!("{:>4}: {:<30} {:<10} ", i, name, bound);
+                    for flag in info.flag_names() {
+                        print!(" {}", flag);
+                    }
+                    println!();
+                }
+            }
+        }
+
+        if args.show_btf {
+            let btf = elf
+                .section_by_name(".BTF")
+                .map(|shdr| elf.section_data(&shdr).map(|data| Btf::parse(&data)));
+
+            match btf {
+                None => println!("\nThere is no .BTF section in this file."),
+                Some(Err(err)) => {
+                    eprintln!("readelf-rs: Error: '{}': .BTF: {}", f, err);
+                    failed = true;
+                }
+                Some(Ok(None)) => println!("\nThe .BTF section is malformed."),
+                Some(Ok(Some(btf))) => {
+                    set_color!(stdout);
+                    print!("\nBTF section");
+                    set_color!(stdout, Color::Magenta);
+                    print!(" '.BTF' ");
+                    set_color!(stdout);
+                    print!("version {} contains", btf.header.version);
+                    set_color!(stdout, Color::Green);
+                    print!(" {} ", btf.types.len());
+                    set_color!(stdout);
+                    println!("types:");
+
+                    for (i, ty) in btf.types.iter().enumerate() {
+                        println!("{}", btf.display_type(i + 1, ty));
+                    }
+
+                    let ext = elf
+                        .section_by_name(".BTF.ext")
+                        .and_then(|shdr| or_report(f, elf.section_data(&shdr), &mut failed))
+                        .and_then(|data| BtfExt::parse(&data));
+
+                    if let Some(ext) = ext {
+                        for sec in &ext.func_info {
+                            println!(
+                                "\nBTF.ext func_info for section '{}':",
+                                btf.string(sec.sec_name_off)
+                            );
+                            for rec in &sec.records {
+                                let func = btf
+                                    .type_by_id(rec.type_id)
+                                    .map(|ty| btf.type_name(ty))
+                                    .unwrap_or_default();
+                                println!(
+                                    "  insn_off={:<6} type_id={:<6} {}",
+                                    rec.insn_off, rec.type_id, func
+                                );
+                            }
+                        }
+
+                        for sec in &ext.line_info {
+                            println!(
+                                "\nBTF.ext line_info for section '{}':",
+                                btf.string(sec.sec_name_off)
+                            );
+                            for rec in &sec.records {
+                                println!(
+                                    "  insn_off={:<6} {}:{}:{} {}",
+                                    rec.insn_off,
+                                    btf.string(rec.file_name_off),
+                                    rec.line,
+                                    rec.column,
+                                    btf.string(rec.line_off).trim()
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        if args.show_bpf {
+            if elf.header().machine() != EM_BPF {
+                println!(
+                    "\n{} is not an eBPF object (e_machine {})",
+                    f,
+                    elf.header().machine()
+                );
+            } else {
+                if let Some(obj) = or_report(f, BpfObject::read(&elf), &mut failed) {
+                    set_color!(stdout);
+                    print!("\neBPF object with");
+                    set_color!(stdout, Color::Green);
+                    print!(" {} ", obj.programs.len());
+                    set_color!(stdout);
+                    print!("programs and");
+                    set_color!(stdout, Color::Green);
+                    print!(" {} ", obj.maps.len());
+                    set_color!(stdout);
+                    println!("maps");
+                    println!("  License: {}", obj.license.as_deref().unwrap_or("(none)"));
+                    if let Some(version) = obj.kern_version {
+                        println!("  Kernel version: 0x{:x}", version);
+                    }
+
+                    print_color!(stdout, Color::Yellow, "{}\n", "\nPrograms:");
+                    set_color!(stdout);
+                    println!(
+                        "  [Nr] {:24} {:16} {:24} {:>6} Functions",
+                        "Section", "Type", "Attach", "Insns"
+                    );
+                    for prog in &obj.programs {
+                        let attach = match (&prog.attach, &prog.target) {
+                            (Some(attach), Some(target)) => format!("{} {}", attach, target),
+                            (Some(attach), None) => attach.to_string(),
+                            _ => "-".to_string(),
+                        };
+                        println!(
+                            "  [{:2}] {:24} {:16} {:24} {:>6} {}",
+                            prog.index,
+                            prog.section,
+                            prog.prog_type.unwrap_or("(subprograms)"),
+                            attach,
+                            prog.insns,
+                            prog.functions.join(", ")
+                        );
+                    }
+
+                    print_color!(stdout, Color::Yellow, "{}\n", "\nMaps:");
+                    set_color!(stdout);
+                    println!(
+                        "  {:24} {:10} {:22} {:>6} {:>8} {:>11} Flags",
+                        "Name", "Section", "Type", "Key", "Value", "Max entries"
+                    );
+                    let or_dash = |v: Option<u64>| v.map_or("-".to_string(), |v| v.to_string());
+                    for map in &obj.maps {
+                        println!(
+                            "  {:24} {:10} {:22} {:>6} {:>8} {:>11} {}",
+                            map.name,
+                            map.section,
+                            map_type_name(map.map_type),
+                            or_dash(map.key_size),
+                            or_dash(map.value_size),
+                            or_dash(map.max_entries.map(u64::from)),
+                            map.flags.map_or("-".to_string(), |v| format!("0x{:x}", v))
+                        );
+                    }
+
+                    print_color!(stdout, Color::Yellow, "{}\n", "\nRelocations:");
+                    set_color!(stdout);
+                    println!(
+                        "  {:24} {:>6} {:>10} {:18} {:24} Target",
+                        "Section", "Insn", "Offset", "Type", "Symbol"
+                    );
+                    for rel in &obj.relocations {
+                        println!(
+                            "  {:24} {:>6} {:>10} {:18} {:24} {}",
+                            rel.section,
+                            rel.offset / elf::bpf::BPF_INSN_SIZE,
+                            format!("0x{:x}", rel.offset),
+                            reloc_type_name(EM_BPF, rel.rtype).unwrap_or("<unknown>"),
+                            rel.symbol,
+                            match &rel.target {
+                                BpfRelocTarget::Map(name) => format!("map '{}'", name),
+                                BpfRelocTarget::GlobalData(sec) => format!("global data '{}'", sec),
+                                BpfRelocTarget::Subprog(name) => format!("call '{}'", name),
+                                BpfRelocTarget::Extern(name) => format!("extern '{}'", name),
+                                BpfRelocTarget::Unknown(sec) => format!("section '{}'", sec),
+                            }
+                        );
+                    }
+                }
+            }
+        }
+
+        if args.show_modinfo {
+            match or_report(f, KernelModule::read(&elf), &mut failed) {
+                None => {}
+                Some(None) => println!("\n{} is not a kernel module (no .modinfo section)", f),
+                Some(Some(module)) => {
+                    println!("{:16}{}", "filename:", f);
+                    for (key, value) in &module.info {
+                        println!("{:16}{}", format!("{}:", key), value);
+                    }
+                    for param in &module.params {
+                        println!(
+                            "{:16}{}:{}{}",
+                            "parm:",
+                            param.name,
+                            param.description.as_deref().unwrap_or(""),
+                            param
+                                .param_type
+                                .as_ref()
+                                .map_or(String::new(), |t| format!(" ({})", t))
+                        );
+                    }
+
+                    if !module.versions.is_empty() {
+                        set_color!(stdout);
+                        print!("\nModule versions");
+                        set_color!(stdout, Color::Magenta);
+                        print!(" __versions ");
+                        set_color!(stdout);
+                        print!("contains");
+                        set_color!(stdout, Color::Green);
+                        print!(" {} ", module.versions.len());
+                        set_color!(stdout);
+                        println!("entries:");
+                        println!("  CRC                 Symbol");
+                        for version in &module.versions {
+                            println!("  0x{:016x}  {}", version.crc, version.name);
+                        }
+                    }
+
+                    if let Some(name) = &module.name {
+                        println!("\nModule structure .gnu.linkonce.this_module:");
+                        println!("  name: {}", name);
+                        for (offset, hook) in &module.hooks {
+                            println!("  +0x{:<4x} {}", offset, hook);
+                        }
+                    }
+                }
+            }
+        }
+
+        if args.show_go {
+            match or_report(f, GoBinary::read(&elf), &mut failed) {
+                None => {}
+                Some(None) => println!("\n{} does not look like a Go binary", f),
+                Some(Some(go)) => {
+                    set_color!(stdout);
+                    println!("\nGo binary information:");
+                    if let Some(build_id) = &go.build_id {
+                        println!("  {:<12}{}", "Build ID:", build_id);
+                    }
+
+                    if let Some(info) = &go.buildinfo {
+                        println!("  {:<12}{}", "Version:", info.version);
+                        if let Some(path) = &info.path {
+                            println!("  {:<12}{}", "Path:", path);
+                        }
+                        if let Some((path, version)) = &info.main {
+                            println!("  {:<12}{} {}", "Module:", path, version);
+                        }
+                        if !info.settings.is_empty() {
+                            println!("\n  Build settings:");
+                            for (key, value) in &info.settings {
+                                println!("    {}={}", key, value);
+                            }
+                        }
+                        if !info.deps.is_empty() {
+                            set_color!(stdout);
+                            print!("\n  Dependencies:");
+                            set_color!(stdout, Color::Green);
+                            println!(" {}", info.deps.len());
+                            set_color!(stdout);
+                            for (path, version) in &info.deps {
+                                println!("    {} {}", path, version);
+                            }
+                        }
+                    }
+
+                    if let Some(pclntab) = &go.pclntab {
+                        set_color!(stdout);
+                        print!("\nPC line table");
+                        set_color!(stdout, Color::Magenta);
+                        print!(" .gopclntab ");
+                        set_color!(stdout);
+                        println!("({} format):", pclntab.version.display());
+                        set_color!(stdout);
+                        print!("  Functions: ");
+                        set_color!(stdout, Color::Green);
+                        println!("{}", pclntab.nfunc);
+                        set_color!(stdout);
+                        if let Some(nfiles) = pclntab.nfiles {
+                            print!("  Files:     ");
+                            set_color!(stdout, Color::Green);
+                            println!("{}", nfiles);
+                            set_color!(stdout);
+                        }
+                        for (label, func) in [("First:", &pclntab.first), ("Last:", &pclntab.last)]
+                        {
+                            if let Some(func) = func {
+                                println!("  {:<11}0x{:x} {}", label, func.entry, func.name);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        if args.show_rust {
+            match or_report(f, RustInfo::read(&elf), &mut failed) {
+                None => {}
+                Some(None) => println!("\n{} does not look like a Rust binary", f),
+                Some(Some(info)) => {
+                    set_color!(stdout);
+                    println!("\nRust binary information:");
+                    for compiler in &info.compilers {
+                        println!("  {:<16}{}", "Compiler:", compiler);
+                    }
+                    println!("  {:<16}{}", "Panic strategy:", info.panic.display());
+                    println!("  {:<16}{}", "Runtime:", info.std.display());
+                    println!(
+                        "  {:<16}{} v0, {} legacy",
+                        "Mangling:", info.v0_symbols, info.legacy_symbols
+                    );
+
+                    if !info.crates.is_empty() {
+                        set_color!(stdout);
+                        print!("\nCrates recovered from");
+                        set_color!(stdout, Color::Green);
+                        print!(" {} ", info.v0_symbols + info.legacy_symbols);
+                        set_color!(stdout);
+                        println!("mangled symbols:");
+                        println!("  {:>8}  Crate", "Symbols");
+                        for (name, count) in &info.crates {
+                            print!("  {:>8}  ", count);
+                            set_color!(stdout, Color::Magenta);
+                            println!("{}", name);
+                            set_color!(stdout);
+                        }
+                    }
+                }
+            }
+        }
+
+        if args.show_x86_isa {
+            let class = elf.header().class().unwrap_or(ElfClass::ElfClass64);
+            let endian = elf.header().endian().unwrap_or(Endian::Little);
+            let mut needed = None;
+            let mut used = None;
+            for note in or_report(f, elf.notes(), &mut failed).unwrap_or_default() {
+                for property in GnuProperty::parse(&note, class, endian) {
+                    match property.ptype {
+                        GNU_PROPERTY_X86_ISA_1_NEEDED => needed = property.bitmask(endian),
+                        GNU_PROPERTY_X86_ISA_1_USED => used = property.bitmask(endian),
+                        _ => {}
+                    }
+                }
+            }
+
+            set_color!(stdout);
+            println!("\nx86 ISA requirements:");
+            for (label, bitmask) in [("Needed:", needed), ("Used:", used)] {
+                match bitmask {
+                    Some(bitmask) => {
+                        println!("  {:<8}{}", label, x86_isa_levels(bitmask).join(", "))
+                    }
+                    None => println!("  {:<8}<not recorded>", label),
+                }
+            }
+
+            print!("  Minimum microarchitecture level: ");
+            set_color!(stdout, Color::Green);
+            println!(
+                "{}",
+                needed
+                    .and_then(x86_isa_minimum)
+                    .unwrap_or("unknown (no ISA needed property)")
+            );
+            set_color!(stdout);
+        }
+
+        if args.show_branch_protection {
+            if elf.header().machine() != EM_AARCH64 {
+                println!(
+                    "\n{} is not an AArch64 file (e_machine {})",
+                    f,
+                    elf.header().machine()
+                );
+            } else {
+                if let Some(report) = or_report(f, BranchProtection::read(&elf), &mut failed) {
+                    set_color!(stdout);
+                    println!("\nAArch64 branch protection:");
+                    print!("  Property note:   ");
+                    match report.feature_1_and {
+                        Some(bitmask) => println!("{}", aarch64_features(bitmask)),
+                        None => println!("<not recorded>"),
+                    }
+                    let plt = |variant: bool, tag: bool| match (variant, tag) {
+                        (true, true) => "yes",
+                        (true, false) => "yes, tag missing",
+                        (false, true) => "no, tag set",
+                        (false, false) => "no",
+                    };
+                    println!(
+                        "  BTI PLT:         {}",
+                        plt(report.bti_plt, report.bti_plt_tag)
+                    );
+                    println!(
+                        "  PAC PLT:         {}",
+                        plt(report.pac_plt, report.pac_plt_tag)
+                    );
+                    for (label, count) in [
+                        ("Landing pads:", report.landing_pads),
+                        ("Signed returns:", report.signed_returns),
+                    ] {
+                        print!("  {:<17}", label);
+                        set_color!(stdout, Color::Green);
+                        print!("{}", count);
+                        set_color!(stdout);
+                        println!(" of {} functions", report.functions);
+                    }
+
+                    let problems = report.problems();
+                    if problems.is_empty() {
+                        set_color!(stdout, Color::Green);
+                        println!("  Consistent");
+                    } else {
+                        set_color!(stdout, Color::Red);
+                        for problem in &problems {
+                            println!("  {}", problem);
+                        }
+                    }
+                    set_color!(stdout);
+                    if report.bti() && !report.unprotected.is_empty() {
+                        println!("  Global functions without a landing pad:");
+                        set_color!(stdout, Color::Magenta);
+                        for name in &report.unprotected {
+                            println!("    {}", name);
+                        }
+                        set_color!(stdout);
+                    }
+                }
+            }
+        }
+
+        #[cfg(feature = "script")]
+        if let Some(script) = &script {
+            if let Err(err) = script.run(f, &elf) {
+                eprintln!("readelf-rs: Error: '{}': script: {}", f, err);
+                failed = true;
+            }
+        }
+
+        if args.show_analyze {
+            if let Some(results) = or_report(f, analyze_sections(&elf), &mut failed) {
+                if results.is_empty() {
+                    println!("\nNo section analyzer recognized a section of this file.");
+                }
+                for analysis in &results {
+                    set_color!(stdout);
+                    print!("\nAnalysis of section '");
+                    set_color!(stdout, Color::Magenta);
+                    print!("{}", analysis.section);
+                    set_color!(stdout);
+                    println!("' by {}:", analysis.analyzer);
+                    for line in analysis.output.lines() {
+                        println!("  {}", line);
+                    }
+                }
+            }
+        }
+
+        if args.show_ifunc {
+            if let Some((report, symbols)) = or_report(
+                f,
+                IfuncReport::read(&elf).and_then(|report| Ok((report, elf.symbol_map()?))),
+                &mut failed,
+            ) {
+                let describe = |addr: u64| {
+                    symbols
+                        .describe(addr)
+                        .map_or(String::new(), |name| format!(" <{}>", name))
+                };
+
+                set_color!(stdout);
+                print!("\nIRELATIVE relocations: ");
+                set_color!(stdout, Color::Green);
+                println!("{}", report.relocs.len());
+                set_color!(stdout);
+                if !report.relocs.is_empty() {
+                    println!("  {:<16}  {:<16}  Resolver", "Section", "Offset");
+                    for rel in &report.relocs {
+                        print!("  {:<16}  {:016x}  ", rel.section, rel.offset);
+                        match rel.resolver {
+                            Some(resolver) => {
+                                print!("0x{:016x}", resolver);
+                                set_color!(stdout, Color::Magenta);
+                                println!("{}", describe(resolver));
+                                set_color!(stdout);
+                            }
+                            None => println!("<unreadable>"),
+                        }
+                    }
+                }
+
+                print!("\nSTT_GNU_IFUNC symbols: ");
+                set_color!(stdout, Color::Green);
+                println!("{}", report.symbols.len());
+                set_color!(stdout);
+                if !report.symbols.is_empty() {
+                    println!("  {:<18}  {:<7}  {:<7}  Name", "Resolver", "Bind", "Table");
+                    for sym in &report.symbols {
+                        print!(
+                            "  0x{:016x}  {:<7}  {:<7}  ",
+                            sym.resolver,
+                            sym.binding,
+                            if sym.dynamic { ".dynsym" } else { ".symtab" }
+                        );
+                        set_color!(stdout, Color::Magenta);
+                        println!("{}", sym.name);
+                        set_color!(stdout);
+                    }
+                }
+            }
+        }
+
+        if args.show_copy_relocs {
+            if let Some(copies) = or_report(f, CopyReloc::read(&elf), &mut failed) {
+                set_color!(stdout);
+                print!("\nCOPY relocations: ");
+                set_color!(stdout, Color::Green);
+                println!("{}", copies.len());
+                set_color!(stdout);
+                if !copies.is_empty() {
+                    println!(
+                        "  {:<16}  {:>8}  {:<32}  Library",
+                        "Offset", "Size", "Symbol"
+                    );
+                    for copy in &copies {
+                        print!("  {:016x}  {:>8}  ", copy.offset, copy.size);
+                        set_color!(stdout, Color::Magenta);
+                        print!(
+                            "{:<32}",
+                            match &copy.version {
+                                Some(version) => format!("{}@{}", copy.symbol, version),
+                                None => copy.symbol.clone(),
+                            }
+                        );
+                        set_color!(stdout);
+                        println!("  {}", copy.library.as_deref().unwrap_or("<unknown>"));
+                    }
+                }
+            }
+        }
+
+        if args.show_tls {
+            if let Some(relocs) = or_report(f, TlsReloc::read(&elf), &mut failed) {
+                set_color!(stdout);
+                match TlsTemplate::read(&elf) {
+                    Some(template) => println!(
+                        "\nTLS template at 0x{:x}: 0x{:x} bytes initialized, 0x{:x} in memory, \
+                     aligned to 0x{:x}",
+                        template.vaddr, template.filesz, template.memsz, template.align
+                    ),
+                    None => println!("\nThere is no TLS segment in this file."),
+                }
+                print!("TLS relocations: ");
+                set_color!(stdout, Color::Green);
+                println!("{}", relocs.len());
+                set_color!(stdout);
+                if !relocs.is_empty() {
+                    println!(
+                        "  {:<16}  {:<20}  {:<24}  {:>8}  {:>8}  Meaning",
+                        "Offset", "Type", "Symbol", "Template", "TP off"
+                    );
+                    for reloc in &relocs {
+                        print!("  {:016x}  {:<20}  ", reloc.offset, reloc.type_name);
+                        set_color!(stdout, Color::Magenta);
+                        print!(
+                            "{:<24}",
+                            match reloc.symbol.as_str() {
+                                "" => "<this module>",
+                                symbol => symbol,
+                            }
+                        );
+                        set_color!(stdout);
+                        println!(
+                            "  {:>8}  {:>8}  {}",
+                            reloc
+                                .template_offset
+                                .map_or("-".to_string(), |offset| format!("{:#x}", offset)),
+                            reloc
+                                .thread_pointer_offset
+                                .map_or("-".to_string(), |offset| {
+                                    match offset < 0 {
+                                        true => format!("-{:#x}", offset.unsigned_abs()),
+                                        false => format!("{:#x}", offset),
+                                    }
+                                }),
+                            reloc.kind.meaning()
+                        );
+                    }
+                }
+            }
+        }
+
+        if args.show_weak {
+            if let Some(report) = or_report(f, WeakReport::read(&elf), &mut failed) {
+                let value_width = match elf.header().class() {
+                    Some(ElfClass::ElfClass32) => 8,
+                    _ => 16,
+                };
+
+                for (heading, note, symbols) in [
+                    (
+                        "Weak definitions",
+                        "a global definition earlier in lookup order takes their place",
+                        &report.definitions,
+                    ),
+                    (
+                        "Weak undefined references",
+                        "these resolve to 0 when no object defines them",
+                        &report.undefined,
+                    ),
+                ] {
+                    set_color!(stdout);
+                    print!("\n{}: ", heading);
+                    set_color!(stdout, Color::Green);
+                    println!("{}", symbols.len());
+                    set_color!(stdout);
+                    if symbols.is_empty() {
+                        continue;
+                    }
+                    println!("  ({})", note);
+                    println!(
+                        "  {:<8}  {:<w$}  {:>6}  {:<7}  Name",
+                        "Table",
+                        "Value",
+                        "Size",
+                        "Type",
+                        w = value_width
+                    );
+                    for WeakSymbol {
+                        table,
+                        name,
+                        version,
+                        value,
+                        size,
+                        symbol_type,
+                    } in symbols
+                    {
+                        print!(
+                            "  {:<8}  {:0w$x}  {:>6}  {:<7}  ",
+                            table,
+                            value,
+                            size,
+                            symbol_type.map_or("?".to_string(), |t| t.to_string()),
+                            w = value_width
+                        );
+                        set_color!(stdout, Color::Magenta);
+                        println!(
+                            "{}{}",
+                            args.symbol_name(name.clone()),
+                            version.as_deref().unwrap_or_default()
+                        );
+                        set_color!(stdout);
+                    }
+                }
+            }
+        }
+
+        if args.show_segment_alignment {
+            let machine = elf.header().machine();
+            let segments = SegmentAlignment::read(&elf);
+
+            set_color!(stdout);
+            println!(
+                "\nLoadable segment alignment, page sizes {}:",
+                page_sizes(machine)
+                    .iter()
+                    .map(|page| format!("{:#x}", page))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+            if segments.is_empty() {
+                println!("  There are no loadable segments in this file.");
+            } else {
+                println!(
+                    "  {:<7}  {:<18}  {:<18}  {:<10}  Issues",
+                    "Segment", "Offset", "VirtAddr", "Align"
+                );
+            }
+            for segment in &segments {
+                print!(
+                    "  {:<7}  0x{:016x}  0x{:016x}  {:<10}  ",
+                    format!("{:02}", segment.index),
+                    segment.offset,
+                    segment.vaddr,
+                    format!("{:#x}", segment.align)
+                );
+                if segment.issues.is_empty() {
+                    set_color!(stdout, Color::Green);
+                    println!("none");
+                }
+                for (i, issue) in segment.issues.iter().enumerate() {
+                    if i != 0 {
+                        print!("{:62}", "");
+                    }
+                    if issue.is_fatal() {
+                        set_color!(stdout, Color::Red);
+                    } else {
+                        set_color!(stdout, Color::Yellow);
+                    }
+                    println!("{}", issue);
+                }
+                set_color!(stdout);
+            }
+
+            if let (Some(huge), false) = (huge_page_size(machine), segments.is_empty()) {
+                print!("  Huge pages ({:#x}): ", huge);
+                match segments.iter().all(|s| s.huge_pages) {
+                    true => println!("every segment qualifies"),
+                    false => println!(
+                        "{} of {} segments qualify",
+                        segments.iter().filter(|s| s.huge_pages).count(),
+                        segments.len()
+                    ),
+                }
+            }
+        }
+
+        #[cfg(target_os = "linux")]
+        if let (true, Target::Process(pid)) = (args.diff_memory, target) {
+            if let Some((disk, modifications)) = or_report(
+                f,
+                FileData::new(f).and_then(|disk| {
+                    let modifications = disk.diff_image(&elf)?;
+                    Ok((disk, modifications))
+                }),
+                &mut failed,
+            ) {
+                set_color!(stdout);
+                print!("\nModified bytes in the image of process {}: ", pid);
+                set_color!(stdout, Color::Green);
+                println!("{}", modifications.len());
+                set_color!(stdout);
+                for modification in &modifications {
+                    let section = disk
+                        .section_at_vaddr(modification.vaddr)
+                        .and_then(|shdr| disk.section_name(&shdr))
+                        .unwrap_or_default();
+                    print!(
+                        "  {:016x}  {:>6} bytes  ",
+                        modification.vaddr,
+                        modification.disk.len()
+                    );
+                    set_color!(stdout, Color::Magenta);
+                    println!("{}", section);
+                    set_color!(stdout);
+                    for (label, bytes) in [
+                        ("disk", &modification.disk),
+                        ("memory", &modification.memory),
+                    ] {
+                        print!("    {:<8}", label);
+                        for byte in bytes.iter().take(16) {
+                            print!("{:02x} ", byte);
+                        }
+                        println!("{}", if bytes.len() > 16 { "..." } else { "" });
+                    }
+                }
+            }
+        }
+
+        if args.show_map {
+            set_color!(stdout);
+            println!("\nLink map of {}:\n", f);
+            let sections = or_report(f, OutputSection::read(&elf), &mut failed);
+            for section in sections.unwrap_or_default() {
+                set_color!(stdout, Color::Magenta);
+                // Like ld, long names get a line of their own
+                match section.name.len() {
+                    0..=14 => print!("{:<15}", section.name),
+                    _ => print!("{}\n{:15}", section.name, ""),
+                }
+                set_color!(stdout);
+                println!(
+                    "0x{:016x} {:>10}",
+                    section.addr,
+                    format!("0x{:x}", section.size)
+                );
+                for item in &section.items {
+                    match item {
+                        MapItem::Symbol { addr, size, name } => {
+                            print!("{:15}0x{:016x} {:>10} ", "", addr, format!("0x{:x}", size));
+                            set_color!(stdout, Color::Green);
+                            println!("{}", name);
+                            set_color!(stdout);
+                        }
+                        MapItem::Fill { addr, size } => println!(
+                            " {:<14}0x{:016x} {:>10}",
+                            "*fill*",
+                            addr,
+                            format!("0x{:x}", size)
+                        ),
+                    }
+                }
+                println!();
+            }
+        }
+
+        for section in &args.relocated_dump {
+            let index = section
+                .parse::<usize>()
+                .ok()
+                .filter(|&index| index < elf.section_headers().len())
+                .or_else(|| elf.section_index(section));
+            let index = match index {
+                Some(index) => index,
+                None => {
+                    eprintln!(
+                        "readelf-rs: Warning: Section '{}' was not dumped because it does not exist",
+                        section
+                    );
+                    continue;
+                }
+            };
+            let shdr = elf.section_headers()[index];
+            let name = elf.section_name(&shdr).unwrap_or_default();
+            let (data, skipped) = match or_report(f, elf.relocated_section_data(index), &mut failed)
+            {
+                Some(dump) => dump,
+                None => continue,
+            };
+            if data.is_empty() {
+                println!("Section '{}' has no data to dump.", name);
+                continue;
+            }
+
+            println!("\nHex dump of section '{}':", name);
+            if skipped > 0 {
+                println!(
+                    " NOTE: {} relocations of unsupported types were not applied to this dump.",
+                    skipped
+                );
+            }
+            for (row, chunk) in data.chunks(16).enumerate() {
+                print!("  0x{:08x} ", shdr.addr() + row as u64 * 16);
+                for group in 0..4 {
+                    for i in group * 4..group * 4 + 4 {
+                        match chunk.get(i) {
+                            Some(byte) => print!("{:02x}", byte),
+                            None => print!("  "),
+                        }
+                    }
+                    print!(" ");
+                }
+                println!(
+                    "{}",
+                    chunk
+                        .iter()
+                        .map(|&c| match c {
+                            0x20..=0x7e => c as char,
+                            _ => '.',
+                        })
+                        .collect::<String>()
+                );
+            }
+            println!();
+        }
+
+        if let Some(pattern) = &find_bytes {
+            if let Some(matches) = or_report(f, elf.find_bytes(pattern), &mut failed) {
+                set_color!(stdout);
+                print!("\nMatches of");
+                set_color!(stdout, Color::Magenta);
+                print!(" {} ", args.find_bytes.as_deref().unwrap_or_default());
+                set_color!(stdout);
+                print!("in {}:", f);
+                set_color!(stdout, Color::Green);
+                println!(" {}", matches.len());
+                set_color!(stdout);
+                if !matches.is_empty() {
+                    println!(
+                        "  {:<10}  {:<20}  {:<18}  Symbol",
+                        "Offset", "Section", "Address"
+                    );
+                }
+                for found in &matches {
+                    println!(
+                        "  0x{:08x}  {:<20}  {:<18}  {}",
+                        found.offset,
+                        found.section.as_deref().unwrap_or("<none>"),
+                        found
+                            .vaddr
+                            .map_or_else(|| "-".to_string(), |vaddr| format!("0x{:016x}", vaddr)),
+                        found.symbol.as_deref().unwrap_or("")
+                    );
+                }
+            }
+        }
+
+        if let Some(symbol) = &args.xref {
+            if let Some(sites) = or_report(f, find_xrefs(&elf, symbol), &mut failed) {
+                set_color!(stdout);
+                print!("\nRelocations referencing");
+                set_color!(stdout, Color::Magenta);
+                print!(" {} ", symbol);
+                set_color!(stdout);
+                print!("in {}:", f);
+                set_color!(stdout, Color::Green);
+                println!(" {}", sites.len());
+                set_color!(stdout);
+                if !sites.is_empty() {
+                    println!(
+                        "  {:<16}  {:<16}  {:<24}  {:>8}  Site",
+                        "Section", "Offset", "Type", "Addend"
+                    );
+                    for site in &sites {
+                        print!(
+                            "  {:<16}  {:016x}  {:<24}  {:>8}  {}",
+                            site.section,
+                            site.offset,
+                            site.rtype,
+                            site.addend
+                                .map_or(String::new(), |addend| addend.to_string()),
+                            site.target.as_deref().unwrap_or("?")
+                        );
+                        set_color!(stdout, Color::Magenta);
+                        println!(
+                            "{}",
+                            site.function
+                                .as_ref()
+                                .map_or(String::new(), |function| format!(" <{}>", function))
+                        );
+                        set_color!(stdout);
+                    }
+                }
+            }
+        }
+
+        if args.show_version_script || args.show_exports || args.show_dynamic_list {
+            let exports = match ExportList::read(&elf) {
+                Ok(exports) => exports,
+                Err(err) => {
+                    eprintln!("readelf-rs: Error: '{}': {}", f, err);
+                    failed = true;
+                    continue;
+                }
+            };
+            if args.show_version_script {
+                print!("{}", exports.version_script());
+            }
+            if args.show_exports {
+                for export in exports.exports() {
+                    println!("{}", export.versioned_name());
+                }
+            }
+            if args.show_dynamic_list {
+                print!("{}", exports.dynamic_list());
+            }
+        }
+
+        if args.show_deps_dot {
+            if let Some(graph) = or_report(f, DepGraph::resolve(f), &mut failed) {
+                print!("{}", graph.to_dot());
+            }
+        }
+
+        if args.check_unresolved {
+            let resolution = match Resolution::against(f, &args.with_libs) {
+                Ok(resolution) => resolution,
+                Err(err) => {
+                    eprintln!("readelf-rs: Error: --check-unresolved: {}", err);
+                    failed = true;
+                    continue;
+                }
+            };
+            let unresolved = resolution.unresolved().collect::<Vec<_>>();
+
+            set_color!(stdout);
+            println!(
+                "\nUndefined symbols of {} checked against {} libraries:",
+                f,
+                resolution.objects.len() - 1
+            );
+            for object in resolution.objects.iter().skip(1) {
+                println!("  {}", object.name);
+            }
+            print!("Unresolved symbols: ");
+            if unresolved.is_empty() {
+                set_color!(stdout, Color::Green);
+            } else {
+                set_color!(stdout, Color::Red);
+            }
+            println!("{}", unresolved.len());
+            set_color!(stdout);
+            for binding in &unresolved {
+                set_color!(stdout, Color::Magenta);
+                print!(
+                    "  {}{}",
+                    binding.symbol,
+                    binding
+                        .version
+                        .as_ref()
+                        .map_or(String::new(), |version| format!("@{}", version))
+                );
+                set_color!(stdout);
+                // A definition under another version is the usual reason for a leftover
+                let others = resolution
+                    .versions_defined(&binding.symbol)
+                    .into_iter()
+                    .map(|(object, version)| {
+                        format!("{}@{}", object.name, version.unwrap_or("<unversioned>"))
+                    })
+                    .collect::<Vec<_>>();
+                match others.is_empty() {
+                    true => println!(),
+                    false => println!("  (version mismatch, found {})", others.join(", ")),
+                }
+            }
+            if !unresolved.is_empty() {
+                failed = true;
+            }
+        }
+
+        if args.show_resolve {
+            if let Some(resolution) =
+                or_report(f, Resolution::simulate(f, &args.with_libs), &mut failed)
+            {
+                set_color!(stdout);
+                print!("\nSymbol resolution scope for {} contains", f);
+                set_color!(stdout, Color::Green);
+                print!(" {} ", resolution.objects.len());
+                set_color!(stdout);
+                println!("objects:");
+                for (index, object) in resolution.objects.iter().enumerate() {
+                    println!(
+                        "  [{:>2}] {} ({})",
+                        index,
+                        object.name,
+                        object.path.display()
+                    );
+                }
+                for missing in &resolution.missing {
+                    println!("  [--] {} (not found)", missing);
+                }
+
+                println!(
+                    "\n  {:<40}  {:<20}  {:<24}  Provider",
+                    "Symbol", "Version", "Referenced by"
+                );
+                for binding in &resolution.bindings {
+                    set_color!(stdout, Color::Magenta);
+                    print!("  {:<40}", binding.symbol);
+                    set_color!(stdout);
+                    print!(
+                        "  {:<20}  {:<24}  ",
+                        binding.version.as_deref().unwrap_or(""),
+                        resolution.objects[binding.referrer].name
+                    );
+                    match binding.provider {
+                        Some(provider) => println!("{}", resolution.objects[provider].name),
+                        None if binding.weak => println!("<unresolved, weak>"),
+                        None => {
+                            set_color!(stdout, Color::Red);
+                            println!("<unresolved>");
+                            set_color!(stdout);
+                        }
+                    }
+                }
+
+                print!("\nUnresolved symbols: ");
+                set_color!(stdout, Color::Green);
+                println!("{}", resolution.unresolved().count());
+                set_color!(stdout);
+            }
+        }
+
+        if args.show_unwind {
+            match or_report(f, EhFrameHdr::read(&elf), &mut failed) {
+                None => {}
+                Some(None) => println!("\nThere is no .eh_frame_hdr in this file."),
+                Some(Some(hdr)) => {
+                    let empty = SymbolMap::default();
+                    let symbols = or_report(f, elf.symbol_map(), &mut failed).unwrap_or(&empty);
+
+                    set_color!(stdout);
+                    print!("\nUnwind search table");
+                    set_color!(stdout, Color::Magenta);
+                    print!(" .eh_frame_hdr ");
+                    set_color!(stdout);
+                    println!(
+                        "at address 0x{:x}, offset 0x{:x}, size 0x{:x}:",
+                        hdr.address, hdr.offset, hdr.size
+                    );
+                    println!("  Version:          {}", hdr.version);
+                    println!(
+                        "  eh_frame_ptr_enc: 0x{:02x} ({})",
+                        hdr.eh_frame_ptr_enc,
+                        encoding_name(hdr.eh_frame_ptr_enc)
+                    );
+                    println!(
+                        "  fde_count_enc:    0x{:02x} ({})",
+                        hdr.fde_count_enc,
+                        encoding_name(hdr.fde_count_enc)
+                    );
+                    println!(
+                        "  table_enc:        0x{:02x} ({})",
+                        hdr.table_enc,
+                        encoding_name(hdr.table_enc)
+                    );
+                    if let Some(eh_frame_ptr) = hdr.eh_frame_ptr {
+                        println!("  eh_frame_ptr:     0x{:x}", eh_frame_ptr);
+                    }
+
+                    print!("\n  The search table contains");
+                    set_color!(stdout, Color::Green);
+                    print!(" {} ", hdr.table.len());
+                    set_color!(stdout);
+                    println!(
+                        "entries{}:",
+                        if hdr.is_sorted() { "" } else { " (NOT SORTED)" }
+                    );
+                    println!("  {:<18}  {:<18}  Function", "Initial PC", "FDE");
+                    for (initial, fde) in &hdr.table {
+                        print!("  0x{:016x}  0x{:016x}  ", initial, fde);
+                        set_color!(stdout, Color::Magenta);
+                        let base = elf.section_at_vaddr(*initial).map_or(0, |shdr| shdr.addr());
+                        println!(
+                            "{}",
+                            symbols.describe_from(*initial, base).unwrap_or_default()
+                        );
+                        set_color!(stdout);
+                    }
+                }
+            }
+        }
+
+        #[cfg(feature = "dwarf")]
+        if args.debug_dump.iter().any(|dump| {
+            matches!(
+                dump,
+                DebugDump::Info | DebugDump::DecodedLine | DebugDump::Ranges | DebugDump::Loc
+            )
+        }) {
+            let endian = elf.header().endian().unwrap_or(Endian::Little);
+            let section = |name: &str| debug_section_data(f, &elf, name).unwrap_or_default();
+            let (debug_info, debug_abbrev) = (section(".debug_info"), section(".debug_abbrev"));
+            let (debug_str, line_str) = (section(".debug_str"), section(".debug_line_str"));
+            let (str_offsets, addr) = (section(".debug_str_offsets"), section(".debug_addr"));
+            let (ranges, rnglists) = (section(".debug_ranges"), section(".debug_rnglists"));
+            let (loc, loclists) = (section(".debug_loc"), section(".debug_loclists"));
+            let debug_line = section(".debug_line");
+            let sections = DwarfSections {
+                endian,
+                debug_str: &debug_str,
+                line_str: &line_str,
+                str_offsets: &str_offsets,
+                addr: &addr,
+                ranges: &ranges,
+                loc: &loc,
+                rnglists: &rnglists,
+                loclists: &loclists,
+            };
+
+            let dwarf = DwarfFile::new(f, sections, &debug_info, &debug_abbrev, &debug_line, "");
+            print_dwarf(&mut stdout, f, &args, &dwarf);
+
+            // Skeleton units name the .dwo file holding the rest of their unit, which may
+            // also have been packed into a .dwp next to the executable
+            for (unit, dies, bases) in &dwarf.units {
+                let skeleton = match dies.first() {
+                    Some(die) => die,
+                    None => continue,
+                };
+                let string = |name| {
+                    let attr = skeleton.attr(name)?;
+                    sections.string(attr, bases, unit.encoding)
+                };
+                let dwo_name = match string(DW_AT_DWO_NAME).or_else(|| string(DW_AT_GNU_DWO_NAME)) {
+                    Some(name) => name,
+                    None => continue,
+                };
+                let executable = Path::new(f);
+                let dwo_id = unit.dwo_id.or_else(|| skeleton.value(DW_AT_GNU_DWO_ID));
+                let dwo_file = dwo_path(executable, string(DW_AT_COMP_DIR).as_deref(), &dwo_name);
+                let dwo = match (dwo_file, dwp_path(executable), dwo_id) {
+                    (Some(path), _, _) => DwoSections::read(&path).map(Some),
+                    (None, Some(path), Some(dwo_id)) => DwoSections::read_package(&path, dwo_id),
+                    _ => Ok(None),
+                };
+                let dwo = match dwo {
+                    Ok(Some(dwo)) => dwo,
+                    Ok(None) => {
+                        eprintln!(
+                            "readelf-rs: Warning: '{}': cannot find the split unit {}",
+                            f, dwo_name
+                        );
+                        continue;
+                    }
+                    Err(err) => {
+                        eprintln!(
+                            "readelf-rs: Warning: '{}': {}: {}, skipped",
+                            f, dwo_name, err
+                        );
+                        continue;
+                    }
+                };
+
+                // Lists before DWARF 5 used a GNU encoding in .debug_loc.dwo that is not
+                // decoded, and their ranges stayed in the executable
+                let split_sections = DwarfSections {
+                    debug_str: &dwo.str,
+                    str_offsets: &dwo.str_offsets,
+                    addr: &addr,
+                    rnglists: &dwo.rnglists,
+                    loclists: &dwo.loclists,
+                    ..DwarfSections::new(endian)
+                };
+                let mut split =
+                    DwarfFile::new(f, split_sections, &dwo.info, &dwo.abbrev, &dwo.line, ".dwo");
+                split.loaded_from = Some(dwo.path.display().to_string());
+                // Split units index the skeleton's part of .debug_addr
+                for (_, _, split_bases) in &mut split.units {
+                    split_bases.addr = bases.addr;
+                    split_bases.low_pc = bases.low_pc;
+                }
+                print_dwarf(&mut stdout, f, &args, &split);
+            }
+        }
+
+        #[cfg(feature = "dwarf")]
+        if args.debug_dump.contains(&DebugDump::Pubnames) {
+            let endian = elf.header().endian().unwrap_or(Endian::Little);
+            let section = |name: &str| debug_section_data(f, &elf, name);
+            for (name, gnu) in [(".debug_pubnames", false), (".debug_gnu_pubnames", true)] {
+                let data = match section(name) {
+                    Some(data) => data,
+                    None => continue,
+                };
+                set_color!(stdout);
+                print!("\nContents of the ");
+                set_color!(stdout, Color::Magenta);
+                print!("{}", name);
+                set_color!(stdout);
+                println!(" section:\n");
+
+                for set in PubNameSet::parse(&data, endian, gnu) {
+                    println!("  Length:                              {}", set.length);
+                    println!("  Version:                             {}", set.version);
+                    println!(
+                        "  Offset into .debug_info section:     {}",
+                        gnu_hex(set.info_offset)
+                    );
+                    println!("  Size of area in .debug_info section: {}", set.info_size);
+                    match gnu {
+                        true => println!("\n    Offset  Kind          Name"),
+                        false => println!("\n    Offset\tName"),
+                    }
+                    for name in &set.names {
+                        match name.kind() {
+                            Some(kind) => print!("    {:<8x}{:<14}", name.die_offset, kind),
+                            None => print!("    {:<6x}\t", name.die_offset),
+                        }
+                        set_color!(stdout, Color::Magenta);
+                        println!("{}", name.name);
+                        set_color!(stdout);
+                    }
+                }
+            }
+
+            if let Some(data) = section(".debug_names") {
+                let strtab = section(".debug_str").unwrap_or_default();
+                set_color!(stdout);
+                print!("\nContents of the ");
+                set_color!(stdout, Color::Magenta);
+                print!(".debug_names");
+                set_color!(stdout);
+                println!(" section:");
+
+                for table in DebugNames::parse(&data, &strtab, endian) {
+                    println!("\n  Table at offset 0x{:x}:", table.offset);
+                    println!("  Length:               {}", table.length);
+                    println!("  Version:              {}", table.version);
+                    println!(
+                        "  Augmentation string:  \"{}\"",
+                        String::from_utf8_lossy(&table.augmentation).trim_end_matches('\0')
+                    );
+                    println!("  Abbrev table size:    {}", table.abbrev_table_size);
+                    for (heading, units) in [
+                        ("CU table", &table.comp_units),
+                        ("TU table", &table.local_type_units),
+                        ("Foreign TU table", &table.foreign_type_units),
+                    ] {
+                        if units.is_empty() {
+                            continue;
+                        }
+                        println!("\n  {}:", heading);
+                        for (i, unit) in units.iter().enumerate() {
+                            println!("    [{:3}] 0x{:x}", i, unit);
+                        }
+                    }
+
+                    let used = table.buckets.iter().filter(|&&bucket| bucket != 0).count();
+                    println!(
+                        "\n  Used {} of {} buckets, {} names:",
+                        used,
+                        table.buckets.len(),
+                        table.names.len()
+                    );
+                    for (i, bucket) in table.buckets.iter().enumerate() {
+                        if *bucket != 0 {
+                            println!("    Bucket {:3}: starts at name {}", i, bucket);
+                        }
+                    }
+
+                    println!();
+                    for (i, name) in table.names.iter().enumerate() {
+                        print!("    [{:3}] ", i + 1);
+                        if let Some(hash) = name.hash {
+                            print!("#{:08x} ", hash);
+                        }
+                        set_color!(stdout, Color::Magenta);
+                        println!("{}", name.name);
+                        set_color!(stdout);
+                        for entry in &name.entries {
+                            print!("          <0x{:x}> {}", entry.offset, tag_string(entry.tag));
+                            for (idx, value) in &entry.attributes {
+                                print!(" {}: ", index_attribute_name(*idx));
+                                match *idx {
+                                    DW_IDX_DIE_OFFSET => print!("<0x{:x}>", value),
+                                    _ => print!("{}", value),
+                                }
+                            }
+                            // The DIE offset counts from the start of its unit
+                            match (table.comp_unit(entry), entry.attribute(DW_IDX_DIE_OFFSET)) {
+                                (Some(unit), Some(die)) => {
+                                    println!(" (0x{:x} in .debug_info)", unit + die)
+                                }
+                                _ => println!(),
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        #[cfg(feature = "dwarf")]
+        if args.debug_dump.contains(&DebugDump::Macro) {
+            let endian = elf.header().endian().unwrap_or(Endian::Little);
+            let section = |name: &str| debug_section_data(f, &elf, name);
+            let debug_str = section(".debug_str").unwrap_or_default();
+            let print_ops = |stdout: &mut StandardStream,
+                             ops: &[MacroOp],
+                             files: Option<&FileTable>,
+                             macinfo: bool| {
+                for op in ops {
+                    print!(" {}", macro_op_name(op.opcode, macinfo));
+                    match &op.entry {
+                        MacroEntry::Define { line, text } | MacroEntry::Undef { line, text } => {
+                            print!(" - lineno : {} macro : ", line);
+                            set_color!(stdout, Color::Magenta);
+                            println!("{}", text);
+                            set_color!(stdout);
+                        }
+                        MacroEntry::StartFile { line, file } => {
+                            print!(" - lineno: {} filenum: {}", line, file);
+                            match files.and_then(|files| files.name(*file)) {
+                                Some(name) => println!(" filename: {}", name),
+                                None => println!(),
+                            }
+                        }
+                        MacroEntry::Import { offset } => println!(" - offset : 0x{:x}", offset),
+                        MacroEntry::Vendor { constant, text } => {
+                            println!(" - constant : {} string : {}", constant, text)
+                        }
+                        MacroEntry::EndFile | MacroEntry::Unknown => println!(),
+                    }
+                }
+            };
+
+            if let Some(data) = section(".debug_macro") {
+                let debug_line = section(".debug_line").unwrap_or_default();
+                let line_str = section(".debug_line_str").unwrap_or_default();
+                set_color!(stdout);
+                print!("\nContents of the ");
+                set_color!(stdout, Color::Magenta);
+                print!(".debug_macro");
+                set_color!(stdout);
+                println!(" section:");
+
+                for unit in MacroUnit::parse(&data, &debug_str, endian) {
+                    println!(
+                        "\n  Offset:                      {}",
+                        gnu_hex(unit.offset as u64)
+                    );
+                    println!("  Version:                     {}", unit.version);
+                    println!("  Offset size:                 {}", unit.offset_size);
+                    let files = unit.line_offset.and_then(|offset| {
+                        println!("  Offset into .debug_line:     {}", gnu_hex(offset));
+                        FileTable::parse(&debug_line, offset, endian, &debug_str, &line_str)
+                    });
+                    println!();
+                    print_ops(&mut stdout, &unit.ops, files.as_ref(), false);
+                }
+            }
+
+            if let Some(data) = section(".debug_macinfo") {
+                set_color!(stdout);
+                print!("\nContents of the ");
+                set_color!(stdout, Color::Magenta);
+                print!(".debug_macinfo");
+                set_color!(stdout);
+                println!(" section:\n");
+
+                for (_, ops) in parse_macinfo(&data, endian) {
+                    print_ops(&mut stdout, &ops, None, true);
+                }
+            }
+        }
+
+        #[cfg(feature = "dwarf")]
+        if args.debug_dump.contains(&DebugDump::GdbIndex) {
+            if let Some(shdr) = elf.section_by_name(".gdb_index") {
+                set_color!(stdout);
+                print!("\nContents of the ");
+                set_color!(stdout, Color::Magenta);
+                print!(".gdb_index");
+                set_color!(stdout);
+                println!(" section:\n");
+
+                if let Some(data) = or_report(f, elf.section_data(&shdr), &mut failed) {
+                    match GdbIndex::parse(&data) {
+                        Some(index) => {
+                            println!("Version {}\n", index.version);
+                            println!("CU table:");
+                            for (i, (offset, length)) in index.comp_units.iter().enumerate() {
+                                println!(
+                                    "[{:3}] {} - {}",
+                                    i,
+                                    gnu_hex(*offset),
+                                    gnu_hex((offset + length).saturating_sub(1))
+                                );
+                            }
+                            println!("\nTU table:");
+                            for (i, unit) in index.type_units.iter().enumerate() {
+                                println!(
+                                    "[{:3}] {} {} {:x}",
+                                    i,
+                                    gnu_hex(unit.offset),
+                                    gnu_hex(unit.type_offset),
+                                    unit.signature
+                                );
+                            }
+                            println!("\nAddress table:");
+                            for address in &index.addresses {
+                                println!(
+                                    "{:016x} {:016x} {}",
+                                    address.low, address.high, address.cu
+                                );
+                            }
+
+                            set_color!(stdout);
+                            print!("\nSymbol table:");
+                            set_color!(stdout, Color::Green);
+                            print!(" {}", index.symbols.len());
+                            set_color!(stdout);
+                            println!(" of {} slots used", index.slots);
+                            let comp_units = index.comp_units.len() as u32;
+                            for symbol in &index.symbols {
+                                print!("[{:3}] ", symbol.slot);
+                                set_color!(stdout, Color::Magenta);
+                                print!("{}", symbol.name);
+                                set_color!(stdout);
+                                let several = symbol.units.len() > 1;
+                                match several {
+                                    true => println!(":"),
+                                    false => print!(":"),
+                                }
+                                for unit in &symbol.units {
+                                    let separator = if several { '\t' } else { ' ' };
+                                    // Units past the CU list index the TU list
+                                    match unit.unit().checked_sub(comp_units) {
+                                        Some(tu) => print!("{}T{}", separator, tu),
+                                        None => print!("{}{}", separator, unit.unit()),
+                                    }
+                                    print!(
+                                        " [{}, {}]",
+                                        if unit.is_static() { "static" } else { "global" },
+                                        unit.kind()
+                                    );
+                                    if several {
+                                        println!();
+                                    }
+                                }
+                                if !several {
+                                    println!();
+                                }
+                            }
+                        }
+                        None => {
+                            println!("The section could not be read, or has a version before 3")
+                        }
+                    }
+                }
+            }
+        }
+
+        #[cfg(feature = "dwarf")]
+        if args.show_debug_stats {
+            if let Some(stats) = or_report(f, DebugStats::read(&elf), &mut failed) {
+                for (name, err) in &stats.skipped {
+                    eprintln!("readelf-rs: Warning: '{}': {}: {}, skipped", f, name, err);
+                }
+
+                set_color!(stdout);
+                print!("\nDebug sections:");
+                set_color!(stdout, Color::Green);
+                println!(" {}", stats.sections.len());
+                set_color!(stdout);
+                if !stats.sections.is_empty() {
+                    println!("  {:<22}  {:>10}  {:>12}", "Name", "Size", "Uncompressed");
+                    for section in &stats.sections {
+                        set_color!(stdout, Color::Magenta);
+                        print!("  {:<22}", section.name);
+                        set_color!(stdout);
+                        match section.uncompressed {
+                            Some(uncompressed) => {
+                                println!("  {:>10}  {:>12}", section.size, uncompressed)
+                            }
+                            None => println!("  {:>10}", section.size),
+                        }
+                    }
+                    println!(
+                        "  {:<22}  {:>10}",
+                        "Total",
+                        stats
+                            .sections
+                            .iter()
+                            .map(|section| section.size)
+                            .sum::<u64>()
+                    );
+                }
+
+                let units = stats.units.values().sum::<usize>();
+                if units != 0 {
+                    print!("\nUnits:");
+                    set_color!(stdout, Color::Green);
+                    print!(" {}", units);
+                    set_color!(stdout);
+                    println!(
+                        " ({}), DWARF {}",
+                        stats
+                            .units
+                            .iter()
+                            .map(|(unit_type, count)| format!(
+                                "{} {}",
+                                count,
+                                unit_type_name(*unit_type).unwrap_or("unknown")
+                            ))
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                        stats
+                            .versions
+                            .iter()
+                            .map(|(version, count)| format!("v{}: {}", version, count))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    );
+                    if stats.truncated_units != 0 {
+                        println!(
+                        "  {} units hold forms this reader does not know, their DIEs are not all \
+                         counted",
+                        stats.truncated_units
+                    );
+                    }
+
+                    print!("\nDIEs:");
+                    set_color!(stdout, Color::Green);
+                    println!(" {}", stats.die_count());
+                    set_color!(stdout);
+                    let mut tags = stats.dies.iter().collect::<Vec<_>>();
+                    tags.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+                    if !tags.is_empty() {
+                        println!("  {:>8}  {:>6}  Tag", "Count", "Share");
+                    }
+                    for (tag, count) in tags {
+                        println!(
+                            "  {:>8}  {:>5.1}%  {}",
+                            count,
+                            *count as f64 * 100.0 / stats.die_count() as f64,
+                            tag_string(*tag)
+                        );
+                    }
+
+                    println!(
+                        "\nAbbreviation tables: {} (used by {} units)",
+                        stats.abbrev_tables, units
+                    );
+                    println!(
+                        "Abbreviations:       {} ({:.1} DIEs each)",
+                        stats.abbrevs,
+                        stats.die_count() as f64 / stats.abbrevs.max(1) as f64
+                    );
+                    println!(
+                    "Strings: .debug_str holds {} bytes in {} strings, .debug_line_str {} bytes",
+                    stats.str_size, stats.str_count, stats.line_str_size
+                );
+                    println!(
+                    "  {} attributes refer to string tables, {} strings ({} bytes) are stored in \
+                     the DIEs",
+                    stats.strp_refs, stats.inline_strings, stats.inline_string_bytes
+                );
+                }
+            }
+        }
+
+        #[cfg(feature = "dwarf")]
+        if !args.addr2line.is_empty() {
+            if let Some((debug, symbols)) = or_report(
+                f,
+                DebugInfo::read(&elf).and_then(|debug| Ok((debug, elf.symbol_map()?))),
+                &mut failed,
+            ) {
+                let resolver = Addr2Line::new(&debug);
+                let width = match elf.header().class() {
+                    Some(ElfClass::ElfClass32) => 8,
+                    _ => 16,
+                };
+                for &address in &args.addr2line {
+                    set_color!(stdout);
+                    println!("0x{:0width$x}", address, width = width);
+                    let mut frames = resolver.frames(address);
+                    // Addresses outside the DWARF fall back to the symbol table, as in
+                    // addr2line
+                    if let [frame @ Frame { function: None, .. }] = frames.as_mut_slice() {
+                        frame.function = symbols.lookup(address).map(|(name, _)| name.to_string());
+                    }
+                    for frame in frames {
+                        let known = frame.function.is_some();
+                        set_color!(stdout, Color::Magenta);
+                        println!(
+                            "{}",
+                            frame
+                                .function
+                                .map_or_else(|| "??".to_string(), |name| args.symbol_name(name))
+                        );
+                        set_color!(stdout);
+                        match frame.location {
+                            Some(location) => println!("{}:{}", location.file, location.line),
+                            None if known => println!("??:?"),
+                            None => println!("??:0"),
+                        }
+                    }
+                }
+            }
+        }
+
+        #[cfg(feature = "disasm")]
+        if args.disassembling() {
+            or_report(
+                f,
+                print_disassembly(&mut stdout, f, &elf, &args),
+                &mut failed,
+            );
+        }
+
+        if args.show_lsda {
+            if let Some((lsdas, types, symbols)) = or_report(
+                f,
+                read_lsdas(&elf).and_then(|lsdas| {
+                    let types = typeinfo_names(&elf, &lsdas)?;
+                    Ok((lsdas, types, elf.symbol_map()?))
+                }),
+                &mut failed,
+            ) {
+                set_color!(stdout);
+                print!("\nFDEs with a language specific data area:");
+                set_color!(stdout, Color::Green);
+                println!(" {}", lsdas.len());
+                set_color!(stdout);
+
+                for (fde, lsda) in &lsdas {
+                    print!("\n  FDE at 0x{:x} for ", fde.offset);
+                    set_color!(stdout, Color::Magenta);
+                    print!(
+                        "{}",
+                        symbols
+                            .describe(fde.pc_begin)
+                            .unwrap_or_else(|| format!("0x{:x}", fde.pc_begin))
+                    );
+                    set_color!(stdout);
+                    println!(
+                        " [0x{:x}-0x{:x}), LSDA at 0x{:x}",
+                        fde.pc_begin,
+                        fde.pc_begin + fde.pc_range,
+                        fde.lsda.unwrap_or_default()
+                    );
+
+                    let lsda = match lsda {
+                        Some(lsda) => lsda,
+                        None => {
+                            println!("    <LSDA outside .gcc_except_table>");
+                            continue;
+                        }
+                    };
+                    println!(
+                        "    LPStart: 0x{:x}  TType encoding: {}  Call site encoding: {}",
+                        lsda.lpstart,
+                        encoding_name(lsda.ttype_encoding),
+                        encoding_name(lsda.call_site_encoding)
+                    );
+                    println!(
+                        "    {:<18}  {:<8}  {:<18}  Actions",
+                        "Call site", "Length", "Landing pad"
+                    );
+                    for site in &lsda.call_sites {
+                        println!(
+                            "    0x{:016x}  {:<8}  {:<18}  {}",
+                            site.start,
+                            format!("0x{:x}", site.length),
+                            site.landing_pad
+                                .map_or("-".to_string(), |pad| format!("0x{:016x}", pad)),
+                            match site.actions.is_empty() {
+                                true if site.landing_pad.is_some() => "cleanup".to_string(),
+                                true => String::new(),
+                                false => site
+                                    .actions
+                                    .iter()
+                                    .map(|filter| filter.to_string())
+                                    .collect::<Vec<_>>()
+                                    .join(" -> "),
+                            }
+                        );
+                    }
+
+                    if !lsda.types.is_empty() {
+                        println!("    Type table:");
+                        for (index, entry) in lsda.types.iter().enumerate() {
+                            println!(
+                                "      [{}] 0x{:x}{} {}",
+                                index + 1,
+                                entry.address,
+                                if entry.indirect { " (indirect)" } else { "" },
+                                types.get(&entry.address).map_or("?", String::as_str)
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        if !args.quiet {
+            let mut diagnostics = elf.diagnostics();
+            if args.check {
+                diagnostics.extend(elf.pointer_diagnostics());
+            }
+            renderer.diagnostics(&diagnostics).unwrap();
+        }
+        renderer.end_file().unwrap();
+    }
+
+    #[cfg(feature = "json")]
+    if args.reporting() && !args.quiet {
+        let diagnostics = report.iter().flat_map(|(_, diagnostics)| diagnostics);
+        let count = |severity| {
+            diagnostics
+                .clone()
+                .filter(|d| d.severity == severity)
+                .count()
+        };
+        let (warnings, errors) = (count(Severity::Warning), count(Severity::Error));
+        let files = report
+            .iter()
+            .map(|(file, diagnostics)| {
+                Value::Map(vec![
+                    ("file".to_string(), file.as_str().into()),
+                    (
+                        "diagnostics".to_string(),
+                        Value::List(diagnostics.iter().map(Value::from).collect()),
+                    ),
+                ])
+            })
+            .collect();
+        let document = Value::Map(vec![
+            ("files".to_string(), Value::List(files)),
+            ("warnings".to_string(), (warnings as u64).into()),
+            ("errors".to_string(), (errors as u64).into()),
+        ]);
+        println!("{}", document.to_json());
+    }
+
+    if failed {
+        1
+    } else {
+        0
     }
 }
@@ -0,0 +1,89 @@
+use clap::{Arg, Command};
+use roff::{bold, italic, roman, Inline, Roff};
+
+fn flag_inlines(arg: &Arg) -> Vec<Inline> {
+    let mut inlines = Vec::new();
+    if let Some(short) = arg.get_short() {
+        inlines.push(bold(format!("-{}", short)));
+    }
+    if let Some(long) = arg.get_long() {
+        if !inlines.is_empty() {
+            inlines.push(roman(", "));
+        }
+        inlines.push(bold(format!("--{}", long)));
+    }
+    if arg.is_takes_value_set() {
+        let names = arg
+            .get_value_names()
+            .map(|names| names.join(" "))
+            .unwrap_or_else(|| arg.get_id().to_uppercase());
+        inlines.push(roman(" "));
+        inlines.push(italic(names));
+    }
+    inlines
+}
+
+/// Renders a roff man page from the clap definitions, using the long help of each argument
+/// so multi paragraph doc comments carry over
+pub fn render(cmd: &Command) -> String {
+    let name = cmd.get_name();
+    let mut page = Roff::new();
+
+    page.control("TH", [name.to_uppercase().as_str(), "1"]);
+
+    page.control("SH", ["NAME"]);
+    page.text([roman(format!(
+        "{} - {}",
+        name,
+        cmd.get_about().unwrap_or_default()
+    ))]);
+
+    page.control("SH", ["SYNOPSIS"]);
+    page.text([
+        bold(name),
+        roman(" ["),
+        italic("OPTIONS"),
+        roman("] "),
+        italic("FILES"),
+        roman("..."),
+    ]);
+
+    page.control("SH", ["OPTIONS"]);
+    for arg in cmd
+        .get_arguments()
+        .filter(|arg| !arg.is_positional() && !arg.is_hide_set())
+    {
+        page.control("TP", []);
+        page.text(flag_inlines(arg));
+        let help = arg.get_long_help().or_else(|| arg.get_help());
+        for (i, paragraph) in help.unwrap_or_default().split("\n\n").enumerate() {
+            if i > 0 {
+                page.control("IP", []);
+            }
+            page.text([roman(paragraph.replace('\n', " "))]);
+        }
+        let values = arg.get_possible_values().unwrap_or_default();
+        if !values.is_empty() {
+            page.text([roman(format!(
+                "[possible values: {}]",
+                values
+                    .iter()
+                    .map(|value| value.get_name())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))]);
+        }
+    }
+
+    if let Some(after) = cmd.get_after_help() {
+        page.control("SH", ["EXIT STATUS"]);
+        page.text([roman(after.replace('\n', " "))]);
+    }
+
+    if let Some(author) = cmd.get_author() {
+        page.control("SH", ["AUTHOR"]);
+        page.text([roman(author)]);
+    }
+
+    page.render()
+}
@@ -0,0 +1,56 @@
+use std::{
+    env,
+    io::{self, Write},
+    os::unix::io::AsRawFd,
+    process::{Child, Command, Stdio},
+};
+
+/// A running pager that stdout has been redirected into, restored and waited on when dropped
+pub struct Pager {
+    child: Child,
+    stdout: i32,
+}
+
+impl Pager {
+    /// Starts `$PAGER` (`less` by default) and points stdout at it. Returns `None` when stdout
+    /// is not a terminal or the pager cannot be started, leaving stdout untouched.
+    pub fn start() -> Option<Self> {
+        if unsafe { libc::isatty(libc::STDOUT_FILENO) } != 1 {
+            return None;
+        }
+
+        let pager = env::var("PAGER")
+            .ok()
+            .filter(|pager| !pager.trim().is_empty())
+            .unwrap_or_else(|| "less".to_string());
+        let mut words = pager.split_whitespace();
+        let mut command = Command::new(words.next()?);
+        command.args(words).stdin(Stdio::piped());
+        // Pass colors through, quit when the output fits on one screen
+        if env::var_os("LESS").is_none() {
+            command.env("LESS", "FRX");
+        }
+
+        let child = command.spawn().ok()?;
+        let stdin = child.stdin.as_ref()?.as_raw_fd();
+        io::stdout().flush().ok()?;
+        let stdout = unsafe { libc::dup(libc::STDOUT_FILENO) };
+        if stdout < 0 || unsafe { libc::dup2(stdin, libc::STDOUT_FILENO) } < 0 {
+            return None;
+        }
+
+        Some(Pager { child, stdout })
+    }
+}
+
+impl Drop for Pager {
+    fn drop(&mut self) {
+        let _ = io::stdout().flush();
+        unsafe {
+            libc::dup2(self.stdout, libc::STDOUT_FILENO);
+            libc::close(self.stdout);
+        }
+        drop(self.child.stdin.take());
+        let _ = self.child.wait();
+    }
+}
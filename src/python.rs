@@ -0,0 +1,234 @@
+//! Python bindings, built as the `readelf_rs` extension module by the bindings crate with its
+//! `python` feature
+//!
+//! ```python
+//! import readelf_rs
+//! elf = readelf_rs.ElfFile("/bin/ls")
+//! for section in elf.sections():
+//!     print(section.name, hex(section.addr))
+//! ```
+
+use pyo3::{exceptions::PyIOError, prelude::*};
+
+use crate::elf::{
+    bytes::string_at,
+    core::FileData,
+    dynamic::DynamicTag,
+    hdr::{ElfClass, Endian},
+};
+
+use num_traits::FromPrimitive;
+
+#[pyclass(name = "Header", get_all)]
+#[derive(Clone)]
+pub struct PyHeader {
+    elf_class: u8,
+    data: u8,
+    os_abi: String,
+    object_type: String,
+    machine: u16,
+    entry: u64,
+    flags: u32,
+    phnum: u16,
+    shnum: u16,
+}
+
+#[pyclass(name = "Section", get_all)]
+#[derive(Clone)]
+pub struct PySection {
+    index: usize,
+    name: String,
+    section_type: u32,
+    type_name: String,
+    flags: u64,
+    addr: u64,
+    offset: u64,
+    size: u64,
+    link: u32,
+    info: u32,
+    addralign: u64,
+    entsize: u64,
+}
+
+#[pyclass(name = "Symbol", get_all)]
+#[derive(Clone)]
+pub struct PySymbol {
+    table: String,
+    index: usize,
+    name: String,
+    value: u64,
+    size: u64,
+    symbol_type: String,
+    binding: String,
+    visibility: String,
+    shndx: u16,
+}
+
+#[pyclass(name = "DynamicEntry", get_all)]
+#[derive(Clone)]
+pub struct PyDynamicEntry {
+    tag: u64,
+    tag_name: String,
+    value: u64,
+}
+
+#[pyclass(name = "Note", get_all)]
+#[derive(Clone)]
+pub struct PyNote {
+    owner: String,
+    note_type: u32,
+    type_name: String,
+    desc: Vec<u8>,
+}
+
+/// A parsed ELF file, the Python face of [`FileData`]
+#[pyclass(name = "ElfFile")]
+pub struct PyElfFile {
+    elf: FileData,
+}
+
+fn name_or_unknown<T: ToString>(value: Option<T>) -> String {
+    value.map_or_else(|| "UNKNOWN".to_string(), |value| value.to_string())
+}
+
+#[pymethods]
+impl PyElfFile {
+    #[new]
+    fn new(path: &str) -> PyResult<Self> {
+        let elf = FileData::new(path).map_err(|err| PyIOError::new_err(err.to_string()))?;
+        Ok(Self { elf })
+    }
+
+    /// Parses an image held in a `bytes` object
+    #[staticmethod]
+    fn from_bytes(data: &[u8]) -> PyResult<Self> {
+        let elf = FileData::from_bytes(data).map_err(|err| PyIOError::new_err(err.to_string()))?;
+        Ok(Self { elf })
+    }
+
+    fn header(&self) -> PyHeader {
+        let hdr = self.elf.header();
+        PyHeader {
+            elf_class: match hdr.class() {
+                Some(ElfClass::ElfClass32) => 32,
+                Some(ElfClass::ElfClass64) => 64,
+                _ => 0,
+            },
+            data: match hdr.endian() {
+                Some(Endian::Little) => 1,
+                Some(Endian::Big) => 2,
+                _ => 0,
+            },
+            os_abi: hdr.os_abi().to_string(),
+            object_type: hdr.type_name(),
+            machine: hdr.machine(),
+            entry: hdr.entry(),
+            flags: hdr.flags(),
+            phnum: hdr.nheaders(),
+            shnum: hdr.nsection_headers(),
+        }
+    }
+
+    fn sections(&self) -> Vec<PySection> {
+        self.elf
+            .section_headers()
+            .iter()
+            .enumerate()
+            .map(|(index, shdr)| PySection {
+                index,
+                name: self.elf.section_name(shdr).unwrap_or_default(),
+                section_type: shdr.raw_type(),
+                type_name: shdr.type_name(),
+                flags: shdr.flags(),
+                addr: shdr.addr(),
+                offset: shdr.offset(),
+                size: shdr.size(),
+                link: shdr.link(),
+                info: shdr.info(),
+                addralign: shdr.addralign(),
+                entsize: shdr.entsize(),
+            })
+            .collect()
+    }
+
+    /// Raw contents of the named section, `None` when there is no such section
+    fn section_data(&self, name: &str) -> PyResult<Option<Vec<u8>>> {
+        match self.elf.section_by_name(name) {
+            Some(shdr) => self
+                .elf
+                .section_data(&shdr)
+                .map(Some)
+                .map_err(|err| PyIOError::new_err(err.to_string())),
+            None => Ok(None),
+        }
+    }
+
+    /// Entries of `.symtab` and `.dynsym`, each tagged with its table
+    fn symbols(&self) -> PyResult<Vec<PySymbol>> {
+        let tables = self
+            .elf
+            .table_symbols()
+            .map_err(|err| PyIOError::new_err(err.to_string()))?;
+        Ok(tables
+            .into_iter()
+            .flat_map(|(table, strtab, syms)| {
+                syms.iter()
+                    .enumerate()
+                    .map(|(index, sym)| PySymbol {
+                        table: table.clone(),
+                        index,
+                        name: string_at(strtab, sym.name() as usize),
+                        value: sym.value(),
+                        size: sym.size(),
+                        symbol_type: name_or_unknown(sym.symbol_type()),
+                        binding: name_or_unknown(sym.binding()),
+                        visibility: name_or_unknown(sym.visibility()),
+                        shndx: sym.shndx(),
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect())
+    }
+
+    fn dynamic(&self) -> Vec<PyDynamicEntry> {
+        if !self.elf.has_dynamic_section() {
+            return Vec::new();
+        }
+        self.elf
+            .dynamic_section()
+            .iter()
+            .map(|entry| PyDynamicEntry {
+                tag: entry.tag,
+                tag_name: name_or_unknown(DynamicTag::from_u64(entry.tag)),
+                value: unsafe { entry.value.val },
+            })
+            .collect()
+    }
+
+    fn notes(&self) -> PyResult<Vec<PyNote>> {
+        let notes = self
+            .elf
+            .notes()
+            .map_err(|err| PyIOError::new_err(err.to_string()))?;
+        Ok(notes
+            .into_iter()
+            .map(|note| PyNote {
+                type_name: note.type_name().to_string(),
+                owner: note.name,
+                note_type: note.ntype,
+                desc: note.desc,
+            })
+            .collect())
+    }
+}
+
+#[pymodule]
+fn readelf_rs(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyElfFile>()?;
+    module.add_class::<PyHeader>()?;
+    module.add_class::<PySection>()?;
+    module.add_class::<PySymbol>()?;
+    module.add_class::<PyDynamicEntry>()?;
+    module.add_class::<PyNote>()?;
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use std::fs;
+
+/// Nesting limit for `@file`s referring to other `@file`s, guards against cycles
+const MAX_DEPTH: usize = 32;
+
+/// Splits the contents of a response file into arguments the way libiberty's `buildargv`
+/// does: whitespace separated, with single quotes, double quotes and backslash escapes
+pub fn split_args(contents: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut arg = String::new();
+    let mut in_arg = false;
+    let mut quote = None;
+    let mut chars = contents.chars();
+
+    while let Some(c) = chars.next() {
+        match (c, quote) {
+            ('\\', _) => {
+                if let Some(escaped) = chars.next() {
+                    arg.push(escaped);
+                }
+                in_arg = true;
+            }
+            (c, Some(q)) if c == q => quote = None,
+            (c, Some(_)) => arg.push(c),
+            ('\'' | '"', None) => {
+                quote = Some(c);
+                in_arg = true;
+            }
+            (c, None) if c.is_whitespace() => {
+                if in_arg {
+                    args.push(std::mem::take(&mut arg));
+                    in_arg = false;
+                }
+            }
+            (c, None) => {
+                arg.push(c);
+                in_arg = true;
+            }
+        }
+    }
+    if in_arg {
+        args.push(arg);
+    }
+
+    args
+}
+
+fn expand_into(args: impl IntoIterator<Item = String>, depth: usize, out: &mut Vec<String>) {
+    for arg in args {
+        let contents = match arg.strip_prefix('@') {
+            Some(path) if depth < MAX_DEPTH => fs::read_to_string(path).ok(),
+            _ => None,
+        };
+
+        match contents {
+            Some(contents) => expand_into(split_args(&contents), depth + 1, out),
+            // Like binutils, an unreadable @file is passed through as a literal argument
+            None => out.push(arg),
+        }
+    }
+}
+
+/// Replaces every `@file` argument with the arguments read from `file`
+pub fn expand_response_files(args: impl IntoIterator<Item = String>) -> Vec<String> {
+    let mut expanded = Vec::new();
+    expand_into(args, 0, &mut expanded);
+    expanded
+}
+
+#[cfg(test)]
+mod test {
+    use super::split_args;
+
+    #[test]
+    fn splits_quoted_args() {
+        assert_eq!(
+            split_args("-h  'a b.o'\n\"c\\\"d.o\" e\\ f.o ''"),
+            vec!["-h", "a b.o", "c\"d.o", "e f.o", ""]
+        );
+    }
+}
@@ -0,0 +1,148 @@
+use std::fs;
+
+use rhai::{Array, Dynamic, Engine, Map, Scope, AST};
+
+use readelf_rs::elf::{
+    bytes::string_at, core::FileData, dynamic::dynamic_tag_name, phdr::program_type_name, EI_OSABI,
+};
+
+/// A `--script` file, compiled once and run against every file on the command line.
+///
+/// The script sees the file as the constant `elf`, a map of:
+/// - `path`, `class`, `machine`, `type` and `entry`
+/// - `sections`: `name`, `type`, `flags` (the `WAX` letters), `addr`, `offset` and `size`
+/// - `segments`: `type`, `offset`, `vaddr`, `filesz`, `memsz`, `align`, and `read`,
+///   `write` and `execute` flags
+/// - `symbols`: `name`, `table`, `value`, `size`, `type`, `bind` and `shndx`
+/// - `dynamic`: `tag` and `value`
+///
+/// Numbers are Rhai integers, so addresses past `i64::MAX` read as negative.
+pub struct Script {
+    engine: Engine,
+    ast: AST,
+}
+
+impl Script {
+    pub fn load(path: &str) -> Result<Self, String> {
+        let source = fs::read_to_string(path).map_err(|err| format!("'{}': {}", path, err))?;
+        let engine = Engine::new();
+        let ast = engine
+            .compile(source)
+            .map_err(|err| format!("'{}': {}", path, err))?;
+        Ok(Self { engine, ast })
+    }
+
+    pub fn run(&self, path: &str, elf: &FileData) -> Result<(), String> {
+        let mut scope = Scope::new();
+        scope.push_constant("elf", model(path, elf));
+        self.engine
+            .run_ast_with_scope(&mut scope, &self.ast)
+            .map_err(|err| err.to_string())
+    }
+}
+
+fn model(path: &str, elf: &FileData) -> Map {
+    let header = elf.header();
+    let machine = header.machine();
+    let int = |value: u64| Dynamic::from(value as i64);
+    let mut model = Map::new();
+
+    model.insert("path".into(), path.into());
+    model.insert(
+        "class".into(),
+        match header.class() {
+            Some(class) => format!("{:?}", class).into(),
+            None => Dynamic::UNIT,
+        },
+    );
+    model.insert(
+        "machine".into(),
+        header.machine_name().unwrap_or("unknown").into(),
+    );
+    model.insert("type".into(), header.type_name().into());
+    model.insert("entry".into(), int(header.e_entry));
+
+    let sections = elf
+        .section_headers()
+        .iter()
+        .map(|shdr| {
+            let mut section = Map::new();
+            section.insert(
+                "name".into(),
+                elf.section_name(shdr).unwrap_or_default().into(),
+            );
+            section.insert("type".into(), shdr.type_name().into());
+            section.insert(
+                "flags".into(),
+                shdr.flag_letters(machine, header.e_ident[EI_OSABI]).into(),
+            );
+            section.insert("addr".into(), int(shdr.addr()));
+            section.insert("offset".into(), int(shdr.offset()));
+            section.insert("size".into(), int(shdr.size()));
+            Dynamic::from(section)
+        })
+        .collect::<Array>();
+    model.insert("sections".into(), sections.into());
+
+    let segments = elf
+        .program_headers()
+        .iter()
+        .map(|phdr| {
+            let mut segment = Map::new();
+            segment.insert(
+                "type".into(),
+                program_type_name(machine, phdr.p_type()).into(),
+            );
+            segment.insert("offset".into(), int(phdr.offset()));
+            segment.insert("vaddr".into(), int(phdr.vaddr()));
+            segment.insert("filesz".into(), int(phdr.filesz()));
+            segment.insert("memsz".into(), int(phdr.memsz()));
+            segment.insert("align".into(), int(phdr.align()));
+            segment.insert("read".into(), phdr.flags().read().into());
+            segment.insert("write".into(), phdr.flags().write().into());
+            segment.insert("execute".into(), phdr.flags().execute().into());
+            Dynamic::from(segment)
+        })
+        .collect::<Array>();
+    model.insert("segments".into(), segments.into());
+
+    let mut symbols = Array::new();
+    for (table, strtab, syms) in elf.table_symbols().unwrap_or_default() {
+        for sym in syms.iter().skip(1) {
+            let mut symbol = Map::new();
+            symbol.insert("name".into(), string_at(strtab, sym.name() as usize).into());
+            symbol.insert("table".into(), table.clone().into());
+            symbol.insert("value".into(), int(sym.value()));
+            symbol.insert("size".into(), int(sym.size()));
+            symbol.insert(
+                "type".into(),
+                sym.symbol_type()
+                    .map_or_else(String::new, |t| t.to_string())
+                    .into(),
+            );
+            symbol.insert(
+                "bind".into(),
+                sym.binding()
+                    .map_or_else(String::new, |b| b.to_string())
+                    .into(),
+            );
+            symbol.insert("shndx".into(), int(sym.shndx() as u64));
+            symbols.push(symbol.into());
+        }
+    }
+    model.insert("symbols".into(), symbols.into());
+
+    let dynamic = elf
+        .dynamic_section()
+        .iter()
+        .map(|entry| {
+            let mut map = Map::new();
+            map.insert("tag".into(), dynamic_tag_name(entry.tag).into());
+            map.insert("value".into(), int(unsafe { entry.value.val }));
+            Dynamic::from(map)
+        })
+        .collect::<Array>();
+    model.insert("dynamic".into(), dynamic.into());
+
+    model
+}